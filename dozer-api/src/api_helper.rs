@@ -1,7 +1,9 @@
 use crate::auth::Access;
 use crate::errors::{ApiError, AuthError};
-use dozer_cache::cache::expression::QueryExpression;
-use dozer_cache::cache::CacheRecord;
+use dozer_cache::cache::aggregate::AggregateResult;
+use dozer_cache::cache::expression::{AggregateExpression, FilterExpression, QueryExpression};
+use dozer_cache::cache::{CacheRecord, QueryPage};
+use dozer_cache::errors::CacheError;
 use dozer_cache::{AccessFilter, CacheReader};
 use dozer_types::models::api_security::ApiSecurity;
 
@@ -36,7 +38,7 @@ pub fn get_records_count(
     let access_filter = get_access_filter(access, endpoint)?;
     cache_reader
         .count(exp, access_filter)
-        .map_err(ApiError::CountFailed)
+        .map_err(|e| map_restricted_field_error(e, ApiError::CountFailed))
 }
 
 /// Get multiple records
@@ -49,10 +51,61 @@ pub fn get_records(
     let access_filter = get_access_filter(access, endpoint)?;
     cache_reader
         .query(exp, access_filter)
-        .map_err(ApiError::QueryFailed)
+        .map_err(|e| map_restricted_field_error(e, ApiError::QueryFailed))
 }
 
-fn get_access_filter(access: Option<Access>, endpoint: &str) -> Result<AccessFilter, ApiError> {
+/// Get multiple records and the cursor of the next page
+pub fn get_records_page(
+    cache_reader: &CacheReader,
+    exp: &mut QueryExpression,
+    endpoint: &str,
+    access: Option<Access>,
+) -> Result<QueryPage, ApiError> {
+    let access_filter = get_access_filter(access, endpoint)?;
+    cache_reader
+        .query_page(exp, access_filter)
+        .map_err(|e| map_restricted_field_error(e, ApiError::QueryFailed))
+}
+
+/// Group and aggregate records
+pub fn get_aggregate(
+    cache_reader: &CacheReader,
+    exp: &AggregateExpression,
+    endpoint: &str,
+    access: Option<Access>,
+) -> Result<AggregateResult, ApiError> {
+    let access_filter = get_access_filter(access, endpoint)?;
+    cache_reader
+        .aggregate(exp, access_filter)
+        .map_err(|e| map_restricted_field_error(e, ApiError::AggregateFailed))
+}
+
+/// Get the access filter of an event subscription, checking the subscription's filter against it
+pub fn get_event_access_filter(
+    access: Option<Access>,
+    endpoint: &str,
+    filter: Option<&FilterExpression>,
+) -> Result<AccessFilter, ApiError> {
+    let access_filter = get_access_filter(access, endpoint)?;
+    if let Some(filter) = filter {
+        access_filter
+            .check_filter(filter)
+            .map_err(|e| map_restricted_field_error(e, ApiError::QueryFailed))?;
+    }
+    Ok(access_filter)
+}
+
+fn map_restricted_field_error(
+    error: CacheError,
+    otherwise: impl FnOnce(CacheError) -> ApiError,
+) -> ApiError {
+    match error {
+        CacheError::RestrictedField(field) => ApiError::RestrictedField(field),
+        error => otherwise(error),
+    }
+}
+
+pub fn get_access_filter(access: Option<Access>, endpoint: &str) -> Result<AccessFilter, ApiError> {
     match access {
         None | Some(Access::All) => Ok(AccessFilter {
             filter: None,
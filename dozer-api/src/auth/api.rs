@@ -31,10 +31,10 @@ pub fn auth_grpc(
             let api_security = api_security
                 .ok_or_else(|| Status::permission_denied("Cannot access this method."))?;
 
-            let ApiSecurity::Jwt(secret) = api_security;
-
-            let auth = Authorizer::new(&secret, None, None);
-            let token = auth.generate_token(tenant_access, None).unwrap();
+            let auth = Authorizer::from(&api_security);
+            let token = auth
+                .generate_token(tenant_access, None)
+                .map_err(ApiError::ApiAuthError)?;
             Ok(Response::new(GetAuthTokenResponse { token }))
         }
         Access::Custom(_) => Err(Status::permission_denied("Cannot access this method.")),
@@ -54,24 +54,17 @@ pub async fn auth_route(
     match access {
         // Master Key or Uninitialized
         Access::All => {
-            let secret = get_secret(&req)?;
-            let auth = Authorizer::new(secret, None, None);
-            let token = auth.generate_token(tenant_access.0, None).unwrap();
+            let api_security = req
+                .app_data::<ApiSecurity>()
+                .ok_or(AuthError::Unauthorized)?;
+            let auth = Authorizer::from(api_security);
+            let token = auth.generate_token(tenant_access.0, None)?;
             Ok(HttpResponse::Ok().body(json!({ "token": token }).to_string()))
         }
         Access::Custom(_) => Err(ApiError::ApiAuthError(AuthError::Unauthorized)),
     }
 }
 
-fn get_secret(req: &HttpRequest) -> Result<&str, AuthError> {
-    let api_security = req
-        .app_data::<ApiSecurity>()
-        .ok_or(AuthError::Unauthorized)?;
-
-    match api_security {
-        ApiSecurity::Jwt(secret) => Ok(secret.as_str()),
-    }
-}
 pub async fn validate(
     req: ServiceRequest,
     credentials: BearerAuth,
@@ -79,21 +72,17 @@ pub async fn validate(
     let api_security = req
         .app_data::<ApiSecurity>()
         .expect("We only validate bearer tokens if ApiSecurity is set");
-    match api_security {
-        ApiSecurity::Jwt(secret) => {
-            let api_auth = Authorizer::new(secret, None, None);
-            let res = api_auth
-                .validate_token(credentials.token())
-                .map_err(|e| (Error::from(ApiError::ApiAuthError(e))));
+    let api_auth = Authorizer::from(api_security);
+    let res = api_auth
+        .validate_token(credentials.token())
+        .map_err(|e| (Error::from(ApiError::ApiAuthError(e))));
 
-            match res {
-                Ok(claims) => {
-                    // Provide access to all
-                    req.extensions_mut().insert(claims.access);
-                    Ok(req)
-                }
-                Err(e) => Err((e, req)),
-            }
+    match res {
+        Ok(claims) => {
+            // Provide access to all
+            req.extensions_mut().insert(claims.access);
+            Ok(req)
         }
+        Err(e) => Err((e, req)),
     }
 }
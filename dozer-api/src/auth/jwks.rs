@@ -0,0 +1,59 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::RwLock,
+    time::SystemTime,
+};
+
+use dozer_types::serde_json;
+use jsonwebtoken::jwk::{Jwk, JwkSet};
+use once_cell::sync::Lazy;
+
+use crate::errors::AuthError;
+
+/// Modification time and length of a JWKS file, which change when the file is rewritten.
+type FileVersion = (Option<SystemTime>, u64);
+
+/// JWKS documents by path, with the version of the file they were loaded from.
+static JWK_SETS: Lazy<RwLock<HashMap<PathBuf, (FileVersion, JwkSet)>>> =
+    Lazy::new(Default::default);
+
+/// Finds the key with id `kid` in the JWKS document at `path`.
+///
+/// The whole document is reloaded when the file changes, so keys can be rotated and removed
+/// without a restart. Unknown key ids are looked up in the loaded document only, so they don't
+/// cause reloads.
+pub fn find_key(path: &Path, kid: &str) -> Result<Jwk, AuthError> {
+    let version = file_version(path)?;
+    if let Some((loaded_version, jwk_set)) = JWK_SETS.read().unwrap().get(path) {
+        if loaded_version == &version {
+            return find(jwk_set, kid);
+        }
+    }
+
+    let jwk_set = load(path)?;
+    let jwk = find(&jwk_set, kid);
+    JWK_SETS
+        .write()
+        .unwrap()
+        .insert(path.to_path_buf(), (version, jwk_set));
+    jwk
+}
+
+fn find(jwk_set: &JwkSet, kid: &str) -> Result<Jwk, AuthError> {
+    jwk_set
+        .find(kid)
+        .cloned()
+        .ok_or_else(|| AuthError::UnknownKeyId(kid.to_string()))
+}
+
+fn file_version(path: &Path) -> Result<FileVersion, AuthError> {
+    let metadata =
+        std::fs::metadata(path).map_err(|e| AuthError::ReadJwks(path.to_path_buf(), e))?;
+    Ok((metadata.modified().ok(), metadata.len()))
+}
+
+fn load(path: &Path) -> Result<JwkSet, AuthError> {
+    let document = std::fs::read(path).map_err(|e| AuthError::ReadJwks(path.to_path_buf(), e))?;
+    serde_json::from_slice(&document).map_err(AuthError::ParseJwks)
+}
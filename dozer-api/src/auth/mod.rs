@@ -5,6 +5,7 @@ use dozer_types::serde;
 use serde::{Deserialize, Serialize};
 pub mod api;
 pub mod authorizer;
+mod jwks;
 pub use authorizer::Authorizer;
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 #[serde(crate = "self::serde")]
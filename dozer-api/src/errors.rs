@@ -62,12 +62,20 @@ pub enum ApiError {
     CountFailed(#[source] CacheError),
     #[error("Failed to query cache: {0}")]
     QueryFailed(#[source] CacheError),
+    #[error("Failed to aggregate records: {0}")]
+    AggregateFailed(#[source] CacheError),
     #[error("Failed to get cache phase: {0}")]
     GetPhaseFailed(#[source] CacheError),
     #[error("Invalid primary key: {0}")]
     InvalidPrimaryKey(#[source] TypeError),
     #[error("Invalid access filter: {0}")]
     InvalidAccessFilter(#[source] serde_json::Error),
+    #[error("Invalid event filter: {0}")]
+    InvalidEventFilter(#[source] serde_json::Error),
+    #[error("Push events are not enabled. Enable them in the config")]
+    PushEventsDisabled,
+    #[error("Access to field {0} is restricted")]
+    RestrictedField(String),
     #[error(transparent)]
     CannotConvertF64ToJson(#[from] CannotConvertF64ToJson),
     #[error("SQL query failed: {0}")]
@@ -86,7 +94,14 @@ pub enum GrpcError {
 
 impl From<ApiError> for dozer_types::tonic::Status {
     fn from(input: ApiError) -> Self {
-        dozer_types::tonic::Status::new(dozer_types::tonic::Code::Unknown, input.to_string())
+        let code = match input {
+            ApiError::RestrictedField(_) => dozer_types::tonic::Code::PermissionDenied,
+            ApiError::AggregateFailed(CacheError::Aggregate(_)) => {
+                dozer_types::tonic::Code::InvalidArgument
+            }
+            _ => dozer_types::tonic::Code::Unknown,
+        };
+        dozer_types::tonic::Status::new(code, input.to_string())
     }
 }
 
@@ -130,6 +145,18 @@ pub enum AuthError {
     Unauthorized,
     #[error("JWT error: {0}")]
     JWT(#[from] jsonwebtoken::errors::Error),
+    #[error("Tokens can only be generated with a JWT secret")]
+    CannotGenerateToken,
+    #[error("Token algorithm {0:?} is not supported")]
+    UnsupportedAlgorithm(jsonwebtoken::Algorithm),
+    #[error("Token has no key id")]
+    MissingKeyId,
+    #[error("Key id {0} is not found in JWKS")]
+    UnknownKeyId(String),
+    #[error("Failed to read JWKS from {0:?}: {1}")]
+    ReadJwks(PathBuf, #[source] std::io::Error),
+    #[error("Failed to parse JWKS: {0}")]
+    ParseJwks(#[source] serde_json::Error),
 }
 
 impl actix_web::error::ResponseError for ApiError {
@@ -141,16 +168,20 @@ impl actix_web::error::ResponseError for ApiError {
 
     fn status_code(&self) -> StatusCode {
         match *self {
-            ApiError::InvalidPrimaryKey(_) | ApiError::InvalidAccessFilter(_) => {
-                StatusCode::BAD_REQUEST
-            }
+            ApiError::InvalidPrimaryKey(_)
+            | ApiError::InvalidAccessFilter(_)
+            | ApiError::InvalidEventFilter(_) => StatusCode::BAD_REQUEST,
             ApiError::ApiAuthError(_) => StatusCode::UNAUTHORIZED,
+            ApiError::RestrictedField(_) => StatusCode::FORBIDDEN,
             ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::PushEventsDisabled => StatusCode::SERVICE_UNAVAILABLE,
             ApiError::NoPrimaryKey | ApiError::MultiIndexFetch(_) => {
                 StatusCode::UNPROCESSABLE_ENTITY
             }
+            ApiError::AggregateFailed(CacheError::Aggregate(_)) => StatusCode::BAD_REQUEST,
             ApiError::QueryFailed(_)
             | ApiError::CountFailed(_)
+            | ApiError::AggregateFailed(_)
             | ApiError::GetPhaseFailed(_)
             | ApiError::CannotConvertF64ToJson(_)
             | ApiError::SQLQueryFailed(_) => StatusCode::INTERNAL_SERVER_ERROR,
@@ -203,7 +203,7 @@ impl<'a> OpenApiGenerator<'a> {
             tags: vec![format!("{}", self.table_name)],
             summary: Some("Query documents based on an expression".to_owned()),
             description: Some(
-                "Documents can be queried based on a simple or a composite expression".to_owned(),
+                "Documents can be queried based on a simple or a composite expression. If there're more documents, the `x-dozer-next-cursor` response header can be passed as `$cursor` to get the next page".to_owned(),
             ),
             operation_id: Some(format!("query-{}", self.table_name)),
             request_body: Some(ReferenceOr::Item(request_body)),
@@ -216,16 +216,81 @@ impl<'a> OpenApiGenerator<'a> {
         })
     }
 
+    fn generate_aggregate_example(&self) -> Value {
+        let group_by = self
+            .secondary_indexes
+            .first()
+            .and_then(|index| match index {
+                IndexDefinition::SortedInverted(fields) => fields.first(),
+                _ => None,
+            })
+            .map(|index| vec![self.schema.fields[*index].name.clone()])
+            .unwrap_or_default();
+        json!({
+            "$group_by": group_by,
+            "$aggregates": { "count": { "$count": null } }
+        })
+    }
+
+    fn generate_aggregate_route(&self) -> ReferenceOr<PathItem> {
+        let request_body = RequestBody {
+            content: indexmap::indexmap! {
+                "application/json".to_owned() => MediaType { example: Some(self.generate_aggregate_example()), ..Default::default() }
+            },
+            required: true,
+            ..Default::default()
+        };
+        let responses = Responses {
+            responses: indexmap::indexmap! {
+                StatusCode::Code(200) => ReferenceOr::Item(
+                    create_response(
+                        "One object per group, with the group by fields and the aggregates".to_string(),
+                        Schema {
+                            schema_data: Default::default(),
+                            schema_kind: SchemaKind::Type(Type::Array(ArrayType {
+                                items: Some(ReferenceOr::boxed_item(Schema {
+                                    schema_data: Default::default(),
+                                    schema_kind: SchemaKind::Type(Type::Object(ObjectType::default())),
+                                })),
+                                min_items: None,
+                                max_items: None,
+                                unique_items: false,
+                            })),
+                        }
+                    )
+                )
+            },
+            ..Default::default()
+        };
+        let operation = Some(Operation {
+            tags: vec![format!("{}", self.table_name)],
+            summary: Some("Aggregate documents based on an expression".to_owned()),
+            description: Some(
+                "Documents matching the filter are grouped and aggregated with count, sum, min, max or avg".to_owned(),
+            ),
+            operation_id: Some(format!("aggregate-{}", self.table_name)),
+            request_body: Some(ReferenceOr::Item(request_body)),
+            responses,
+            ..Default::default()
+        });
+        ReferenceOr::Item(PathItem {
+            post: operation,
+            ..Default::default()
+        })
+    }
+
     fn _generate_available_paths(&self) -> Paths {
         let get_list = self.generate_list_route();
         let get_by_id_item = self.generate_get_route();
         let count_list = self.generate_count_route();
         let query_list = self.generate_query_route();
+        let aggregate_list = self.generate_aggregate_route();
         let path_items = indexmap::indexmap! {
             self.endpoint.path.to_owned() => get_list,
             format!("{}/{}", self.endpoint.path.to_owned(), "{id}") => get_by_id_item,
             format!("{}/count", self.endpoint.path.to_owned()) => count_list,
-            format!("{}/query", self.endpoint.path.to_owned()) => query_list
+            format!("{}/query", self.endpoint.path.to_owned()) => query_list,
+            format!("{}/aggregate", self.endpoint.path.to_owned()) => aggregate_list
         };
         Paths {
             paths: path_items,
@@ -237,6 +237,7 @@ impl<'a> ProtoGeneratorImpl<'a> {
                 "query" => {
                     let message = method.output();
                     let records_field = get_field(&message, "records")?;
+                    let next_cursor_field = get_field(&message, "next_cursor")?;
                     let records_filed_kind = records_field.kind();
                     let Kind::Message(record_message) = records_filed_kind else {
                         return Err(GenerationError::ExpectedMessageField {
@@ -249,6 +250,7 @@ impl<'a> ProtoGeneratorImpl<'a> {
                         response_desc: QueryResponseDesc {
                             message,
                             records_field,
+                            next_cursor_field,
                             record_desc: record_desc_from_message(record_message)?,
                         },
                     });
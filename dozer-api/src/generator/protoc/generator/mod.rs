@@ -85,6 +85,7 @@ pub struct DecimalDesc {
 pub struct QueryResponseDesc {
     pub message: MessageDescriptor,
     pub records_field: FieldDescriptor,
+    pub next_cursor_field: FieldDescriptor,
     pub record_desc: RecordDesc,
 }
 
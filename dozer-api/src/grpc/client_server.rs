@@ -185,6 +185,8 @@ impl ApiServer {
             "Starting gRPC server on {addr} with security: {}",
             security.as_ref().map_or("None".to_string(), |s| match s {
                 ApiSecurity::Jwt(_) => "JWT".to_string(),
+                ApiSecurity::JwtPublicKey(_) => "JWT public key".to_string(),
+                ApiSecurity::Jwks(_) => "JWKS".to_string(),
             })
         );
         let addr = addr
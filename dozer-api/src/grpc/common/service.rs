@@ -7,14 +7,14 @@ use crate::grpc::shared_impl::{self, EndpointFilter};
 use crate::grpc::types_helper::map_record;
 use crate::CacheEndpoint;
 use dozer_types::grpc_types::common::common_grpc_service_server::CommonGrpcService;
-use dozer_types::grpc_types::conversions::field_definition_to_grpc;
+use dozer_types::grpc_types::conversions::{field_definition_to_grpc, field_to_grpc};
 use dozer_types::indexmap::IndexMap;
 use dozer_types::tonic::{self, Request, Response, Status};
 use tokio_stream::wrappers::ReceiverStream;
 
 use dozer_types::grpc_types::common::{
-    CountResponse, GetEndpointsRequest, GetEndpointsResponse, GetFieldsRequest, GetFieldsResponse,
-    OnEventRequest, QueryRequest, QueryResponse,
+    AggregateResponse, AggregateRow, CountResponse, GetEndpointsRequest, GetEndpointsResponse,
+    GetFieldsRequest, GetFieldsResponse, OnEventRequest, QueryRequest, QueryResponse,
 };
 use dozer_types::grpc_types::types::Operation;
 
@@ -94,7 +94,7 @@ impl CommonGrpcService for CommonService {
         let (cache_endpoint, query_request, access) = self.parse_request(request)?;
 
         let cache_reader = cache_endpoint.cache_reader();
-        let records = shared_impl::query(
+        let page = shared_impl::query(
             &cache_reader,
             query_request.query.as_deref(),
             &cache_endpoint.table_name,
@@ -104,8 +104,39 @@ impl CommonGrpcService for CommonService {
         let schema = &cache_reader.get_schema().0;
 
         let fields = field_definition_to_grpc(schema.fields.clone());
-        let records = records.into_iter().map(map_record).collect();
-        let reply = QueryResponse { fields, records };
+        let records = page.records.into_iter().map(map_record).collect();
+        let next_cursor = page.cursor.map(|cursor| cursor.to_token());
+        let reply = QueryResponse {
+            fields,
+            records,
+            next_cursor,
+        };
+
+        Ok(Response::new(reply))
+    }
+
+    async fn aggregate(
+        &self,
+        request: Request<QueryRequest>,
+    ) -> Result<Response<AggregateResponse>, Status> {
+        let (cache_endpoint, query_request, access) = self.parse_request(request)?;
+
+        let result = shared_impl::aggregate(
+            &cache_endpoint.cache_reader(),
+            query_request.query.as_deref(),
+            &cache_endpoint.table_name,
+            access,
+        )?;
+
+        let fields = field_definition_to_grpc(result.fields);
+        let rows = result
+            .rows
+            .into_iter()
+            .map(|row| AggregateRow {
+                values: row.into_iter().map(field_to_grpc).collect(),
+            })
+            .collect();
+        let reply = AggregateResponse { fields, rows };
 
         Ok(Response::new(reply))
     }
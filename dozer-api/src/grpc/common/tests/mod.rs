@@ -77,6 +77,40 @@ async fn test_grpc_common_count_and_query() {
     assert_eq!(records.len(), 11);
 }
 
+#[tokio::test]
+async fn test_grpc_common_aggregate() {
+    let service = setup_common_service().await;
+    let query =
+        r#"{ "$group_by": ["release_year"], "$aggregates": { "count": { "$count": null } } }"#;
+    let response = service
+        .aggregate(Request::new(QueryRequest {
+            endpoint: "films".to_string(),
+            query: Some(query.to_string()),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+
+    let names = response
+        .fields
+        .iter()
+        .map(|field| field.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["release_year", "count"]);
+    assert_eq!(response.rows.len(), 1);
+    assert_eq!(
+        response.rows[0].values,
+        vec![
+            Value {
+                value: Some(value::Value::UintValue(2006)),
+            },
+            Value {
+                value: Some(value::Value::UintValue(52)),
+            },
+        ]
+    );
+}
+
 #[tokio::test]
 async fn test_grpc_common_get_endpoints() {
     let service = setup_common_service().await;
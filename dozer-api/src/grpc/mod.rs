@@ -7,7 +7,7 @@ pub mod internal;
 mod auth_middleware;
 mod grpc_web_middleware;
 mod metric_middleware;
-mod shared_impl;
+pub(crate) mod shared_impl;
 pub mod typed;
 pub mod types_helper;
 
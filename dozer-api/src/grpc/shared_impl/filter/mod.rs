@@ -2,37 +2,54 @@ use dozer_cache::cache::expression::{FilterExpression, Operator};
 use dozer_types::{
     json_value_to_field,
     ordered_float::OrderedFloat,
+    serde_json,
     types::{Field, Schema},
 };
 
 use dozer_types::grpc_types::types::{value, EventType, Operation, OperationType, Record, Value};
 
-pub fn op_satisfies_filter(
-    op: &Operation,
+/// The operation `op` is to a subscriber that only sees the records satisfying `filter`,
+/// or `None` if it doesn't see `op` or the operation isn't of `event_type`.
+///
+/// An update is an insert of the new record if the old one doesn't satisfy `filter`,
+/// and a delete of the old record if the new one doesn't.
+pub fn filter_op(
+    op: Operation,
     event_type: EventType,
     filter: Option<&FilterExpression>,
     schema: &Schema,
-) -> bool {
-    if let Some(filter) = filter {
-        if (op.typ == OperationType::Insert as i32) || (op.typ == OperationType::Delete as i32) {
-            if check_with_event_type(event_type, op) {
-                record_satisfies_filter(op.new.as_ref().unwrap(), filter, schema)
-            } else {
-                false
-            }
-        } else if op.typ == OperationType::Update as i32 {
-            if check_with_event_type(event_type, op) {
-                record_satisfies_filter(op.old.as_ref().unwrap(), filter, schema)
-                    || record_satisfies_filter(op.new.as_ref().unwrap(), filter, schema)
-            } else {
-                false
-            }
-        } else {
-            false
+) -> Option<Operation> {
+    let Some(filter) = filter else {
+        return Some(op);
+    };
+    let satisfies = |record: &Option<Record>| {
+        record
+            .as_ref()
+            .is_some_and(|record| record_satisfies_filter(record, filter, schema))
+    };
+
+    let op = if op.typ == OperationType::Update as i32 {
+        match (satisfies(&op.old), satisfies(&op.new)) {
+            (true, true) => op,
+            (true, false) => Operation {
+                typ: OperationType::Delete as i32,
+                old: None,
+                new: op.old,
+                endpoint: op.endpoint,
+            },
+            (false, true) => Operation {
+                typ: OperationType::Insert as i32,
+                old: None,
+                ..op
+            },
+            (false, false) => return None,
         }
+    } else if satisfies(&op.new) {
+        op
     } else {
-        true
-    }
+        return None;
+    };
+    check_with_event_type(event_type, &op).then_some(op)
 }
 
 fn check_with_event_type(event_type: EventType, op: &Operation) -> bool {
@@ -55,10 +72,28 @@ fn check_with_event_type(event_type: EventType, op: &Operation) -> bool {
 }
 
 fn record_satisfies_filter(record: &Record, filter: &FilterExpression, schema: &Schema) -> bool {
+    filter_satisfied(record, filter, schema, false)
+}
+
+/// Evaluates `filter`, or its negation if `negated`, with the same `null` semantics as the cache queries.
+fn filter_satisfied(
+    record: &Record,
+    filter: &FilterExpression,
+    schema: &Schema,
+    negated: bool,
+) -> bool {
     match filter {
-        FilterExpression::And(filters) => filters
+        FilterExpression::And(filters) if !negated => filters
             .iter()
-            .all(|filter| record_satisfies_filter(record, filter, schema)),
+            .all(|filter| filter_satisfied(record, filter, schema, false)),
+        // De Morgan's laws.
+        FilterExpression::Or(filters) if negated => filters
+            .iter()
+            .all(|filter| filter_satisfied(record, filter, schema, true)),
+        FilterExpression::And(filters) | FilterExpression::Or(filters) => filters
+            .iter()
+            .any(|filter| filter_satisfied(record, filter, schema, negated)),
+        FilterExpression::Not(filter) => filter_satisfied(record, filter, schema, !negated),
         FilterExpression::Simple(field_name, operator, value) => {
             let Some((field_index, field_definition)) = schema
                 .fields
@@ -73,19 +108,78 @@ fn record_satisfies_filter(record: &Record, filter: &FilterExpression, schema: &
                 return false;
             };
 
-            let Ok(value) = json_value_to_field(
-                value.clone(),
-                field_definition.typ,
-                field_definition.nullable,
-            ) else {
-                return false;
+            let to_field = |value: &serde_json::Value| {
+                json_value_to_field(
+                    value.clone(),
+                    field_definition.typ,
+                    field_definition.nullable,
+                )
+                .ok()
             };
+            let is_null = filed_value.value.is_none();
+            let not_equal = |value: &Field| !is_null && !field_equals(filed_value, value);
 
-            field_satisfies_op(filed_value, *operator, &value)
+            match (operator, negated) {
+                (Operator::EQ, false) | (Operator::NE, true) => {
+                    to_field(value).map_or(false, |value| field_equals(filed_value, &value))
+                }
+                (Operator::NE, false) | (Operator::EQ, true) => {
+                    to_field(value).map_or(false, |value| not_equal(&value))
+                }
+                (Operator::In, _) => {
+                    let Some(values) = value.as_array() else {
+                        return false;
+                    };
+                    let Some(values) = values.iter().map(to_field).collect::<Option<Vec<_>>>()
+                    else {
+                        return false;
+                    };
+                    if negated {
+                        values.iter().all(not_equal)
+                    } else {
+                        values.iter().any(|value| field_equals(filed_value, value))
+                    }
+                }
+                (Operator::IsNull, _) => value.as_bool().map_or(false, |is_null_filter| {
+                    is_null == (is_null_filter != negated)
+                }),
+                (operator, negated) => {
+                    let Some(value) = to_field(value) else {
+                        return false;
+                    };
+                    if is_null || value == Field::Null {
+                        return false;
+                    }
+                    match (operator, negated) {
+                        (Operator::LT, true) => {
+                            field_satisfies_op(filed_value, Operator::GTE, &value)
+                        }
+                        (Operator::LTE, true) => {
+                            field_satisfies_op(filed_value, Operator::GT, &value)
+                        }
+                        (Operator::GT, true) => {
+                            field_satisfies_op(filed_value, Operator::LTE, &value)
+                        }
+                        (Operator::GTE, true) => {
+                            field_satisfies_op(filed_value, Operator::LT, &value)
+                        }
+                        (operator, true) => !field_satisfies_op(filed_value, *operator, &value),
+                        (operator, false) => field_satisfies_op(filed_value, *operator, &value),
+                    }
+                }
+            }
         }
     }
 }
 
+/// `null` fields are only equal to `null`.
+fn field_equals(field: &Value, value: &Field) -> bool {
+    match field.value {
+        Some(_) => field_satisfies_op(field, Operator::EQ, value),
+        None => *value == Field::Null,
+    }
+}
+
 fn field_satisfies_op(field: &Value, operator: Operator, value: &Field) -> bool {
     match operator {
         Operator::LT => match (field.value.as_ref().unwrap(), value) {
@@ -138,6 +232,9 @@ fn field_satisfies_op(field: &Value, operator: Operator, value: &Field) -> bool
             _ => false,
         },
         Operator::MatchesAll | Operator::MatchesAny => unimplemented!(),
+        Operator::NE | Operator::In | Operator::IsNull => {
+            unreachable!("{operator:?} is evaluated by filter_satisfied")
+        }
     }
 }
 
@@ -1,7 +1,7 @@
 use super::*;
 use dozer_cache::cache::test_utils::schema_1;
 use dozer_types::grpc_types::types::EventType;
-use dozer_types::serde_json::json;
+use dozer_types::serde_json::{self, json};
 
 fn test_field_satisfies_op_impl(
     field: value::Value,
@@ -765,10 +765,52 @@ fn test_record_satisfies_filter() {
         ]),
         false,
     );
+
+    let check_json = |filter: serde_json::Value, expected| {
+        check(serde_json::from_value(filter).unwrap(), expected);
+    };
+    check_json(json!({"$or": [{"a": 2}, {"c": 3}]}), true);
+    check_json(json!({"$or": [{"a": 2}, {"c": 4}]}), false);
+    check_json(json!({"$not": {"a": 1}}), false);
+    check_json(json!({"$not": {"a": {"$lt": 1}}}), true);
+    check_json(json!({"a": {"$ne": 2}}), true);
+    check_json(json!({"a": {"$in": [2, 1]}}), true);
+    check_json(json!({"$not": {"a": {"$in": [2, 1]}}}), false);
+    check_json(json!({"b": {"$null": false}}), true);
+    check_json(json!({"$not": {"b": {"$null": false}}}), false);
 }
 
 #[test]
-fn test_op_satisfies_filter() {
+fn test_record_satisfies_filter_with_null() {
+    let schema = schema_1().0;
+    let record = Record {
+        values: vec![
+            Value {
+                value: Some(value::Value::IntValue(1)),
+            },
+            Value { value: None },
+            Value {
+                value: Some(value::Value::IntValue(3)),
+            },
+        ],
+        id: 1,
+        version: 1,
+    };
+
+    let check = |filter: serde_json::Value, expected| {
+        let filter = serde_json::from_value(filter).unwrap();
+        assert_eq!(record_satisfies_filter(&record, &filter, &schema), expected);
+    };
+    check(json!({ "b": null }), true);
+    check(json!({"b": {"$null": true}}), true);
+    check(json!({"b": {"$ne": "b"}}), false);
+    check(json!({"b": {"$lt": "b"}}), false);
+    check(json!({"$not": {"b": {"$lt": "b"}}}), false);
+    check(json!({"$or": [{"b": {"$gt": "b"}}, {"a": 1}]}), true);
+}
+
+#[test]
+fn test_filter_op() {
     let schema = schema_1().0;
     let old = Record {
         values: vec![
@@ -798,99 +840,88 @@ fn test_op_satisfies_filter() {
             },
         ],
         id: 1,
-        version: 1,
+        version: 2,
     };
     let filter1 = FilterExpression::Simple("a".into(), Operator::EQ, json!(1));
     let filter2 = FilterExpression::Simple("a".into(), Operator::EQ, json!(2));
     let filter3 = FilterExpression::Simple("a".into(), Operator::EQ, json!(3));
+    let filter_b = FilterExpression::Simple("b".into(), Operator::EQ, json!("b"));
 
-    let check = |typ, old: Option<&Record>, new: &Record, filter, expected, event_type| {
-        assert_eq!(
-            op_satisfies_filter(
-                &Operation {
-                    typ: typ as _,
-                    old: old.cloned(),
-                    new: Some(new.clone()),
-                    endpoint: "".into()
-                },
-                event_type,
-                filter,
-                &schema
-            ),
-            expected
-        );
+    let op = |typ: OperationType, old: Option<&Record>, new: &Record| Operation {
+        typ: typ as _,
+        old: old.cloned(),
+        new: Some(new.clone()),
+        endpoint: "".into(),
+    };
+    let check = |op: Operation, filter, event_type, expected: Option<Operation>| {
+        assert_eq!(filter_op(op, event_type, filter, &schema), expected);
     };
 
+    let insert = op(OperationType::Insert, None, &new);
+    check(insert.clone(), Some(&filter1), EventType::DeleteOnly, None);
     check(
-        OperationType::Insert,
-        None,
-        &new,
-        Some(&filter1),
-        false,
-        EventType::DeleteOnly,
-    );
-    check(
-        OperationType::Insert,
-        None,
-        &new,
+        insert.clone(),
         Some(&filter2),
-        true,
         EventType::InsertOnly,
+        Some(insert.clone()),
     );
     check(
-        OperationType::Insert,
-        None,
-        &new,
+        insert.clone(),
         Some(&filter2),
-        true,
         EventType::All,
+        Some(insert.clone()),
     );
+    check(insert.clone(), Some(&filter1), EventType::UpdateOnly, None);
+    check(insert.clone(), None, EventType::All, Some(insert));
+
+    let delete = op(OperationType::Delete, None, &new);
+    check(delete.clone(), Some(&filter1), EventType::UpdateOnly, None);
     check(
-        OperationType::Insert,
-        None,
-        &new,
-        Some(&filter1),
-        false,
-        EventType::UpdateOnly,
+        delete.clone(),
+        Some(&filter2),
+        EventType::DeleteOnly,
+        Some(delete),
     );
+
+    // Updates within the filter stay updates.
+    let update = op(OperationType::Update, Some(&old), &new);
     check(
-        OperationType::Delete,
-        None,
-        &new,
-        Some(&filter1),
-        false,
+        update.clone(),
+        Some(&filter_b),
         EventType::UpdateOnly,
+        Some(update.clone()),
     );
+    check(update.clone(), Some(&filter_b), EventType::InsertOnly, None);
+    // Updates out of the filter delete the old record.
+    let deleted = Some(op(OperationType::Delete, None, &old));
     check(
-        OperationType::Delete,
-        None,
-        &new,
-        Some(&filter2),
-        true,
-        EventType::DeleteOnly,
+        update.clone(),
+        Some(&filter1),
+        EventType::All,
+        deleted.clone(),
     );
     check(
-        OperationType::Update,
-        Some(&old),
-        &new,
+        update.clone(),
         Some(&filter1),
-        true,
-        EventType::UpdateOnly,
+        EventType::DeleteOnly,
+        deleted,
     );
+    check(update.clone(), Some(&filter1), EventType::UpdateOnly, None);
+    // Updates into the filter insert the new record.
+    let inserted = Some(op(OperationType::Insert, None, &new));
     check(
-        OperationType::Update,
-        Some(&old),
-        &new,
+        update.clone(),
         Some(&filter2),
-        true,
-        EventType::UpdateOnly,
+        EventType::All,
+        inserted.clone(),
     );
     check(
-        OperationType::Update,
-        Some(&old),
-        &new,
-        Some(&filter3),
-        false,
-        EventType::UpdateOnly,
+        update.clone(),
+        Some(&filter2),
+        EventType::InsertOnly,
+        inserted,
     );
+    check(update.clone(), Some(&filter2), EventType::UpdateOnly, None);
+    // Updates outside of the filter aren't seen.
+    check(update, Some(&filter3), EventType::All, None);
 }
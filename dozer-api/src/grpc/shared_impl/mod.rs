@@ -1,9 +1,10 @@
 use std::collections::HashMap;
 
-use dozer_cache::cache::expression::{FilterExpression, QueryExpression};
-use dozer_cache::cache::CacheRecord;
-use dozer_cache::CacheReader;
-use dozer_types::grpc_types::types::Operation;
+use dozer_cache::cache::aggregate::AggregateResult;
+use dozer_cache::cache::expression::{AggregateExpression, FilterExpression, QueryExpression};
+use dozer_cache::cache::QueryPage;
+use dozer_cache::{AccessFilter, CacheReader};
+use dozer_types::grpc_types::types::{Operation, Value};
 use dozer_types::log::warn;
 use dozer_types::serde_json;
 use dozer_types::tonic::{Code, Response, Status};
@@ -14,7 +15,9 @@ use tokio_stream::wrappers::ReceiverStream;
 
 use dozer_types::grpc_types::types::EventType;
 
-use crate::api_helper::{get_records, get_records_count};
+use crate::api_helper::{
+    get_aggregate, get_event_access_filter, get_records_count, get_records_page,
+};
 use crate::auth::Access;
 
 mod filter;
@@ -55,15 +58,28 @@ pub fn query(
     table_name: &str,
     access: Option<Access>,
     default_max_num_records: usize,
-) -> Result<Vec<CacheRecord>, Status> {
+) -> Result<QueryPage, Status> {
     let mut query = parse_query(query, || {
         QueryExpression::with_limit(default_max_num_records)
     })?;
     if query.limit.is_none() {
         query.limit = Some(default_max_num_records);
     }
-    let records = get_records(reader, &mut query, table_name, access)?;
-    Ok(records)
+    let page = get_records_page(reader, &mut query, table_name, access)?;
+    Ok(page)
+}
+
+pub fn aggregate(
+    reader: &CacheReader,
+    query: Option<&str>,
+    table_name: &str,
+    access: Option<Access>,
+) -> Result<AggregateResult, Status> {
+    let expression = match query {
+        Some(query) if !query.is_empty() => serde_json::from_str(query).map_err(from_error)?,
+        _ => AggregateExpression::default(),
+    };
+    Ok(get_aggregate(reader, &expression, table_name, access)?)
 }
 
 #[derive(Debug)]
@@ -98,63 +114,126 @@ impl EndpointFilter {
             .transpose()
             .map_err(from_error)?;
         let event_type = EndpointFilter::convert_event_type(event_type);
-        Ok(Self {
+        Ok(Self::with_expression(schema, event_type, filter))
+    }
+
+    pub fn with_expression(
+        schema: Schema,
+        event_type: EventType,
+        filter: Option<FilterExpression>,
+    ) -> Self {
+        Self {
             schema,
             filter,
             event_type,
-        })
+        }
     }
 }
 
+/// Streams the operations on `endpoints`. A client that can't keep up with the operations
+/// gets a `DATA_LOSS` error, which ends the stream.
 pub fn on_event<T: Send + 'static>(
     endpoints: HashMap<String, EndpointFilter>,
-    mut broadcast_receiver: Option<Receiver<Operation>>,
-    _access: Option<Access>,
-    event_mapper: impl Fn(Operation) -> T + Send + Sync + 'static,
-) -> Result<Response<ReceiverStream<T>>, Status> {
-    // TODO: Use access.
-
-    if broadcast_receiver.is_none() {
+    broadcast_receiver: Option<Receiver<Operation>>,
+    access: Option<Access>,
+    event_mapper: impl Fn(Operation) -> Result<T, Status> + Send + Sync + 'static,
+) -> Result<Response<ReceiverStream<Result<T, Status>>>, Status> {
+    let Some(broadcast_receiver) = broadcast_receiver else {
         return Err(Status::unavailable(
             "on_event is not enabled. This is currently an experimental feature. Enable it in the config.",
         ));
-    }
+    };
 
     if endpoints.is_empty() {
         return Err(Status::invalid_argument("empty endpoints array"));
     }
 
+    let mut access_filters = HashMap::new();
+    for (endpoint, filter) in &endpoints {
+        let access_filter =
+            get_event_access_filter(access.clone(), endpoint, filter.filter.as_ref())?;
+        access_filters.insert(endpoint.clone(), access_filter);
+    }
+
+    Ok(Response::new(event_stream(
+        endpoints,
+        access_filters,
+        broadcast_receiver,
+        event_mapper,
+        |skipped| {
+            Err(Status::data_loss(format!(
+                "event stream lagged behind and skipped {skipped} operations"
+            )))
+        },
+    )))
+}
+
+/// Forwards the operations on `endpoints` as seen through both the endpoint and the access filters,
+/// with restricted fields masked. Updates that move a record into or out of the filters are forwarded
+/// as inserts or deletes. The stream ends when the broadcast channel is closed.
+///
+/// If the receiver lags behind the broadcast channel, operations are lost. The number of skipped
+/// operations is then sent through `lagged_mapper`, and the stream ends.
+pub fn event_stream<T: Send + 'static>(
+    endpoints: HashMap<String, EndpointFilter>,
+    access_filters: HashMap<String, AccessFilter>,
+    mut broadcast_receiver: Receiver<Operation>,
+    event_mapper: impl Fn(Operation) -> T + Send + Sync + 'static,
+    lagged_mapper: impl FnOnce(u64) -> T + Send + 'static,
+) -> ReceiverStream<T> {
     let (tx, rx) = tokio::sync::mpsc::channel(1);
 
     tokio::spawn(async move {
         loop {
-            if let Some(broadcast_receiver) = broadcast_receiver.as_mut() {
-                let event = broadcast_receiver.recv().await;
-                match event {
-                    Ok(op) => {
-                        if let Some(filter) = endpoints.get(&op.endpoint) {
-                            if filter::op_satisfies_filter(
-                                &op,
+            let event = broadcast_receiver.recv().await;
+            match event {
+                Ok(op) => {
+                    if let (Some(filter), Some(access_filter)) = (
+                        endpoints.get(&op.endpoint),
+                        access_filters.get(&op.endpoint),
+                    ) {
+                        let Some(mut op) = filter::filter_op(
+                            op,
+                            EventType::All,
+                            access_filter.filter.as_ref(),
+                            &filter.schema,
+                        )
+                        .and_then(|op| {
+                            filter::filter_op(
+                                op,
                                 filter.event_type,
                                 filter.filter.as_ref(),
                                 &filter.schema,
-                            ) && (tx.send(event_mapper(op)).await).is_err()
-                            {
-                                // receiver dropped
-                                break;
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        warn!("Failed to receive event from broadcast channel: {}", e);
-                        if e == RecvError::Closed {
+                            )
+                        }) else {
+                            continue;
+                        };
+                        mask_restricted_fields(&mut op, access_filter, &filter.schema);
+                        if (tx.send(event_mapper(op)).await).is_err() {
+                            // receiver dropped
                             break;
                         }
                     }
                 }
+                Err(RecvError::Closed) => break,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Event stream lagged behind, skipped {} operations", skipped);
+                    let _ = tx.send(lagged_mapper(skipped)).await;
+                    break;
+                }
             }
         }
     });
 
-    Ok(Response::new(ReceiverStream::new(rx)))
+    ReceiverStream::new(rx)
+}
+
+fn mask_restricted_fields(op: &mut Operation, access_filter: &AccessFilter, schema: &Schema) {
+    for record in op.old.iter_mut().chain(op.new.iter_mut()) {
+        for (field, value) in schema.fields.iter().zip(record.values.iter_mut()) {
+            if access_filter.is_field_restricted(&field.name) {
+                *value = Value { value: None };
+            }
+        }
+    }
 }
@@ -2,7 +2,7 @@ use crate::generator::protoc::generator::{
     CountResponseDesc, EventDesc, QueryResponseDesc, RecordDesc, TokenResponseDesc,
 };
 use crate::grpc::types_helper::map_record;
-use dozer_cache::cache::CacheRecord;
+use dozer_cache::cache::QueryPage;
 use dozer_types::grpc_types::types as GrpcTypes;
 use prost_reflect::{DynamicMessage, ReflectMessage, SetFieldError, Value};
 
@@ -131,12 +131,13 @@ pub fn count_response_to_typed_response(
 }
 
 pub fn query_response_to_typed_response(
-    records: Vec<CacheRecord>,
+    page: QueryPage,
     response_desc: QueryResponseDesc,
 ) -> Result<TypedResponse, SetFieldError> {
     let mut msg = DynamicMessage::new(response_desc.message);
 
-    let data: Result<Vec<prost_reflect::Value>, SetFieldError> = records
+    let data: Result<Vec<prost_reflect::Value>, SetFieldError> = page
+        .records
         .into_iter()
         .map(|record| {
             let record = internal_record_to_pb(map_record(record), &response_desc.record_desc)?;
@@ -147,6 +148,12 @@ pub fn query_response_to_typed_response(
         &response_desc.records_field,
         prost_reflect::Value::List(data?),
     )?;
+    if let Some(cursor) = page.cursor {
+        msg.try_set_field(
+            &response_desc.next_cursor_field,
+            prost_reflect::Value::String(cursor.to_token()),
+        )?;
+    }
     Ok(TypedResponse::new(msg))
 }
 
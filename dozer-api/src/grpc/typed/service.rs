@@ -8,7 +8,7 @@ use super::{
 };
 use crate::{
     auth::{Access, Authorizer},
-    errors::ApiInitError,
+    errors::{ApiError, ApiInitError},
     generator::protoc::generator::{
         CountResponseDesc, EventDesc, ProtoGenerator, QueryResponseDesc, ServiceDesc,
         TokenResponseDesc,
@@ -341,14 +341,14 @@ fn query(
     let mut parts = request.into_parts();
     let (query, access) = parse_request(&mut parts)?;
 
-    let records = shared_impl::query(
+    let page = shared_impl::query(
         reader,
         query.as_deref(),
         table_name,
         access,
         default_max_num_records,
     )?;
-    let res = query_response_to_typed_response(records, response_desc).map_err(|e| {
+    let res = query_response_to_typed_response(page, response_desc).map_err(|e| {
         error!("Query API error: {:?}", e);
         Status::internal("Query API error")
     })?;
@@ -408,7 +408,9 @@ fn token(
         let _parts = request.into_parts();
 
         let auth = Authorizer::from(&security);
-        let token = auth.generate_token(Access::All, None).unwrap();
+        let token = auth
+            .generate_token(Access::All, None)
+            .map_err(ApiError::ApiAuthError)?;
         let res = token_response(token, response_desc);
         Ok(Response::new(res))
     } else {
@@ -1,28 +1,50 @@
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
+use actix_web::http::header;
 use actix_web::web::ReqData;
 use actix_web::{web, HttpResponse};
+use async_stream::stream;
+use bytes::Bytes;
 use datafusion::common::plan_datafusion_err;
 use datafusion::error::DataFusionError;
-use dozer_cache::cache::expression::{QueryExpression, Skip};
+use dozer_cache::cache::aggregate::AggregateResult;
+use dozer_cache::cache::expression::{FilterExpression, QueryCursor, QueryExpression, Skip};
 use dozer_cache::cache::CacheRecord;
 use dozer_cache::{CacheReader, Phase};
+use dozer_types::chrono::{TimeZone, Utc};
 use dozer_types::errors::types::CannotConvertF64ToJson;
 use dozer_types::indexmap::IndexMap;
 use dozer_types::models::endpoint::ApiEndpoint;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::serde::Deserialize;
+use dozer_types::serde_json;
 use dozer_types::types::{Field, Schema};
 use openapiv3::OpenAPI;
+use tokio::sync::broadcast::Receiver;
+use tokio::time::{interval_at, Instant};
+use tokio_stream::StreamExt;
 
-use crate::api_helper::{get_record, get_records, get_records_count};
+use crate::api_helper::{
+    get_aggregate, get_event_access_filter, get_record, get_records_count, get_records_page,
+};
 use crate::generator::oapi::generator::OpenApiGenerator;
+use crate::grpc::shared_impl::{event_stream, EndpointFilter};
 use crate::sql::datafusion::json::record_batches_to_json_rows;
 use crate::sql::datafusion::{PlannedStatement, SQLExecutor};
 use crate::CacheEndpoint;
 use crate::{auth::Access, errors::ApiError};
 use dozer_types::grpc_types::health::health_check_response::ServingStatus;
-use dozer_types::json_types::{field_to_json_value, json, JsonValue};
+use dozer_types::grpc_types::types::{
+    value, EventType, Operation, OperationType, Record as GrpcRecord, Value as GrpcValue,
+};
+use dozer_types::json_types::{field_to_json_value, json, prost_to_json_value, JsonValue};
 
-use self::extractor::QueryExpressionExtractor;
+use self::extractor::{AggregateExpressionExtractor, QueryExpressionExtractor};
+
+/// Response header of `query` carrying the token to pass as `$cursor` for the next page.
+pub const NEXT_CURSOR_HEADER: &str = "x-dozer-next-cursor";
 
 fn generate_oapi3(
     reader: &CacheReader,
@@ -95,7 +117,7 @@ pub async fn list(
 ) -> Result<HttpResponse, ApiError> {
     let mut exp =
         QueryExpression::new(None, vec![], Some(**default_max_num_records), Skip::Skip(0));
-    get_records_map(access, cache_endpoint, &mut exp).map(|map| HttpResponse::Ok().json(map))
+    get_records_map(access, cache_endpoint, &mut exp).map(|(maps, _)| HttpResponse::Ok().json(maps))
 }
 
 // Generated get function for health check
@@ -130,30 +152,221 @@ pub async fn query(
         query_expression.limit = Some(**default_max_num_records);
     }
 
-    get_records_map(access, cache_endpoint, &mut query_expression)
-        .map(|maps| HttpResponse::Ok().json(maps))
+    let (maps, cursor) = get_records_map(access, cache_endpoint, &mut query_expression)?;
+    let mut response = HttpResponse::Ok();
+    if let Some(cursor) = cursor {
+        response.insert_header((NEXT_CURSOR_HEADER, cursor.to_token()));
+    }
+    Ok(response.json(maps))
+}
+
+// Generated aggregate function for grouped records
+pub async fn aggregate(
+    access: Option<ReqData<Access>>,
+    cache_endpoint: ReqData<Arc<CacheEndpoint>>,
+    aggregate_expression: AggregateExpressionExtractor,
+) -> Result<HttpResponse, ApiError> {
+    get_aggregate(
+        &cache_endpoint.cache_reader(),
+        &aggregate_expression.0,
+        &cache_endpoint.table_name,
+        access.map(|a| a.into_inner()),
+    )
+    .map(|result| HttpResponse::Ok().json(aggregate_result_to_maps(result)))
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(crate = "dozer_types::serde", rename_all = "lowercase")]
+pub enum EventTypeParam {
+    #[default]
+    All,
+    Insert,
+    Update,
+    Delete,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct EventsParams {
+    #[serde(default, rename = "type")]
+    event_type: EventTypeParam,
+    /// A filter expression in the same JSON format as `$filter` of queries.
+    filter: Option<String>,
+}
+
+/// Interval of the comments sent on idle event streams, so that proxies don't close them
+const EVENTS_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+// Generated events function, streaming the endpoint's operations as server-sent events
+pub async fn events(
+    access: Option<ReqData<Access>>,
+    cache_endpoint: ReqData<Arc<CacheEndpoint>>,
+    params: web::Query<EventsParams>,
+    operations_receiver: web::Data<Option<Receiver<Operation>>>,
+) -> Result<HttpResponse, ApiError> {
+    let Some(operations_receiver) = operations_receiver.get_ref() else {
+        return Err(ApiError::PushEventsDisabled);
+    };
+
+    let params = params.into_inner();
+    let filter = params
+        .filter
+        .filter(|filter| !filter.is_empty())
+        .map(|filter| serde_json::from_str::<FilterExpression>(&filter))
+        .transpose()
+        .map_err(ApiError::InvalidEventFilter)?;
+    let endpoint = cache_endpoint.table_name.clone();
+    let access_filter =
+        get_event_access_filter(access.map(|a| a.into_inner()), &endpoint, filter.as_ref())?;
+
+    let event_type = match params.event_type {
+        EventTypeParam::All => EventType::All,
+        EventTypeParam::Insert => EventType::InsertOnly,
+        EventTypeParam::Update => EventType::UpdateOnly,
+        EventTypeParam::Delete => EventType::DeleteOnly,
+    };
+    let schema = cache_endpoint.cache_reader().get_schema().0.clone();
+    let endpoint_filter = EndpointFilter::with_expression(schema.clone(), event_type, filter);
+
+    let mut events = event_stream(
+        HashMap::from([(endpoint.clone(), endpoint_filter)]),
+        HashMap::from([(endpoint, access_filter)]),
+        operations_receiver.resubscribe(),
+        Ok,
+        Err,
+    );
+    let stream = stream! {
+        let mut keep_alive = interval_at(
+            Instant::now() + EVENTS_KEEP_ALIVE_INTERVAL,
+            EVENTS_KEEP_ALIVE_INTERVAL,
+        );
+        let mut id = 0;
+        loop {
+            let bytes = tokio::select! {
+                event = events.next() => {
+                    let Some(event) = event else {
+                        break;
+                    };
+                    id += 1;
+                    match event {
+                        Ok(op) => operation_to_server_sent_event(op, id, &schema),
+                        Err(skipped) => Ok(lagged_server_sent_event(skipped, id)),
+                    }
+                }
+                _ = keep_alive.tick() => Ok(Bytes::from_static(b": keep-alive\n\n")),
+            };
+            yield bytes;
+        }
+    };
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(stream))
+}
+
+/// Formats an operation as a server-sent event named after the operation type,
+/// with the old and new records as data.
+///
+/// Ids count the events of the stream. They can't be used to resume a stream,
+/// which always starts from the latest operation.
+fn operation_to_server_sent_event(
+    op: Operation,
+    id: u64,
+    schema: &Schema,
+) -> Result<Bytes, serde_json::Error> {
+    let event = match OperationType::try_from(op.typ) {
+        Ok(OperationType::Insert) => "insert",
+        Ok(OperationType::Delete) => "delete",
+        Ok(OperationType::Update) => "update",
+        Err(_) => "unknown",
+    };
+    let mut data = IndexMap::new();
+    if let Some(old) = op.old {
+        data.insert("old", grpc_record_to_map(old, schema));
+    }
+    if let Some(new) = op.new {
+        data.insert("new", grpc_record_to_map(new, schema));
+    }
+    let data = serde_json::to_string(&data)?;
+    Ok(Bytes::from(format!(
+        "id: {id}\nevent: {event}\ndata: {data}\n\n"
+    )))
+}
+
+/// The last event of a stream that lagged behind the operations, with the number of skipped operations
+fn lagged_server_sent_event(skipped: u64, id: u64) -> Bytes {
+    Bytes::from(format!(
+        "id: {id}\nevent: lagged\ndata: {}\n\n",
+        json!({ "skipped": skipped })
+    ))
 }
 
-/// Get multiple records
+/// Same as `record_to_map`, for records broadcast as gRPC types
+fn grpc_record_to_map(record: GrpcRecord, schema: &Schema) -> IndexMap<String, JsonValue> {
+    let mut map = IndexMap::new();
+
+    for (field_def, value) in schema.fields.iter().zip(record.values) {
+        map.insert(field_def.name.clone(), grpc_value_to_json_value(value));
+    }
+
+    map.insert("__dozer_record_id".to_string(), JsonValue::from(record.id));
+    map.insert(
+        "__dozer_record_version".to_string(),
+        JsonValue::from(record.version),
+    );
+
+    map
+}
+
+/// Should be consistent with `field_to_json_value`.
+fn grpc_value_to_json_value(value: GrpcValue) -> JsonValue {
+    let Some(value) = value.value else {
+        return JsonValue::NULL;
+    };
+    match value {
+        value::Value::UintValue(n) => n.into(),
+        value::Value::IntValue(n) => n.into(),
+        value::Value::Uint128Value(s)
+        | value::Value::Int128Value(s)
+        | value::Value::StringValue(s) => s.into(),
+        value::Value::FloatValue(n) => n.into(),
+        value::Value::BoolValue(b) => b.into(),
+        value::Value::BytesValue(b) => b.into(),
+        value::Value::DecimalValue(d) => field_to_json_value(Field::Decimal(Decimal::from_parts(
+            d.lo, d.mid, d.hi, d.negative, d.scale,
+        ))),
+        value::Value::TimestampValue(ts) => Utc
+            .timestamp_opt(ts.seconds, ts.nanos as u32)
+            .single()
+            .map_or(JsonValue::NULL, |ts| {
+                field_to_json_value(Field::Timestamp(ts.fixed_offset()))
+            }),
+        value::Value::PointValue(p) => field_to_json_value(Field::Point((p.x, p.y).into())),
+        value::Value::DurationValue(d) => json!({ "value": d.value, "time_unit": d.time_unit }),
+        value::Value::JsonValue(v) => prost_to_json_value(v),
+    }
+}
+
+/// Get multiple records and the cursor of the next page
 fn get_records_map(
     access: Option<ReqData<Access>>,
     cache_endpoint: ReqData<Arc<CacheEndpoint>>,
     exp: &mut QueryExpression,
-) -> Result<Vec<IndexMap<String, JsonValue>>, ApiError> {
+) -> Result<(Vec<IndexMap<String, JsonValue>>, Option<QueryCursor>), ApiError> {
     let mut maps = vec![];
     let cache_reader = &cache_endpoint.cache_reader();
-    let records = get_records(
+    let page = get_records_page(
         cache_reader,
         exp,
         &cache_endpoint.table_name,
         access.map(|a| a.into_inner()),
     )?;
     let schema = &cache_reader.get_schema().0;
-    for record in records.into_iter() {
+    for record in page.records.into_iter() {
         let map = record_to_map(record, schema)?;
         maps.push(map);
     }
-    Ok(maps)
+    Ok((maps, page.cursor))
 }
 
 /// Used in REST APIs for converting to JSON
@@ -177,6 +390,22 @@ fn record_to_map(
     Ok(map)
 }
 
+/// Converts each row of an aggregate result to a JSON object keyed by field name
+fn aggregate_result_to_maps(result: AggregateResult) -> Vec<IndexMap<String, JsonValue>> {
+    result
+        .rows
+        .into_iter()
+        .map(|row| {
+            result
+                .fields
+                .iter()
+                .zip(row)
+                .map(|(field_def, field)| (field_def.name.clone(), field_to_json_value(field)))
+                .collect()
+        })
+        .collect()
+}
+
 pub async fn get_phase(
     cache_endpoint: ReqData<Arc<CacheEndpoint>>,
 ) -> Result<web::Json<Phase>, ApiError> {
@@ -186,13 +415,20 @@ pub async fn get_phase(
 }
 
 pub(crate) async fn sql(
-    // access: Option<ReqData<Access>>, // TODO:
+    access: Option<ReqData<Access>>,
     sql_executor: web::Data<Option<Arc<SQLExecutor>>>,
     sql: extractor::SQLQueryExtractor,
 ) -> Result<actix_web::HttpResponse, crate::errors::ApiError> {
     let Some(sql_executor) = sql_executor.as_deref() else {
         return Ok(HttpResponse::NotFound().json(json!({ "error": "SQL endpoint is disabled" })));
     };
+    // Access is per request, so it's set on a copy of the executor.
+    let sql_executor = SQLExecutor::clone(sql_executor);
+    if let Some(access) = access {
+        sql_executor
+            .set_access(access.into_inner())
+            .expect("access of a new executor should not be set");
+    }
     let query = sql.0 .0;
     let planned = sql_executor
         .parse(&query)
@@ -213,10 +449,21 @@ pub(crate) async fn sql(
         .map_err(ApiError::SQLQueryFailed)?
         .collect()
         .await
-        .map_err(ApiError::SQLQueryFailed)?;
+        .map_err(map_sql_execution_error)?;
     Ok(HttpResponse::Ok().json(record_batches_to_json_rows(&record_batches)))
 }
 
+/// Surfaces errors from reading the cache, such as access to a restricted field, as they are.
+fn map_sql_execution_error(error: DataFusionError) -> ApiError {
+    match error {
+        DataFusionError::External(error) => match error.downcast::<ApiError>() {
+            Ok(error) => *error,
+            Err(error) => ApiError::SQLQueryFailed(DataFusionError::External(error)),
+        },
+        error => ApiError::SQLQueryFailed(error),
+    }
+}
+
 mod extractor {
     use std::{
         future::{ready, Ready},
@@ -228,7 +475,7 @@ mod extractor {
         error::{ErrorBadRequest, JsonPayloadError},
         Error, FromRequest, HttpRequest,
     };
-    use dozer_cache::cache::expression::{QueryExpression, SQLQuery};
+    use dozer_cache::cache::expression::{AggregateExpression, QueryExpression, SQLQuery};
     use dozer_types::serde_json;
     use futures_util::{future::Either, Future};
     use pin_project::pin_project;
@@ -251,6 +498,26 @@ mod extractor {
         }
     }
 
+    pub struct AggregateExpressionExtractor(pub AggregateExpression);
+
+    impl FromRequest for AggregateExpressionExtractor {
+        type Error = Error;
+        type Future = Either<
+            Ready<Result<AggregateExpressionExtractor, Error>>,
+            AggregateExpressionExtractFuture,
+        >;
+
+        fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+            if let Err(e) = check_content_type(req) {
+                Either::Left(ready(Err(e)))
+            } else {
+                Either::Right(AggregateExpressionExtractFuture(String::from_request(
+                    req, payload,
+                )))
+            }
+        }
+    }
+
     pub struct SQLQueryExtractor(pub SQLQuery);
 
     impl FromRequest for SQLQueryExtractor {
@@ -297,6 +564,27 @@ mod extractor {
         }
     }
 
+    #[pin_project]
+    pub struct AggregateExpressionExtractFuture(#[pin] StringExtractFut);
+
+    impl Future for AggregateExpressionExtractFuture {
+        type Output = Result<AggregateExpressionExtractor, Error>;
+
+        fn poll(
+            self: std::pin::Pin<&mut Self>,
+            cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<Self::Output> {
+            let this = self.project();
+            match this.0.poll(cx) {
+                Poll::Pending => Poll::Pending,
+                Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+                Poll::Ready(Ok(body)) => {
+                    Poll::Ready(parse_aggregate_expression(&body).map(AggregateExpressionExtractor))
+                }
+            }
+        }
+    }
+
     #[pin_project]
     pub struct SQLQueryExtractFuture(#[pin] StringExtractFut);
 
@@ -326,6 +614,16 @@ mod extractor {
             .map_err(Into::into)
     }
 
+    fn parse_aggregate_expression(body: &str) -> Result<AggregateExpression, Error> {
+        if body.is_empty() {
+            return Ok(AggregateExpression::default());
+        }
+
+        serde_json::from_str(body)
+            .map_err(JsonPayloadError::Deserialize)
+            .map_err(Into::into)
+    }
+
     fn parse_sql_query(body: &str) -> Result<SQLQuery, Error> {
         if body.is_empty() {
             return Ok(SQLQuery(String::new()));
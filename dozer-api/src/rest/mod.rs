@@ -21,6 +21,7 @@ use actix_web::{
 };
 use actix_web_httpauth::middleware::HttpAuthentication;
 use dozer_tracing::LabelsAndProgress;
+use dozer_types::grpc_types::types::Operation;
 use dozer_types::models::api_config::{default_host, default_rest_port};
 use dozer_types::{log::info, models::api_config::RestApiOptions};
 use dozer_types::{
@@ -28,6 +29,7 @@ use dozer_types::{
     serde::{self, Deserialize, Serialize},
 };
 use futures_util::Future;
+use tokio::sync::broadcast::Receiver;
 use tracing_actix_web::TracingLogger;
 mod api_generator;
 mod rest_metric_middleware;
@@ -90,6 +92,7 @@ impl ApiServer {
             CorsOptions::Custom(origins, max_age) => origins
                 .into_iter()
                 .fold(Cors::default(), |cors, origin| cors.allowed_origin(&origin))
+                .expose_headers([api_generator::NEXT_CURSOR_HEADER])
                 .max_age(max_age),
         }
     }
@@ -101,6 +104,7 @@ impl ApiServer {
         labels: LabelsAndProgress,
         default_max_num_records: usize,
         sql_executor: Option<Arc<SQLExecutor>>,
+        operations_receiver: Option<Receiver<Operation>>,
     ) -> App<
         impl ServiceFactory<
             ServiceRequest,
@@ -120,6 +124,7 @@ impl ApiServer {
             .app_data(web::Data::new(default_max_num_records))
             .app_data(web::Data::new(cache_endpoints.clone()))
             .app_data(web::Data::new(sql_executor))
+            .app_data(web::Data::new(operations_receiver))
             .app_data(cfg)
             .wrap(Logger::default())
             .wrap(TracingLogger::default())
@@ -158,8 +163,10 @@ impl ApiServer {
                         })
                         .route("/count", web::post().to(api_generator::count))
                         .route("/query", web::post().to(api_generator::query))
+                        .route("/aggregate", web::post().to(api_generator::aggregate))
                         .route("/phase", web::post().to(api_generator::get_phase))
                         .route("/oapi", web::post().to(api_generator::generate_oapi))
+                        .route("/events", web::get().to(api_generator::events))
                         .route("/{id}", web::get().to(api_generator::get))
                         .route("/", web::get().to(api_generator::list))
                         .route("", web::get().to(api_generator::list)),
@@ -182,6 +189,7 @@ impl ApiServer {
         self,
         cache_endpoints: Vec<Arc<CacheEndpoint>>,
         shutdown: impl Future<Output = ()> + Send + 'static,
+        operations_receiver: Option<Receiver<Operation>>,
         labels: LabelsAndProgress,
     ) -> Result<Server, ApiInitError> {
         let security = get_api_security(self.security.to_owned());
@@ -191,12 +199,16 @@ impl ApiServer {
             self.port,
             security.as_ref().map_or("None".to_string(), |s| match s {
                 ApiSecurity::Jwt(_) => "JWT".to_string(),
+                ApiSecurity::JwtPublicKey(_) => "JWT public key".to_string(),
+                ApiSecurity::Jwks(_) => "JWKS".to_string(),
             })
         );
         let cors = self.cors;
 
         let address = format!("{}:{}", self.host, self.port);
         let default_max_num_records = self.default_max_num_records;
+        // `Receiver` is not `Clone`, which the app factory has to be.
+        let operations_receiver = operations_receiver.map(Arc::new);
         let sql_executor = if self.enable_sql {
             let sql_executor = SQLExecutor::try_new(&cache_endpoints)
                 .await
@@ -213,6 +225,7 @@ impl ApiServer {
                 labels.clone(),
                 default_max_num_records,
                 sql_executor.clone(),
+                operations_receiver.as_ref().map(|r| r.resubscribe()),
             )
         })
         .bind(&address)
@@ -6,6 +6,10 @@ use crate::{
     test_utils, CacheEndpoint,
 };
 use actix_web::{body::MessageBody, dev::ServiceResponse};
+use dozer_cache::{
+    cache::expression::{FilterExpression, Operator},
+    AccessFilter,
+};
 use dozer_types::{
     models::api_security::ApiSecurity,
     serde,
@@ -60,6 +64,89 @@ async fn verify_token_test() {
     assert!(res.status().is_success());
 }
 
+#[actix_web::test]
+async fn restricted_access_test() {
+    let secret = "secret";
+    let table_name = "films";
+    let endpoint = test_utils::get_endpoint();
+    let cache_manager = test_utils::initialize_cache(table_name, None);
+    let api_server = ApiServer::create_app_entry(
+        Some(ApiSecurity::Jwt(secret.to_string())),
+        CorsOptions::Permissive,
+        vec![Arc::new(
+            CacheEndpoint::open(
+                &*cache_manager,
+                Default::default(),
+                table_name.to_string(),
+                endpoint.clone(),
+            )
+            .unwrap(),
+        )],
+        Default::default(),
+        50,
+        None,
+        None,
+    );
+    let app = actix_web::test::init_service(api_server).await;
+
+    let auth = Authorizer::new(secret, None, None);
+    let access = Access::Custom(
+        [(
+            table_name.to_string(),
+            AccessFilter {
+                filter: Some(FilterExpression::Simple(
+                    "film_id".to_string(),
+                    Operator::LT,
+                    Value::from(300),
+                )),
+                fields: vec!["release_year".to_string()],
+            },
+        )]
+        .into_iter()
+        .collect(),
+    );
+    let token = auth.generate_token(access, None).unwrap();
+    let authorization = ("Authorization", format!("Bearer {token}"));
+
+    // Restricted fields are masked.
+    let req = actix_web::test::TestRequest::get()
+        .uri(&endpoint.path)
+        .append_header(authorization.clone())
+        .to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+    assert!(res.status().is_success());
+    let body: Value = actix_web::test::read_body_json(res).await;
+    let records = body.as_array().unwrap();
+    assert!(!records.is_empty());
+    for record in records {
+        assert!(record["film_id"].as_u64().unwrap() < 300);
+        assert!(record["release_year"].is_null());
+    }
+
+    // Filtering on restricted fields is forbidden.
+    let req = actix_web::test::TestRequest::post()
+        .uri(&format!("{}/query", endpoint.path))
+        .append_header(authorization.clone())
+        .set_json(json!({"$filter": {"release_year": 2006}}))
+        .to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+    assert_eq!(res.status().as_u16(), 403);
+
+    // Records filtered out by the row filter are not found.
+    let req = actix_web::test::TestRequest::get()
+        .uri(&format!("{}/268", endpoint.path))
+        .append_header(authorization.clone())
+        .to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+    assert!(res.status().is_success());
+    let req = actix_web::test::TestRequest::get()
+        .uri(&format!("{}/524", endpoint.path))
+        .append_header(authorization)
+        .to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+    assert_eq!(res.status().as_u16(), 404);
+}
+
 async fn check_status(
     security: Option<ApiSecurity>,
     token: Option<String>,
@@ -82,6 +169,7 @@ async fn check_status(
         Default::default(),
         50,
         None,
+        None,
     );
     let app = actix_web::test::init_service(api_server).await;
 
@@ -119,6 +207,7 @@ async fn _call_auth_token_api(
         Default::default(),
         50,
         None,
+        None,
     );
     let app = actix_web::test::init_service(api_server).await;
 
@@ -6,9 +6,13 @@ use actix_http::{body::MessageBody, Request};
 use actix_web::dev::{Service, ServiceResponse};
 use actix_web::http::header::ContentType;
 use dozer_cache::Phase;
+use dozer_types::grpc_types::types::{
+    value, Operation, OperationType, Record as GrpcRecord, Value as GrpcValue,
+};
 use dozer_types::models::endpoint::ApiEndpoint;
 use dozer_types::serde_json::{json, Value};
 use http::StatusCode;
+use tokio::sync::broadcast;
 
 #[test]
 fn test_generate_oapi() {
@@ -25,7 +29,7 @@ fn test_generate_oapi() {
     );
     let generated = oapi_generator.generate_oas3();
 
-    assert_eq!(generated.paths.paths.len(), 4, " paths must be generated");
+    assert_eq!(generated.paths.paths.len(), 5, " paths must be generated");
 }
 
 #[actix_web::test]
@@ -48,6 +52,7 @@ async fn list_route() {
         Default::default(),
         50,
         None,
+        None,
     );
     let app = actix_web::test::init_service(api_server).await;
 
@@ -154,6 +159,243 @@ async fn count_and_query_route() {
     assert_eq!(records.len(), 11);
 }
 
+#[actix_web::test]
+async fn aggregate_route() {
+    let (app, endpoint) = setup_service().await;
+    let aggregate = |query: Value| {
+        actix_web::test::TestRequest::post()
+            .uri(&format!("{}/aggregate", endpoint.path))
+            .set_json(query)
+            .to_request()
+    };
+
+    let res = actix_web::test::call_service(
+        &app,
+        aggregate(json!({
+            "$group_by": ["release_year"],
+            "$aggregates": {"count": {"$count": null}}
+        })),
+    )
+    .await;
+    assert!(res.status().is_success());
+    let body: Value = actix_web::test::read_body_json(res).await;
+    assert_eq!(body, json!([{"release_year": 2006, "count": 52}]));
+
+    let res = actix_web::test::call_service(
+        &app,
+        aggregate(json!({
+            "$filter": {"film_id": 268},
+            "$aggregates": {"sum": {"$sum": "film_id"}, "max": {"$max": "film_id"}}
+        })),
+    )
+    .await;
+    assert!(res.status().is_success());
+    let body: Value = actix_web::test::read_body_json(res).await;
+    assert_eq!(body, json!([{"sum": 268, "max": 268}]));
+
+    let res = actix_web::test::call_service(
+        &app,
+        aggregate(json!({"$aggregates": {"sum": {"$sum": "description"}}})),
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn query_cursor_route() {
+    let (app, endpoint) = setup_service().await;
+    let query = |query: Value| {
+        actix_web::test::TestRequest::post()
+            .uri(&format!("{}/query", endpoint.path))
+            .set_json(query)
+            .to_request()
+    };
+
+    let res = actix_web::test::call_service(&app, query(json!({"$limit": 50}))).await;
+    assert!(res.status().is_success());
+    let cursor = res
+        .headers()
+        .get(super::super::api_generator::NEXT_CURSOR_HEADER)
+        .expect("must return a cursor")
+        .to_str()
+        .unwrap()
+        .to_string();
+    let first_page: Value = actix_web::test::read_body_json(res).await;
+    assert_eq!(first_page.as_array().unwrap().len(), 50);
+
+    let res =
+        actix_web::test::call_service(&app, query(json!({"$limit": 50, "$cursor": cursor}))).await;
+    assert!(res.status().is_success());
+    assert!(res
+        .headers()
+        .get(super::super::api_generator::NEXT_CURSOR_HEADER)
+        .is_none());
+    let second_page: Value = actix_web::test::read_body_json(res).await;
+    let second_page = second_page.as_array().unwrap();
+    assert_eq!(second_page.len(), 2);
+    assert!(second_page
+        .iter()
+        .all(|record| !first_page.as_array().unwrap().contains(record)));
+
+    let res = actix_web::test::call_service(&app, query(json!({"$cursor": "not a cursor"}))).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn events_route() {
+    let table_name = "films";
+    let endpoint = test_utils::get_endpoint();
+    let cache_manager = test_utils::initialize_cache(table_name, None);
+    let (sender, receiver) = broadcast::channel::<Operation>(16);
+    let api_server = ApiServer::create_app_entry(
+        None,
+        CorsOptions::Permissive,
+        vec![Arc::new(
+            CacheEndpoint::open(
+                &*cache_manager,
+                Default::default(),
+                table_name.to_string(),
+                endpoint.clone(),
+            )
+            .unwrap(),
+        )],
+        Default::default(),
+        50,
+        None,
+        Some(receiver),
+    );
+    let app = actix_web::test::init_service(api_server).await;
+
+    // filter: {"film_id": {"$gt": 100}}
+    let req = actix_web::test::TestRequest::get()
+        .uri(&format!(
+            "{}/events?type=insert&filter=%7B%22film_id%22%3A%7B%22%24gt%22%3A100%7D%7D",
+            endpoint.path
+        ))
+        .to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+    assert!(res.status().is_success());
+    assert_eq!(
+        res.headers().get(http::header::CONTENT_TYPE).unwrap(),
+        "text/event-stream"
+    );
+
+    let record = |film_id: u64| GrpcRecord {
+        values: vec![
+            GrpcValue {
+                value: Some(value::Value::UintValue(film_id)),
+            },
+            GrpcValue {
+                value: Some(value::Value::StringValue("description".to_string())),
+            },
+            GrpcValue { value: None },
+            GrpcValue { value: None },
+            GrpcValue { value: None },
+        ],
+        id: film_id,
+        version: 1,
+    };
+    for (typ, film_id) in [
+        (OperationType::Insert, 268),
+        (OperationType::Insert, 32),
+        (OperationType::Delete, 268),
+        (OperationType::Insert, 269),
+    ] {
+        sender
+            .send(Operation {
+                typ: typ as i32,
+                old: None,
+                new: Some(record(film_id)),
+                endpoint: table_name.to_string(),
+            })
+            .unwrap();
+    }
+    // Closing the channel ends the stream.
+    drop(sender);
+
+    let body = actix_web::test::read_body(res).await;
+    let expected = [(1, 268), (2, 269)]
+        .map(|(id, film_id)| {
+            format!(
+                "id: {id}\nevent: insert\ndata: {{\"new\":{{\"film_id\":{film_id},\"description\":\"description\",\"rental_rate\":null,\"release_year\":null,\"updated_at\":null,\"__dozer_record_id\":{film_id},\"__dozer_record_version\":1}}}}\n\n"
+            )
+        })
+        .concat();
+    assert_eq!(std::str::from_utf8(&body).unwrap(), expected);
+
+    let req = actix_web::test::TestRequest::get()
+        .uri(&format!("{}/events?filter=invalid", endpoint.path))
+        .to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn events_route_lagged() {
+    let table_name = "films";
+    let endpoint = test_utils::get_endpoint();
+    let cache_manager = test_utils::initialize_cache(table_name, None);
+    let (sender, receiver) = broadcast::channel::<Operation>(2);
+    let api_server = ApiServer::create_app_entry(
+        None,
+        CorsOptions::Permissive,
+        vec![Arc::new(
+            CacheEndpoint::open(
+                &*cache_manager,
+                Default::default(),
+                table_name.to_string(),
+                endpoint.clone(),
+            )
+            .unwrap(),
+        )],
+        Default::default(),
+        50,
+        None,
+        Some(receiver),
+    );
+    let app = actix_web::test::init_service(api_server).await;
+
+    let req = actix_web::test::TestRequest::get()
+        .uri(&format!("{}/events", endpoint.path))
+        .to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+    assert!(res.status().is_success());
+
+    // More operations than the channel holds, before the stream reads any of them.
+    for film_id in 0..4 {
+        sender
+            .send(Operation {
+                typ: OperationType::Insert as i32,
+                old: None,
+                new: Some(GrpcRecord {
+                    values: vec![],
+                    id: film_id,
+                    version: 1,
+                }),
+                endpoint: table_name.to_string(),
+            })
+            .unwrap();
+    }
+
+    // The stream ends after the lagged event, even though the channel is still open.
+    let body = actix_web::test::read_body(res).await;
+    assert_eq!(
+        std::str::from_utf8(&body).unwrap(),
+        "id: 1\nevent: lagged\ndata: {\"skipped\":2}\n\n"
+    );
+    drop(sender);
+}
+
+#[actix_web::test]
+async fn events_route_disabled() {
+    let (app, endpoint) = setup_service().await;
+    let req = actix_web::test::TestRequest::get()
+        .uri(&format!("{}/events", endpoint.path))
+        .to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
 #[actix_web::test]
 async fn get_route() {
     let (app, endpoint) = setup_service().await;
@@ -247,6 +489,7 @@ async fn path_collision_test() {
         Default::default(),
         50,
         None,
+        None,
     );
     let app = actix_web::test::init_service(api_server).await;
 
@@ -282,6 +525,7 @@ async fn setup_service() -> (
         Default::default(),
         50,
         None,
+        None,
     );
     (actix_web::test::init_service(api_server).await, endpoint)
 }
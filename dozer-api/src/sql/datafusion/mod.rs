@@ -49,8 +49,9 @@ use futures_util::stream::BoxStream;
 use futures_util::StreamExt;
 use once_cell::sync::OnceCell;
 
-use crate::api_helper::get_records;
+use crate::api_helper::{get_access_filter, get_records};
 use crate::auth::Access;
+use crate::errors::ApiError;
 use crate::CacheEndpoint;
 
 use predicate_pushdown::{predicate_pushdown, supports_predicates_pushdown};
@@ -62,9 +63,13 @@ pub(crate) struct SQLExecutor {
 
 impl Clone for SQLExecutor {
     fn clone(&self) -> Self {
+        // The data sources read the access from the session config, so the clone gets its own.
+        let access = Arc::new(OnceCell::<Access>::new());
+        let mut state = self.ctx.state();
+        state.config_mut().set_extension(access.clone());
         Self {
-            ctx: SessionContext::new_with_state(self.ctx.state()),
-            access: Default::default(),
+            ctx: SessionContext::new_with_state(state),
+            access,
         }
     }
 }
@@ -482,12 +487,14 @@ fn resolve_table_ref<'a>(
 
 impl SQLExecutor {
     pub async fn try_new(cache_endpoints: &[Arc<CacheEndpoint>]) -> Result<Self, DataFusionError> {
+        let access = Arc::new(OnceCell::<Access>::new());
         let ctx = SessionContext::new_with_config(
-            SessionConfig::new().with_default_catalog_and_schema("dozer", "public"),
+            SessionConfig::new()
+                .with_default_catalog_and_schema("dozer", "public")
+                .with_extension(access.clone()),
         );
-        let access = Arc::new(OnceCell::<Access>::new());
         for cache_endpoint in cache_endpoints {
-            let data_source = CacheEndpointDataSource::new(cache_endpoint.clone(), access.clone());
+            let data_source = CacheEndpointDataSource::new(cache_endpoint.clone());
             let _provider = ctx
                 .register_table(
                     TableReference::Bare {
@@ -583,11 +590,10 @@ impl SQLExecutor {
 pub struct CacheEndpointDataSource {
     cache_endpoint: Arc<CacheEndpoint>,
     schema: SchemaRef,
-    access: Arc<OnceCell<Access>>,
 }
 
 impl CacheEndpointDataSource {
-    pub fn new(cache_endpoint: Arc<CacheEndpoint>, access: Arc<OnceCell<Access>>) -> Self {
+    pub fn new(cache_endpoint: Arc<CacheEndpoint>) -> Self {
         let schema = {
             let cache_reader = &cache_endpoint.cache_reader();
             let schema = &cache_reader.get_schema().0;
@@ -596,7 +602,6 @@ impl CacheEndpointDataSource {
         Self {
             cache_endpoint,
             schema,
-            access,
         }
     }
 }
@@ -617,7 +622,7 @@ impl TableProvider for CacheEndpointDataSource {
 
     async fn scan(
         &self,
-        _state: &SessionState,
+        state: &SessionState,
         projection: Option<&Vec<usize>>,
         // filters and limit can be used here to inject some push-down operations if needed
         filters: &[Expr],
@@ -629,7 +634,10 @@ impl TableProvider for CacheEndpointDataSource {
             projection,
             filters.to_vec(),
             limit,
-            self.access.clone(),
+            state
+                .config()
+                .get_extension::<OnceCell<Access>>()
+                .unwrap_or_default(),
         )?))
     }
 
@@ -678,6 +686,46 @@ impl CacheEndpointExec {
             access,
         })
     }
+
+    /// Returns the records with the schema to convert them with, where restricted fields are
+    /// nullable because they're masked.
+    fn get_records(&self) -> Result<(DozerSchema, Vec<CacheRecord>), ApiError> {
+        let cache_reader = &self.cache_endpoint.cache_reader();
+        let access = self.access.get().cloned();
+        let access_filter = get_access_filter(access.clone(), &self.cache_endpoint.table_name)?;
+
+        let mut schema = cache_reader.get_schema().0.clone();
+        for (index, field) in schema.fields.iter_mut().enumerate() {
+            if !access_filter.is_field_restricted(&field.name) {
+                continue;
+            }
+            if self
+                .projection
+                .as_ref()
+                .map_or(true, |projection| projection.contains(&index))
+            {
+                return Err(ApiError::RestrictedField(field.name.clone()));
+            }
+            field.nullable = true;
+        }
+
+        let mut expr = QueryExpression {
+            limit: self.limit,
+            filter: predicate_pushdown(self.filters.iter()),
+            ..Default::default()
+        };
+        debug!(
+            "Using predicate pushdown {:?} with access {:?}",
+            expr.filter, access
+        );
+        let records = get_records(
+            cache_reader,
+            &mut expr,
+            &self.cache_endpoint.table_name,
+            access,
+        )?;
+        Ok((schema, records))
+    }
 }
 
 #[async_trait]
@@ -718,34 +766,12 @@ impl ExecutionPlan for CacheEndpointExec {
         _partition: usize,
         _ctx: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
-        let stream = {
-            let cache_reader = &self.cache_endpoint.cache_reader();
-            let mut expr = QueryExpression {
-                limit: self.limit,
-                filter: predicate_pushdown(self.filters.iter()),
-                ..Default::default()
-            };
-            let access = self.access.get().cloned();
-            debug!(
-                "Using predicate pushdown {:?} with access {:?}",
-                expr.filter, access
-            );
-            match get_records(
-                cache_reader,
-                &mut expr,
-                &self.cache_endpoint.table_name,
-                access,
-            ) {
-                Ok(records) => futures_util::stream::iter(transpose(
-                    cache_reader.get_schema().0.clone(),
-                    records,
-                ))
-                .boxed(),
-                Err(err) => futures_util::stream::once(futures_util::future::ready(Err(
-                    DataFusionError::External(err.into()),
-                )))
-                .boxed(),
-            }
+        let stream = match self.get_records() {
+            Ok((schema, records)) => futures_util::stream::iter(transpose(schema, records)).boxed(),
+            Err(err) => futures_util::stream::once(futures_util::future::ready(Err(
+                DataFusionError::External(err.into()),
+            )))
+            .boxed(),
         };
         Ok(Box::pin(RecordBatchStreamAdapter::new(
             self.projected_schema.clone(),
@@ -60,17 +60,17 @@ pub struct PgWireServer {
 
 struct MakeQueryHandler {
     sql_executor: SQLExecutor,
-    jwt_secret: Option<String>,
+    api_security: Option<ApiSecurity>,
 }
 
 impl MakeQueryHandler {
     pub async fn try_new(
         cache_endpoints: Vec<Arc<CacheEndpoint>>,
-        jwt_secret: Option<String>,
+        api_security: Option<ApiSecurity>,
     ) -> Result<Self, DataFusionError> {
         Ok(Self {
             sql_executor: SQLExecutor::try_new(&cache_endpoints).await?,
-            jwt_secret,
+            api_security,
         })
     }
 }
@@ -79,7 +79,7 @@ impl MakeHandler for MakeQueryHandler {
     type Handler = QueryProcessor;
 
     fn make(&self) -> Self::Handler {
-        QueryProcessor::new(self.sql_executor.clone(), self.jwt_secret.clone())
+        QueryProcessor::new(self.sql_executor.clone(), self.api_security.clone())
     }
 }
 
@@ -101,17 +101,13 @@ impl PgWireServer {
         let port = config.port.unwrap_or_else(default_sql_port);
         let server_addr = format!("{}:{}", host, port);
         let api_security = get_api_security(self.api_security.to_owned());
-        let jwt_secret = api_security.as_ref().map(|security| {
-            let ApiSecurity::Jwt(secret) = security.clone();
-            secret
-        });
         info!(
             "Starting Postgres Wire Protocol Server on {server_addr} with security: {}",
-            jwt_secret.as_ref().map_or("None", |_| "JWT")
+            api_security.as_ref().map_or("None", |_| "JWT")
         );
 
         let processor_factory = Arc::new(
-            MakeQueryHandler::try_new(cache_endpoints, jwt_secret)
+            MakeQueryHandler::try_new(cache_endpoints, api_security)
                 .await
                 .map_err(|err| std::io::Error::new(ErrorKind::Other, err))?,
         );
@@ -145,15 +141,15 @@ impl PgWireServer {
 struct QueryProcessor {
     sql_executor: Arc<SQLExecutor>,
     portal_store: Arc<MemPortalStore<Option<PlannedStatement>>>,
-    jwt_secret: Option<String>,
+    api_security: Option<ApiSecurity>,
 }
 
 impl QueryProcessor {
-    pub fn new(sql_executor: SQLExecutor, jwt_secret: Option<String>) -> Self {
+    pub fn new(sql_executor: SQLExecutor, api_security: Option<ApiSecurity>) -> Self {
         Self {
             sql_executor: Arc::new(sql_executor),
             portal_store: Arc::new(MemPortalStore::new()),
-            jwt_secret,
+            api_security,
         }
     }
 
@@ -397,7 +393,7 @@ impl StartupHandler for QueryProcessor {
         match message {
             PgWireFrontendMessage::Startup(ref startup) => {
                 pgwire::api::auth::save_startup_parameters_to_metadata(client, startup);
-                if self.jwt_secret.is_none() {
+                if self.api_security.is_none() {
                     pgwire::api::auth::finish_authentication(
                         client,
                         &DefaultServerParameterProvider::default(),
@@ -415,7 +411,7 @@ impl StartupHandler for QueryProcessor {
             PgWireFrontendMessage::PasswordMessageFamily(pwd) => {
                 let pwd = pwd.into_password()?;
                 let token = pwd.password();
-                let api_auth = Authorizer::new(self.jwt_secret.as_ref().unwrap(), None, None);
+                let api_auth = Authorizer::from(self.api_security.as_ref().unwrap());
                 let res = api_auth.validate_token(token);
                 match res {
                     Ok(claims) => {
@@ -0,0 +1,390 @@
+use std::collections::BTreeMap;
+
+use dozer_types::{
+    ordered_float::OrderedFloat,
+    rust_decimal::Decimal,
+    types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition},
+};
+
+use crate::errors::AggregateError;
+
+use super::expression::{AggregateExpression, AggregateFunction};
+
+/// Result of an `AggregateExpression`, with one row per group, ordered by the group values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregateResult {
+    /// Definitions of the `group_by` fields, followed by those of the aggregates.
+    pub fields: Vec<FieldDefinition>,
+    pub rows: Vec<Vec<Field>>,
+}
+
+/// Groups and aggregates records as specified by an `AggregateExpression`.
+///
+/// The filter of the expression is not evaluated here. It's applied when querying the records.
+#[derive(Debug)]
+pub struct Aggregator {
+    group_by: Vec<usize>,
+    aggregates: Vec<(AggregateFunction, Option<usize>)>,
+    fields: Vec<FieldDefinition>,
+    groups: BTreeMap<Vec<Field>, Vec<Accumulator>>,
+}
+
+impl Aggregator {
+    pub fn new(schema: &Schema, expression: &AggregateExpression) -> Result<Self, AggregateError> {
+        let mut group_by = vec![];
+        let mut fields = vec![];
+        for field_name in &expression.group_by {
+            let (index, definition) = schema.get_field_index(field_name)?;
+            group_by.push(index);
+            fields.push(definition.clone());
+        }
+
+        let mut aggregates = vec![];
+        for aggregate in &expression.aggregates {
+            let field = aggregate
+                .field_name
+                .as_deref()
+                .map(|field_name| schema.get_field_index(field_name))
+                .transpose()?;
+            let typ = output_type(aggregate.function, field.map(|(_, definition)| definition))?;
+            aggregates.push((aggregate.function, field.map(|(index, _)| index)));
+            fields.push(FieldDefinition::new(
+                aggregate.name.clone(),
+                typ,
+                aggregate.function != AggregateFunction::Count,
+                SourceDefinition::Dynamic,
+            ));
+        }
+
+        let mut aggregator = Self {
+            group_by,
+            aggregates,
+            fields,
+            groups: BTreeMap::new(),
+        };
+        // Without `group_by`, there's always a single row, even if no record matches.
+        if aggregator.group_by.is_empty() {
+            let accumulators = new_accumulators(&aggregator.aggregates);
+            aggregator.groups.insert(vec![], accumulators);
+        }
+        Ok(aggregator)
+    }
+
+    pub fn aggregate<'a>(
+        mut self,
+        records: impl IntoIterator<Item = &'a Record>,
+    ) -> Result<AggregateResult, AggregateError> {
+        for record in records {
+            self.add(record)?;
+        }
+        Ok(self.finish())
+    }
+
+    /// Adds a record to its group, so records can be aggregated as they are read.
+    pub fn add(&mut self, record: &Record) -> Result<(), AggregateError> {
+        let key = self
+            .group_by
+            .iter()
+            .map(|index| record.values[*index].clone())
+            .collect::<Vec<_>>();
+        let accumulators = self
+            .groups
+            .entry(key)
+            .or_insert_with(|| new_accumulators(&self.aggregates));
+        for (i, (accumulator, (_, field_index))) in
+            accumulators.iter_mut().zip(&self.aggregates).enumerate()
+        {
+            let value = field_index.map_or(&Field::Null, |index| &record.values[index]);
+            if accumulator.add(value).is_none() {
+                let name = &self.fields[self.group_by.len() + i].name;
+                return Err(AggregateError::Overflow(name.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the rows of the groups of the records added so far.
+    pub fn finish(self) -> AggregateResult {
+        let rows = self
+            .groups
+            .into_iter()
+            .map(|(mut row, accumulators)| {
+                row.extend(accumulators.into_iter().map(Accumulator::finish));
+                row
+            })
+            .collect();
+        AggregateResult {
+            fields: self.fields,
+            rows,
+        }
+    }
+}
+
+fn new_accumulators(aggregates: &[(AggregateFunction, Option<usize>)]) -> Vec<Accumulator> {
+    aggregates
+        .iter()
+        .map(|(function, field_index)| match function {
+            AggregateFunction::Count => Accumulator::Count {
+                count: 0,
+                all_records: field_index.is_none(),
+            },
+            AggregateFunction::Sum => Accumulator::Sum(Field::Null),
+            AggregateFunction::Min => Accumulator::Min(Field::Null),
+            AggregateFunction::Max => Accumulator::Max(Field::Null),
+            AggregateFunction::Avg => Accumulator::Avg {
+                sum: Field::Null,
+                count: 0,
+            },
+        })
+        .collect()
+}
+
+fn output_type(
+    function: AggregateFunction,
+    field: Option<&FieldDefinition>,
+) -> Result<FieldType, AggregateError> {
+    let Some(field) = field else {
+        return if function == AggregateFunction::Count {
+            Ok(FieldType::UInt)
+        } else {
+            Err(AggregateError::MissingField(function))
+        };
+    };
+
+    let is_numeric = matches!(
+        field.typ,
+        FieldType::UInt
+            | FieldType::U128
+            | FieldType::Int
+            | FieldType::I128
+            | FieldType::Float
+            | FieldType::Decimal
+    );
+    match function {
+        AggregateFunction::Count => Ok(FieldType::UInt),
+        AggregateFunction::Sum if is_numeric => Ok(field.typ),
+        AggregateFunction::Avg if field.typ == FieldType::Decimal => Ok(FieldType::Decimal),
+        AggregateFunction::Avg if is_numeric => Ok(FieldType::Float),
+        AggregateFunction::Min | AggregateFunction::Max
+            if !matches!(field.typ, FieldType::Json | FieldType::Point) =>
+        {
+            Ok(field.typ)
+        }
+        _ => Err(AggregateError::UnsupportedFieldType {
+            function,
+            field: field.name.clone(),
+            typ: field.typ,
+        }),
+    }
+}
+
+/// State of an aggregate within a group. `null` values are ignored, except when counting records.
+#[derive(Debug)]
+enum Accumulator {
+    Count {
+        count: u64,
+        all_records: bool,
+    },
+    Sum(Field),
+    Min(Field),
+    Max(Field),
+    /// The sum is a `Float`, or a `Decimal` for decimal fields.
+    Avg {
+        sum: Field,
+        count: u64,
+    },
+}
+
+impl Accumulator {
+    /// Returns `None` on overflow.
+    fn add(&mut self, value: &Field) -> Option<()> {
+        match self {
+            Accumulator::Count { count, all_records } => {
+                if *all_records || *value != Field::Null {
+                    *count += 1;
+                }
+            }
+            _ if *value == Field::Null => (),
+            Accumulator::Sum(sum) => *sum = checked_add(sum, value)?,
+            Accumulator::Min(min) => {
+                if *min == Field::Null || value < min {
+                    *min = value.clone();
+                }
+            }
+            Accumulator::Max(max) => {
+                if *max == Field::Null || value > max {
+                    *max = value.clone();
+                }
+            }
+            Accumulator::Avg { sum, count } => {
+                let value = match value {
+                    Field::Decimal(_) => value.clone(),
+                    _ => Field::Float(OrderedFloat(
+                        value.to_float().expect("type is checked by `output_type`"),
+                    )),
+                };
+                *sum = checked_add(sum, &value)?;
+                *count += 1;
+            }
+        }
+        Some(())
+    }
+
+    fn finish(self) -> Field {
+        match self {
+            Accumulator::Count { count, .. } => Field::UInt(count),
+            Accumulator::Sum(value) | Accumulator::Min(value) | Accumulator::Max(value) => value,
+            Accumulator::Avg { sum, count } => match sum {
+                Field::Float(sum) => Field::Float(OrderedFloat(sum.0 / count as f64)),
+                Field::Decimal(sum) => Field::Decimal(sum / Decimal::from(count)),
+                _ => Field::Null,
+            },
+        }
+    }
+}
+
+fn checked_add(lhs: &Field, rhs: &Field) -> Option<Field> {
+    Some(match (lhs, rhs) {
+        (Field::Null, value) => value.clone(),
+        (Field::UInt(lhs), Field::UInt(rhs)) => Field::UInt(lhs.checked_add(*rhs)?),
+        (Field::U128(lhs), Field::U128(rhs)) => Field::U128(lhs.checked_add(*rhs)?),
+        (Field::Int(lhs), Field::Int(rhs)) => Field::Int(lhs.checked_add(*rhs)?),
+        (Field::I128(lhs), Field::I128(rhs)) => Field::I128(lhs.checked_add(*rhs)?),
+        (Field::Float(lhs), Field::Float(rhs)) => Field::Float(OrderedFloat(lhs.0 + rhs.0)),
+        (Field::Decimal(lhs), Field::Decimal(rhs)) => Field::Decimal(lhs.checked_add(*rhs)?),
+        _ => unreachable!("type is checked by `output_type`"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::serde_json::{self, json, Value};
+
+    use crate::cache::test_utils::schema_1;
+
+    use super::*;
+
+    fn aggregate(expression: Value) -> Result<AggregateResult, AggregateError> {
+        let schema = schema_1().0;
+        let expression = serde_json::from_value::<AggregateExpression>(expression).unwrap();
+        let records = [
+            (1, Some("a"), Some(10)),
+            (2, Some("b"), Some(20)),
+            (3, Some("a"), None),
+            (4, Some("a"), Some(30)),
+        ]
+        .map(|(a, b, c)| {
+            Record::new(vec![
+                Field::Int(a),
+                b.map_or(Field::Null, |b| Field::String(b.into())),
+                c.map_or(Field::Null, Field::Int),
+            ])
+        });
+        Aggregator::new(&schema, &expression)?.aggregate(&records)
+    }
+
+    #[test]
+    fn test_aggregate_group_by() {
+        let result = aggregate(json!({
+            "$group_by": ["b"],
+            "$aggregates": {
+                "records": {"$count": null},
+                "values": {"$count": "c"},
+                "sum": {"$sum": "c"},
+                "min": {"$min": "c"},
+                "max": {"$max": "a"},
+                "avg": {"$avg": "c"}
+            }
+        }))
+        .unwrap();
+
+        let names = result
+            .fields
+            .iter()
+            .map(|field| (field.name.as_str(), field.typ))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                ("b", FieldType::String),
+                ("records", FieldType::UInt),
+                ("values", FieldType::UInt),
+                ("sum", FieldType::Int),
+                ("min", FieldType::Int),
+                ("max", FieldType::Int),
+                ("avg", FieldType::Float),
+            ]
+        );
+        assert_eq!(
+            result.rows,
+            vec![
+                vec![
+                    Field::String("a".into()),
+                    Field::UInt(3),
+                    Field::UInt(2),
+                    Field::Int(40),
+                    Field::Int(10),
+                    Field::Int(4),
+                    Field::Float(OrderedFloat(20.0)),
+                ],
+                vec![
+                    Field::String("b".into()),
+                    Field::UInt(1),
+                    Field::UInt(1),
+                    Field::Int(20),
+                    Field::Int(20),
+                    Field::Int(2),
+                    Field::Float(OrderedFloat(20.0)),
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_aggregate_without_group_by() {
+        let result = aggregate(json!({"$aggregates": {"sum": {"$sum": "a"}}})).unwrap();
+        assert_eq!(result.rows, vec![vec![Field::Int(10)]]);
+
+        // A single row even if no record matches.
+        let schema = schema_1().0;
+        let expression = serde_json::from_value::<AggregateExpression>(
+            json!({"$aggregates": {"count": {"$count": null}, "max": {"$max": "c"}}}),
+        )
+        .unwrap();
+        let result = Aggregator::new(&schema, &expression)
+            .unwrap()
+            .aggregate(std::iter::empty())
+            .unwrap();
+        assert_eq!(result.rows, vec![vec![Field::UInt(0), Field::Null]]);
+    }
+
+    #[test]
+    fn test_aggregate_errors() {
+        assert!(matches!(
+            aggregate(json!({"$aggregates": {"sum": {"$sum": "b"}}})),
+            Err(AggregateError::UnsupportedFieldType { .. })
+        ));
+        assert!(matches!(
+            aggregate(json!({"$aggregates": {"avg": {"$avg": null}}})),
+            Err(AggregateError::MissingField(AggregateFunction::Avg))
+        ));
+        assert!(matches!(
+            aggregate(json!({"$group_by": ["d"]})),
+            Err(AggregateError::Type(_))
+        ));
+
+        let schema = schema_1().0;
+        let expression = serde_json::from_value::<AggregateExpression>(
+            json!({"$aggregates": {"sum": {"$sum": "c"}}}),
+        )
+        .unwrap();
+        let records = [Field::Int(1), Field::Int(i64::MAX)]
+            .map(|c| Record::new(vec![Field::Int(0), Field::Null, c]));
+        assert!(matches!(
+            Aggregator::new(&schema, &expression)
+                .unwrap()
+                .aggregate(&records),
+            Err(AggregateError::Overflow(name)) if name == "sum"
+        ));
+    }
+}
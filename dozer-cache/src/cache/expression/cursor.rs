@@ -0,0 +1,64 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+/// Position of the last record of a query page, from which the next page continues.
+///
+/// It's passed to clients as an opaque token and only makes sense with the query that produced it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryCursor {
+    /// The secondary index key of the last record, if the records were read from a single index scan.
+    key: Option<Vec<u8>>,
+    /// The operation id of the last record.
+    operation_id: u64,
+}
+
+const NO_KEY: u8 = 0;
+const WITH_KEY: u8 = 1;
+
+impl QueryCursor {
+    pub fn new(key: Option<Vec<u8>>, operation_id: u64) -> Self {
+        Self { key, operation_id }
+    }
+
+    pub fn key(&self) -> Option<&[u8]> {
+        self.key.as_deref()
+    }
+
+    pub fn operation_id(&self) -> u64 {
+        self.operation_id
+    }
+
+    /// Drops the index key, so the next page is found by the operation id only.
+    pub fn without_key(self) -> Self {
+        Self {
+            key: None,
+            operation_id: self.operation_id,
+        }
+    }
+
+    pub fn to_token(&self) -> String {
+        let mut bytes = Vec::with_capacity(9 + self.key.as_ref().map_or(0, Vec::len));
+        bytes.push(if self.key.is_some() { WITH_KEY } else { NO_KEY });
+        bytes.extend_from_slice(&self.operation_id.to_be_bytes());
+        if let Some(key) = &self.key {
+            bytes.extend_from_slice(key);
+        }
+        URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    /// Returns `None` if `token` is not generated by `to_token`.
+    pub fn from_token(token: &str) -> Option<Self> {
+        let bytes = URL_SAFE_NO_PAD.decode(token).ok()?;
+        let (&flag, rest) = bytes.split_first()?;
+        if rest.len() < 8 {
+            return None;
+        }
+        let (operation_id, key) = rest.split_at(8);
+        let operation_id = u64::from_be_bytes(operation_id.try_into().ok()?);
+        let key = match flag {
+            NO_KEY if key.is_empty() => None,
+            WITH_KEY => Some(key.to_vec()),
+            _ => return None,
+        };
+        Some(Self { key, operation_id })
+    }
+}
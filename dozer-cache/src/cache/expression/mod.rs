@@ -1,15 +1,19 @@
 use dozer_types::serde::{Deserialize, Serialize};
 use dozer_types::serde_json::Value;
+mod cursor;
 mod query_helper;
 mod query_serde;
+pub use cursor::QueryCursor;
 use dozer_types::constants::DEFAULT_DEFAULT_MAX_NUM_RECORDS;
 #[cfg(test)]
 mod tests;
 
-#[derive(Clone, Debug, Copy, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Skip {
     Skip(usize),
     After(u64),
+    /// Continues from the cursor returned with the previous page of the same query.
+    Cursor(QueryCursor),
 }
 
 impl Default for Skip {
@@ -73,6 +77,8 @@ pub enum FilterExpression {
     // a = 1, a containts "s", a > 4
     Simple(String, Operator, Value),
     And(Vec<FilterExpression>),
+    Or(Vec<FilterExpression>),
+    Not(Box<FilterExpression>),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -88,6 +94,14 @@ pub enum Operator {
     GT,
     #[serde(rename = "$gte")]
     GTE,
+    #[serde(rename = "$ne")]
+    NE,
+    /// The value is an array, any element of which the field must be equal to.
+    #[serde(rename = "$in")]
+    In,
+    /// The value is a boolean, `true` for `IS NULL` and `false` for `IS NOT NULL`.
+    #[serde(rename = "$null")]
+    IsNull,
     #[serde(rename = "$contains")]
     Contains,
     #[serde(rename = "$matches_any")]
@@ -97,16 +111,29 @@ pub enum Operator {
 }
 
 impl Operator {
+    /// `NE`, `In` and `IsNull` are rewritten into the other operators before planning.
     pub fn supported_by_sorted_inverted(&self) -> bool {
         match self {
             Operator::LT | Operator::LTE | Operator::EQ | Operator::GT | Operator::GTE => true,
-            Operator::Contains | Operator::MatchesAny | Operator::MatchesAll => false,
+            Operator::NE
+            | Operator::In
+            | Operator::IsNull
+            | Operator::Contains
+            | Operator::MatchesAny
+            | Operator::MatchesAll => false,
         }
     }
 
     pub fn supported_by_full_text(&self) -> bool {
         match self {
-            Operator::LT | Operator::LTE | Operator::EQ | Operator::GT | Operator::GTE => false,
+            Operator::LT
+            | Operator::LTE
+            | Operator::EQ
+            | Operator::GT
+            | Operator::GTE
+            | Operator::NE
+            | Operator::In
+            | Operator::IsNull => false,
             Operator::Contains | Operator::MatchesAny | Operator::MatchesAll => true,
         }
     }
@@ -114,9 +141,13 @@ impl Operator {
     pub fn is_range_operator(&self) -> bool {
         match self {
             Operator::LT | Operator::LTE | Operator::GT | Operator::GTE => true,
-            Operator::EQ | Operator::Contains | Operator::MatchesAny | Operator::MatchesAll => {
-                false
-            }
+            Operator::EQ
+            | Operator::NE
+            | Operator::In
+            | Operator::IsNull
+            | Operator::Contains
+            | Operator::MatchesAny
+            | Operator::MatchesAll => false,
         }
     }
 }
@@ -149,5 +180,37 @@ pub enum SortDirection {
     Descending,
 }
 
+/// Groups the records satisfying `filter` by the `group_by` fields and aggregates each group.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct AggregateExpression {
+    pub filter: Option<FilterExpression>,
+    pub group_by: Vec<String>,
+    pub aggregates: Vec<Aggregate>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Aggregate {
+    /// Name of the aggregated value in the result.
+    pub name: String,
+    pub function: AggregateFunction,
+    /// The aggregated field. `$count` without a field counts records.
+    pub field_name: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub enum AggregateFunction {
+    #[serde(rename = "$count")]
+    Count,
+    #[serde(rename = "$sum")]
+    Sum,
+    #[serde(rename = "$min")]
+    Min,
+    #[serde(rename = "$max")]
+    Max,
+    #[serde(rename = "$avg")]
+    Avg,
+}
+
 #[derive(Debug, Clone)]
 pub struct SQLQuery(pub String);
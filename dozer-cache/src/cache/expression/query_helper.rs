@@ -5,7 +5,7 @@ use dozer_types::serde::{
 };
 use dozer_types::serde_json::Value;
 
-use super::super::expression::Operator;
+use super::super::expression::{AggregateFunction, Operator};
 
 pub struct OperatorAndValue {
     pub operator: Operator,
@@ -236,3 +236,64 @@ impl<'a> Serialize for OperatorAndValueBorrow<'a> {
         }
     }
 }
+
+pub struct FunctionAndField {
+    pub function: AggregateFunction,
+    pub field_name: Option<String>,
+}
+
+impl<'de> Deserialize<'de> for FunctionAndField {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct FunctionAndFieldVisitor {}
+        impl<'de> Visitor<'de> for FunctionAndFieldVisitor {
+            type Value = FunctionAndField;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("map from aggregate function to field name")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                if let Some((function, field_name)) = map.next_entry()? {
+                    if map
+                        .next_entry::<AggregateFunction, Option<String>>()?
+                        .is_some()
+                    {
+                        Err(de::Error::custom(
+                            "More than one function passed in aggregate",
+                        ))
+                    } else {
+                        Ok(FunctionAndField {
+                            function,
+                            field_name,
+                        })
+                    }
+                } else {
+                    Err(de::Error::custom("empty object passed as aggregate"))
+                }
+            }
+        }
+        deserializer.deserialize_map(FunctionAndFieldVisitor {})
+    }
+}
+
+pub struct FunctionAndFieldBorrow<'a> {
+    pub function: &'a AggregateFunction,
+    pub field_name: &'a Option<String>,
+}
+
+impl<'a> Serialize for FunctionAndFieldBorrow<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: dozer_types::serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(self.function, self.field_name)?;
+        map.end()
+    }
+}
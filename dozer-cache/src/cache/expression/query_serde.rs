@@ -5,8 +5,10 @@ use dozer_types::serde::{
 
 use super::{
     super::expression::{FilterExpression, Skip, SortOption},
-    query_helper::{OperatorAndValue, OperatorAndValueBorrow},
-    QueryExpression, SQLQuery, SortOptions,
+    query_helper::{
+        FunctionAndField, FunctionAndFieldBorrow, OperatorAndValue, OperatorAndValueBorrow,
+    },
+    Aggregate, AggregateExpression, QueryCursor, QueryExpression, SQLQuery, SortOptions,
 };
 
 impl<'de> Deserialize<'de> for FilterExpression {
@@ -29,6 +31,10 @@ impl<'de> Deserialize<'de> for FilterExpression {
                 while let Some(key) = map.next_key::<String>()? {
                     if key == "$and" {
                         expressions.push(FilterExpression::And(map.next_value()?));
+                    } else if key == "$or" {
+                        expressions.push(FilterExpression::Or(map.next_value()?));
+                    } else if key == "$not" {
+                        expressions.push(FilterExpression::Not(Box::new(map.next_value()?)));
                     } else {
                         let operator_and_value = map.next_value::<OperatorAndValue>()?;
                         expressions.push(FilterExpression::Simple(
@@ -65,6 +71,16 @@ impl Serialize for FilterExpression {
                 state.serialize_entry("$and", &expressions)?;
                 state.end()
             }
+            FilterExpression::Or(expressions) => {
+                let mut state = serializer.serialize_map(Some(1))?;
+                state.serialize_entry("$or", &expressions)?;
+                state.end()
+            }
+            FilterExpression::Not(expression) => {
+                let mut state = serializer.serialize_map(Some(1))?;
+                state.serialize_entry("$not", &expression)?;
+                state.end()
+            }
         }
     }
 }
@@ -146,16 +162,31 @@ impl<'de> Deserialize<'de> for QueryExpression {
                         }
                         "$skip" => {
                             if skip.is_some() {
-                                return Err(Error::custom("$skip cannot be used with $after"));
+                                return Err(Error::custom(
+                                    "$skip cannot be used with $after or $cursor",
+                                ));
                             }
                             skip = Some(Skip::Skip(map.next_value()?));
                         }
                         "$after" => {
                             if skip.is_some() {
-                                return Err(Error::custom("$after cannot be used with $skip"));
+                                return Err(Error::custom(
+                                    "$after cannot be used with $skip or $cursor",
+                                ));
                             }
                             skip = Some(Skip::After(map.next_value()?));
                         }
+                        "$cursor" => {
+                            if skip.is_some() {
+                                return Err(Error::custom(
+                                    "$cursor cannot be used with $skip or $after",
+                                ));
+                            }
+                            let token = map.next_value::<String>()?;
+                            let cursor = QueryCursor::from_token(&token)
+                                .ok_or_else(|| Error::custom("invalid $cursor"))?;
+                            skip = Some(Skip::Cursor(cursor));
+                        }
                         _ => {}
                     }
                 }
@@ -186,15 +217,139 @@ impl Serialize for QueryExpression {
         if let Some(limit) = self.limit {
             state.serialize_entry("$limit", &limit)?;
         }
-        match self.skip {
+        match &self.skip {
             Skip::Skip(skip) => {
-                if skip > 0 {
-                    state.serialize_entry("$skip", &skip)?;
+                if *skip > 0 {
+                    state.serialize_entry("$skip", skip)?;
                 }
             }
             Skip::After(after) => {
-                state.serialize_entry("$after", &after)?;
+                state.serialize_entry("$after", after)?;
+            }
+            Skip::Cursor(cursor) => {
+                state.serialize_entry("$cursor", &cursor.to_token())?;
+            }
+        }
+        state.end()
+    }
+}
+
+/// A wrapper of `Vec<Aggregate>`, deserialized from a map from the aggregate name to its function and field.
+struct Aggregates(Vec<Aggregate>);
+
+impl<'de> Deserialize<'de> for Aggregates {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct AggregatesVisitor {}
+        impl<'de> Visitor<'de> for AggregatesVisitor {
+            type Value = Aggregates;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("map from aggregate name to function and field")
             }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut aggregates = vec![];
+                while let Some((name, function_and_field)) =
+                    map.next_entry::<String, FunctionAndField>()?
+                {
+                    aggregates.push(Aggregate {
+                        name,
+                        function: function_and_field.function,
+                        field_name: function_and_field.field_name,
+                    });
+                }
+                Ok(Aggregates(aggregates))
+            }
+        }
+        deserializer.deserialize_map(AggregatesVisitor {})
+    }
+}
+
+struct AggregatesBorrow<'a>(&'a [Aggregate]);
+
+impl<'a> Serialize for AggregatesBorrow<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_map(Some(self.0.len()))?;
+        for aggregate in &self.0 {
+            state.serialize_entry(
+                &aggregate.name,
+                &FunctionAndFieldBorrow {
+                    function: &aggregate.function,
+                    field_name: &aggregate.field_name,
+                },
+            )?;
+        }
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for AggregateExpression {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct AggregateExpressionVisitor {}
+        impl<'de> Visitor<'de> for AggregateExpressionVisitor {
+            type Value = AggregateExpression;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("map of dozer aggregate options")
+            }
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut filter = None;
+                let mut group_by = None;
+                let mut aggregates = None;
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "$filter" => {
+                            filter = Some(map.next_value()?);
+                        }
+                        "$group_by" => {
+                            group_by = Some(map.next_value()?);
+                        }
+                        "$aggregates" => {
+                            aggregates = Some(map.next_value::<Aggregates>()?.0);
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(AggregateExpression {
+                    filter,
+                    group_by: group_by.unwrap_or_default(),
+                    aggregates: aggregates.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_map(AggregateExpressionVisitor {})
+    }
+}
+
+impl Serialize for AggregateExpression {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_map(Some(3))?;
+        if let Some(filter) = &self.filter {
+            state.serialize_entry("$filter", filter)?;
+        }
+        if !self.group_by.is_empty() {
+            state.serialize_entry("$group_by", &self.group_by)?;
+        }
+        if !self.aggregates.is_empty() {
+            state.serialize_entry("$aggregates", &AggregatesBorrow(&self.aggregates))?;
         }
         state.end()
     }
@@ -1,7 +1,9 @@
 use crate::cache::expression::FilterExpression;
 use crate::cache::expression::Operator;
+use crate::cache::expression::QueryCursor;
 use crate::cache::expression::Skip;
 use crate::cache::expression::SortOptions;
+use crate::cache::expression::{Aggregate, AggregateExpression, AggregateFunction};
 use crate::cache::expression::{
     QueryExpression,
     SortDirection::{Ascending, Descending},
@@ -19,6 +21,9 @@ fn test_operators() {
         (Operator::LT, "$lt"),
         (Operator::LTE, "$lte"),
         (Operator::EQ, "$eq"),
+        (Operator::NE, "$ne"),
+        (Operator::In, "$in"),
+        (Operator::IsNull, "$null"),
         (Operator::Contains, "$contains"),
         (Operator::MatchesAny, "$matches_any"),
         (Operator::MatchesAll, "$matches_all"),
@@ -129,6 +134,32 @@ fn test_filter_query_deserialize_complex() {
     test_deserialize_filter_error(json!({"and": [{"a":  {"$lt": 1}}]}));
 }
 
+#[test]
+fn test_filter_query_deserialize_or_not() {
+    test_deserialize_filter(
+        json!({"$or": [{"a": 1}, {"b": {"$null": true}}]}),
+        FilterExpression::Or(vec![
+            FilterExpression::Simple("a".to_string(), Operator::EQ, Value::from(1)),
+            FilterExpression::Simple("b".to_string(), Operator::IsNull, Value::from(true)),
+        ]),
+    );
+    test_deserialize_filter(
+        json!({"$not": {"a": {"$in": [1, 2]}}, "b": {"$ne": "x"}}),
+        FilterExpression::And(vec![
+            FilterExpression::Not(Box::new(FilterExpression::Simple(
+                "a".to_string(),
+                Operator::In,
+                json!([1, 2]),
+            ))),
+            FilterExpression::Simple("b".to_string(), Operator::NE, Value::from("x")),
+        ]),
+    );
+
+    test_deserialize_filter_error(json!({"$or": {}}));
+    test_deserialize_filter_error(json!({"$not": []}));
+    test_deserialize_filter_error(json!({"$or": [{"a":  {"ne": 1}}]}));
+}
+
 #[test]
 fn test_sort_options_query_deserialize() {
     test_deserialize_sort_options(json!({}), vec![]);
@@ -200,6 +231,11 @@ fn test_query_expression_deserialize() {
         json!({ "$after": 30 }),
         QueryExpression::new(None, vec![], None, Skip::After(30)),
     );
+    let cursor = QueryCursor::new(Some(vec![1, 2, 3]), 30);
+    test_deserialize_query(
+        json!({ "$cursor": cursor.to_token() }),
+        QueryExpression::new(None, vec![], None, Skip::Cursor(cursor)),
+    );
     test_deserialize_query(
         json!({"$filter": {"a":  {"$lt": 1}, "b":  {"$gte": 3}, "c": 3}}),
         QueryExpression::new(
@@ -215,9 +251,58 @@ fn test_query_expression_deserialize() {
     );
 }
 
+#[test]
+fn test_aggregate_expression_deserialize() {
+    assert_eq!(
+        serde_json::from_value::<AggregateExpression>(json!({})).unwrap(),
+        AggregateExpression::default()
+    );
+    assert_eq!(
+        serde_json::from_value::<AggregateExpression>(json!({
+            "$filter": {"a": 1},
+            "$group_by": ["b"],
+            "$aggregates": {"n": {"$count": null}, "total": {"$sum": "c"}}
+        }))
+        .unwrap(),
+        AggregateExpression {
+            filter: Some(FilterExpression::Simple(
+                "a".to_string(),
+                Operator::EQ,
+                Value::from(1)
+            )),
+            group_by: vec!["b".to_string()],
+            aggregates: vec![
+                Aggregate {
+                    name: "n".to_string(),
+                    function: AggregateFunction::Count,
+                    field_name: None,
+                },
+                Aggregate {
+                    name: "total".to_string(),
+                    function: AggregateFunction::Sum,
+                    field_name: Some("c".to_string()),
+                },
+            ],
+        }
+    );
+
+    for error in [
+        json!({"$group_by": "b"}),
+        json!({"$aggregates": {"n": {}}}),
+        json!({"$aggregates": {"n": {"$median": "c"}}}),
+        json!({"$aggregates": {"n": {"$min": "c", "$max": "c"}}}),
+    ] {
+        assert!(serde_json::from_value::<AggregateExpression>(error).is_err());
+    }
+}
+
 #[test]
 fn test_query_expression_deserialize_error() {
     test_deserialize_query_error(json!({ "$skip": 20, "$after": 30 }));
+    let token = QueryCursor::new(None, 30).to_token();
+    test_deserialize_query_error(json!({ "$skip": 20, "$cursor": token }));
+    test_deserialize_query_error(json!({ "$cursor": "not a cursor" }));
+    test_deserialize_query_error(json!({ "$cursor": 30 }));
 }
 
 fn test_deserialize_query(a: Value, b: QueryExpression) {
@@ -1,10 +1,12 @@
 use crate::cache::expression::FilterExpression;
 use crate::cache::expression::Operator;
+use crate::cache::expression::QueryCursor;
 use crate::cache::expression::QueryExpression;
 use crate::cache::expression::Skip;
 use crate::cache::expression::SortDirection::{Ascending, Descending};
 use crate::cache::expression::SortOption;
 use crate::cache::expression::SortOptions;
+use crate::cache::expression::{Aggregate, AggregateExpression, AggregateFunction};
 use dozer_types::serde_json;
 use dozer_types::serde_json::json;
 use dozer_types::serde_json::Value;
@@ -80,6 +82,25 @@ fn test_serialize_filter_complex() {
     );
 }
 
+#[test]
+fn test_serialize_filter_or_not() {
+    test_serialize_filter(
+        json!({"$or": [{"a": {"$in": [1, 2]}}, {"b": {"$null": false}}]}),
+        FilterExpression::Or(vec![
+            FilterExpression::Simple("a".to_string(), Operator::In, json!([1, 2])),
+            FilterExpression::Simple("b".to_string(), Operator::IsNull, Value::from(false)),
+        ]),
+    );
+    test_serialize_filter(
+        json!({"$not": {"a": {"$ne": 1}}}),
+        FilterExpression::Not(Box::new(FilterExpression::Simple(
+            "a".to_string(),
+            Operator::NE,
+            Value::from(1),
+        ))),
+    );
+}
+
 #[test]
 fn test_serialize_sort_options() {
     test_serialize_sort_options_impl(vec![], json!({}));
@@ -124,6 +145,25 @@ fn test_serialize_skip() {
     test_serialize_skip_impl(Skip::Skip(0), json!({}));
     test_serialize_skip_impl(Skip::Skip(1), json!({"$skip": 1}));
     test_serialize_skip_impl(Skip::After(10), json!({"$after": 10}));
+    let cursor = QueryCursor::new(Some(b"key".to_vec()), 10);
+    test_serialize_skip_impl(
+        Skip::Cursor(cursor.clone()),
+        json!({"$cursor": cursor.to_token()}),
+    );
+}
+
+#[test]
+fn test_cursor_token_round_trip() {
+    for cursor in [
+        QueryCursor::new(None, 0),
+        QueryCursor::new(None, u64::MAX),
+        QueryCursor::new(Some(vec![]), 1),
+        QueryCursor::new(Some(b"key".to_vec()), 42),
+    ] {
+        assert_eq!(QueryCursor::from_token(&cursor.to_token()), Some(cursor));
+    }
+    assert_eq!(QueryCursor::from_token(""), None);
+    assert_eq!(QueryCursor::from_token("AAAA"), None);
 }
 
 fn test_serialize_skip_impl(skip: Skip, json: Value) {
@@ -219,3 +259,24 @@ fn test_serialize_query_expression() {
 fn test_serialize_query_expression_impl(query: QueryExpression, json: Value) {
     assert_eq!(serde_json::to_value(query).unwrap(), json);
 }
+
+#[test]
+fn test_serialize_aggregate_expression() {
+    assert_eq!(
+        serde_json::to_value(AggregateExpression::default()).unwrap(),
+        json!({})
+    );
+    assert_eq!(
+        serde_json::to_value(AggregateExpression {
+            filter: None,
+            group_by: vec!["b".to_string()],
+            aggregates: vec![Aggregate {
+                name: "avg".to_string(),
+                function: AggregateFunction::Avg,
+                field_name: Some("c".to_string()),
+            }],
+        })
+        .unwrap(),
+        json!({"$group_by": ["b"], "$aggregates": {"avg": {"$avg": "c"}}})
+    );
+}
@@ -1,3 +1,5 @@
+use std::ops::Bound;
+
 use dozer_storage::{
     errors::StorageError,
     lmdb::{RoCursor, RwTransaction, Transaction},
@@ -194,6 +196,22 @@ impl OperationLog {
         }
     }
 
+    /// Like `present_operation_ids`, but only the ones greater than `after`.
+    pub fn present_operation_ids_after<'txn, T: Transaction>(
+        &self,
+        txn: &'txn T,
+        schema_is_append_only: bool,
+        after: u64,
+    ) -> Result<KeyIterator<'txn, RoCursor<'txn>, u64>, StorageError> {
+        if schema_is_append_only {
+            self.operation_id_to_operation
+                .key_range(txn, Bound::Excluded(&after), true)
+        } else {
+            self.present_operation_ids
+                .range(txn, Bound::Excluded(&after), true)
+        }
+    }
+
     pub fn contains_operation_id<T: Transaction>(
         &self,
         txn: &T,
@@ -11,7 +11,9 @@ use super::{
     indexing::{secondary_environment_name, IndexingThreadPool},
 };
 use crate::cache::expression::QueryExpression;
-use crate::cache::{CacheRecord, CacheWriteOptions, CommitState, RecordMeta, UpsertResult};
+use crate::cache::{
+    CacheRecord, CacheWriteOptions, CommitState, QueryPage, RecordMeta, UpsertResult,
+};
 use crate::errors::CacheError;
 
 pub mod dump_restore;
@@ -139,6 +141,18 @@ impl<C: LmdbCache> RoCache for C {
         LmdbQueryHandler::new(self, query).query()
     }
 
+    fn query_page(&self, query: &QueryExpression) -> Result<QueryPage, CacheError> {
+        LmdbQueryHandler::new(self, query).query_page()
+    }
+
+    fn query_for_each(
+        &self,
+        query: &QueryExpression,
+        f: &mut dyn FnMut(CacheRecord) -> Result<(), CacheError>,
+    ) -> Result<(), CacheError> {
+        LmdbQueryHandler::new(self, query).for_each(f)
+    }
+
     fn get_schema(&self) -> &SchemaWithIndex {
         self.main_env().schema()
     }
@@ -1,19 +1,21 @@
 use super::intersection::intersection;
-use crate::cache::expression::Skip;
-use crate::cache::lmdb::cache::main_environment::MainEnvironment;
-use crate::cache::lmdb::cache::query::secondary::build_index_scan;
+use crate::cache::expression::{QueryCursor, Skip};
+use crate::cache::lmdb::cache::main_environment::{MainEnvironment, Operation};
+use crate::cache::lmdb::cache::query::secondary::{build_index_scan, get_index_scan_key};
+use crate::cache::lmdb::cache::secondary_environment::SecondaryEnvironment;
 use crate::cache::lmdb::cache::LmdbCache;
-use crate::cache::CacheRecord;
 use crate::cache::{
     expression::QueryExpression,
     plan::{IndexScan, Plan, QueryPlanner},
 };
+use crate::cache::{CacheRecord, QueryPage};
 use crate::errors::{CacheError, PlanError};
 use dozer_storage::errors::StorageError;
 use dozer_storage::lmdb::{RoTransaction, Transaction};
 use dozer_storage::LmdbEnvironment;
 use dozer_types::borrow::IntoOwned;
 use itertools::Either;
+use roaring::RoaringTreemap;
 
 pub struct LmdbQueryHandler<'a, C: LmdbCache> {
     cache: &'a C,
@@ -28,8 +30,13 @@ impl<'a, C: LmdbCache> LmdbQueryHandler<'a, C> {
     pub fn count(&self) -> Result<usize, CacheError> {
         match self.plan()? {
             Plan::IndexScans(index_scans) => {
+                let after = self.cursor_position(&index_scans)?;
                 let secondary_txns = self.create_secondary_txns(&index_scans)?;
-                let ids = self.combine_secondary_queries(&index_scans, &secondary_txns)?;
+                let ids = self.combine_secondary_queries(&index_scans, &secondary_txns, after)?;
+                self.count_secondary_queries(ids)
+            }
+            Plan::Union(index_scans) => {
+                let ids = self.union_secondary_queries(&index_scans)?;
                 self.count_secondary_queries(ids)
             }
             Plan::SeqScan(_) => Ok(match self.query.skip {
@@ -39,31 +46,97 @@ impl<'a, C: LmdbCache> LmdbQueryHandler<'a, C> {
                     .count()?
                     .saturating_sub(skip)
                     .min(self.query.limit.unwrap_or(usize::MAX)),
-                Skip::After(_) => self.all_ids(&self.cache.main_env().begin_txn()?)?.count(),
+                Skip::After(_) | Skip::Cursor(_) => {
+                    self.all_ids(&self.cache.main_env().begin_txn()?)?.count()
+                }
             }),
             Plan::ReturnEmpty => Ok(0),
         }
     }
 
     pub fn query(&self) -> Result<Vec<CacheRecord>, CacheError> {
-        match self.plan()? {
+        self.query_page().map(|page| page.records)
+    }
+
+    pub fn query_page(&self) -> Result<QueryPage, CacheError> {
+        // The index scan is kept only if it's the only one, so the next page can seek in it.
+        let ((records, last_operation_id), index_scan) = match self.plan()? {
             Plan::IndexScans(index_scans) => {
+                let after = self.cursor_position(&index_scans)?;
                 let secondary_txns = self.create_secondary_txns(&index_scans)?;
                 let main_txn = self.cache.main_env().begin_txn()?;
-                #[allow(clippy::let_and_return)] // Must do let binding unless won't compile
                 let result = self.collect_records(
                     &main_txn,
-                    self.combine_secondary_queries(&index_scans, &secondary_txns)?,
-                );
-                result
+                    self.combine_secondary_queries(&index_scans, &secondary_txns, after)?,
+                )?;
+                let index_scan = if index_scans.len() == 1 {
+                    index_scans.into_iter().next()
+                } else {
+                    None
+                };
+                (result, index_scan)
+            }
+            Plan::Union(index_scans) => {
+                let ids = self.union_secondary_queries(&index_scans)?;
+                let main_txn = self.cache.main_env().begin_txn()?;
+                (self.collect_records(&main_txn, ids)?, None)
             }
             Plan::SeqScan(_seq_scan) => {
                 let main_txn = self.cache.main_env().begin_txn()?;
-                #[allow(clippy::let_and_return)] // Must do let binding unless won't compile
-                let result = self.collect_records(&main_txn, self.all_ids(&main_txn)?);
-                result
+                let result = self.collect_records(&main_txn, self.all_ids(&main_txn)?)?;
+                (result, None)
+            }
+            Plan::ReturnEmpty => ((vec![], None), None),
+        };
+
+        // Only a full page has a next page.
+        let cursor = match (self.query.limit, last_operation_id, records.last()) {
+            (Some(limit), Some(operation_id), Some(last)) if records.len() == limit => {
+                let key = index_scan
+                    .map(|index_scan| {
+                        get_index_scan_key(
+                            self.cache
+                                .secondary_env(index_scan.index_id)
+                                .index_definition(),
+                            &index_scan.kind,
+                            &last.record.values,
+                        )
+                    })
+                    .transpose()?;
+                Some(QueryCursor::new(key, operation_id))
             }
-            Plan::ReturnEmpty => Ok(vec![]),
+            _ => None,
+        };
+        Ok(QueryPage { records, cursor })
+    }
+
+    /// Calls `f` with every record of the query, without collecting them.
+    pub fn for_each(
+        &self,
+        mut f: impl FnMut(CacheRecord) -> Result<(), CacheError>,
+    ) -> Result<(), CacheError> {
+        let mut f = |_: u64, record| f(record);
+        match self.plan()? {
+            Plan::IndexScans(index_scans) => {
+                let after = self.cursor_position(&index_scans)?;
+                let secondary_txns = self.create_secondary_txns(&index_scans)?;
+                let main_txn = self.cache.main_env().begin_txn()?;
+                self.visit_records(
+                    &main_txn,
+                    self.combine_secondary_queries(&index_scans, &secondary_txns, after)?,
+                    &mut f,
+                )
+            }
+            Plan::Union(index_scans) => {
+                let ids = self.union_secondary_queries(&index_scans)?;
+                let main_txn = self.cache.main_env().begin_txn()?;
+                self.visit_records(&main_txn, ids, &mut f)
+            }
+            Plan::SeqScan(_seq_scan) => {
+                let main_txn = self.cache.main_env().begin_txn()?;
+                self.visit_records(&main_txn, self.all_ids(&main_txn)?, &mut f)
+            }
+            Plan::ReturnEmpty => Ok(()),
         }
     }
 
@@ -83,17 +156,28 @@ impl<'a, C: LmdbCache> LmdbQueryHandler<'a, C> {
         main_txn: &'txn T,
     ) -> Result<impl Iterator<Item = Result<u64, CacheError>> + 'txn, CacheError> {
         let schema_is_append_only = self.cache.main_env().schema().0.is_append_only();
-        let all_ids = self
-            .cache
-            .main_env()
-            .operation_log()
-            .present_operation_ids(main_txn, schema_is_append_only)?
-            .map(|result| {
-                result
-                    .map(|id| id.into_owned())
-                    .map_err(CacheError::Storage)
-            });
-        Ok(skip(all_ids, self.query.skip).take(self.query.limit.unwrap_or(usize::MAX)))
+        let operation_log = self.cache.main_env().operation_log();
+        // Operation ids are ascending, so a cursor is sought directly.
+        let (all_ids, remaining_skip) = match &self.query.skip {
+            Skip::Cursor(cursor) => (
+                operation_log.present_operation_ids_after(
+                    main_txn,
+                    schema_is_append_only,
+                    cursor.operation_id(),
+                )?,
+                Skip::default(),
+            ),
+            other => (
+                operation_log.present_operation_ids(main_txn, schema_is_append_only)?,
+                other.clone(),
+            ),
+        };
+        let all_ids = all_ids.map(|result| {
+            result
+                .map(|id| id.into_owned())
+                .map_err(CacheError::Storage)
+        });
+        Ok(skip(all_ids, remaining_skip).take(self.query.limit.unwrap_or(usize::MAX)))
     }
 
     fn create_secondary_txns(
@@ -106,21 +190,121 @@ impl<'a, C: LmdbCache> LmdbQueryHandler<'a, C> {
             .collect()
     }
 
+    /// The `(index key, operation id)` pair a single index scan resumes after, if the query has a cursor.
+    ///
+    /// The index key is computed from the record of the cursor if the cursor doesn't carry it.
+    /// Inserted records stay in the operation log, so this works even if the record has been deleted.
+    fn cursor_position(
+        &self,
+        index_scans: &[IndexScan],
+    ) -> Result<Option<(Vec<u8>, u64)>, CacheError> {
+        let (Skip::Cursor(cursor), [index_scan]) = (&self.query.skip, index_scans) else {
+            return Ok(None);
+        };
+        let operation_id = cursor.operation_id();
+        if let Some(key) = cursor.key() {
+            return Ok(Some((key.to_vec(), operation_id)));
+        }
+
+        let main_txn = self.cache.main_env().begin_txn()?;
+        let Some(Operation::Insert { record, .. }) = self
+            .cache
+            .main_env()
+            .operation_log()
+            .get_operation(&main_txn, operation_id)?
+        else {
+            return Err(CacheError::InvalidCursor);
+        };
+        let key = get_index_scan_key(
+            self.cache
+                .secondary_env(index_scan.index_id)
+                .index_definition(),
+            &index_scan.kind,
+            &record.values,
+        )?;
+        Ok(Some((key, operation_id)))
+    }
+
     fn combine_secondary_queries<'txn, T: Transaction>(
         &self,
         index_scans: &[IndexScan],
         secondary_txns: &'txn [T],
+        after: Option<(Vec<u8>, u64)>,
+    ) -> Result<impl Iterator<Item = Result<u64, CacheError>> + 'txn, CacheError> {
+        let limit = self.query.limit.unwrap_or(usize::MAX);
+        let paginated = self.query.limit.is_some() || matches!(self.query.skip, Skip::Cursor(_));
+        if index_scans.len() > 1 && paginated {
+            // Intersections don't follow the order of any index, so the pages of an intersection are
+            // collected in ascending operation ids like unions, and a cursor seeks the first id after it.
+            let mut ids = RoaringTreemap::new();
+            for id in self.intersect_secondary_queries(index_scans, secondary_txns, None)? {
+                ids.insert(id?);
+            }
+            let remaining_skip = match &self.query.skip {
+                Skip::Cursor(cursor) => {
+                    ids.remove_range(..=cursor.operation_id());
+                    Skip::default()
+                }
+                other => other.clone(),
+            };
+            return Ok(Either::Left(
+                skip(ids.into_iter().map(Ok), remaining_skip).take(limit),
+            ));
+        }
+
+        // A single index scan seeks to the cursor.
+        let remaining_skip = match &self.query.skip {
+            Skip::Cursor(_) => Skip::default(),
+            other => other.clone(),
+        };
+        let combined = self.intersect_secondary_queries(
+            index_scans,
+            secondary_txns,
+            after.as_ref().map(|(key, id)| (key.as_slice(), *id)),
+        )?;
+        Ok(Either::Right(skip(combined, remaining_skip).take(limit)))
+    }
+
+    /// Collects the ids of all the intersections, so the union is deduplicated and in ascending order.
+    fn union_secondary_queries(
+        &self,
+        all_index_scans: &[Vec<IndexScan>],
+    ) -> Result<impl Iterator<Item = Result<u64, CacheError>>, CacheError> {
+        let mut ids = RoaringTreemap::new();
+        for index_scans in all_index_scans {
+            let secondary_txns = self.create_secondary_txns(index_scans)?;
+            for id in self.intersect_secondary_queries(index_scans, &secondary_txns, None)? {
+                ids.insert(id?);
+            }
+        }
+        let remaining_skip = match &self.query.skip {
+            Skip::Cursor(cursor) => {
+                ids.remove_range(..=cursor.operation_id());
+                Skip::default()
+            }
+            other => other.clone(),
+        };
+        Ok(skip(ids.into_iter().map(Ok), remaining_skip)
+            .take(self.query.limit.unwrap_or(usize::MAX)))
+    }
+
+    fn intersect_secondary_queries<'txn, T: Transaction>(
+        &self,
+        index_scans: &[IndexScan],
+        secondary_txns: &'txn [T],
+        after: Option<(&[u8], u64)>,
     ) -> Result<impl Iterator<Item = Result<u64, CacheError>> + 'txn, CacheError> {
         debug_assert!(
             !index_scans.is_empty(),
             "Planner should not generate empty index scan"
         );
-        let combined = if index_scans.len() == 1 {
+        Ok(if index_scans.len() == 1 {
             // The fast path, without intersection calculation.
             Either::Left(build_index_scan(
                 &secondary_txns[0],
                 self.cache.secondary_env(index_scans[0].index_id),
                 &index_scans[0].kind,
+                after,
             )?)
         } else {
             // Intersection of multiple index scans.
@@ -132,6 +316,7 @@ impl<'a, C: LmdbCache> LmdbQueryHandler<'a, C> {
                         secondary_txn,
                         self.cache.secondary_env(index_scan.index_id),
                         &index_scan.kind,
+                        None,
                     )
                 })
                 .collect::<Result<Vec<_>, CacheError>>()?;
@@ -139,8 +324,7 @@ impl<'a, C: LmdbCache> LmdbQueryHandler<'a, C> {
                 iterators,
                 self.cache.main_env().intersection_chunk_size(),
             ))
-        };
-        Ok(skip(combined, self.query.skip).take(self.query.limit.unwrap_or(usize::MAX)))
+        })
     }
 
     fn filter_secondary_queries<'txn, T: Transaction>(
@@ -177,22 +361,39 @@ impl<'a, C: LmdbCache> LmdbQueryHandler<'a, C> {
         Ok(result)
     }
 
+    /// Returns the records and the operation id of the last one.
     fn collect_records<'txn, T: Transaction>(
         &'txn self,
         main_txn: &'txn T,
         ids: impl Iterator<Item = Result<u64, CacheError>> + 'txn,
-    ) -> Result<Vec<CacheRecord>, CacheError> {
-        self.filter_secondary_queries(main_txn, ids)
-            .map(|id| {
-                id.and_then(|id| {
-                    self.cache
-                        .main_env()
-                        .operation_log()
-                        .get_record_by_operation_id_unchecked(main_txn, id)
-                        .map_err(Into::into)
-                })
-            })
-            .collect()
+    ) -> Result<(Vec<CacheRecord>, Option<u64>), CacheError> {
+        let mut records = vec![];
+        let mut last_operation_id = None;
+        self.visit_records(main_txn, ids, &mut |id, record| {
+            records.push(record);
+            last_operation_id = Some(id);
+            Ok(())
+        })?;
+        Ok((records, last_operation_id))
+    }
+
+    /// Calls `f` with the operation id and the record of every id that is still present.
+    fn visit_records<'txn, T: Transaction>(
+        &'txn self,
+        main_txn: &'txn T,
+        ids: impl Iterator<Item = Result<u64, CacheError>> + 'txn,
+        f: &mut impl FnMut(u64, CacheRecord) -> Result<(), CacheError>,
+    ) -> Result<(), CacheError> {
+        for id in self.filter_secondary_queries(main_txn, ids) {
+            let id = id?;
+            let record = self
+                .cache
+                .main_env()
+                .operation_log()
+                .get_record_by_operation_id_unchecked(main_txn, id)?;
+            f(id, record)?;
+        }
+        Ok(())
     }
 }
 
@@ -203,6 +404,7 @@ fn skip(
     match skip {
         Skip::Skip(n) => Either::Left(iter.skip(n)),
         Skip::After(after) => Either::Right(skip_after(iter, after)),
+        Skip::Cursor(_) => unreachable!("every scan seeks to the cursor itself"),
     }
 }
 
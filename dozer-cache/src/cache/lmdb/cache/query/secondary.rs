@@ -18,10 +18,12 @@ use crate::{
 
 use super::lmdb_cmp::lmdb_cmp;
 
+/// Scans the index, starting right after the `(key, id)` pair in `after` if it's within the range of the scan.
 pub fn build_index_scan<'txn, T: Transaction, S: SecondaryEnvironment>(
     secondary_txn: &'txn T,
     secondary_env: &S,
     index_scan_kind: &IndexScanKind,
+    after: Option<(&[u8], u64)>,
 ) -> Result<impl Iterator<Item = Result<u64, CacheError>> + 'txn, CacheError> {
     let is_single_field_sorted_inverted =
         is_single_field_sorted_inverted(secondary_env.index_definition());
@@ -34,13 +36,35 @@ pub fn build_index_scan<'txn, T: Transaction, S: SecondaryEnvironment>(
     };
 
     let database = secondary_env.database().database();
-    Ok(secondary_env
-        .database()
-        .range(
-            secondary_txn,
-            start,
-            range.direction == SortDirection::Ascending,
-        )?
+    let ascending = range.direction == SortDirection::Ascending;
+    // A key before the start of the range would leak records not satisfying the filter.
+    let after = after.filter(|(key, _)| match &range.start {
+        Some(start) => {
+            let ordering = lmdb_cmp(secondary_txn, database, key, start.key());
+            let ordering = if ascending {
+                ordering
+            } else {
+                ordering.reverse()
+            };
+            match start {
+                KeyEndpoint::Including(_) => ordering.is_ge(),
+                KeyEndpoint::Excluding(_) => ordering.is_gt(),
+            }
+        }
+        None => true,
+    });
+    let iterator = match after {
+        Some((key, id)) => {
+            secondary_env
+                .database()
+                .range_after(secondary_txn, key, &id, ascending)?
+        }
+        None => secondary_env
+            .database()
+            .range(secondary_txn, start, ascending)?,
+    };
+
+    Ok(iterator
         .take_while(move |result| match result {
             Ok((key, _)) => {
                 if let Some(end_key) = &range.end {
@@ -66,6 +90,33 @@ pub fn build_index_scan<'txn, T: Transaction, S: SecondaryEnvironment>(
         }))
 }
 
+/// The key of the entry of a record with `values` that `index_scan_kind` scans, so the scan can be resumed from it.
+pub fn get_index_scan_key(
+    index_definition: &IndexDefinition,
+    index_scan_kind: &IndexScanKind,
+    values: &[Field],
+) -> Result<Vec<u8>, CacheError> {
+    match (index_definition, index_scan_kind) {
+        (IndexDefinition::SortedInverted(fields), _) => {
+            let values = fields
+                .iter()
+                .map(|index| &values[*index])
+                .collect::<Vec<_>>();
+            // `values.len() == 1` criteria must be kept the same with `comparator.rs`.
+            Ok(index::get_secondary_index(&values, values.len() == 1))
+        }
+        (IndexDefinition::FullText(_), IndexScanKind::FullText { filter }) => match &filter.val {
+            Field::String(token) | Field::Text(token) => {
+                Ok(index::get_full_text_secondary_index(token))
+            }
+            _ => Err(CacheError::Index(IndexError::ExpectedStringFullText)),
+        },
+        (IndexDefinition::FullText(_), IndexScanKind::SortedInverted { .. }) => {
+            unreachable!("planner should not scan a full text index as sorted inverted")
+        }
+    }
+}
+
 fn is_single_field_sorted_inverted(index: &IndexDefinition) -> bool {
     match index {
         // `fields.len() == 1` criteria must be kept the same with `comparator.rs`.
@@ -1,5 +1,5 @@
 use crate::cache::{
-    expression::{FilterExpression, Operator, QueryExpression},
+    expression::{FilterExpression, Operator, QueryExpression, Skip},
     lmdb::tests::utils::{create_cache, insert_rec_1},
     test_utils::{query_from_filter, schema_1, schema_full_text, schema_multi_indices},
     CacheRecord, RoCache, RwCache,
@@ -179,6 +179,251 @@ fn query_secondary_vars() {
     );
 }
 
+#[test]
+fn query_secondary_or_not_in() {
+    let (mut cache, indexing_thread_pool, _, _) = create_cache(schema_1);
+
+    let items = vec![
+        (1, Some("yuri".to_string()), Some(521)),
+        (2, Some("mega".to_string()), Some(521)),
+        (3, Some("james".to_string()), Some(523)),
+        (4, Some("james".to_string()), Some(524)),
+        (5, Some("steff".to_string()), Some(526)),
+        (6, Some("mega".to_string()), Some(527)),
+        (7, Some("james".to_string()), Some(528)),
+        (8, Some("ava".to_string()), None),
+    ];
+    for val in items {
+        insert_rec_1(&mut cache, val);
+    }
+    cache.commit(&Default::default()).unwrap();
+    indexing_thread_pool.lock().wait_until_catchup();
+
+    test_query_record(
+        json!({"$filter": {"$or": [{"a": 1}, {"c": 524}]}}),
+        vec![
+            (0, 1, "yuri".to_string(), 521),
+            (3, 4, "james".to_string(), 524),
+        ],
+        &cache,
+    );
+    // Records matching several alternatives are returned once.
+    test_query(
+        json!({"$filter": {"$or": [{"b": "james"}, {"c": {"$gte": 524}}]}}),
+        5,
+        &cache,
+    );
+    test_query(json!({"$filter": {"c": {"$in": [521, 528]}}}), 3, &cache);
+    test_query(json!({"$filter": {"c": {"$in": [523, null]}}}), 2, &cache);
+    test_query(json!({"$filter": {"c": {"$in": []}}}), 0, &cache);
+    test_query(json!({"$filter": {"c": {"$ne": 521}}}), 5, &cache);
+    test_query(json!({"$filter": {"c": {"$ne": null}}}), 7, &cache);
+    test_query(json!({"$filter": {"c": {"$null": true}}}), 1, &cache);
+    test_query(json!({"$filter": {"c": {"$null": false}}}), 7, &cache);
+    test_query(json!({"$filter": {"$not": {"c": {"$gt": 524}}}}), 4, &cache);
+    test_query(json!({"$filter": {"$not": {"b": "james"}}}), 5, &cache);
+    test_query(
+        json!({"$filter": {"$not": {"$or": [{"a": {"$ne": 4}}, {"b": {"$lt": "james"}}]}}}),
+        1,
+        &cache,
+    );
+    test_query_record(
+        json!({
+            "$filter": {"c": {"$in": [521, 528]}},
+            "$skip": 1,
+            "$limit": 1
+        }),
+        vec![(1, 2, "mega".to_string(), 521)],
+        &cache,
+    );
+
+    // The union isn't sorted.
+    test_query_err(
+        json!({
+            "$filter": {"$or": [{"a": 1}, {"a": 2}]},
+            "$order_by": {"c": "asc"}
+        }),
+        &cache,
+    );
+}
+
+#[test]
+fn query_cursor_pagination() {
+    let (mut cache, indexing_thread_pool, _, _) = create_cache(schema_1);
+
+    let items = vec![
+        (1, Some("yuri".to_string()), Some(521)),
+        (2, Some("mega".to_string()), Some(521)),
+        (3, Some("james".to_string()), Some(523)),
+        (4, Some("james".to_string()), Some(524)),
+        (5, Some("steff".to_string()), Some(526)),
+        (6, Some("mega".to_string()), Some(527)),
+        (7, Some("james".to_string()), Some(528)),
+        (8, Some("ava".to_string()), None),
+    ];
+    for val in items {
+        insert_rec_1(&mut cache, val);
+    }
+    cache.commit(&Default::default()).unwrap();
+    indexing_thread_pool.lock().wait_until_catchup();
+
+    for query in [
+        // Seq scan.
+        json!({}),
+        // Single index scan, with duplicate keys and null.
+        json!({"$order_by": {"c": "asc"}}),
+        json!({"$order_by": {"c": "desc"}}),
+        json!({"$filter": {"c": {"$gte": 523}}, "$order_by": {"c": "asc"}}),
+        json!({"$filter": {"c": {"$lt": 527}}, "$order_by": {"c": "desc"}}),
+        json!({"$filter": {"b": "james"}}),
+        // Union.
+        json!({"$filter": {"$or": [{"b": "james"}, {"c": 521}]}}),
+    ] {
+        let query = from_value::<QueryExpression>(query).unwrap();
+        let all = cache
+            .query(&query)
+            .unwrap()
+            .into_iter()
+            .map(|record| record.record.values[0].clone())
+            .collect::<Vec<_>>();
+        for page_size in [1, 2, 3] {
+            assert_eq!(query_all_pages(&query, page_size, &cache), all, "{query:?}");
+        }
+    }
+
+    // The next page is found even if the last record has been deleted.
+    let mut query =
+        from_value::<QueryExpression>(json!({"$order_by": {"c": "asc"}, "$limit": 3})).unwrap();
+    let cursor = cache.query_page(&query).unwrap().cursor.unwrap();
+    cache
+        .delete(&Record::new(vec![
+            Field::Int(3),
+            Field::String("james".to_string()),
+            Field::Int(523),
+        ]))
+        .unwrap();
+    cache.commit(&Default::default()).unwrap();
+    indexing_thread_pool.lock().wait_until_catchup();
+    query.skip = Skip::Cursor(cursor);
+    let a = cache
+        .query(&query)
+        .unwrap()
+        .into_iter()
+        .map(|record| record.record.values[0].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(a, vec![Field::Int(4), Field::Int(5), Field::Int(6)]);
+
+    // A cursor outside the range of the filter doesn't leak records.
+    let query =
+        from_value::<QueryExpression>(json!({"$order_by": {"c": "asc"}, "$limit": 1})).unwrap();
+    let cursor = cache.query_page(&query).unwrap().cursor.unwrap();
+    let mut query = from_value::<QueryExpression>(
+        json!({"$filter": {"c": {"$gt": 524}}, "$order_by": {"c": "asc"}}),
+    )
+    .unwrap();
+    query.skip = Skip::Cursor(cursor);
+    let c = cache
+        .query(&query)
+        .unwrap()
+        .into_iter()
+        .map(|record| record.record.values[2].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(c, vec![Field::Int(526), Field::Int(527), Field::Int(528)]);
+}
+
+#[test]
+fn query_cursor_pagination_after_deleted_record() {
+    // A single index scan, with a cursor that doesn't carry the index key.
+    let (mut cache, indexing_thread_pool, _, _) = create_cache(schema_1);
+    for val in [
+        (1, Some("yuri".to_string()), Some(521)),
+        (2, Some("mega".to_string()), Some(522)),
+        (3, Some("james".to_string()), Some(523)),
+        (4, Some("steff".to_string()), Some(524)),
+    ] {
+        insert_rec_1(&mut cache, val);
+    }
+    cache.commit(&Default::default()).unwrap();
+    indexing_thread_pool.lock().wait_until_catchup();
+
+    let mut query =
+        from_value::<QueryExpression>(json!({"$order_by": {"c": "desc"}, "$limit": 2})).unwrap();
+    let cursor = cache.query_page(&query).unwrap().cursor.unwrap();
+    cache
+        .delete(&Record::new(vec![
+            Field::Int(3),
+            Field::String("james".to_string()),
+            Field::Int(523),
+        ]))
+        .unwrap();
+    cache.commit(&Default::default()).unwrap();
+    indexing_thread_pool.lock().wait_until_catchup();
+    query.skip = Skip::Cursor(cursor.without_key());
+    let a = cache
+        .query(&query)
+        .unwrap()
+        .into_iter()
+        .map(|record| record.record.values[0].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(a, vec![Field::Int(2), Field::Int(1)]);
+
+    // An intersection of index scans.
+    let (mut cache, indexing_thread_pool, _, _) = create_cache(schema_multi_indices);
+    let record = |id, text: &str| Record::new(vec![Field::Int(id), Field::String(text.into())]);
+    for (id, text) in [
+        (1, "apple egg"),
+        (2, "ball egg"),
+        (3, "cake egg"),
+        (4, "dance"),
+        (5, "egg fish"),
+        (6, "egg glove"),
+    ] {
+        cache.insert(&record(id, text)).unwrap();
+    }
+    cache.commit(&Default::default()).unwrap();
+    indexing_thread_pool.lock().wait_until_catchup();
+
+    let mut query = from_value::<QueryExpression>(
+        json!({"$filter": {"id": {"$gt": 1}, "text": {"$contains": "egg"}}, "$limit": 2}),
+    )
+    .unwrap();
+    let ids = |query: &QueryExpression, cache: &dyn RwCache| {
+        cache
+            .query(query)
+            .unwrap()
+            .into_iter()
+            .map(|record| record.record.values[0].clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ids(&query, &cache), vec![Field::Int(2), Field::Int(3)]);
+    let cursor = cache.query_page(&query).unwrap().cursor.unwrap();
+    cache.delete(&record(3, "cake egg")).unwrap();
+    cache.commit(&Default::default()).unwrap();
+    indexing_thread_pool.lock().wait_until_catchup();
+    query.skip = Skip::Cursor(cursor);
+    assert_eq!(ids(&query, &cache), vec![Field::Int(5), Field::Int(6)]);
+}
+
+/// Follows the cursors and returns the `a` field of all records.
+fn query_all_pages(query: &QueryExpression, page_size: usize, cache: &dyn RwCache) -> Vec<Field> {
+    let mut query = query.clone();
+    query.limit = Some(page_size);
+    let mut result = vec![];
+    loop {
+        let page = cache.query_page(&query).unwrap();
+        assert!(page.records.len() <= page_size);
+        result.extend(
+            page.records
+                .into_iter()
+                .map(|record| record.record.values[0].clone()),
+        );
+        match page.cursor {
+            Some(cursor) => query.skip = Skip::Cursor(cursor),
+            None => return result,
+        }
+    }
+}
+
 #[test]
 fn query_secondary_multi_indices() {
     let (mut cache, indexing_thread_pool, _, _) = create_cache(schema_multi_indices);
@@ -4,4 +4,4 @@ mod indexing;
 mod utils;
 
 #[cfg(test)]
-mod tests;
+pub mod tests;
@@ -2,7 +2,7 @@ mod lmdb;
 use std::collections::HashSet;
 use std::fmt::Debug;
 
-use self::expression::QueryExpression;
+use self::expression::{QueryCursor, QueryExpression};
 use crate::errors::CacheError;
 use dozer_tracing::Labels;
 use dozer_types::models::endpoint::{
@@ -16,6 +16,7 @@ use dozer_types::{
 pub use lmdb::cache_manager::{
     begin_dump_txn, dump, CacheManagerOptions, LmdbRoCacheManager, LmdbRwCacheManager,
 };
+pub mod aggregate;
 pub mod expression;
 mod index;
 mod plan;
@@ -39,6 +40,14 @@ impl CacheRecord {
     }
 }
 
+/// Records of a query, and where the next page of the same query starts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryPage {
+    pub records: Vec<CacheRecord>,
+    /// `None` if the query has no limit or fewer records than the limit are returned.
+    pub cursor: Option<QueryCursor>,
+}
+
 pub trait RoCacheManager: Send + Sync + Debug {
     /// Opens a cache in read-only mode, and attach given labels.
     fn open_ro_cache(
@@ -98,6 +107,13 @@ pub trait RoCache: Send + Sync + Debug {
     fn get(&self, key: &[u8]) -> Result<CacheRecord, CacheError>;
     fn count(&self, query: &QueryExpression) -> Result<usize, CacheError>;
     fn query(&self, query: &QueryExpression) -> Result<Vec<CacheRecord>, CacheError>;
+    fn query_page(&self, query: &QueryExpression) -> Result<QueryPage, CacheError>;
+    /// Calls `f` with every record of the query, without collecting them.
+    fn query_for_each(
+        &self,
+        query: &QueryExpression,
+        f: &mut dyn FnMut(CacheRecord) -> Result<(), CacheError>,
+    ) -> Result<(), CacheError>;
 
     // Cache metadata
     fn get_commit_state(&self) -> Result<Option<CommitState>, CacheError>;
@@ -11,6 +11,8 @@ mod tests;
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Plan {
     IndexScans(Vec<IndexScan>),
+    /// Union of the results of several intersections of index scans, in operation id order.
+    Union(Vec<Vec<IndexScan>>),
     SeqScan(SeqScan),
     ReturnEmpty,
 }
@@ -1,6 +1,7 @@
 use crate::cache::expression::{FilterExpression, Operator, SortDirection, SortOptions};
 use crate::errors::PlanError;
 use dozer_types::models::endpoint::{FullText, SecondaryIndex, SortedInverted};
+use dozer_types::serde_json::Value;
 use dozer_types::types::{Field, FieldDefinition, Schema};
 use dozer_types::types::{FieldType, IndexDefinition};
 use dozer_types::{json_value_to_field, serde_yaml};
@@ -31,13 +32,49 @@ impl<'a> QueryPlanner<'a> {
     }
 
     pub fn plan(&self) -> Result<Plan, PlanError> {
-        // Collect all the filters.
-        // TODO: Handle filters like And([a > 0, a < 10]).
-        let mut filters = vec![];
-        if let Some(expression) = &self.filter {
-            collect_filters(self.schema, expression, &mut filters)?;
+        // Rewrite the filter as a union of conjunctions, each of which is answered by intersecting index scans.
+        let conjunctions = match &self.filter {
+            Some(expression) => normalize(self.schema, expression, false)?,
+            None => vec![vec![]],
+        };
+
+        let mut plans = vec![];
+        for filters in conjunctions {
+            match self.plan_conjunction(filters)? {
+                Plan::ReturnEmpty => (),
+                plan => plans.push(plan),
+            }
+        }
+
+        // Results of a union are not sorted by any field.
+        if plans.len() > 1 && !self.order_by.0.is_empty() {
+            return Err(PlanError::CannotSortUnion);
         }
 
+        let mut union = vec![];
+        for plan in plans {
+            match plan {
+                Plan::IndexScans(index_scans) => union.push(index_scans),
+                Plan::SeqScan(seq_scan) => return Ok(Plan::SeqScan(seq_scan)),
+                Plan::Union(_) | Plan::ReturnEmpty => {
+                    unreachable!("a conjunction is planned as index scans or a seq scan")
+                }
+            }
+        }
+        Ok(match union.len() {
+            0 => Plan::ReturnEmpty,
+            1 => Plan::IndexScans(union.remove(0)),
+            _ => Plan::Union(union),
+        })
+    }
+
+    fn plan_conjunction(&self, filters: Vec<IndexFilter>) -> Result<Plan, PlanError> {
+        // TODO: Handle filters like And([a > 0, a < 10]).
+        let mut filters = filters
+            .into_iter()
+            .map(|filter| (filter, None))
+            .collect::<Vec<_>>();
+
         // Filter the sort options.
         // TODO: Handle duplicate fields.
         let mut order_by = vec![];
@@ -61,14 +98,6 @@ impl<'a> QueryPlanner<'a> {
             }));
         }
 
-        // If non-`Eq` filter is applied to `null` value, return empty result.
-        if filters
-            .iter()
-            .any(|f| matches!(f.0.val, Field::Null) && f.0.op != Operator::EQ)
-        {
-            return Ok(Plan::ReturnEmpty);
-        }
-
         // Find the range query, can be a range filter or a sort option.
         let range_query = find_range_query(&mut filters, &order_by)?;
 
@@ -108,26 +137,138 @@ fn get_field_index_and_type(
         .map(|(i, f)| (i, f.typ, f.nullable))
 }
 
-fn collect_filters(
+/// Upper limit of the number of conjunctions a filter can be rewritten into.
+const MAX_CONJUNCTIONS: usize = 256;
+
+/// Rewrites `expression` (or its negation) into a union of conjunctions of filters that indexes can answer.
+///
+/// `NOT` is pushed down to the filters, `$ne` becomes `< v OR > v`, `$in` becomes `= v1 OR = v2 ...`,
+/// and `IS NOT NULL` becomes `< null`, using the invariant that `null` is greater than anything.
+/// Comparisons with `null` never match, so the negation of a comparison doesn't match `null` either.
+fn normalize(
     schema: &Schema,
     expression: &FilterExpression,
-    filters: &mut Vec<(IndexFilter, Option<SortDirection>)>,
-) -> Result<(), PlanError> {
+    negated: bool,
+) -> Result<Vec<Vec<IndexFilter>>, PlanError> {
     match expression {
         FilterExpression::Simple(field_name, operator, value) => {
             let (field_index, field_type, nullable) =
                 get_field_index_and_type(field_name, &schema.fields)
                     .ok_or_else(|| PlanError::FieldNotFound(field_name.clone()))?;
-            let field = json_value_to_field(value.clone(), field_type, nullable)?;
-            filters.push((IndexFilter::new(field_index, *operator, field), None));
+            let to_field = |value: &Value| json_value_to_field(value.clone(), field_type, nullable);
+            let filter = |op, val| vec![vec![IndexFilter::new(field_index, op, val)]];
+
+            Ok(match (operator, negated) {
+                (Operator::EQ, false) | (Operator::NE, true) => {
+                    filter(Operator::EQ, to_field(value)?)
+                }
+                (Operator::NE, false) | (Operator::EQ, true) => match to_field(value)? {
+                    Field::Null => filter(Operator::LT, Field::Null),
+                    field => vec![
+                        vec![IndexFilter::new(field_index, Operator::LT, field.clone())],
+                        vec![IndexFilter::new(field_index, Operator::GT, field)],
+                    ],
+                },
+                (Operator::In, _) => {
+                    let Value::Array(values) = value else {
+                        return Err(PlanError::ExpectedArray(field_name.clone()));
+                    };
+                    let op = if negated { Operator::NE } else { Operator::EQ };
+                    let expressions = values
+                        .iter()
+                        .map(|value| {
+                            FilterExpression::Simple(field_name.clone(), op, value.clone())
+                        })
+                        .collect();
+                    if negated {
+                        normalize(schema, &FilterExpression::And(expressions), false)?
+                    } else {
+                        normalize(schema, &FilterExpression::Or(expressions), false)?
+                    }
+                }
+                (Operator::IsNull, _) => {
+                    let Value::Bool(is_null) = value else {
+                        return Err(PlanError::ExpectedBool(field_name.clone()));
+                    };
+                    if *is_null != negated {
+                        filter(Operator::EQ, Field::Null)
+                    } else {
+                        filter(Operator::LT, Field::Null)
+                    }
+                }
+                (Operator::Contains | Operator::MatchesAny | Operator::MatchesAll, true) => {
+                    return Err(PlanError::CannotNegateFullTextFilter);
+                }
+                (op, negated) => {
+                    let op = if negated {
+                        match op {
+                            Operator::LT => Operator::GTE,
+                            Operator::LTE => Operator::GT,
+                            Operator::GT => Operator::LTE,
+                            Operator::GTE => Operator::LT,
+                            _ => unreachable!("other operators are handled above"),
+                        }
+                    } else {
+                        *op
+                    };
+                    match to_field(value)? {
+                        // Non-`Eq` filter applied to `null` value matches nothing.
+                        Field::Null => vec![],
+                        field => filter(op, field),
+                    }
+                }
+            })
+        }
+        FilterExpression::And(expressions) if !negated => {
+            let mut result = vec![vec![]];
+            for expression in expressions {
+                let conjunctions = normalize(schema, expression, false)?;
+                if result.len() * conjunctions.len() > MAX_CONJUNCTIONS {
+                    return Err(PlanError::TooManyConjunctions(MAX_CONJUNCTIONS));
+                }
+                result = result
+                    .iter()
+                    .flat_map(|lhs| {
+                        conjunctions.iter().map(move |rhs| {
+                            let mut filters = lhs.clone();
+                            filters.extend(rhs.iter().cloned());
+                            filters
+                        })
+                    })
+                    .collect();
+            }
+            Ok(result)
         }
-        FilterExpression::And(expressions) => {
+        FilterExpression::Or(expressions) if !negated => {
+            let mut result = vec![];
             for expression in expressions {
-                collect_filters(schema, expression, filters)?;
+                result.extend(normalize(schema, expression, false)?);
+                if result.len() > MAX_CONJUNCTIONS {
+                    return Err(PlanError::TooManyConjunctions(MAX_CONJUNCTIONS));
+                }
             }
+            Ok(result)
         }
+        // De Morgan's laws.
+        FilterExpression::And(expressions) => normalize(
+            schema,
+            &FilterExpression::Or(negate_all(expressions)),
+            false,
+        ),
+        FilterExpression::Or(expressions) => normalize(
+            schema,
+            &FilterExpression::And(negate_all(expressions)),
+            false,
+        ),
+        FilterExpression::Not(expression) => normalize(schema, expression, !negated),
     }
-    Ok(())
+}
+
+fn negate_all(expressions: &[FilterExpression]) -> Vec<FilterExpression> {
+    expressions
+        .iter()
+        .map(|expression| FilterExpression::Not(Box::new(expression.clone())))
+        .collect()
 }
 
 fn seen_in_sorted_inverted_filter(
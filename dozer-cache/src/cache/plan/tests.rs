@@ -1,11 +1,15 @@
-use super::{Plan, QueryPlanner};
+use super::{IndexScan, Plan, QueryPlanner};
 use crate::cache::{
     expression::{self, FilterExpression, Operator, SortDirection, SortOption, SortOptions},
     plan::{IndexScanKind, SortedInvertedRangeQuery},
     test_utils,
 };
+use crate::errors::PlanError;
 
-use dozer_types::{serde_json::Value, types::Field};
+use dozer_types::{
+    serde_json::{self, json, Value},
+    types::Field,
+};
 
 #[test]
 fn test_generate_plan_simple() {
@@ -133,3 +137,108 @@ fn test_generate_plan_empty() {
     .unwrap();
     assert!(matches!(plan, Plan::ReturnEmpty));
 }
+
+#[test]
+fn test_generate_plan_union() {
+    let (schema, secondary_indexes) = test_utils::schema_1();
+    let plan = |filter: Value| {
+        let filter = serde_json::from_value::<FilterExpression>(filter).unwrap();
+        QueryPlanner::new(
+            &schema,
+            &secondary_indexes,
+            Some(&filter),
+            &Default::default(),
+        )
+        .plan()
+    };
+    let eq_scan = |index_id, field_index, value: Field| IndexScan {
+        index_id,
+        kind: IndexScanKind::SortedInverted {
+            eq_filters: vec![(field_index, value)],
+            range_query: None,
+        },
+    };
+
+    assert_eq!(
+        plan(json!({"a": {"$in": [1, 2]}})).unwrap(),
+        Plan::Union(vec![
+            vec![eq_scan(0, 0, Field::Int(1))],
+            vec![eq_scan(0, 0, Field::Int(2))],
+        ])
+    );
+    assert_eq!(
+        plan(json!({"$or": [{"a": 1}, {"c": {"$lt": null}}]})).unwrap(),
+        Plan::IndexScans(vec![eq_scan(0, 0, Field::Int(1))])
+    );
+    assert_eq!(
+        plan(json!({"$not": {"c": {"$null": false}}})).unwrap(),
+        Plan::IndexScans(vec![eq_scan(2, 2, Field::Null)])
+    );
+    assert_eq!(plan(json!({"a": {"$in": []}})).unwrap(), Plan::ReturnEmpty);
+    assert!(matches!(
+        plan(json!({"$or": [{"a": 1}, {}]})).unwrap(),
+        Plan::SeqScan(_)
+    ));
+
+    let Plan::Union(scans) = plan(json!({"$not": {"c": 1}})).unwrap() else {
+        panic!("Union expected");
+    };
+    let operators = scans
+        .iter()
+        .map(|scans| match &scans[..] {
+            [IndexScan {
+                index_id: 2,
+                kind:
+                    IndexScanKind::SortedInverted {
+                        range_query:
+                            Some(SortedInvertedRangeQuery {
+                                operator_and_value: Some((operator, Field::Int(1))),
+                                ..
+                            }),
+                        ..
+                    },
+            }] => *operator,
+            _ => panic!("Range scan on c expected"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(operators, vec![Operator::LT, Operator::GT]);
+
+    assert!(matches!(
+        plan(json!({"$or": [{"a": 1}, {"a": {"$null": 1}}]})),
+        Err(PlanError::ExpectedBool(_))
+    ));
+}
+
+#[test]
+fn test_generate_plan_union_errors() {
+    let (schema, secondary_indexes) = test_utils::schema_full_text();
+    let filter = FilterExpression::Not(Box::new(FilterExpression::Simple(
+        "foo".into(),
+        Operator::Contains,
+        "good".into(),
+    )));
+    assert!(matches!(
+        QueryPlanner::new(
+            &schema,
+            &secondary_indexes,
+            Some(&filter),
+            &Default::default()
+        )
+        .plan(),
+        Err(PlanError::CannotNegateFullTextFilter)
+    ));
+
+    let (schema, secondary_indexes) = test_utils::schema_1();
+    let filter = FilterExpression::Or(vec![
+        FilterExpression::Simple("a".into(), Operator::EQ, 1.into()),
+        FilterExpression::Simple("a".into(), Operator::EQ, 2.into()),
+    ]);
+    let order_by = SortOptions(vec![SortOption {
+        field_name: "a".into(),
+        direction: SortDirection::Ascending,
+    }]);
+    assert!(matches!(
+        QueryPlanner::new(&schema, &secondary_indexes, Some(&filter), &order_by).plan(),
+        Err(PlanError::CannotSortUnion)
+    ));
+}
@@ -8,8 +8,9 @@ use dozer_types::thiserror::Error;
 
 use dozer_log::errors::ReaderError;
 use dozer_types::errors::types::{DeserializationError, SerializationError, TypeError};
-use dozer_types::types::{Field, IndexDefinition, SchemaWithIndex};
+use dozer_types::types::{Field, FieldType, IndexDefinition, SchemaWithIndex};
 
+use crate::cache::expression::AggregateFunction;
 use crate::cache::RecordMeta;
 
 #[derive(Debug)]
@@ -29,6 +30,8 @@ pub enum CacheError {
     Index(#[from] IndexError),
     #[error("Plan error: {0}")]
     Plan(#[from] PlanError),
+    #[error("Aggregate error: {0}")]
+    Aggregate(#[from] AggregateError),
     #[error("Type error: {0}")]
     Type(#[from] TypeError),
     #[error("Restore error: {0}")]
@@ -65,6 +68,10 @@ pub enum CacheError {
     AppendOnlySchema,
     #[error("Primary key is not found")]
     PrimaryKeyNotFound,
+    #[error("Cursor doesn't point to a record of the cache")]
+    InvalidCursor,
+    #[error("Access to field {0} is restricted")]
+    RestrictedField(String),
     #[error("Primary key {key:?} already exists: record id {}, version {}, insert operation id {insert_operation_id}", .meta.id, .meta.version)]
     PrimaryKeyExists {
         key: Vec<(String, Field)>,
@@ -148,6 +155,22 @@ pub enum IndexError {
     MissingCompoundIndex(String),
 }
 
+#[derive(Error, Debug)]
+pub enum AggregateError {
+    #[error("Type error: {0}")]
+    Type(#[from] TypeError),
+    #[error("{0:?} requires a field")]
+    MissingField(AggregateFunction),
+    #[error("{function:?} is not supported on field {field:?} of type {typ}")]
+    UnsupportedFieldType {
+        function: AggregateFunction,
+        field: String,
+        typ: FieldType,
+    },
+    #[error("Aggregate {0:?} overflowed")]
+    Overflow(String),
+}
+
 #[derive(Error, Debug)]
 pub enum PlanError {
     #[error("Field {0:?} not found in query")]
@@ -160,6 +183,16 @@ pub enum PlanError {
     ConflictingSortOptions,
     #[error("Cannot have more than one range query")]
     RangeQueryLimit,
+    #[error("Cannot negate full text filter")]
+    CannotNegateFullTextFilter,
+    #[error("Cannot sort the union of $or alternatives")]
+    CannotSortUnion,
+    #[error("Filter has more than {0} alternatives")]
+    TooManyConjunctions(usize),
+    #[error("$in on field {0:?} expects an array")]
+    ExpectedArray(String),
+    #[error("$null on field {0:?} expects a boolean")]
+    ExpectedBool(String),
     #[error("Matching index not found. Try to add following secondary index configuration:\n{0}")]
     MatchingIndexNotFound(String),
 }
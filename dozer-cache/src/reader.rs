@@ -1,12 +1,18 @@
-use crate::cache::{expression::QueryExpression, CacheRecord, CommitState, RoCache};
+use crate::cache::{
+    aggregate::{AggregateResult, Aggregator},
+    expression::{AggregateExpression, QueryCursor, QueryExpression},
+    CacheRecord, CommitState, QueryPage, RoCache,
+};
 
-use super::cache::expression::FilterExpression;
-use crate::errors::CacheError;
+use super::cache::expression::{FilterExpression, Operator};
+use crate::errors::{CacheError, PlanError};
 use dozer_types::{
-    serde,
-    types::{Record, SchemaWithIndex},
+    json_value_to_field, serde,
+    serde_json::Value,
+    types::{Field, Record, Schema, SchemaWithIndex},
 };
 use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 #[serde(crate = "self::serde")]
@@ -21,6 +27,53 @@ pub struct AccessFilter {
     pub fields: Vec<String>,
 }
 
+impl AccessFilter {
+    pub fn is_field_restricted(&self, field_name: &str) -> bool {
+        self.fields.iter().any(|field| field == field_name)
+    }
+
+    /// Rejects queries filtering or sorting on restricted fields, which would leak their values.
+    fn check_query(&self, query: &QueryExpression) -> Result<(), CacheError> {
+        if let Some(filter) = &query.filter {
+            self.check_filter(filter)?;
+        }
+        for sort_option in &query.order_by.0 {
+            self.check_field(&sort_option.field_name)?;
+        }
+        Ok(())
+    }
+
+    pub fn check_filter(&self, filter: &FilterExpression) -> Result<(), CacheError> {
+        match filter {
+            FilterExpression::Simple(field_name, _, _) => self.check_field(field_name),
+            FilterExpression::And(filters) | FilterExpression::Or(filters) => filters
+                .iter()
+                .try_for_each(|filter| self.check_filter(filter)),
+            FilterExpression::Not(filter) => self.check_filter(filter),
+        }
+    }
+
+    fn check_field(&self, field_name: &str) -> Result<(), CacheError> {
+        if self.is_field_restricted(field_name) {
+            Err(CacheError::RestrictedField(field_name.to_string()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Replaces the values of restricted fields with `Field::Null`.
+    fn mask_record(&self, schema: &Schema, record: &mut Record) {
+        if self.fields.is_empty() {
+            return;
+        }
+        for (definition, value) in schema.fields.iter().zip(record.values.iter_mut()) {
+            if self.is_field_restricted(&definition.name) {
+                *value = Field::Null;
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(crate = "dozer_types::serde")]
 pub enum Phase {
@@ -39,9 +92,16 @@ impl CacheReader {
         Self { cache }
     }
 
-    // TODO: Implement check_access
-    fn check_access(&self, _rec: &Record, _access_filter: &AccessFilter) -> Result<(), CacheError> {
-        Ok(())
+    /// Checks the record against the row filter of `access_filter`.
+    ///
+    /// A record that doesn't pass is reported as not found, so its existence isn't leaked.
+    fn check_access(&self, rec: &Record, access_filter: &AccessFilter) -> Result<(), CacheError> {
+        match &access_filter.filter {
+            Some(filter) if !record_matches(&self.get_schema().0, rec, filter)? => {
+                Err(CacheError::PrimaryKeyNotFound)
+            }
+            _ => Ok(()),
+        }
     }
 
     pub fn cache_name(&self) -> &str {
@@ -57,11 +117,10 @@ impl CacheReader {
     }
 
     pub fn get(&self, key: &[u8], access_filter: &AccessFilter) -> Result<CacheRecord, CacheError> {
-        let record = self.cache.get(key)?;
-        match self.check_access(&record.record, access_filter) {
-            Ok(_) => Ok(record),
-            Err(e) => Err(e),
-        }
+        let mut record = self.cache.get(key)?;
+        self.check_access(&record.record, access_filter)?;
+        access_filter.mask_record(&self.get_schema().0, &mut record.record);
+        Ok(record)
     }
 
     pub fn query(
@@ -69,8 +128,28 @@ impl CacheReader {
         query: &mut QueryExpression,
         access_filter: AccessFilter,
     ) -> Result<Vec<CacheRecord>, CacheError> {
-        self.apply_access_filter(query, access_filter);
-        self.cache.query(query)
+        self.query_page(query, access_filter)
+            .map(|page| page.records)
+    }
+
+    /// Like `query`, also returning the cursor of the next page.
+    ///
+    /// If some fields are restricted, the cursor doesn't carry the index key, which may contain their values.
+    pub fn query_page(
+        &self,
+        query: &mut QueryExpression,
+        access_filter: AccessFilter,
+    ) -> Result<QueryPage, CacheError> {
+        access_filter.check_query(query)?;
+        self.apply_access_filter(query, &access_filter);
+        let mut page = self.cache.query_page(query)?;
+        for record in &mut page.records {
+            access_filter.mask_record(&self.get_schema().0, &mut record.record);
+        }
+        if !access_filter.fields.is_empty() {
+            page.cursor = page.cursor.map(QueryCursor::without_key);
+        }
+        Ok(page)
     }
 
     pub fn count(
@@ -78,10 +157,41 @@ impl CacheReader {
         query: &mut QueryExpression,
         access_filter: AccessFilter,
     ) -> Result<usize, CacheError> {
-        self.apply_access_filter(query, access_filter);
+        access_filter.check_query(query)?;
+        self.apply_access_filter(query, &access_filter);
         self.cache.count(query)
     }
 
+    /// Groups and aggregates the records satisfying the filter of `expression` and the access filter.
+    ///
+    /// Restricted fields can't be grouped by or aggregated, which would leak their values.
+    pub fn aggregate(
+        &self,
+        expression: &AggregateExpression,
+        access_filter: AccessFilter,
+    ) -> Result<AggregateResult, CacheError> {
+        if let Some(filter) = &expression.filter {
+            access_filter.check_filter(filter)?;
+        }
+        for field_name in expression.group_by.iter().chain(
+            expression
+                .aggregates
+                .iter()
+                .filter_map(|aggregate| aggregate.field_name.as_ref()),
+        ) {
+            access_filter.check_field(field_name)?;
+        }
+        let mut aggregator = Aggregator::new(&self.get_schema().0, expression)?;
+
+        let mut query = QueryExpression::with_no_limit();
+        query.filter = expression.filter.clone();
+        self.apply_access_filter(&mut query, &access_filter);
+        self.cache.query_for_each(&query, &mut |record| {
+            aggregator.add(&record.record).map_err(Into::into)
+        })?;
+        Ok(aggregator.finish())
+    }
+
     pub fn get_phase(&self) -> Result<Phase, CacheError> {
         if self.cache.is_snapshotting_done()? {
             Ok(Phase::Streaming)
@@ -91,9 +201,8 @@ impl CacheReader {
     }
 
     // Apply filter if specified in access
-    fn apply_access_filter(&self, query: &mut QueryExpression, access_filter: AccessFilter) {
-        // TODO: Use `fields` in `access_filter`.
-        if let Some(access_filter) = access_filter.filter {
+    fn apply_access_filter(&self, query: &mut QueryExpression, access_filter: &AccessFilter) {
+        if let Some(access_filter) = access_filter.filter.clone() {
             let filter = match query.filter.take() {
                 Some(query_filter) => FilterExpression::And(vec![access_filter, query_filter]),
                 None => access_filter,
@@ -103,3 +212,335 @@ impl CacheReader {
         }
     }
 }
+
+/// Evaluates `filter` on `record` the way the cache indexes would.
+fn record_matches(
+    schema: &Schema,
+    record: &Record,
+    filter: &FilterExpression,
+) -> Result<bool, CacheError> {
+    filter_matches(schema, record, filter, false)
+}
+
+/// Evaluates `filter`, or its negation if `negated`, pushing `NOT` down like `QueryPlanner` does,
+/// so comparisons involving `null` never match, negated or not.
+fn filter_matches(
+    schema: &Schema,
+    record: &Record,
+    filter: &FilterExpression,
+    negated: bool,
+) -> Result<bool, CacheError> {
+    match filter {
+        FilterExpression::Simple(field_name, operator, value) => {
+            let (index, definition) = schema.get_field_index(field_name)?;
+            let field = &record.values[index];
+            let to_field = |value: &Value| {
+                json_value_to_field(value.clone(), definition.typ, definition.nullable)
+            };
+            let not_equal = |value: &Field| *field != Field::Null && field != value;
+
+            Ok(match operator {
+                Operator::EQ | Operator::NE => {
+                    let value = to_field(value)?;
+                    if (*operator == Operator::EQ) != negated {
+                        field == &value
+                    } else {
+                        not_equal(&value)
+                    }
+                }
+                Operator::In => {
+                    let Value::Array(values) = value else {
+                        return Err(PlanError::ExpectedArray(field_name.clone()).into());
+                    };
+                    let values = values.iter().map(to_field).collect::<Result<Vec<_>, _>>()?;
+                    if negated {
+                        values.iter().all(not_equal)
+                    } else {
+                        values.contains(field)
+                    }
+                }
+                Operator::IsNull => {
+                    let Value::Bool(is_null) = value else {
+                        return Err(PlanError::ExpectedBool(field_name.clone()).into());
+                    };
+                    (*field == Field::Null) == (*is_null != negated)
+                }
+                Operator::LT | Operator::LTE | Operator::GT | Operator::GTE => {
+                    let value = to_field(value)?;
+                    if *field == Field::Null || value == Field::Null {
+                        return Ok(false);
+                    }
+                    let matches = match operator {
+                        Operator::LT => field < &value,
+                        Operator::LTE => field <= &value,
+                        Operator::GT => field > &value,
+                        _ => field >= &value,
+                    };
+                    matches != negated
+                }
+                Operator::Contains | Operator::MatchesAny | Operator::MatchesAll if negated => {
+                    return Err(PlanError::CannotNegateFullTextFilter.into());
+                }
+                Operator::Contains => {
+                    let value = to_field(value)?;
+                    text(field).unicode_words().any(|word| word == text(&value))
+                }
+                Operator::MatchesAny => {
+                    let value = to_field(value)?;
+                    let words = text(field).unicode_words().collect::<Vec<_>>();
+                    text(&value)
+                        .unicode_words()
+                        .any(|token| words.contains(&token))
+                }
+                Operator::MatchesAll => {
+                    let value = to_field(value)?;
+                    let words = text(field).unicode_words().collect::<Vec<_>>();
+                    text(&value)
+                        .unicode_words()
+                        .all(|token| words.contains(&token))
+                }
+            })
+        }
+        FilterExpression::And(filters) if !negated => {
+            for filter in filters {
+                if !filter_matches(schema, record, filter, false)? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        }
+        // De Morgan's laws.
+        FilterExpression::Or(filters) if negated => {
+            for filter in filters {
+                if !filter_matches(schema, record, filter, true)? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        }
+        FilterExpression::And(filters) | FilterExpression::Or(filters) => {
+            for filter in filters {
+                if filter_matches(schema, record, filter, negated)? {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        }
+        FilterExpression::Not(filter) => filter_matches(schema, record, filter, !negated),
+    }
+}
+
+fn text(field: &Field) -> &str {
+    match field {
+        Field::String(string) | Field::Text(string) => string,
+        _ => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::{
+        serde_json::{self, json, Value},
+        types::{FieldDefinition, FieldType, SourceDefinition},
+    };
+
+    use crate::cache::{
+        expression::{SortDirection, SortOption},
+        index,
+        lmdb::tests::utils::{create_cache, insert_rec_1},
+        test_utils::{query_from_filter, schema_1},
+        RwCache,
+    };
+
+    use super::*;
+
+    fn create_reader() -> CacheReader {
+        let (mut cache, indexing_thread_pool, _, _) = create_cache(schema_1);
+        insert_rec_1(&mut cache, (1, Some("public".to_string()), Some(10)));
+        insert_rec_1(&mut cache, (2, Some("private".to_string()), Some(20)));
+        cache.commit(&Default::default()).unwrap();
+        indexing_thread_pool.lock().wait_until_catchup();
+        CacheReader::new(Box::new(cache))
+    }
+
+    fn access_filter(filter: Option<FilterExpression>, fields: &[&str]) -> AccessFilter {
+        AccessFilter {
+            filter,
+            fields: fields.iter().map(|field| field.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_restricted_fields_are_masked() {
+        let reader = create_reader();
+        let access_filter = access_filter(None, &["c"]);
+
+        let records = reader
+            .query(&mut QueryExpression::default(), access_filter.clone())
+            .unwrap();
+        assert_eq!(records.len(), 2);
+        assert!(records
+            .iter()
+            .all(|record| record.record.values[2] == Field::Null));
+
+        let key = index::get_primary_key(&[0], &[Field::Int(1)]);
+        let record = reader.get(&key, &access_filter).unwrap();
+        assert_eq!(
+            record.record.values,
+            vec![
+                Field::Int(1),
+                Field::String("public".to_string()),
+                Field::Null
+            ]
+        );
+    }
+
+    #[test]
+    fn test_query_on_restricted_field_is_rejected() {
+        let reader = create_reader();
+        let access_filter = access_filter(None, &["c"]);
+
+        let mut query = query_from_filter(FilterExpression::Simple(
+            "c".into(),
+            Operator::GT,
+            15.into(),
+        ));
+        assert!(matches!(
+            reader.query(&mut query, access_filter.clone()),
+            Err(CacheError::RestrictedField(field)) if field == "c"
+        ));
+        assert!(matches!(
+            reader.count(&mut query, access_filter.clone()),
+            Err(CacheError::RestrictedField(_))
+        ));
+
+        let mut query = QueryExpression::new(
+            None,
+            vec![SortOption::new("c".into(), SortDirection::Descending)],
+            None,
+            Default::default(),
+        );
+        assert!(matches!(
+            reader.query(&mut query, access_filter),
+            Err(CacheError::RestrictedField(_))
+        ));
+    }
+
+    #[test]
+    fn test_get_checks_row_filter() {
+        let reader = create_reader();
+        let access_filter = access_filter(
+            Some(FilterExpression::Simple(
+                "b".into(),
+                Operator::EQ,
+                Value::from("public"),
+            )),
+            &[],
+        );
+
+        let key = index::get_primary_key(&[0], &[Field::Int(1)]);
+        assert!(reader.get(&key, &access_filter).is_ok());
+        let key = index::get_primary_key(&[0], &[Field::Int(2)]);
+        assert!(matches!(
+            reader.get(&key, &access_filter),
+            Err(CacheError::PrimaryKeyNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_aggregate_applies_access_filter() {
+        let reader = create_reader();
+        let expression = serde_json::from_value::<AggregateExpression>(json!({
+            "$group_by": ["b"],
+            "$aggregates": {"total": {"$sum": "c"}}
+        }))
+        .unwrap();
+
+        let result = reader
+            .aggregate(&expression, access_filter(None, &[]))
+            .unwrap();
+        assert_eq!(
+            result.rows,
+            vec![
+                vec![Field::String("private".into()), Field::Int(20)],
+                vec![Field::String("public".into()), Field::Int(10)],
+            ]
+        );
+
+        let public_only = access_filter(
+            Some(FilterExpression::Simple(
+                "b".into(),
+                Operator::EQ,
+                Value::from("public"),
+            )),
+            &[],
+        );
+        let result = reader.aggregate(&expression, public_only).unwrap();
+        assert_eq!(
+            result.rows,
+            vec![vec![Field::String("public".into()), Field::Int(10)]]
+        );
+
+        assert!(matches!(
+            reader.aggregate(&expression, access_filter(None, &["c"])),
+            Err(CacheError::RestrictedField(field)) if field == "c"
+        ));
+    }
+
+    #[test]
+    fn test_record_matches() {
+        let schema = Schema::default()
+            .field(
+                FieldDefinition::new(
+                    "text".into(),
+                    FieldType::Text,
+                    false,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            )
+            .clone();
+        let record = Record::new(vec![Field::Text("the quick brown fox".into())]);
+        let matches = |operator, value: &str| {
+            record_matches(
+                &schema,
+                &record,
+                &FilterExpression::Simple("text".into(), operator, value.into()),
+            )
+            .unwrap()
+        };
+
+        assert!(matches(Operator::Contains, "quick"));
+        assert!(!matches(Operator::Contains, "qui"));
+        assert!(matches(Operator::MatchesAny, "slow brown"));
+        assert!(!matches(Operator::MatchesAll, "slow brown"));
+        assert!(matches(Operator::MatchesAll, "fox quick"));
+        assert!(matches(Operator::GT, "a"));
+        assert!(!matches(Operator::EQ, "the"));
+    }
+
+    #[test]
+    fn test_record_matches_or_not() {
+        let schema = schema_1().0;
+        let record = Record::new(vec![Field::Int(1), Field::Null, Field::Int(10)]);
+        let matches = |filter: Value| {
+            record_matches(&schema, &record, &serde_json::from_value(filter).unwrap()).unwrap()
+        };
+
+        assert!(matches(json!({"$or": [{"a": 2}, {"c": 10}]})));
+        assert!(!matches(json!({"$or": [{"a": 2}, {"c": 11}]})));
+        assert!(matches(json!({"a": {"$in": [3, 1]}})));
+        assert!(!matches(json!({"a": {"$in": []}})));
+        assert!(matches(json!({"a": {"$ne": 2}})));
+        assert!(!matches(json!({"b": {"$ne": "x"}})));
+        assert!(matches(json!({"b": {"$null": true}})));
+        assert!(!matches(json!({"a": {"$null": true}})));
+        assert!(matches(json!({"$not": {"a": {"$gt": 1}}})));
+        // Comparisons with `null` never match, even negated.
+        assert!(!matches(json!({"$not": {"b": {"$lt": "x"}}})));
+        assert!(matches(json!({"$not": {"a": {"$in": [2, 3]}}})));
+        assert!(matches(
+            json!({"$not": {"$and": [{"a": 1}, {"$or": [{"c": 1}, {"c": 2}]}]}})
+        ));
+    }
+}
@@ -139,11 +139,18 @@ impl SimpleOrchestrator {
             let security = self.config.api.api_security.clone();
             let cache_endpoints_for_rest = cache_endpoints.clone();
             let shutdown_for_rest = shutdown.create_shutdown_future();
+            let operations_receiver_for_rest =
+                if flags.push_events.unwrap_or_else(default_push_events) {
+                    operations_receiver.as_ref().map(|r| r.resubscribe())
+                } else {
+                    None
+                };
             let api_server = rest::ApiServer::new(rest_config, security, default_max_num_records);
             let api_server = api_server
                 .run(
                     cache_endpoints_for_rest,
                     shutdown_for_rest,
+                    operations_receiver_for_rest,
                     self.labels.clone(),
                 )
                 .await
@@ -316,16 +323,12 @@ impl SimpleOrchestrator {
 
     pub fn generate_token(&self, ttl_in_secs: Option<i32>) -> Result<String, OrchestrationError> {
         if let Some(api_security) = get_api_security(self.config.api.api_security.clone()) {
-            match api_security {
-                dozer_types::models::api_security::ApiSecurity::Jwt(secret) => {
-                    let auth = Authorizer::new(&secret, None, None);
-                    let duration = ttl_in_secs.map(|f| std::time::Duration::from_secs(f as u64));
-                    let token = auth
-                        .generate_token(Access::All, duration)
-                        .map_err(OrchestrationError::GenerateTokenFailed)?;
-                    return Ok(token);
-                }
-            }
+            let auth = Authorizer::from(&api_security);
+            let duration = ttl_in_secs.map(|f| std::time::Duration::from_secs(f as u64));
+            let token = auth
+                .generate_token(Access::All, duration)
+                .map_err(OrchestrationError::GenerateTokenFailed)?;
+            return Ok(token);
         }
         Err(OrchestrationError::MissingSecurityConfig)
     }
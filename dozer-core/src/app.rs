@@ -1,4 +1,6 @@
-use dozer_types::models::flags::{EnableProbabilisticOptimizations, Flags};
+use dozer_types::models::flags::{
+    default_max_recursive_cte_depth, EnableProbabilisticOptimizations, Flags,
+};
 use dozer_types::node::NodeHandle;
 
 use crate::appsource::{self, AppSourceManager};
@@ -104,12 +106,16 @@ impl AppPipeline {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PipelineFlags {
     pub enable_probabilistic_optimizations: EnableProbabilisticOptimizations,
+    pub max_recursive_cte_depth: u32,
 }
 
 impl From<&Flags> for PipelineFlags {
     fn from(flags: &Flags) -> Self {
         Self {
             enable_probabilistic_optimizations: flags.enable_probabilistic_optimizations.clone(),
+            max_recursive_cte_depth: flags
+                .max_recursive_cte_depth
+                .unwrap_or_else(default_max_recursive_cte_depth),
         }
     }
 }
@@ -9,7 +9,7 @@ use daggy::{
 };
 use dozer_types::{
     log::warn,
-    node::{NodeHandle, OpIdentifier},
+    node::{NodeHandle, SourceCheckpoint},
 };
 
 use crate::{
@@ -34,7 +34,7 @@ pub struct NodeType {
 pub enum NodeKind {
     Source {
         source: Box<dyn Source>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourceCheckpoint>,
     },
     Processor(Box<dyn Processor>),
     Sink(Box<dyn Sink>),
@@ -177,7 +177,16 @@ impl BuilderDag {
                             .map_err(ExecutionError::Sink)?;
                     }
 
-                    let last_checkpoint = source_op_ids.remove(&node.handle);
+                    // The connector state is only valid for the commit it was checkpointed with.
+                    let last_checkpoint = source_op_ids.remove(&node.handle).map(|id| {
+                        let state = checkpoint
+                            .get_source_state(&node.handle)
+                            .ok()
+                            .flatten()
+                            .filter(|checkpoint| checkpoint.id == id)
+                            .and_then(|checkpoint| checkpoint.state);
+                        SourceCheckpoint::new(id, state)
+                    });
                     NodeType {
                         handle: node.handle,
                         kind: NodeKind::Source {
@@ -10,7 +10,7 @@ use dozer_types::{
     bincode,
     log::info,
     models::app_config::DataStorage,
-    node::{NodeHandle, OpIdentifier, SourceState, SourceStates},
+    node::{NodeHandle, SourceCheckpoint, SourceState, SourceStates},
     tonic::codegen::tokio_stream::StreamExt,
 };
 use std::sync::Arc;
@@ -101,7 +101,7 @@ impl OptionCheckpoint {
     pub fn get_source_state(
         &self,
         node_handle: &NodeHandle,
-    ) -> Result<Option<OpIdentifier>, ExecutionError> {
+    ) -> Result<Option<SourceCheckpoint>, ExecutionError> {
         let Some(checkpoint) = self.checkpoint.as_ref() else {
             return Ok(None);
         };
@@ -114,7 +114,7 @@ impl OptionCheckpoint {
             SourceState::NonRestartable => {
                 Err(ExecutionError::SourceCannotRestart(node_handle.clone()))
             }
-            SourceState::Restartable(id) => Ok(Some(*id)),
+            SourceState::Restartable(checkpoint) => Ok(Some(checkpoint.clone())),
         }
     }
 
@@ -6,7 +6,7 @@ use dozer_log::tokio::{
     sync::mpsc::{channel, Receiver, Sender},
 };
 use dozer_types::{
-    log::debug, models::ingestion_types::TransactionInfo, node::OpIdentifier,
+    log::debug, models::ingestion_types::TransactionInfo, node::SourceCheckpoint,
     types::OperationWithId,
 };
 use dozer_types::{models::ingestion_types::IngestionMessage, node::SourceState};
@@ -99,9 +99,10 @@ impl<F: Future + Unpin> Node for SourceNode<F> {
                                 .send_op(OperationWithId { op, id }, port)?;
                         }
                         IngestionMessage::TransactionInfo(info) => match info {
-                            TransactionInfo::Commit { id } => {
+                            TransactionInfo::Commit { id, state } => {
                                 if let Some(id) = id {
-                                    source.state = SourceState::Restartable(id);
+                                    source.state =
+                                        SourceState::Restartable(SourceCheckpoint::new(id, state));
                                 } else {
                                     source.state = SourceState::NonRestartable;
                                 }
@@ -158,7 +159,7 @@ struct RunningSource {
 #[derive(Debug)]
 struct SourceRunner {
     source: Box<dyn Source>,
-    last_checkpoint: Option<OpIdentifier>,
+    last_checkpoint: Option<SourceCheckpoint>,
     sender: Sender<(PortHandle, IngestionMessage)>,
 }
 
@@ -5,7 +5,7 @@ use dozer_log::storage::{Object, Queue};
 use dozer_log::tokio::sync::mpsc::Sender;
 use dozer_types::errors::internal::BoxedError;
 use dozer_types::models::ingestion_types::IngestionMessage;
-use dozer_types::node::OpIdentifier;
+use dozer_types::node::{OpIdentifier, SourceCheckpoint};
 use dozer_types::serde::{Deserialize, Serialize};
 use dozer_types::tonic::async_trait;
 use dozer_types::types::{OperationWithId, Schema};
@@ -62,7 +62,7 @@ pub trait Source: Send + Sync + Debug {
     async fn start(
         &mut self,
         sender: Sender<(PortHandle, IngestionMessage)>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourceCheckpoint>,
     ) -> Result<(), BoxedError>;
 }
 
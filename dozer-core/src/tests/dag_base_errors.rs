@@ -12,7 +12,7 @@ use dozer_log::storage::{Object, Queue};
 use dozer_log::tokio::sync::mpsc::Sender;
 use dozer_types::errors::internal::BoxedError;
 use dozer_types::models::ingestion_types::{IngestionMessage, TransactionInfo};
-use dozer_types::node::{NodeHandle, OpIdentifier};
+use dozer_types::node::{NodeHandle, OpIdentifier, SourceCheckpoint};
 use dozer_types::tonic::async_trait;
 use dozer_types::types::{
     Field, FieldDefinition, FieldType, Operation, OperationWithId, Record, Schema, SourceDefinition,
@@ -336,7 +336,7 @@ impl Source for ErrGeneratorSource {
     async fn start(
         &mut self,
         sender: Sender<(PortHandle, IngestionMessage)>,
-        _last_checkpoint: Option<OpIdentifier>,
+        _last_checkpoint: Option<SourceCheckpoint>,
     ) -> Result<(), BoxedError> {
         for n in 1..(self.count + 1) {
             if n == self.err_at {
@@ -363,6 +363,7 @@ impl Source for ErrGeneratorSource {
                     GENERATOR_SOURCE_OUTPUT_PORT,
                     IngestionMessage::TransactionInfo(TransactionInfo::Commit {
                         id: Some(OpIdentifier::new(0, n)),
+                        state: None,
                     }),
                 ))
                 .await?;
@@ -3,7 +3,7 @@ use crate::DEFAULT_PORT_HANDLE;
 use dozer_log::tokio::{self, sync::mpsc::Sender};
 use dozer_types::errors::internal::BoxedError;
 use dozer_types::models::ingestion_types::{IngestionMessage, TransactionInfo};
-use dozer_types::node::OpIdentifier;
+use dozer_types::node::{OpIdentifier, SourceCheckpoint};
 use dozer_types::tonic::async_trait;
 use dozer_types::types::{
     Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
@@ -100,10 +100,10 @@ impl Source for GeneratorSource {
     async fn start(
         &mut self,
         sender: Sender<(PortHandle, IngestionMessage)>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourceCheckpoint>,
     ) -> Result<(), BoxedError> {
         let start = last_checkpoint
-            .map(|checkpoint| checkpoint.seq_in_tx + 1)
+            .map(|checkpoint| checkpoint.id.seq_in_tx + 1)
             .unwrap_or(0);
         for n in start..(start + self.count) {
             sender
@@ -126,6 +126,7 @@ impl Source for GeneratorSource {
                     GENERATOR_SOURCE_OUTPUT_PORT,
                     IngestionMessage::TransactionInfo(TransactionInfo::Commit {
                         id: Some(OpIdentifier::new(0, n)),
+                        state: None,
                     }),
                 ))
                 .await?;
@@ -242,7 +243,7 @@ impl Source for DualPortGeneratorSource {
     async fn start(
         &mut self,
         sender: Sender<(PortHandle, IngestionMessage)>,
-        _last_checkpoint: Option<OpIdentifier>,
+        _last_checkpoint: Option<SourceCheckpoint>,
     ) -> Result<(), BoxedError> {
         for n in 1..(self.count + 1) {
             sender
@@ -280,6 +281,7 @@ impl Source for DualPortGeneratorSource {
                     DUAL_PORT_GENERATOR_SOURCE_OUTPUT_PORT_1,
                     IngestionMessage::TransactionInfo(TransactionInfo::Commit {
                         id: Some(OpIdentifier::new(0, n)),
+                        state: None,
                     }),
                 ))
                 .await?;
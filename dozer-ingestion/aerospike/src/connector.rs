@@ -4,7 +4,7 @@ use dozer_ingestion_connector::dozer_types::models::connection::AerospikeConnect
 use dozer_ingestion_connector::dozer_types::models::ingestion_types::{
     IngestionMessage, TransactionInfo,
 };
-use dozer_ingestion_connector::dozer_types::node::OpIdentifier;
+use dozer_ingestion_connector::dozer_types::node::SourceCheckpoint;
 use dozer_ingestion_connector::dozer_types::types::Operation::Insert;
 use dozer_ingestion_connector::dozer_types::types::{Field, FieldDefinition, FieldType, Schema};
 use dozer_ingestion_connector::{
@@ -158,7 +158,7 @@ impl Connector for AerospikeConnector {
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        _last_checkpoint: Option<OpIdentifier>,
+        _last_checkpoint: Option<SourceCheckpoint>,
     ) -> Result<(), BoxedError> {
         ingestor
             .handle_message(IngestionMessage::TransactionInfo(
@@ -264,6 +264,7 @@ async fn process_event(
         let _ = ingestor
             .handle_message(IngestionMessage::TransactionInfo(TransactionInfo::Commit {
                 id: None,
+                state: None,
             }))
             .await;
     } else {
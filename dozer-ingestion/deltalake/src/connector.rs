@@ -3,8 +3,8 @@ use crate::schema_helper::SchemaHelper;
 use dozer_ingestion_connector::{
     async_trait,
     dozer_types::{
-        errors::internal::BoxedError, models::ingestion_types::DeltaLakeConfig, node::OpIdentifier,
-        types::FieldType,
+        errors::internal::BoxedError, models::ingestion_types::DeltaLakeConfig,
+        node::SourceCheckpoint, types::FieldType,
     },
     utils::{ListOrFilterColumns, TableNotFound},
     Connector, Ingestor, SourceSchemaResult, TableIdentifier, TableInfo,
@@ -117,10 +117,9 @@ impl Connector for DeltaLakeConnector {
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourceCheckpoint>,
     ) -> Result<(), BoxedError> {
-        assert!(last_checkpoint.is_none());
         let reader = DeltaLakeReader::new(self.config.clone());
-        reader.read(&tables, ingestor).await
+        reader.read(&tables, ingestor, last_checkpoint).await
     }
 }
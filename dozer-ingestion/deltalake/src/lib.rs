@@ -1,6 +1,35 @@
+use dozer_ingestion_connector::dozer_types::{
+    bincode,
+    thiserror::{self, Error},
+};
+
 mod connector;
 mod reader;
 mod schema_helper;
 mod test;
 
 pub use connector::DeltaLakeConnector;
+
+#[derive(Error, Debug)]
+pub enum DeltaLakeError {
+    #[error("Change data file {0} has no `_change_type` column")]
+    MissingChangeType(String),
+
+    #[error("Unknown change type {0:?} in change data file {1}")]
+    UnknownChangeType(String, String),
+
+    #[error("Checkpoint has the versions of {checkpointed} tables, but {configured} tables are configured")]
+    TableCountChanged {
+        checkpointed: usize,
+        configured: usize,
+    },
+
+    #[error("Failed to encode table versions: {0}")]
+    EncodeVersions(#[source] bincode::error::EncodeError),
+
+    #[error("Failed to decode checkpointed table versions: {0}")]
+    DecodeVersions(#[source] bincode::error::DecodeError),
+
+    #[error("Checkpoint doesn't have the table versions")]
+    MissingVersions,
+}
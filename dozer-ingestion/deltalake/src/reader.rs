@@ -1,11 +1,26 @@
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
+use deltalake::arrow::array::{new_null_array, Array, ArrayRef, StringArray};
+use deltalake::arrow::compute::cast;
+use deltalake::arrow::datatypes::SchemaRef;
+use deltalake::arrow::record_batch::RecordBatch;
 use deltalake::datafusion::prelude::SessionContext;
+use deltalake::kernel::Action;
+use deltalake::parquet::arrow::async_reader::{
+    ParquetObjectReader, ParquetRecordBatchStream, ParquetRecordBatchStreamBuilder,
+};
+use deltalake::table::PeekCommit;
+use deltalake::{DeltaTable, Path};
 use dozer_ingestion_connector::{
     dozer_types::{
         arrow_types::from_arrow::{map_schema_to_dozer, map_value_to_dozer_field},
+        bincode,
         errors::internal::BoxedError,
-        models::ingestion_types::{DeltaLakeConfig, IngestionMessage},
+        log::info,
+        models::ingestion_types::{DeltaLakeConfig, IngestionMessage, TransactionInfo},
+        node::{OpIdentifier, SourceCheckpoint},
         types::{Operation, Record},
     },
     futures::StreamExt,
@@ -14,6 +29,14 @@ use dozer_ingestion_connector::{
     Ingestor, TableInfo,
 };
 
+use crate::DeltaLakeError;
+
+/// How long to wait before polling the tables again when all of them are up to date.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Column of change data files that tells the kind of change of each row.
+const CHANGE_TYPE_COLUMN: &str = "_change_type";
+
 pub struct DeltaLakeReader {
     config: DeltaLakeConfig,
 }
@@ -23,60 +46,355 @@ impl DeltaLakeReader {
         Self { config }
     }
 
-    pub async fn read(&self, table: &[TableInfo], ingestor: &Ingestor) -> Result<(), BoxedError> {
-        for (table_index, table) in table.iter().enumerate() {
-            self.read_impl(table_index, table, ingestor).await?;
+    /// Snapshots the tables if there's no checkpoint, then tails their new versions.
+    ///
+    /// Commits are numbered, and the versions that have been ingested are checkpointed in their state, see `encode_versions`.
+    pub async fn read(
+        &self,
+        tables: &[TableInfo],
+        ingestor: &Ingestor,
+        last_checkpoint: Option<SourceCheckpoint>,
+    ) -> Result<(), BoxedError> {
+        if tables.is_empty() {
+            return Ok(());
+        }
+
+        let mut delta_tables = vec![];
+        for table in tables {
+            let table_path = table_path(&self.config, &table.name)?;
+            delta_tables.push(deltalake::open_table(table_path).await?);
+        }
+
+        let (mut versions, mut commit) = if let Some(checkpoint) = last_checkpoint {
+            let state = checkpoint.state.ok_or(DeltaLakeError::MissingVersions)?;
+            let versions = decode_versions(&state, tables.len())?;
+            info!("Resuming Delta Lake tables after versions {versions:?}");
+            (versions, checkpoint.id.txid)
+        } else {
+            if ingestor
+                .handle_message(IngestionMessage::TransactionInfo(
+                    TransactionInfo::SnapshottingStarted,
+                ))
+                .await
+                .is_err()
+            {
+                // If receiving end is closed, we should stop the replication
+                return Ok(());
+            }
+            let mut versions = vec![];
+            for (table_index, (table, delta_table)) in tables.iter().zip(&delta_tables).enumerate()
+            {
+                if !self
+                    .snapshot(table_index, table, delta_table, ingestor)
+                    .await?
+                {
+                    return Ok(());
+                }
+                versions.push(delta_table.version());
+            }
+            let id = Some(OpIdentifier::new(0, 0));
+            for info in [
+                TransactionInfo::SnapshottingDone { id },
+                TransactionInfo::Commit {
+                    id,
+                    state: Some(encode_versions(&versions)?),
+                },
+            ] {
+                if ingestor
+                    .handle_message(IngestionMessage::TransactionInfo(info))
+                    .await
+                    .is_err()
+                {
+                    return Ok(());
+                }
+            }
+            (versions, 0)
+        };
+
+        let mut schemas = vec![];
+        for (table, delta_table) in tables.iter().zip(&delta_tables) {
+            schemas.push(selected_schema(table, delta_table)?);
+        }
+
+        loop {
+            let mut up_to_date = true;
+            for (table_index, delta_table) in delta_tables.iter().enumerate() {
+                let PeekCommit::New(version, actions) =
+                    delta_table.peek_next_commit(versions[table_index]).await?
+                else {
+                    continue;
+                };
+                up_to_date = false;
+
+                if !read_commit(
+                    delta_table,
+                    &actions,
+                    &schemas[table_index],
+                    table_index,
+                    ingestor,
+                )
+                .await?
+                {
+                    return Ok(());
+                }
+
+                // The version is only committed once all of its files have been ingested.
+                versions[table_index] = version;
+                commit += 1;
+                if ingestor
+                    .handle_message(IngestionMessage::TransactionInfo(TransactionInfo::Commit {
+                        id: Some(OpIdentifier::new(commit, 0)),
+                        state: Some(encode_versions(&versions)?),
+                    }))
+                    .await
+                    .is_err()
+                {
+                    return Ok(());
+                }
+            }
+
+            if up_to_date {
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
         }
-        Ok(())
     }
 
-    async fn read_impl(
+    /// Inserts every row of the loaded version of `delta_table`. Returns `false` if the receiving end is closed.
+    async fn snapshot(
         &self,
         table_index: usize,
         table: &TableInfo,
+        delta_table: &DeltaTable,
         ingestor: &Ingestor,
-    ) -> Result<(), BoxedError> {
-        let table_path = table_path(&self.config, &table.name)?;
+    ) -> Result<bool, BoxedError> {
         let ctx = SessionContext::new();
-        let delta_table = deltalake::open_table(table_path).await?;
         let cols: Vec<&str> = table.column_names.iter().map(|c| c.as_str()).collect();
         let data = ctx
-            .read_table(Arc::new(delta_table))?
+            .read_table(Arc::new(delta_table.clone()))?
             .select_columns(&cols)?
             .execute_stream()
             .await?;
 
         tokio::pin!(data);
-        while let Some(Ok(batch)) = data.next().await {
-            let batch_schema = batch.schema();
-            let dozer_schema = map_schema_to_dozer(&batch_schema)?;
-            for row in 0..batch.num_rows() {
-                let fields = batch
-                    .columns()
-                    .iter()
-                    .enumerate()
-                    .map(|(col, column)| {
-                        map_value_to_dozer_field(column, row, cols[col], &dozer_schema).unwrap()
-                    })
-                    .collect::<Vec<_>>();
-
-                ingestor
-                    .handle_message(IngestionMessage::OperationEvent {
-                        table_index,
-                        op: Operation::Insert {
-                            new: Record {
-                                values: fields,
-                                lifetime: None,
-                            },
-                        },
-                        id: None,
-                    })
-                    .await
-                    .unwrap();
+        while let Some(batch) = data.next().await {
+            let ops = batch_to_records(&batch?)?
+                .into_iter()
+                .map(|new| Operation::Insert { new });
+            if !ingest(ingestor, table_index, ops).await {
+                return Ok(false);
             }
         }
-        Ok(())
+        Ok(true)
+    }
+}
+
+/// Sends `ops` of the table at `table_index` to `ingestor`. Returns `false` if the receiving end is closed.
+async fn ingest(
+    ingestor: &Ingestor,
+    table_index: usize,
+    ops: impl IntoIterator<Item = Operation>,
+) -> bool {
+    for op in ops {
+        if ingestor
+            .handle_message(IngestionMessage::OperationEvent {
+                table_index,
+                op,
+                id: None,
+            })
+            .await
+            .is_err()
+        {
+            return false;
+        }
+    }
+    true
+}
+
+/// Ingests the changes of a commit, batch by batch as its files are read. Returns `false` if the receiving end is closed.
+///
+/// If the commit has change data files (the table has change data feed enabled), they are the source of truth.
+/// Otherwise the rows of removed files are deleted and the rows of added files are inserted.
+/// Files added or removed without data change, for example by `OPTIMIZE`, are ignored.
+///
+/// Updates are emitted as a `Delete` of the pre-image followed by an `Insert` of the post-image,
+/// because the change data feed doesn't pair them.
+async fn read_commit(
+    delta_table: &DeltaTable,
+    actions: &[Action],
+    schema: &SchemaRef,
+    table_index: usize,
+    ingestor: &Ingestor,
+) -> Result<bool, BoxedError> {
+    let cdc_files = actions
+        .iter()
+        .filter_map(|action| match action {
+            Action::Cdc(cdc) => Some(cdc),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if !cdc_files.is_empty() {
+        for cdc in cdc_files {
+            let mut batches = read_file(delta_table, &cdc.path).await?;
+            while let Some(batch) = batches.next().await {
+                let batch = batch?;
+                let change_types = batch
+                    .column_by_name(CHANGE_TYPE_COLUMN)
+                    .and_then(|column| column.as_any().downcast_ref::<StringArray>())
+                    .ok_or_else(|| DeltaLakeError::MissingChangeType(cdc.path.clone()))?
+                    .clone();
+                let records =
+                    batch_to_records(&project_batch(&batch, &cdc.partition_values, schema)?)?;
+                let mut ops = vec![];
+                for (row, record) in records.into_iter().enumerate() {
+                    let op = match change_types.value(row) {
+                        "insert" | "update_postimage" => Operation::Insert { new: record },
+                        "delete" | "update_preimage" => Operation::Delete { old: record },
+                        change_type => {
+                            return Err(DeltaLakeError::UnknownChangeType(
+                                change_type.to_string(),
+                                cdc.path.clone(),
+                            )
+                            .into())
+                        }
+                    };
+                    ops.push(op);
+                }
+                if !ingest(ingestor, table_index, ops).await {
+                    return Ok(false);
+                }
+            }
+        }
+        return Ok(true);
+    }
+
+    for action in actions {
+        if let Action::Remove(remove) = action {
+            if !remove.data_change {
+                continue;
+            }
+            let partition_values = remove.partition_values.clone().unwrap_or_default();
+            let mut batches = read_file(delta_table, &remove.path).await?;
+            while let Some(batch) = batches.next().await {
+                let ops = batch_to_records(&project_batch(&batch?, &partition_values, schema)?)?
+                    .into_iter()
+                    .map(|old| Operation::Delete { old });
+                if !ingest(ingestor, table_index, ops).await {
+                    return Ok(false);
+                }
+            }
+        }
+    }
+    for action in actions {
+        if let Action::Add(add) = action {
+            if !add.data_change {
+                continue;
+            }
+            let mut batches = read_file(delta_table, &add.path).await?;
+            while let Some(batch) = batches.next().await {
+                let ops =
+                    batch_to_records(&project_batch(&batch?, &add.partition_values, schema)?)?
+                        .into_iter()
+                        .map(|new| Operation::Insert { new });
+                if !ingest(ingestor, table_index, ops).await {
+                    return Ok(false);
+                }
+            }
+        }
+    }
+    Ok(true)
+}
+
+/// Opens a parquet file of `delta_table` as a stream of batches. `path` is relative to the table root, as in the log.
+async fn read_file(
+    delta_table: &DeltaTable,
+    path: &str,
+) -> Result<ParquetRecordBatchStream<ParquetObjectReader>, BoxedError> {
+    let store = delta_table.object_store();
+    let location = Path::from_url_path(path)?;
+    let meta = store.head(&location).await?;
+    let reader = ParquetObjectReader::new(store, meta);
+    Ok(ParquetRecordBatchStreamBuilder::new(reader)
+        .await?
+        .build()?)
+}
+
+/// Arrow schema of the selected columns of `table`, including partition columns.
+fn selected_schema(table: &TableInfo, delta_table: &DeltaTable) -> Result<SchemaRef, BoxedError> {
+    let ctx = SessionContext::new();
+    let cols: Vec<&str> = table.column_names.iter().map(|c| c.as_str()).collect();
+    let schema = ctx
+        .read_table(Arc::new(delta_table.clone()))?
+        .select_columns(&cols)?
+        .schema()
+        .clone();
+    Ok(schema.into())
+}
+
+/// Projects a batch read from a data file onto `schema`.
+///
+/// Partition columns aren't stored in data files, so they're filled with the file's partition values.
+/// Columns added to the table after the file was written are filled with `null`.
+fn project_batch(
+    batch: &RecordBatch,
+    partition_values: &HashMap<String, Option<String>>,
+    schema: &SchemaRef,
+) -> Result<RecordBatch, BoxedError> {
+    let num_rows = batch.num_rows();
+    let mut columns: Vec<ArrayRef> = vec![];
+    for field in schema.fields() {
+        let column = if let Some(column) = batch.column_by_name(field.name()) {
+            column.clone()
+        } else if let Some(value) = partition_values.get(field.name()) {
+            Arc::new(StringArray::from(vec![value.as_deref(); num_rows]))
+        } else {
+            new_null_array(field.data_type(), num_rows)
+        };
+        let column = if column.data_type() == field.data_type() {
+            column
+        } else {
+            cast(&column, field.data_type())?
+        };
+        columns.push(column);
+    }
+    Ok(RecordBatch::try_new(schema.clone(), columns)?)
+}
+
+fn batch_to_records(batch: &RecordBatch) -> Result<Vec<Record>, BoxedError> {
+    let batch_schema = batch.schema();
+    let dozer_schema = map_schema_to_dozer(&batch_schema)?;
+    let mut records = vec![];
+    for row in 0..batch.num_rows() {
+        let values = batch
+            .columns()
+            .iter()
+            .zip(batch_schema.fields())
+            .map(|(column, field)| {
+                map_value_to_dozer_field(column, row, field.name(), &dozer_schema)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        records.push(Record::new(values));
+    }
+    Ok(records)
+}
+
+/// Serializes the version of every table, to be checkpointed with a commit.
+pub(crate) fn encode_versions(versions: &[i64]) -> Result<Vec<u8>, DeltaLakeError> {
+    bincode::encode_to_vec(versions, bincode::config::legacy())
+        .map_err(DeltaLakeError::EncodeVersions)
+}
+
+/// Deserializes the versions of `num_tables` tables, rejecting checkpoints of a different number of tables.
+pub(crate) fn decode_versions(state: &[u8], num_tables: usize) -> Result<Vec<i64>, DeltaLakeError> {
+    let versions: Vec<i64> = bincode::decode_from_slice(state, bincode::config::legacy())
+        .map_err(DeltaLakeError::DecodeVersions)?
+        .0;
+    if versions.len() != num_tables {
+        return Err(DeltaLakeError::TableCountChanged {
+            checkpointed: versions.len(),
+            configured: num_tables,
+        });
     }
+    Ok(versions)
 }
 
 pub fn table_path(config: &DeltaLakeConfig, table_name: &str) -> Result<String, TableNotFound> {
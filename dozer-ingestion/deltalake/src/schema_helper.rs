@@ -40,6 +40,6 @@ impl SchemaHelper {
             .clone()
             .into();
         let schema = map_schema(arrow_schema, table)?;
-        Ok(SourceSchema::new(schema, CdcType::Nothing))
+        Ok(SourceSchema::new(schema, CdcType::FullChanges))
     }
 }
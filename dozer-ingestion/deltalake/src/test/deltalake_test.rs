@@ -1,11 +1,15 @@
-use crate::DeltaLakeConnector;
+use std::time::Duration;
+
+use crate::reader::{decode_versions, encode_versions};
+use crate::{DeltaLakeConnector, DeltaLakeError};
 use dozer_ingestion_connector::{
     dozer_types::{
-        models::ingestion_types::{DeltaLakeConfig, DeltaTable, IngestionMessage},
+        models::ingestion_types::{DeltaLakeConfig, DeltaTable, IngestionMessage, TransactionInfo},
+        node::{OpIdentifier, SourceCheckpoint},
         types::{Field, FieldType, Operation, SourceDefinition},
     },
     test_util::create_runtime_and_spawn_connector_all_tables,
-    tokio, Connector,
+    tokio, Connector, Ingestor,
 };
 
 #[tokio::test]
@@ -48,14 +52,104 @@ fn read_deltalake() {
     let fields = vec![Field::Int(0), Field::Int(1), Field::Int(2), Field::Int(4)];
     let mut values = vec![];
     for message in iterator {
-        if let IngestionMessage::OperationEvent {
-            op: Operation::Insert { new },
-            ..
-        } = message
-        {
-            values.extend(new.values);
+        match message {
+            IngestionMessage::OperationEvent {
+                op: Operation::Insert { new },
+                ..
+            } => values.extend(new.values),
+            // The snapshot is committed at the latest version, after which the connector tails the table.
+            IngestionMessage::TransactionInfo(TransactionInfo::Commit { id, state }) => {
+                assert_eq!(id, Some(OpIdentifier::new(0, 0)));
+                assert_eq!(state, Some(encode_versions(&[1]).unwrap()));
+                break;
+            }
+            _ => (),
         }
     }
     values.sort();
     assert_eq!(fields, values);
 }
+
+#[tokio::test]
+async fn resume_deltalake_from_checkpoint() {
+    let path = "src/test/data/delta-0.8.0";
+    let table_name = "test_table";
+    let delta_table = DeltaTable {
+        path: path.to_string(),
+        name: table_name.to_string(),
+    };
+    let config = DeltaLakeConfig {
+        tables: vec![delta_table],
+    };
+
+    let mut connector = DeltaLakeConnector::new(config);
+    let tables = connector.list_tables().await.unwrap();
+    let tables = connector.list_columns(tables).await.unwrap();
+    let (ingestor, mut iterator) = Ingestor::initialize_channel(Default::default());
+    // Version 1 deletes `value = 3` by rewriting the file containing 2, 3 and 4.
+    tokio::spawn(async move {
+        connector
+            .start(
+                &ingestor,
+                tables,
+                Some(SourceCheckpoint::new(
+                    OpIdentifier::new(0, 0),
+                    Some(encode_versions(&[0]).unwrap()),
+                )),
+            )
+            .await
+            .unwrap();
+    });
+
+    let mut deleted = vec![];
+    let mut inserted = vec![];
+    loop {
+        match iterator
+            .next_timeout(Duration::from_secs(10))
+            .await
+            .unwrap()
+        {
+            IngestionMessage::OperationEvent {
+                op: Operation::Delete { old },
+                ..
+            } => deleted.extend(old.values),
+            IngestionMessage::OperationEvent {
+                op: Operation::Insert { new },
+                ..
+            } => inserted.extend(new.values),
+            IngestionMessage::TransactionInfo(TransactionInfo::Commit { id, state }) => {
+                assert_eq!(id, Some(OpIdentifier::new(1, 0)));
+                assert_eq!(state, Some(encode_versions(&[1]).unwrap()));
+                break;
+            }
+            message => panic!("unexpected message {message:?}"),
+        }
+    }
+    deleted.sort();
+    inserted.sort();
+    assert_eq!(deleted, vec![Field::Int(2), Field::Int(3), Field::Int(4)]);
+    assert_eq!(inserted, vec![Field::Int(2), Field::Int(4)]);
+}
+
+#[test]
+fn versions_round_trip() {
+    for versions in [vec![0], vec![i64::MAX], vec![3, 5], (0..1000).collect()] {
+        let state = encode_versions(&versions).unwrap();
+        assert_eq!(decode_versions(&state, versions.len()).unwrap(), versions);
+    }
+
+    // A table was added since the checkpoint.
+    let state = encode_versions(&[3, 5]).unwrap();
+    assert!(matches!(
+        decode_versions(&state, 3),
+        Err(DeltaLakeError::TableCountChanged {
+            checkpointed: 2,
+            configured: 3
+        })
+    ));
+
+    assert!(matches!(
+        decode_versions(&state[..state.len() - 1], 2),
+        Err(DeltaLakeError::DecodeVersions(_))
+    ));
+}
@@ -12,7 +12,7 @@ use dozer_ingestion_connector::{
             default_buffer_size, default_log_batch_size, default_timeout, IngestionMessage,
             NestedDozerConfig, NestedDozerLogOptions,
         },
-        node::OpIdentifier,
+        node::{OpIdentifier, SourceCheckpoint},
         serde_json,
         tonic::{async_trait, transport::Channel},
         types::{FieldType, Operation, Record, Schema},
@@ -130,7 +130,7 @@ impl Connector for NestedDozerConnector {
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourceCheckpoint>,
     ) -> Result<(), BoxedError> {
         let mut joinset = JoinSet::new();
         let (sender, mut receiver) = channel(100);
@@ -215,11 +215,11 @@ impl NestedDozerConnector {
 async fn read_table(
     table_index: usize,
     table_info: TableInfo,
-    last_checkpoint: Option<OpIdentifier>,
+    last_checkpoint: Option<SourceCheckpoint>,
     reader_builder: LogReaderBuilder,
     sender: Sender<IngestionMessage>,
 ) -> Result<(), NestedDozerConnectorError> {
-    let state = last_checkpoint.map(|state| state.seq_in_tx);
+    let state = last_checkpoint.map(|checkpoint| checkpoint.id.seq_in_tx);
     let starting_point = state.map(|pos| pos + 1).unwrap_or(0);
     let mut reader = reader_builder.build(starting_point);
     let schema = reader.schema.schema.clone();
@@ -3,7 +3,7 @@ use std::{str::FromStr, sync::Arc};
 
 use super::helper;
 use super::sender::{run, EthDetails};
-use dozer_ingestion_connector::dozer_types::node::OpIdentifier;
+use dozer_ingestion_connector::dozer_types::node::SourceCheckpoint;
 use dozer_ingestion_connector::utils::TableNotFound;
 use dozer_ingestion_connector::{
     async_trait,
@@ -242,7 +242,7 @@ impl Connector for EthLogConnector {
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        _last_checkpoint: Option<OpIdentifier>,
+        _last_checkpoint: Option<SourceCheckpoint>,
     ) -> Result<(), BoxedError> {
         // Start a new thread that interfaces with ETH node
         let wss_url = self.config.wss_url.to_owned();
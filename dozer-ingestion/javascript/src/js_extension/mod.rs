@@ -134,6 +134,7 @@ async fn send(ingestor: Ingestor, val: JsMessage) -> Result<(), Error> {
     let _ = ingestor
         .handle_message(IngestionMessage::TransactionInfo(TransactionInfo::Commit {
             id: None,
+            state: None,
         }))
         .await;
     Ok(())
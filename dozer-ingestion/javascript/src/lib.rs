@@ -5,7 +5,7 @@ use dozer_ingestion_connector::{
     dozer_types::{
         errors::internal::BoxedError,
         models::ingestion_types::{default_bootstrap_path, JavaScriptConfig},
-        node::OpIdentifier,
+        node::SourceCheckpoint,
         types::{FieldDefinition, FieldType, Schema, SourceDefinition},
     },
     tokio::runtime::Runtime,
@@ -82,7 +82,7 @@ impl Connector for JavaScriptConnector {
         &mut self,
         ingestor: &Ingestor,
         _tables: Vec<TableInfo>,
-        _last_checkpoint: Option<OpIdentifier>,
+        _last_checkpoint: Option<SourceCheckpoint>,
     ) -> Result<(), BoxedError> {
         let js_path = self
             .config
@@ -1,7 +1,9 @@
 use dozer_ingestion_connector::async_trait;
 use dozer_ingestion_connector::dozer_types::errors::internal::BoxedError;
-use dozer_ingestion_connector::dozer_types::models::ingestion_types::KafkaConfig;
-use dozer_ingestion_connector::dozer_types::node::OpIdentifier;
+use dozer_ingestion_connector::dozer_types::models::ingestion_types::{
+    KafkaConfig, KafkaTopicConfig,
+};
+use dozer_ingestion_connector::dozer_types::node::SourceCheckpoint;
 use dozer_ingestion_connector::dozer_types::types::FieldType;
 use dozer_ingestion_connector::Connector;
 use dozer_ingestion_connector::Ingestor;
@@ -35,9 +37,14 @@ impl KafkaConnector {
         table_names: Option<&[String]>,
     ) -> Result<Vec<SourceSchema>, KafkaError> {
         if let Some(schema_registry_url) = &self.config.schema_registry_url {
-            SchemaRegistryBasic::get_schema(table_names, schema_registry_url.clone()).await
+            SchemaRegistryBasic::get_schema(
+                table_names,
+                schema_registry_url.clone(),
+                &self.config.topics,
+            )
+            .await
         } else {
-            NoSchemaRegistryBasic::get_schema(table_names)
+            NoSchemaRegistryBasic::get_schema(table_names, &self.config.topics)
         }
     }
 }
@@ -135,7 +142,7 @@ impl Connector for KafkaConnector {
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourceCheckpoint>,
     ) -> Result<(), BoxedError> {
         let broker = self.config.broker.to_owned();
         run(
@@ -144,6 +151,7 @@ impl Connector for KafkaConnector {
             last_checkpoint,
             ingestor,
             &self.config.schema_registry_url,
+            self.config.topics.clone(),
         )
         .await
         .map_err(Into::into)
@@ -153,9 +161,10 @@ impl Connector for KafkaConnector {
 async fn run(
     broker: String,
     tables: Vec<TableInfo>,
-    last_checkpoint: Option<OpIdentifier>,
+    last_checkpoint: Option<SourceCheckpoint>,
     ingestor: &Ingestor,
     schema_registry_url: &Option<String>,
+    topics: Vec<KafkaTopicConfig>,
 ) -> Result<(), KafkaError> {
     let mut client_config = ClientConfig::new();
     client_config
@@ -163,7 +172,7 @@ async fn run(
         .set("group.id", "dozer")
         .set("enable.auto.commit", "true");
 
-    let consumer = StreamConsumerBasic::default();
+    let consumer = StreamConsumerBasic::new(topics);
     consumer
         .run(
             client_config,
@@ -0,0 +1,468 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use dozer_ingestion_connector::{
+    dozer_types::{
+        models::ingestion_types::{KafkaKeyMapping, KafkaTopicConfig},
+        serde_json,
+        types::{Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition},
+    },
+    CdcType, SourceSchema,
+};
+use schema_registry_converter::async_impl::schema_registry::SrSettings;
+
+use crate::{
+    formats::{decode_json, split_schema_id, RegisteredFormat},
+    schema_registry_basic::SchemaRegistryBasic,
+    KafkaError, KafkaSchemaError,
+};
+
+/// Name of the key column, unless it's named by the key mapping or the key schema.
+pub const KEY_FIELD: &str = "key";
+
+/// Name of the field of value schemas that aren't records.
+pub const VALUE_FIELD: &str = "value";
+
+/// Key mapping of `topic`, `Auto` if it isn't configured.
+pub fn key_mapping(topics: &[KafkaTopicConfig], topic: &str) -> KafkaKeyMapping {
+    topics
+        .iter()
+        .find(|config| config.name == topic)
+        .map(|config| config.key.clone())
+        .unwrap_or_default()
+}
+
+/// Builds the schema of a topic: the key column, if the key is stored, followed by the fields of the values.
+/// Returns whether the key is stored.
+///
+/// Topics with a primary key are upserted by `TopicDecoder`, which reads the primary key from the message key.
+/// Topics without one are append-only.
+///
+/// `key_fields` are the fields of the key schema registered for the topic, if any.
+pub fn topic_schema(
+    topic: &str,
+    key_mapping: &KafkaKeyMapping,
+    key_fields: Option<&[FieldDefinition]>,
+    value_fields: Vec<FieldDefinition>,
+) -> Result<(SourceSchema, bool), KafkaSchemaError> {
+    let position = |name: &str| value_fields.iter().position(|field| field.name == name);
+    let string_field = |name: &str| FieldDefinition {
+        name: name.to_string(),
+        typ: FieldType::String,
+        nullable: false,
+        source: SourceDefinition::Dynamic,
+    };
+
+    let (key_column, primary_index) = match key_mapping {
+        KafkaKeyMapping::Auto => match key_fields {
+            Some(fields)
+                if !fields.is_empty() && fields.iter().all(|f| position(&f.name).is_some()) =>
+            {
+                let primary_index = fields.iter().filter_map(|f| position(&f.name)).collect();
+                (None, primary_index)
+            }
+            Some([field]) => (Some(field.clone()), vec![0]),
+            Some(_) => return Err(KafkaSchemaError::KeyNotMappable(topic.to_string())),
+            None => (Some(string_field(KEY_FIELD)), vec![0]),
+        },
+        KafkaKeyMapping::Column(name) => {
+            let column = match key_fields {
+                Some([field]) => FieldDefinition {
+                    name: name.clone(),
+                    ..field.clone()
+                },
+                Some(_) => return Err(KafkaSchemaError::KeyNotMappable(topic.to_string())),
+                None => string_field(name),
+            };
+            (Some(column), vec![0])
+        }
+        KafkaKeyMapping::Fields(names) => {
+            let primary_index = names
+                .iter()
+                .map(|name| {
+                    position(name).ok_or_else(|| KafkaSchemaError::FieldNotFound(name.clone()))
+                })
+                .collect::<Result<_, _>>()?;
+            (None, primary_index)
+        }
+        KafkaKeyMapping::None => (None, vec![]),
+    };
+
+    let key_stored = key_column.is_some();
+    let mut fields = vec![];
+    if let Some(column) = key_column {
+        if position(&column.name).is_some() {
+            return Err(KafkaSchemaError::DuplicateField(column.name));
+        }
+        fields.push(column);
+    }
+    fields.extend(value_fields);
+
+    let cdc_type = if primary_index.is_empty() {
+        CdcType::Nothing
+    } else {
+        CdcType::OnlyPK
+    };
+    let schema = Schema {
+        fields,
+        primary_index,
+    };
+    Ok((SourceSchema::new(schema, cdc_type), key_stored))
+}
+
+/// Definitions of the primary key fields of `schema`, which message keys are decoded into
+/// if the key isn't stored in a column.
+pub fn primary_key_fields(schema: &Schema) -> Vec<FieldDefinition> {
+    schema
+        .primary_index
+        .iter()
+        .map(|index| schema.fields[*index].clone())
+        .collect()
+}
+
+/// Decodes the messages of a topic into operations on its table.
+///
+/// If the table has a primary key, it's decoded from the message key, and messages are upserts: a message
+/// with a payload is an update of the record of its key, and a tombstone (a message without payload) deletes it.
+/// The old records of those operations only have the primary key, the other fields are `null`. The decoder
+/// doesn't know if the record exists, so endpoints must upsert updates of records that don't.
+/// Messages without a key are skipped.
+///
+/// Otherwise, every message with a payload inserts a record.
+pub struct TopicDecoder {
+    /// Decodes message keys into the primary key. `None` if the table has no primary key.
+    key: Option<Decoder>,
+    /// Whether the key is stored in the first column, in which case it's the whole primary key.
+    key_stored: bool,
+    value: Decoder,
+    primary_index: Vec<usize>,
+    num_fields: usize,
+}
+
+impl TopicDecoder {
+    pub fn new(key: Option<Decoder>, key_stored: bool, value: Decoder, schema: &Schema) -> Self {
+        debug_assert_eq!(key.is_some(), !schema.primary_index.is_empty());
+        Self {
+            key,
+            key_stored,
+            value,
+            primary_index: schema.primary_index.clone(),
+            num_fields: schema.fields.len(),
+        }
+    }
+
+    /// Returns `None` if the message doesn't change the table.
+    pub async fn decode(
+        &mut self,
+        key: Option<&[u8]>,
+        payload: Option<&[u8]>,
+    ) -> Result<Option<Operation>, KafkaError> {
+        let Some(key_decoder) = &mut self.key else {
+            let Some(payload) = payload else {
+                return Ok(None);
+            };
+            let values = self.value.decode(payload).await?;
+            return Ok(Some(Operation::Insert {
+                new: Record::new(values),
+            }));
+        };
+
+        let Some(key) = key else {
+            return Ok(None);
+        };
+        let primary_key = key_decoder.decode(key).await?;
+        let old = self.primary_key_record(&primary_key);
+        let Some(payload) = payload else {
+            return Ok(Some(Operation::Delete { old }));
+        };
+
+        let mut values = vec![];
+        if self.key_stored {
+            values.extend(primary_key);
+        }
+        values.extend(self.value.decode(payload).await?);
+        Ok(Some(Operation::Update {
+            old,
+            new: Record::new(values),
+        }))
+    }
+
+    /// A record with the values of `primary_key` in the primary key columns, and `null` in the others.
+    fn primary_key_record(&self, primary_key: &[Field]) -> Record {
+        let mut values = vec![Field::Null; self.num_fields];
+        for (index, value) in self.primary_index.iter().zip(primary_key) {
+            values[*index] = value.clone();
+        }
+        Record::new(values)
+    }
+}
+
+/// Decodes keys or values into fields.
+pub enum Decoder {
+    /// UTF-8 strings, decoded into a single `String` field.
+    Utf8,
+    Registry(RegistryDecoder),
+}
+
+impl Decoder {
+    async fn decode(&mut self, bytes: &[u8]) -> Result<Vec<Field>, KafkaError> {
+        match self {
+            Decoder::Utf8 => {
+                let value = std::str::from_utf8(bytes).map_err(KafkaError::BytesConvertError)?;
+                Ok(vec![Field::String(value.to_string())])
+            }
+            Decoder::Registry(decoder) => decoder.decode(bytes).await,
+        }
+    }
+}
+
+/// Decodes messages in the Confluent wire format with the schemas of the schema registry.
+///
+/// Messages may be written with any version of the schema of the topic, so their fields are mapped to `fields` by name.
+/// Fields that aren't in the version of a message are `null`.
+pub struct RegistryDecoder {
+    sr_settings: SrSettings,
+    /// Name of the only field of schemas that aren't records.
+    name: &'static str,
+    fields: Vec<FieldDefinition>,
+    /// Formats by schema id, with the index of each of `fields` in their records.
+    formats: HashMap<u32, (RegisteredFormat, Vec<Option<usize>>)>,
+}
+
+impl RegistryDecoder {
+    pub fn new(sr_settings: SrSettings, name: &'static str, fields: Vec<FieldDefinition>) -> Self {
+        Self {
+            sr_settings,
+            name,
+            fields,
+            formats: HashMap::new(),
+        }
+    }
+
+    async fn decode(&mut self, bytes: &[u8]) -> Result<Vec<Field>, KafkaError> {
+        let Some((id, payload)) = split_schema_id(bytes) else {
+            // Plain JSON, as written by the JSON converter of Kafka Connect.
+            let value = serde_json::from_slice(bytes).map_err(KafkaError::JsonDecodeError)?;
+            return decode_json(value, &self.fields);
+        };
+
+        let (format, projection) = match self.formats.entry(id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let schema = SchemaRegistryBasic::fetch_schema(&self.sr_settings, id).await?;
+                let format = RegisteredFormat::new(&schema, self.name)?;
+                let projection = self
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        format
+                            .fields()
+                            .iter()
+                            .position(|format_field| format_field.name == field.name)
+                    })
+                    .collect();
+                entry.insert((format, projection))
+            }
+        };
+
+        let mut values = format
+            .decode(payload)?
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+        Ok(projection
+            .iter()
+            .map(|index| {
+                index
+                    .and_then(|index| values[index].take())
+                    .unwrap_or(Field::Null)
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_ingestion_connector::test_util::create_test_runtime;
+
+    use super::*;
+
+    fn field(name: &str, typ: FieldType) -> FieldDefinition {
+        FieldDefinition {
+            name: name.to_string(),
+            typ,
+            nullable: false,
+            source: SourceDefinition::Dynamic,
+        }
+    }
+
+    fn schema(
+        key_mapping: KafkaKeyMapping,
+        key_fields: Option<&[FieldDefinition]>,
+    ) -> Result<(Vec<String>, Vec<usize>, bool), KafkaSchemaError> {
+        let value_fields = vec![
+            field("id", FieldType::Int),
+            field("name", FieldType::String),
+        ];
+        let (schema, key_stored) = topic_schema("users", &key_mapping, key_fields, value_fields)?;
+        let names = schema
+            .schema
+            .fields
+            .into_iter()
+            .map(|field| field.name)
+            .collect();
+        Ok((names, schema.schema.primary_index, key_stored))
+    }
+
+    #[test]
+    fn test_key_mapping() {
+        let record_key = [field("id", FieldType::Int)];
+        let string_key = [field(KEY_FIELD, FieldType::String)];
+
+        // Fields of the key are in the value.
+        assert_eq!(
+            schema(KafkaKeyMapping::Auto, Some(&record_key)).unwrap(),
+            (vec!["id".into(), "name".into()], vec![0], false)
+        );
+        assert_eq!(
+            schema(KafkaKeyMapping::Auto, Some(&string_key)).unwrap(),
+            (
+                vec!["key".into(), "id".into(), "name".into()],
+                vec![0],
+                true
+            )
+        );
+        assert_eq!(
+            schema(KafkaKeyMapping::Auto, None).unwrap(),
+            (
+                vec!["key".into(), "id".into(), "name".into()],
+                vec![0],
+                true
+            )
+        );
+        assert_eq!(
+            schema(KafkaKeyMapping::Column("user".into()), Some(&string_key)).unwrap(),
+            (
+                vec!["user".into(), "id".into(), "name".into()],
+                vec![0],
+                true
+            )
+        );
+        assert_eq!(
+            schema(KafkaKeyMapping::Fields(vec!["name".into()]), None).unwrap(),
+            (vec!["id".into(), "name".into()], vec![1], false)
+        );
+        assert_eq!(
+            schema(KafkaKeyMapping::None, Some(&record_key)).unwrap(),
+            (vec!["id".into(), "name".into()], vec![], false)
+        );
+
+        assert_eq!(
+            schema(KafkaKeyMapping::Column("name".into()), None),
+            Err(KafkaSchemaError::DuplicateField("name".into()))
+        );
+        assert_eq!(
+            schema(KafkaKeyMapping::Fields(vec!["email".into()]), None),
+            Err(KafkaSchemaError::FieldNotFound("email".into()))
+        );
+        let composite_key = [field("org", FieldType::Int), field("id", FieldType::Int)];
+        assert_eq!(
+            schema(KafkaKeyMapping::Auto, Some(&composite_key)),
+            Err(KafkaSchemaError::KeyNotMappable("users".into()))
+        );
+    }
+
+    #[test]
+    fn test_key_mapping_of_topic() {
+        let topics = vec![KafkaTopicConfig {
+            name: "users".into(),
+            key: KafkaKeyMapping::None,
+        }];
+        assert_eq!(key_mapping(&topics, "users"), KafkaKeyMapping::None);
+        assert_eq!(key_mapping(&topics, "orders"), KafkaKeyMapping::Auto);
+    }
+
+    fn string_record(values: &[Option<&str>]) -> Record {
+        Record::new(
+            values
+                .iter()
+                .map(|value| value.map_or(Field::Null, |value| Field::String(value.into())))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_decode_without_registry() {
+        let runtime = create_test_runtime();
+        let value_fields = vec![field(VALUE_FIELD, FieldType::String)];
+        let (schema, _) =
+            topic_schema("users", &KafkaKeyMapping::Auto, None, value_fields).unwrap();
+        assert_eq!(schema.cdc_type, CdcType::OnlyPK);
+        let mut decoder =
+            TopicDecoder::new(Some(Decoder::Utf8), true, Decoder::Utf8, &schema.schema);
+        let mut decode = |key: Option<&str>, payload: Option<&str>| {
+            let (key, payload) = (key.map(str::as_bytes), payload.map(str::as_bytes));
+            runtime.block_on(decoder.decode(key, payload)).unwrap()
+        };
+
+        // Every message with a payload upserts the record of its key, including the first one.
+        for value in ["hello", "world"] {
+            assert_eq!(
+                decode(Some("1"), Some(value)),
+                Some(Operation::Update {
+                    old: string_record(&[Some("1"), None]),
+                    new: string_record(&[Some("1"), Some(value)])
+                })
+            );
+        }
+        // Messages without keys are skipped.
+        assert_eq!(decode(None, Some("hello")), None);
+        // Tombstones delete the record of their key, whether it has been seen or not.
+        for key in ["1", "2"] {
+            assert_eq!(
+                decode(Some(key), None),
+                Some(Operation::Delete {
+                    old: string_record(&[Some(key), None])
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_without_primary_key() {
+        let runtime = create_test_runtime();
+        let value_fields = vec![field(VALUE_FIELD, FieldType::String)];
+        let (schema, _) =
+            topic_schema("events", &KafkaKeyMapping::None, None, value_fields).unwrap();
+        assert_eq!(schema.cdc_type, CdcType::Nothing);
+        let mut decoder = TopicDecoder::new(None, false, Decoder::Utf8, &schema.schema);
+        let mut decode = |key: Option<&str>, payload: Option<&str>| {
+            let (key, payload) = (key.map(str::as_bytes), payload.map(str::as_bytes));
+            runtime.block_on(decoder.decode(key, payload)).unwrap()
+        };
+
+        // Every message is appended, and tombstones are skipped.
+        for _ in 0..2 {
+            assert_eq!(
+                decode(Some("1"), Some("hello")),
+                Some(Operation::Insert {
+                    new: string_record(&[Some("hello")])
+                })
+            );
+        }
+        assert_eq!(decode(Some("1"), None), None);
+    }
+
+    #[test]
+    fn test_key_fields_need_schema_registry() {
+        let result = crate::no_schema_registry_basic::NoSchemaRegistryBasic::get_single_schema(
+            "events",
+            &KafkaKeyMapping::Fields(vec!["message".into()]),
+        );
+        assert!(matches!(
+            result,
+            Err(KafkaError::KafkaSchemaError(
+                KafkaSchemaError::KeyFieldsNeedSchemaRegistry(_)
+            ))
+        ));
+    }
+}
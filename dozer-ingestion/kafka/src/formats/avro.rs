@@ -0,0 +1,272 @@
+use std::time::Duration;
+
+use apache_avro::{from_avro_datum, types::Value, Decimal as AvroDecimal, Schema};
+use dozer_ingestion_connector::dozer_types::{
+    chrono::{NaiveDate, NaiveDateTime},
+    json_value_to_field,
+    ordered_float::OrderedFloat,
+    rust_decimal::{self, Decimal},
+    serde_json,
+    types::{DozerDuration, Field, FieldDefinition, FieldType, SourceDefinition, TimeUnit},
+};
+
+use crate::{KafkaError, KafkaSchemaError};
+
+/// Days from 0001-01-01 to 1970-01-01, the epoch of Avro dates.
+const UNIX_EPOCH_DAYS_FROM_CE: i32 = 719_163;
+
+pub struct AvroFormat {
+    schema: Schema,
+    fields: Vec<FieldDefinition>,
+}
+
+impl AvroFormat {
+    pub fn new(schema: &str, name: &str) -> Result<Self, KafkaError> {
+        let schema = Schema::parse_str(schema)?;
+        let fields = match &schema {
+            Schema::Record { fields, .. } => fields
+                .iter()
+                .map(|field| field_definition(field.name.clone(), &field.schema))
+                .collect::<Result<_, _>>()?,
+            schema => vec![field_definition(name.to_string(), schema)?],
+        };
+        Ok(Self { schema, fields })
+    }
+
+    pub fn fields(&self) -> &[FieldDefinition] {
+        &self.fields
+    }
+
+    pub fn decode(&self, mut payload: &[u8]) -> Result<Vec<Field>, KafkaError> {
+        let value = from_avro_datum(&self.schema, &mut payload, None)?;
+        match (value, &self.schema) {
+            (Value::Record(values), Schema::Record { fields, .. }) => values
+                .into_iter()
+                .zip(fields)
+                .map(|((_, value), field)| convert_value(value, &field.schema))
+                .collect(),
+            (value, schema) => Ok(vec![convert_value(value, schema)?]),
+        }
+    }
+}
+
+fn field_definition(name: String, schema: &Schema) -> Result<FieldDefinition, KafkaSchemaError> {
+    let (typ, nullable) = map_type(schema)?;
+    Ok(FieldDefinition {
+        name,
+        typ,
+        nullable,
+        source: SourceDefinition::Dynamic,
+    })
+}
+
+/// Maps an Avro type to a field type, and whether it's nullable.
+///
+/// A union of `null` and another type is a nullable field of that type.
+/// Other unions, records, arrays and maps are JSON.
+fn map_type(schema: &Schema) -> Result<(FieldType, bool), KafkaSchemaError> {
+    let typ = match schema {
+        Schema::Union(union) => {
+            let variants = union
+                .variants()
+                .iter()
+                .filter(|variant| **variant != Schema::Null)
+                .collect::<Vec<_>>();
+            let typ = match variants[..] {
+                [variant] => map_type(variant)?.0,
+                _ => FieldType::Json,
+            };
+            return Ok((typ, union.is_nullable()));
+        }
+        Schema::Boolean => FieldType::Boolean,
+        Schema::Int | Schema::Long => FieldType::Int,
+        Schema::Float | Schema::Double => FieldType::Float,
+        Schema::Bytes | Schema::Fixed { .. } => FieldType::Binary,
+        Schema::String | Schema::Enum { .. } | Schema::Uuid => FieldType::String,
+        Schema::Decimal { .. } => FieldType::Decimal,
+        Schema::Date => FieldType::Date,
+        Schema::TimeMillis | Schema::TimeMicros => FieldType::Duration,
+        Schema::TimestampMillis | Schema::TimestampMicros => FieldType::Timestamp,
+        Schema::Record { .. } | Schema::Array(_) | Schema::Map(_) | Schema::Ref { .. } => {
+            FieldType::Json
+        }
+        schema => return Err(KafkaSchemaError::TypeNotSupported(format!("{schema:?}"))),
+    };
+    Ok((typ, false))
+}
+
+fn convert_value(value: Value, schema: &Schema) -> Result<Field, KafkaError> {
+    if map_type(schema)?.0 == FieldType::Json {
+        let value = serde_json::Value::try_from(value)?;
+        return Ok(json_value_to_field(value, FieldType::Json, true)?);
+    }
+
+    Ok(match (value, schema) {
+        (Value::Union(index, value), Schema::Union(union)) => {
+            return convert_value(*value, &union.variants()[index as usize])
+        }
+        (Value::Null, _) => Field::Null,
+        (Value::Boolean(value), _) => Field::Boolean(value),
+        (Value::Int(value), _) => Field::Int(value.into()),
+        (Value::Long(value), _) => Field::Int(value),
+        (Value::Float(value), _) => Field::Float(OrderedFloat(value.into())),
+        (Value::Double(value), _) => Field::Float(OrderedFloat(value)),
+        (Value::Bytes(value) | Value::Fixed(_, value), _) => Field::Binary(value),
+        (Value::String(value) | Value::Enum(_, value), _) => Field::String(value),
+        (Value::Uuid(value), _) => Field::String(value.to_string()),
+        (Value::Decimal(value), Schema::Decimal { scale, .. }) => {
+            Field::Decimal(convert_decimal(&value, *scale)?)
+        }
+        (Value::Date(days), _) => Field::Date(
+            NaiveDate::from_num_days_from_ce_opt(days + UNIX_EPOCH_DAYS_FROM_CE)
+                .ok_or(KafkaSchemaError::InvalidDateError)?,
+        ),
+        (Value::TimeMillis(millis), _) => Field::Duration(DozerDuration(
+            Duration::from_millis(
+                u64::try_from(millis).map_err(|_| KafkaSchemaError::InvalidTimestampError)?,
+            ),
+            TimeUnit::Milliseconds,
+        )),
+        (Value::TimeMicros(micros), _) => Field::Duration(DozerDuration(
+            Duration::from_micros(
+                u64::try_from(micros).map_err(|_| KafkaSchemaError::InvalidTimestampError)?,
+            ),
+            TimeUnit::Microseconds,
+        )),
+        (Value::TimestampMillis(millis), _) => Field::Timestamp(
+            NaiveDateTime::from_timestamp_millis(millis)
+                .ok_or(KafkaSchemaError::InvalidTimestampError)?
+                .and_utc()
+                .fixed_offset(),
+        ),
+        (Value::TimestampMicros(micros), _) => Field::Timestamp(
+            NaiveDateTime::from_timestamp_micros(micros)
+                .ok_or(KafkaSchemaError::InvalidTimestampError)?
+                .and_utc()
+                .fixed_offset(),
+        ),
+        (value, _) => return Err(KafkaSchemaError::TypeNotSupported(format!("{value:?}")).into()),
+    })
+}
+
+/// Avro decimals are big-endian two's complement integers, scaled by the scale of the schema.
+fn convert_decimal(value: &AvroDecimal, scale: usize) -> Result<Decimal, KafkaError> {
+    let bytes = Vec::<u8>::try_from(value)?;
+    if bytes.len() > 16 {
+        return Err(KafkaSchemaError::DecimalConvertError(
+            rust_decimal::Error::ExceedsMaximumPossibleValue,
+        )
+        .into());
+    }
+    let sign = if bytes.first().map_or(false, |byte| byte & 0x80 != 0) {
+        0xff
+    } else {
+        0
+    };
+    let mut unscaled = [sign; 16];
+    unscaled[16 - bytes.len()..].copy_from_slice(&bytes);
+    Ok(
+        Decimal::try_from_i128_with_scale(i128::from_be_bytes(unscaled), scale as u32)
+            .map_err(KafkaSchemaError::DecimalConvertError)?,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use apache_avro::to_avro_datum;
+    use dozer_ingestion_connector::dozer_types::{
+        json_types::serde_json_to_json_value, serde_json::json,
+    };
+
+    use super::*;
+
+    const SCHEMA: &str = r#"{
+        "type": "record",
+        "name": "Order",
+        "fields": [
+            {"name": "id", "type": "long"},
+            {"name": "item", "type": "string"},
+            {"name": "note", "type": ["null", "string"]},
+            {"name": "price", "type": {"type": "bytes", "logicalType": "decimal", "precision": 10, "scale": 2}},
+            {"name": "created_at", "type": {"type": "long", "logicalType": "timestamp-millis"}},
+            {"name": "tags", "type": {"type": "array", "items": "string"}}
+        ]
+    }"#;
+
+    #[test]
+    fn test_avro_schema() {
+        let format = AvroFormat::new(SCHEMA, "value").unwrap();
+        let fields = format
+            .fields()
+            .iter()
+            .map(|field| (field.name.as_str(), field.typ, field.nullable))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                ("id", FieldType::Int, false),
+                ("item", FieldType::String, false),
+                ("note", FieldType::String, true),
+                ("price", FieldType::Decimal, false),
+                ("created_at", FieldType::Timestamp, false),
+                ("tags", FieldType::Json, false),
+            ]
+        );
+
+        let format = AvroFormat::new(r#""string""#, "key").unwrap();
+        assert_eq!(format.fields()[0].name, "key");
+        assert_eq!(format.fields()[0].typ, FieldType::String);
+    }
+
+    #[test]
+    fn test_avro_decode() {
+        let format = AvroFormat::new(SCHEMA, "value").unwrap();
+        let payload = to_avro_datum(
+            &format.schema,
+            Value::Record(vec![
+                ("id".into(), Value::Long(1)),
+                ("item".into(), Value::String("pen".into())),
+                ("note".into(), Value::Union(0, Box::new(Value::Null))),
+                (
+                    "price".into(),
+                    Value::Decimal(AvroDecimal::from(vec![0x04u8, 0xd2])),
+                ),
+                (
+                    "created_at".into(),
+                    Value::TimestampMillis(1_700_000_000_000),
+                ),
+                (
+                    "tags".into(),
+                    Value::Array(vec![Value::String("blue".into())]),
+                ),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            format.decode(&payload).unwrap(),
+            vec![
+                Field::Int(1),
+                Field::String("pen".into()),
+                Field::Null,
+                Field::Decimal(Decimal::new(1234, 2)),
+                Field::Timestamp(
+                    NaiveDateTime::from_timestamp_millis(1_700_000_000_000)
+                        .unwrap()
+                        .and_utc()
+                        .fixed_offset()
+                ),
+                Field::Json(serde_json_to_json_value(json!(["blue"])).unwrap()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_negative_decimal() {
+        let decimal = AvroDecimal::from(vec![0xfbu8, 0x2e]);
+        assert_eq!(
+            convert_decimal(&decimal, 2).unwrap(),
+            Decimal::new(-1234, 2)
+        );
+    }
+}
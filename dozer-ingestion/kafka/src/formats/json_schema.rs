@@ -0,0 +1,153 @@
+use dozer_ingestion_connector::dozer_types::{
+    json_value_to_field,
+    serde_json::{self, Value},
+    types::{Field, FieldDefinition, FieldType, SourceDefinition},
+};
+
+use crate::KafkaError;
+
+use super::decode_json;
+
+/// A JSON Schema. The properties of `object` schemas are the fields, in alphabetical order.
+/// Properties that aren't `required` are nullable.
+pub struct JsonSchemaFormat {
+    fields: Vec<FieldDefinition>,
+    is_object: bool,
+}
+
+impl JsonSchemaFormat {
+    pub fn new(schema: &str, name: &str) -> Result<Self, KafkaError> {
+        let schema: Value = serde_json::from_str(schema).map_err(KafkaError::JsonDecodeError)?;
+        let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+            let (typ, nullable) = map_type(&schema);
+            return Ok(Self {
+                fields: vec![field_definition(name.to_string(), typ, nullable)],
+                is_object: false,
+            });
+        };
+
+        let required = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map_or(vec![], |required| {
+                required.iter().filter_map(Value::as_str).collect()
+            });
+        let fields = properties
+            .iter()
+            .map(|(name, property)| {
+                let (typ, nullable) = map_type(property);
+                let nullable = nullable || !required.contains(&name.as_str());
+                field_definition(name.clone(), typ, nullable)
+            })
+            .collect();
+        Ok(Self {
+            fields,
+            is_object: true,
+        })
+    }
+
+    pub fn fields(&self) -> &[FieldDefinition] {
+        &self.fields
+    }
+
+    pub fn decode(&self, payload: &[u8]) -> Result<Vec<Field>, KafkaError> {
+        let value: Value = serde_json::from_slice(payload).map_err(KafkaError::JsonDecodeError)?;
+        if self.is_object {
+            decode_json(value, &self.fields)
+        } else {
+            let field = &self.fields[0];
+            Ok(vec![json_value_to_field(value, field.typ, field.nullable)?])
+        }
+    }
+}
+
+fn field_definition(name: String, typ: FieldType, nullable: bool) -> FieldDefinition {
+    FieldDefinition {
+        name,
+        typ,
+        nullable,
+        source: SourceDefinition::Dynamic,
+    }
+}
+
+/// Maps the `type` of a schema to a field type, and whether it's nullable.
+/// Objects, arrays, schemas of several types and schemas without a `type` are JSON.
+fn map_type(schema: &Value) -> (FieldType, bool) {
+    let types = match schema.get("type") {
+        Some(Value::String(typ)) => vec![typ.as_str()],
+        Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+        _ => vec![],
+    };
+    let nullable = types.contains(&"null");
+    let types = types
+        .into_iter()
+        .filter(|typ| *typ != "null")
+        .collect::<Vec<_>>();
+    let typ = match types[..] {
+        ["integer"] => FieldType::Int,
+        ["number"] => FieldType::Float,
+        ["boolean"] => FieldType::Boolean,
+        ["string"] => match schema.get("format").and_then(Value::as_str) {
+            Some("date-time") => FieldType::Timestamp,
+            Some("date") => FieldType::Date,
+            _ => FieldType::String,
+        },
+        _ => FieldType::Json,
+    };
+    (typ, nullable)
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_ingestion_connector::dozer_types::chrono::NaiveDate;
+
+    use super::*;
+
+    #[test]
+    fn test_json_schema_decode() {
+        let format = JsonSchemaFormat::new(
+            r#"{
+                "type": "object",
+                "properties": {
+                    "id": {"type": "integer"},
+                    "name": {"type": ["string", "null"]},
+                    "day": {"type": "string", "format": "date"},
+                    "tags": {"type": "array", "items": {"type": "string"}}
+                },
+                "required": ["id", "name", "day"]
+            }"#,
+            "value",
+        )
+        .unwrap();
+        let fields = format
+            .fields()
+            .iter()
+            .map(|field| (field.name.as_str(), field.typ, field.nullable))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                ("day", FieldType::Date, false),
+                ("id", FieldType::Int, false),
+                ("name", FieldType::String, true),
+                ("tags", FieldType::Json, true),
+            ]
+        );
+
+        assert_eq!(
+            format
+                .decode(br#"{"id": 1, "name": null, "day": "2024-01-02"}"#)
+                .unwrap(),
+            vec![
+                Field::Date(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()),
+                Field::Int(1),
+                Field::Null,
+                Field::Null,
+            ]
+        );
+        assert!(format.decode(br#"{"name": "a"}"#).is_err());
+
+        let format = JsonSchemaFormat::new(r#"{"type": "integer"}"#, "key").unwrap();
+        assert_eq!(format.decode(b"42").unwrap(), vec![Field::Int(42)]);
+    }
+}
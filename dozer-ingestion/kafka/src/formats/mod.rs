@@ -0,0 +1,100 @@
+//! Formats of the schemas registered in the schema registry.
+//!
+//! Messages written with them are in the Confluent wire format: a magic byte, the big-endian id of the schema, then the payload.
+
+use dozer_ingestion_connector::dozer_types::{
+    json_value_to_field,
+    serde_json::Value,
+    types::{Field, FieldDefinition},
+};
+use schema_registry_converter::schema_registry_common::{RegisteredSchema, SchemaType};
+
+use crate::{KafkaError, KafkaSchemaError};
+
+mod avro;
+mod json_schema;
+mod protobuf;
+
+pub use avro::AvroFormat;
+pub use json_schema::JsonSchemaFormat;
+pub use protobuf::ProtobufFormat;
+
+const MAGIC_BYTE: u8 = 0;
+
+/// Splits a message into the id of its schema and its payload. Returns `None` if it isn't in the wire format.
+pub fn split_schema_id(bytes: &[u8]) -> Option<(u32, &[u8])> {
+    match bytes {
+        [MAGIC_BYTE, a, b, c, d, payload @ ..] => {
+            Some((u32::from_be_bytes([*a, *b, *c, *d]), payload))
+        }
+        _ => None,
+    }
+}
+
+pub enum RegisteredFormat {
+    Avro(AvroFormat),
+    Protobuf(ProtobufFormat),
+    Json(JsonSchemaFormat),
+}
+
+impl RegisteredFormat {
+    /// `name` is the name of the only field of schemas that aren't records, like a `string` key.
+    pub fn new(schema: &RegisteredSchema, name: &str) -> Result<Self, KafkaError> {
+        match &schema.schema_type {
+            SchemaType::Avro => AvroFormat::new(&schema.schema, name).map(Self::Avro),
+            SchemaType::Protobuf => ProtobufFormat::new(&schema.schema).map(Self::Protobuf),
+            SchemaType::Json => JsonSchemaFormat::new(&schema.schema, name).map(Self::Json),
+            SchemaType::Other(typ) => {
+                Err(KafkaSchemaError::SchemaTypeNotSupported(typ.clone()).into())
+            }
+        }
+    }
+
+    pub fn fields(&self) -> &[FieldDefinition] {
+        match self {
+            Self::Avro(format) => format.fields(),
+            Self::Protobuf(format) => format.fields(),
+            Self::Json(format) => format.fields(),
+        }
+    }
+
+    /// Decodes the payload that follows the schema id. The values are in the order of `fields`.
+    pub fn decode(&self, payload: &[u8]) -> Result<Vec<Field>, KafkaError> {
+        match self {
+            Self::Avro(format) => format.decode(payload),
+            Self::Protobuf(format) => format.decode(payload),
+            Self::Json(format) => format.decode(payload),
+        }
+    }
+}
+
+/// Maps a JSON value to `fields`. Objects are mapped by property name, and missing properties are `null`.
+/// Other values are the value of the only field.
+pub fn decode_json(value: Value, fields: &[FieldDefinition]) -> Result<Vec<Field>, KafkaError> {
+    match (value, fields) {
+        (Value::Object(mut object), _) => fields
+            .iter()
+            .map(|field| {
+                let value = object.remove(&field.name).unwrap_or(Value::Null);
+                Ok(json_value_to_field(value, field.typ, field.nullable)?)
+            })
+            .collect(),
+        (value, [field]) => Ok(vec![json_value_to_field(value, field.typ, field.nullable)?]),
+        (value, _) => Err(KafkaSchemaError::InvalidJsonError(value.to_string()).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_schema_id() {
+        assert_eq!(
+            split_schema_id(&[0, 0, 0, 1, 2, 42]),
+            Some((258, [42].as_slice()))
+        );
+        assert_eq!(split_schema_id(b"{\"id\": 1}"), None);
+        assert_eq!(split_schema_id(&[0, 0, 1]), None);
+    }
+}
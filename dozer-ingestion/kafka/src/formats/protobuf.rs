@@ -0,0 +1,486 @@
+use std::collections::HashMap;
+
+use base64::{engine, Engine};
+use dozer_ingestion_connector::dozer_types::{
+    json_value_to_field,
+    ordered_float::OrderedFloat,
+    serde_json::{Map, Value as JsonValue},
+    types::{Field, FieldDefinition, FieldType, SourceDefinition},
+};
+use protofish::{
+    context::{Context, EnumRef, MessageField, Multiplicity, ValueType},
+    decode::{MessageValue, PackedArray, Value},
+};
+
+use crate::{KafkaError, KafkaSchemaError};
+
+/// A `.proto` schema. Records are messages of the first message type of the schema, as in the Confluent serializers.
+///
+/// Messages of other types of the schema are mapped to the fields of the first one by name.
+/// Missing fields have their default value, except messages and `optional` fields, which are `null`.
+pub struct ProtobufFormat {
+    context: Context,
+    /// Full names of the message types, by their indexes in the schema. See `message_names`.
+    messages: Vec<(Vec<usize>, String)>,
+    fields: Vec<FieldDefinition>,
+}
+
+impl ProtobufFormat {
+    pub fn new(schema: &str) -> Result<Self, KafkaError> {
+        let context = Context::parse(&[schema])
+            .map_err(|e| KafkaError::ProtobufParseError(format!("{e:?}")))?;
+        let messages = message_names(schema);
+        let message = messages
+            .first()
+            .and_then(|(_, name)| context.get_message(name))
+            .ok_or_else(|| KafkaSchemaError::ProtobufMessageNotFound(vec![0]))?;
+        let fields = message
+            .iter_fields()
+            .map(|field| {
+                let (typ, nullable) = map_type(field);
+                FieldDefinition {
+                    name: field.name.clone(),
+                    typ,
+                    nullable,
+                    source: SourceDefinition::Dynamic,
+                }
+            })
+            .collect();
+        Ok(Self {
+            context,
+            messages,
+            fields,
+        })
+    }
+
+    pub fn fields(&self) -> &[FieldDefinition] {
+        &self.fields
+    }
+
+    pub fn decode(&self, payload: &[u8]) -> Result<Vec<Field>, KafkaError> {
+        let (indexes, payload) = read_message_indexes(payload)?;
+        let message = self
+            .messages
+            .iter()
+            .find(|(path, _)| *path == indexes)
+            .and_then(|(_, name)| self.context.get_message(name))
+            .ok_or(KafkaSchemaError::ProtobufMessageNotFound(indexes))?;
+
+        let mut values = HashMap::<u64, Vec<Value>>::new();
+        for field in message.decode(payload, &self.context).fields {
+            values.entry(field.number).or_default().push(field.value);
+        }
+
+        self.fields
+            .iter()
+            .map(|definition| {
+                let Some(field) = message.get_field_by_name(&definition.name) else {
+                    return Ok(Field::Null);
+                };
+                let values = values.remove(&field.number).unwrap_or_default();
+                if is_repeated(field) {
+                    let values = values
+                        .into_iter()
+                        .flat_map(|value| self.repeated_to_json(value))
+                        .collect();
+                    return Ok(json_value_to_field(
+                        JsonValue::Array(values),
+                        FieldType::Json,
+                        false,
+                    )?);
+                }
+                // The last value wins, as in the protobuf encoding.
+                match values.into_iter().last() {
+                    Some(value) => self.convert_value(value),
+                    None if definition.nullable => Ok(Field::Null),
+                    None => Ok(self.default_value(&field.field_type)),
+                }
+            })
+            .collect()
+    }
+
+    fn convert_value(&self, value: Value) -> Result<Field, KafkaError> {
+        Ok(match value {
+            Value::Double(value) => Field::Float(OrderedFloat(value)),
+            Value::Float(value) => Field::Float(OrderedFloat(value.into())),
+            Value::Int32(value) | Value::SInt32(value) | Value::SFixed32(value) => {
+                Field::Int(value.into())
+            }
+            Value::Int64(value) | Value::SInt64(value) | Value::SFixed64(value) => {
+                Field::Int(value)
+            }
+            Value::UInt32(value) | Value::Fixed32(value) => Field::UInt(value.into()),
+            Value::UInt64(value) | Value::Fixed64(value) => Field::UInt(value),
+            Value::Bool(value) => Field::Boolean(value),
+            Value::String(value) => Field::String(value),
+            Value::Bytes(value) => Field::Binary(value.to_vec()),
+            Value::Enum(value) => Field::String(self.enum_name(value.enum_ref, value.value)),
+            value @ Value::Message(_) => {
+                json_value_to_field(self.to_json(value), FieldType::Json, true)?
+            }
+            _ => Field::Null,
+        })
+    }
+
+    /// Default values of proto3 fields that aren't in a message.
+    fn default_value(&self, typ: &ValueType) -> Field {
+        match typ {
+            ValueType::Double | ValueType::Float => Field::Float(OrderedFloat(0.0)),
+            ValueType::Int32
+            | ValueType::Int64
+            | ValueType::SInt32
+            | ValueType::SInt64
+            | ValueType::SFixed32
+            | ValueType::SFixed64 => Field::Int(0),
+            ValueType::UInt32 | ValueType::UInt64 | ValueType::Fixed32 | ValueType::Fixed64 => {
+                Field::UInt(0)
+            }
+            ValueType::Bool => Field::Boolean(false),
+            ValueType::String => Field::String(String::new()),
+            ValueType::Bytes => Field::Binary(vec![]),
+            ValueType::Enum(enum_ref) => Field::String(self.enum_name(*enum_ref, 0)),
+            ValueType::Message(_) => Field::Null,
+        }
+    }
+
+    fn enum_name(&self, enum_ref: EnumRef, value: i64) -> String {
+        self.context
+            .resolve_enum(enum_ref)
+            .get_field_by_value(value)
+            .map_or_else(|| value.to_string(), |field| field.name.clone())
+    }
+
+    /// Bytes are base64 encoded, as in the JSON mapping of protobuf.
+    fn to_json(&self, value: Value) -> JsonValue {
+        match value {
+            Value::Double(value) => value.into(),
+            Value::Float(value) => value.into(),
+            Value::Int32(value) | Value::SInt32(value) | Value::SFixed32(value) => value.into(),
+            Value::Int64(value) | Value::SInt64(value) | Value::SFixed64(value) => value.into(),
+            Value::UInt32(value) | Value::Fixed32(value) => value.into(),
+            Value::UInt64(value) | Value::Fixed64(value) => value.into(),
+            Value::Bool(value) => value.into(),
+            Value::String(value) => value.into(),
+            Value::Bytes(value) => engine::general_purpose::STANDARD.encode(value).into(),
+            Value::Enum(value) => self.enum_name(value.enum_ref, value.value).into(),
+            Value::Message(message) => self.message_to_json(*message),
+            Value::Packed(array) => JsonValue::Array(packed_to_json(array)),
+            _ => JsonValue::Null,
+        }
+    }
+
+    /// Elements of repeated fields are either encoded one by one, or packed together.
+    fn repeated_to_json(&self, value: Value) -> Vec<JsonValue> {
+        match value {
+            Value::Packed(array) => packed_to_json(array),
+            value => vec![self.to_json(value)],
+        }
+    }
+
+    fn message_to_json(&self, message: MessageValue) -> JsonValue {
+        let info = self.context.resolve_message(message.msg_ref);
+        let mut object = Map::new();
+        for field in message.fields {
+            let Some(definition) = info.get_field(field.number) else {
+                continue;
+            };
+            if is_repeated(definition) {
+                let values = self.repeated_to_json(field.value);
+                if let JsonValue::Array(array) = object
+                    .entry(definition.name.clone())
+                    .or_insert_with(|| JsonValue::Array(vec![]))
+                {
+                    array.extend(values);
+                }
+            } else {
+                object.insert(definition.name.clone(), self.to_json(field.value));
+            }
+        }
+        JsonValue::Object(object)
+    }
+}
+
+fn is_repeated(field: &MessageField) -> bool {
+    matches!(
+        field.multiplicity,
+        Multiplicity::Repeated | Multiplicity::RepeatedPacked
+    )
+}
+
+/// Repeated fields and messages are JSON. Messages and `optional` fields are nullable.
+fn map_type(field: &MessageField) -> (FieldType, bool) {
+    if is_repeated(field) {
+        return (FieldType::Json, false);
+    }
+    let nullable = matches!(field.multiplicity, Multiplicity::Optional);
+    match field.field_type {
+        ValueType::Double | ValueType::Float => (FieldType::Float, nullable),
+        ValueType::Int32
+        | ValueType::Int64
+        | ValueType::SInt32
+        | ValueType::SInt64
+        | ValueType::SFixed32
+        | ValueType::SFixed64 => (FieldType::Int, nullable),
+        ValueType::UInt32 | ValueType::UInt64 | ValueType::Fixed32 | ValueType::Fixed64 => {
+            (FieldType::UInt, nullable)
+        }
+        ValueType::Bool => (FieldType::Boolean, nullable),
+        ValueType::String | ValueType::Enum(_) => (FieldType::String, nullable),
+        ValueType::Bytes => (FieldType::Binary, nullable),
+        ValueType::Message(_) => (FieldType::Json, true),
+    }
+}
+
+fn packed_to_json(array: PackedArray) -> Vec<JsonValue> {
+    fn to_json<T: Into<JsonValue>>(values: Vec<T>) -> Vec<JsonValue> {
+        values.into_iter().map(Into::into).collect()
+    }
+    match array {
+        PackedArray::Double(values) => to_json(values),
+        PackedArray::Float(values) => to_json(values),
+        PackedArray::Int32(values)
+        | PackedArray::SInt32(values)
+        | PackedArray::SFixed32(values) => to_json(values),
+        PackedArray::Int64(values)
+        | PackedArray::SInt64(values)
+        | PackedArray::SFixed64(values) => to_json(values),
+        PackedArray::UInt32(values) | PackedArray::Fixed32(values) => to_json(values),
+        PackedArray::UInt64(values) | PackedArray::Fixed64(values) => to_json(values),
+        PackedArray::Bool(values) => to_json(values),
+    }
+}
+
+/// Reads the indexes of the message type that prefix the payload: their count, then the index of the
+/// top-level message type, then of each nested type. `[0]` is encoded as a single `0`.
+fn read_message_indexes(payload: &[u8]) -> Result<(Vec<usize>, &[u8]), KafkaSchemaError> {
+    let (count, mut payload) = read_varint(payload)?;
+    if count == 0 {
+        return Ok((vec![0], payload));
+    }
+    let mut indexes = vec![];
+    for _ in 0..count {
+        let (index, rest) = read_varint(payload)?;
+        indexes.push(index);
+        payload = rest;
+    }
+    Ok((indexes, payload))
+}
+
+/// Reads a zigzag-encoded varint.
+fn read_varint(bytes: &[u8]) -> Result<(usize, &[u8]), KafkaSchemaError> {
+    let mut value = 0u64;
+    for (i, byte) in bytes.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            let value = (value >> 1) as i64 ^ -((value & 1) as i64);
+            let value = usize::try_from(value).map_err(|_| KafkaSchemaError::InvalidWireFormat)?;
+            return Ok((value, &bytes[i + 1..]));
+        }
+    }
+    Err(KafkaSchemaError::InvalidWireFormat)
+}
+
+/// Full names of the message types declared in a `.proto` schema, with their indexes:
+/// the index of the top-level message type, then of each nested type, in order of declaration.
+fn message_names(schema: &str) -> Vec<(Vec<usize>, String)> {
+    struct Scope {
+        /// `None` for blocks that aren't messages, like enums.
+        message: Option<(Vec<usize>, String)>,
+        nested_messages: usize,
+    }
+
+    let tokens = tokenize(schema);
+    let mut package = None;
+    let mut scopes = vec![Scope {
+        message: Some((vec![], String::new())),
+        nested_messages: 0,
+    }];
+    let mut messages = vec![];
+    let mut i = 0;
+    while i < tokens.len() {
+        match tokens[i..] {
+            ["package", name, ";", ..] if scopes.len() == 1 => {
+                package = Some(name);
+                i += 3;
+            }
+            ["message", name, "{", ..] => {
+                let scope = scopes.last_mut().expect("root scope is never popped");
+                let message = scope.message.as_ref().map(|(path, parent)| {
+                    let mut path = path.clone();
+                    path.push(scope.nested_messages);
+                    let full_name = match (parent.as_str(), package) {
+                        ("", None) => name.to_string(),
+                        ("", Some(package)) => format!("{package}.{name}"),
+                        (parent, _) => format!("{parent}.{name}"),
+                    };
+                    (path, full_name)
+                });
+                if let Some(message) = &message {
+                    scope.nested_messages += 1;
+                    messages.push(message.clone());
+                }
+                scopes.push(Scope {
+                    message,
+                    nested_messages: 0,
+                });
+                i += 3;
+            }
+            ["{", ..] => {
+                scopes.push(Scope {
+                    message: None,
+                    nested_messages: 0,
+                });
+                i += 1;
+            }
+            ["}", ..] => {
+                if scopes.len() > 1 {
+                    scopes.pop();
+                }
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+    messages
+}
+
+/// Splits a `.proto` schema into identifiers and punctuation, skipping comments and string literals.
+fn tokenize(schema: &str) -> Vec<&str> {
+    let is_identifier = |c: char| c.is_alphanumeric() || c == '_' || c == '.';
+    let mut tokens = vec![];
+    let mut rest = schema;
+    while let Some(c) = rest.chars().next() {
+        rest = if rest.starts_with("//") {
+            rest.find('\n').map_or("", |end| &rest[end..])
+        } else if rest.starts_with("/*") {
+            rest[2..].find("*/").map_or("", |end| &rest[end + 4..])
+        } else if c == '"' || c == '\'' {
+            rest[1..].find(c).map_or("", |end| &rest[end + 2..])
+        } else if is_identifier(c) {
+            let end = rest.find(|c: char| !is_identifier(c)).unwrap_or(rest.len());
+            tokens.push(&rest[..end]);
+            &rest[end..]
+        } else {
+            if !c.is_whitespace() {
+                tokens.push(&rest[..c.len_utf8()]);
+            }
+            &rest[c.len_utf8()..]
+        };
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_ingestion_connector::dozer_types::{
+        json_types::serde_json_to_json_value, serde_json::json,
+    };
+
+    use super::*;
+
+    const SCHEMA: &str = r#"
+        syntax = "proto3";
+        package shop;
+
+        // An order, with its items.
+        message Order {
+            int64 id = 1;
+            string customer = 2;
+            repeated string items = 3;
+            Status status = 4;
+            optional double discount = 5;
+            Address address = 6;
+
+            message Address { string city = 1; }
+        }
+
+        enum Status {
+            OPEN = 0;
+            SHIPPED = 1;
+        }
+
+        /* The key of orders. */
+        message OrderKey { int64 id = 1; }
+    "#;
+
+    #[test]
+    fn test_message_names() {
+        assert_eq!(
+            message_names(SCHEMA),
+            vec![
+                (vec![0], "shop.Order".to_string()),
+                (vec![0, 0], "shop.Order.Address".to_string()),
+                (vec![1], "shop.OrderKey".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_message_indexes() {
+        assert_eq!(
+            read_message_indexes(&[0, 42]).unwrap(),
+            (vec![0], [42].as_slice())
+        );
+        // Count 2, then indexes 1 and 0, zigzag-encoded.
+        assert_eq!(
+            read_message_indexes(&[4, 2, 0, 42]).unwrap(),
+            (vec![1, 0], [42].as_slice())
+        );
+        assert!(read_message_indexes(&[0x80]).is_err());
+    }
+
+    #[test]
+    fn test_protobuf_decode() {
+        let format = ProtobufFormat::new(SCHEMA).unwrap();
+        let fields = format
+            .fields()
+            .iter()
+            .map(|field| (field.name.as_str(), field.typ, field.nullable))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                ("id", FieldType::Int, false),
+                ("customer", FieldType::String, false),
+                ("items", FieldType::Json, false),
+                ("status", FieldType::String, false),
+                ("discount", FieldType::Float, true),
+                ("address", FieldType::Json, true),
+            ]
+        );
+
+        let payload = [
+            0, // Message indexes [0]
+            0x08, 0x07, // id = 7
+            0x1a, 0x01, b'a', // items = "a"
+            0x1a, 0x01, b'b', // items = "b"
+            0x20, 0x01, // status = SHIPPED
+            0x32, 0x04, 0x0a, 0x02, b'N', b'Y', // address = { city = "NY" }
+        ];
+        assert_eq!(
+            format.decode(&payload).unwrap(),
+            vec![
+                Field::Int(7),
+                Field::String(String::new()),
+                Field::Json(serde_json_to_json_value(json!(["a", "b"])).unwrap()),
+                Field::String("SHIPPED".into()),
+                Field::Null,
+                Field::Json(serde_json_to_json_value(json!({"city": "NY"})).unwrap()),
+            ]
+        );
+
+        // Messages of other types are mapped by field name.
+        let payload = [2, 2, 0x08, 0x03];
+        assert_eq!(
+            format.decode(&payload).unwrap(),
+            vec![
+                Field::Int(3),
+                Field::Null,
+                Field::Null,
+                Field::Null,
+                Field::Null,
+                Field::Null,
+            ]
+        );
+    }
+}
@@ -2,6 +2,8 @@ use std::str::Utf8Error;
 
 use base64::DecodeError;
 use dozer_ingestion_connector::dozer_types::{
+    bincode,
+    errors::types::TypeError,
     rust_decimal, serde_json,
     thiserror::{self, Error},
 };
@@ -9,6 +11,8 @@ use schema_registry_converter::error::SRCError;
 
 pub mod connector;
 pub mod debezium;
+pub mod decoder;
+pub mod formats;
 pub mod no_schema_registry_basic;
 pub mod schema_registry_basic;
 pub mod stream_consumer;
@@ -39,6 +43,24 @@ pub enum KafkaError {
 
     #[error("Topic not defined")]
     TopicNotDefined,
+
+    #[error("Avro error: {0}")]
+    AvroError(#[from] apache_avro::Error),
+
+    #[error("Invalid protobuf schema: {0}")]
+    ProtobufParseError(String),
+
+    #[error("Type error: {0}")]
+    TypeError(#[from] TypeError),
+
+    #[error("Failed to encode partition offsets: {0}")]
+    EncodeOffsets(#[source] bincode::error::EncodeError),
+
+    #[error("Failed to decode checkpointed partition offsets: {0}")]
+    DecodeOffsets(#[source] bincode::error::DecodeError),
+
+    #[error("Checkpoint doesn't have the partition offsets")]
+    MissingOffsets,
 }
 
 #[derive(Error, Debug)]
@@ -86,6 +108,26 @@ pub enum KafkaSchemaError {
     // InvalidTimeError,
     #[error("Invalid timestamp")]
     InvalidTimestampError,
+
+    #[error("Unsupported schema type {0}")]
+    SchemaTypeNotSupported(String),
+
+    #[error("Field \"{0}\" is both in the key column and in the value")]
+    DuplicateField(String),
+
+    #[error("Key of topic \"{0}\" has several fields that aren't all in the value, use the `Fields` or `None` key mapping")]
+    KeyNotMappable(String),
+
+    #[error(
+        "Keys of topic \"{0}\" can only be decoded into fields of the value with a schema registry"
+    )]
+    KeyFieldsNeedSchemaRegistry(String),
+
+    #[error("Message with indexes {0:?} not found in protobuf schema")]
+    ProtobufMessageNotFound(Vec<usize>),
+
+    #[error("Invalid Confluent wire format message")]
+    InvalidWireFormat,
 }
 
 #[cfg(test)]
@@ -1,40 +1,51 @@
 use dozer_ingestion_connector::{
-    dozer_types::types::{FieldDefinition, FieldType, Schema, SourceDefinition},
-    CdcType, SourceSchema,
+    dozer_types::{
+        models::ingestion_types::{KafkaKeyMapping, KafkaTopicConfig},
+        types::{FieldDefinition, FieldType, SourceDefinition},
+    },
+    SourceSchema,
 };
 
-use crate::KafkaError;
+use crate::{
+    decoder::{key_mapping, topic_schema, Decoder, TopicDecoder},
+    KafkaError, KafkaSchemaError,
+};
 
 pub struct NoSchemaRegistryBasic {}
 
 impl NoSchemaRegistryBasic {
-    pub fn get_single_schema() -> SourceSchema {
-        let schema = Schema {
-            fields: vec![
-                FieldDefinition {
-                    name: "key".to_string(),
-                    typ: FieldType::String,
-                    nullable: false,
-                    source: SourceDefinition::Dynamic,
-                },
-                FieldDefinition {
-                    name: "message".to_string(),
-                    typ: FieldType::String,
-                    nullable: true,
-                    source: SourceDefinition::Dynamic,
-                },
-            ],
-            primary_index: vec![0],
-        };
-
-        SourceSchema::new(schema, CdcType::FullChanges)
+    /// Values are stored as strings in a `message` column.
+    pub fn get_single_schema(
+        table_name: &str,
+        key_mapping: &KafkaKeyMapping,
+    ) -> Result<(SourceSchema, TopicDecoder), KafkaError> {
+        let value_fields = vec![FieldDefinition {
+            name: "message".to_string(),
+            typ: FieldType::String,
+            nullable: true,
+            source: SourceDefinition::Dynamic,
+        }];
+        let (schema, key_stored) = topic_schema(table_name, key_mapping, None, value_fields)?;
+        // Without a schema registry, keys are strings that can't be decoded into fields of the value.
+        if !key_stored && !schema.schema.primary_index.is_empty() {
+            return Err(
+                KafkaSchemaError::KeyFieldsNeedSchemaRegistry(table_name.to_string()).into(),
+            );
+        }
+        let key = key_stored.then_some(Decoder::Utf8);
+        let decoder = TopicDecoder::new(key, key_stored, Decoder::Utf8, &schema.schema);
+        Ok((schema, decoder))
     }
 
-    pub fn get_schema(table_names: Option<&[String]>) -> Result<Vec<SourceSchema>, KafkaError> {
+    pub fn get_schema(
+        table_names: Option<&[String]>,
+        topics: &[KafkaTopicConfig],
+    ) -> Result<Vec<SourceSchema>, KafkaError> {
         let mut schemas = vec![];
         if let Some(tables) = table_names {
-            for _ in 0..tables.len() {
-                let schema = Self::get_single_schema();
+            for table_name in tables {
+                let (schema, _) =
+                    Self::get_single_schema(table_name, &key_mapping(topics, table_name))?;
                 schemas.push(schema);
             }
         }
@@ -1,79 +1,116 @@
-#![allow(clippy::type_complexity)]
+use std::future::Future;
+use std::time::Duration;
 
 use dozer_ingestion_connector::{
-    dozer_types::types::{FieldDefinition, Schema, SourceDefinition},
-    CdcType, SourceSchema,
+    dozer_types::{
+        log::error,
+        models::ingestion_types::{KafkaKeyMapping, KafkaTopicConfig},
+    },
+    tokio, SourceSchema,
+};
+use schema_registry_converter::{
+    async_impl::schema_registry::{get_schema_by_id, get_schema_by_subject, SrSettings},
+    error::SRCError,
+    schema_registry_common::{RegisteredSchema, SubjectNameStrategy},
 };
-use schema_registry_converter::async_impl::schema_registry::SrSettings;
-use std::collections::HashMap;
 
 use crate::{
-    debezium::{schema_registry::SchemaRegistry, stream_consumer::DebeziumSchemaStruct},
+    decoder::{
+        key_mapping, primary_key_fields, topic_schema, Decoder, RegistryDecoder, TopicDecoder,
+        KEY_FIELD, VALUE_FIELD,
+    },
+    formats::RegisteredFormat,
     KafkaError,
 };
 
 pub struct SchemaRegistryBasic {}
 
 impl SchemaRegistryBasic {
+    /// Latest schema of the keys or the values of a topic, registered with the topic name strategy.
+    pub async fn fetch_latest_schema(
+        sr_settings: &SrSettings,
+        topic: &str,
+        is_key: bool,
+    ) -> Result<RegisteredSchema, SRCError> {
+        let subject = SubjectNameStrategy::TopicNameStrategy(topic.to_string(), is_key);
+        retry(|| get_schema_by_subject(sr_settings, &subject)).await
+    }
+
+    pub async fn fetch_schema(
+        sr_settings: &SrSettings,
+        id: u32,
+    ) -> Result<RegisteredSchema, KafkaError> {
+        retry(|| get_schema_by_id(id, sr_settings))
+            .await
+            .map_err(KafkaError::SchemaRegistryFetchError)
+    }
+
     pub async fn get_single_schema(
         table_name: &str,
         schema_registry_url: &str,
-    ) -> Result<(SourceSchema, HashMap<String, DebeziumSchemaStruct>), KafkaError> {
+        key_mapping: &KafkaKeyMapping,
+    ) -> Result<(SourceSchema, TopicDecoder), KafkaError> {
         let sr_settings = SrSettings::new(schema_registry_url.to_string());
-        let key_result = SchemaRegistry::fetch_struct(&sr_settings, table_name, true).await?;
-        let schema_result = SchemaRegistry::fetch_struct(&sr_settings, table_name, false).await?;
-
-        let pk_fields = key_result.fields.map_or(vec![], |fields| {
-            fields
-                .iter()
-                .map(|f| f.name.clone().map_or("".to_string(), |name| name))
-                .collect()
-        });
-
-        let fields = schema_result.fields.map_or(vec![], |f| f);
-        let mut pk_keys_indexes = vec![];
-        let mut fields_schema_map: HashMap<String, DebeziumSchemaStruct> = HashMap::new();
+        let value_schema = Self::fetch_latest_schema(&sr_settings, table_name, false)
+            .await
+            .map_err(KafkaError::SchemaRegistryFetchError)?;
+        let value_fields = RegisteredFormat::new(&value_schema, VALUE_FIELD)?
+            .fields()
+            .to_vec();
+        // Keys don't need a schema.
+        let key_fields = match Self::fetch_latest_schema(&sr_settings, table_name, true).await {
+            Ok(key_schema) => Some(
+                RegisteredFormat::new(&key_schema, KEY_FIELD)?
+                    .fields()
+                    .to_vec(),
+            ),
+            Err(_) => None,
+        };
 
-        let defined_fields: Result<Vec<FieldDefinition>, KafkaError> = fields
-            .iter()
-            .to_owned()
-            .enumerate()
-            .map(|(idx, f)| {
-                let (typ, nullable) =
-                    SchemaRegistry::map_typ(f).map_err(KafkaError::KafkaSchemaError)?;
-                let name = f.name.clone().unwrap();
-                if pk_fields.contains(&name) {
-                    pk_keys_indexes.push(idx);
-                }
-                fields_schema_map.insert(name.clone(), f.clone());
-                Ok(FieldDefinition {
-                    name,
-                    typ,
-                    nullable,
-                    source: SourceDefinition::Dynamic,
-                })
+        let (schema, key_stored) = topic_schema(
+            table_name,
+            key_mapping,
+            key_fields.as_deref(),
+            value_fields.clone(),
+        )?;
+        let key = if key_stored {
+            Some(match key_fields {
+                Some(key_fields) => Decoder::Registry(RegistryDecoder::new(
+                    sr_settings.clone(),
+                    KEY_FIELD,
+                    key_fields,
+                )),
+                None => Decoder::Utf8,
             })
-            .collect();
-
-        let schema = Schema {
-            fields: defined_fields?,
-            primary_index: pk_keys_indexes,
+        } else if schema.schema.primary_index.is_empty() {
+            None
+        } else {
+            // The primary key is in the value, and keys must be records with the same fields.
+            Some(Decoder::Registry(RegistryDecoder::new(
+                sr_settings.clone(),
+                KEY_FIELD,
+                primary_key_fields(&schema.schema),
+            )))
         };
-
-        Ok((
-            SourceSchema::new(schema, CdcType::FullChanges),
-            fields_schema_map,
-        ))
+        let value = Decoder::Registry(RegistryDecoder::new(sr_settings, VALUE_FIELD, value_fields));
+        let decoder = TopicDecoder::new(key, key_stored, value, &schema.schema);
+        Ok((schema, decoder))
     }
 
     pub async fn get_schema(
         table_names: Option<&[String]>,
         schema_registry_url: String,
+        topics: &[KafkaTopicConfig],
     ) -> Result<Vec<SourceSchema>, KafkaError> {
         let mut schemas = vec![];
         if let Some(tables) = table_names {
             for table_name in tables.iter() {
-                let (schema, _) = Self::get_single_schema(table_name, &schema_registry_url).await?;
+                let (schema, _) = Self::get_single_schema(
+                    table_name,
+                    &schema_registry_url,
+                    &key_mapping(topics, table_name),
+                )
+                .await?;
                 schemas.push(schema);
             }
         }
@@ -81,3 +118,19 @@ impl SchemaRegistryBasic {
         Ok(schemas)
     }
 }
+
+/// Retries requests to the schema registry until they succeed, or fail with an error that isn't retriable.
+async fn retry<T, F: Future<Output = Result<T, SRCError>>>(
+    mut request: impl FnMut() -> F,
+) -> Result<T, SRCError> {
+    loop {
+        match request().await {
+            Err(err) if err.retriable => {
+                const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+                error!("schema registry fetch error {err}. retrying in {RETRY_INTERVAL:?}...");
+                tokio::time::sleep(RETRY_INTERVAL).await;
+            }
+            result => return result,
+        }
+    }
+}
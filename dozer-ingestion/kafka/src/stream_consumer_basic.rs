@@ -1,21 +1,23 @@
 use std::collections::HashMap;
+use std::time::Duration;
 
 use dozer_ingestion_connector::{
     async_trait,
     dozer_types::{
-        models::ingestion_types::IngestionMessage,
-        node::OpIdentifier,
+        log::info,
+        models::ingestion_types::{IngestionMessage, KafkaTopicConfig, TransactionInfo},
+        node::{OpIdentifier, SourceCheckpoint},
         serde::{Deserialize, Serialize},
-        serde_json::{self, Value},
-        types::{Field, Operation, Record},
+        serde_json::Value,
     },
     Ingestor, TableInfo,
 };
 use rdkafka::{ClientConfig, Message};
 
+use crate::decoder::key_mapping;
 use crate::schema_registry_basic::SchemaRegistryBasic;
 use crate::stream_consumer::StreamConsumer;
-use crate::{debezium::mapper::convert_value_to_schema, KafkaError};
+use crate::KafkaError;
 use crate::{no_schema_registry_basic::NoSchemaRegistryBasic, KafkaStreamError};
 
 use super::stream_consumer_helper::{is_network_failure, OffsetsMap, StreamConsumerHelper};
@@ -73,8 +75,19 @@ pub struct Payload {
     pub op: Option<String>,
 }
 
+/// Messages to ingest before committing, if more are immediately available.
+const MAX_UNCOMMITTED_MESSAGES: usize = 1000;
+
 #[derive(Default)]
-pub struct StreamConsumerBasic {}
+pub struct StreamConsumerBasic {
+    topics: Vec<KafkaTopicConfig>,
+}
+
+impl StreamConsumerBasic {
+    pub fn new(topics: Vec<KafkaTopicConfig>) -> Self {
+        Self { topics }
+    }
+}
 
 #[async_trait]
 impl StreamConsumer for StreamConsumerBasic {
@@ -83,93 +96,92 @@ impl StreamConsumer for StreamConsumerBasic {
         client_config: ClientConfig,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourceCheckpoint>,
         schema_registry_url: &Option<String>,
     ) -> Result<(), KafkaError> {
-        assert!(last_checkpoint.is_none());
         let topics: Vec<String> = tables.iter().map(|t| t.name.clone()).collect();
 
-        let mut schemas = HashMap::new();
+        let mut decoders = HashMap::new();
         for (table_index, table) in tables.into_iter().enumerate() {
-            let schema = if let Some(url) = schema_registry_url {
-                SchemaRegistryBasic::get_single_schema(&table.name, url).await?
+            let key_mapping = key_mapping(&self.topics, &table.name);
+            let (_, decoder) = if let Some(url) = schema_registry_url {
+                SchemaRegistryBasic::get_single_schema(&table.name, url, &key_mapping).await?
             } else {
-                (NoSchemaRegistryBasic::get_single_schema(), HashMap::new())
+                NoSchemaRegistryBasic::get_single_schema(&table.name, &key_mapping)?
             };
 
-            schemas.insert(table.name.clone(), (table_index, schema));
+            decoders.insert(table.name.clone(), (table_index, decoder));
         }
 
         let topics: Vec<&str> = topics.iter().map(|t| t.as_str()).collect();
-        let mut con = StreamConsumerHelper::start(&client_config, &topics).await?;
-
-        let mut offsets = OffsetsMap::new();
+        // Commits are numbered, and the offsets of all partitions are checkpointed in their state.
+        let (mut con, mut offsets, mut commit) = if let Some(checkpoint) = last_checkpoint {
+            let state = checkpoint.state.ok_or(KafkaError::MissingOffsets)?;
+            let offsets = StreamConsumerHelper::decode_offsets(&state)?;
+            info!("Resuming Kafka topics from offsets {offsets:?}");
+            let con = StreamConsumerHelper::resume(&client_config, &topics, &offsets).await?;
+            (con, offsets, checkpoint.id.txid)
+        } else {
+            let con = StreamConsumerHelper::start(&client_config, &topics).await?;
+            (con, OffsetsMap::new(), 0)
+        };
+
+        let mut uncommitted = 0;
         loop {
-            if let Some(result) = con.poll(None) {
-                if matches!(result.as_ref(), Err(err) if is_network_failure(err)) {
-                    con = StreamConsumerHelper::resume(&client_config, &topics, &offsets).await?;
-                    continue;
-                }
-                let m = result
-                    .map_err(|e| KafkaError::KafkaStreamError(KafkaStreamError::PollingError(e)))?;
-                StreamConsumerHelper::update_offsets(&mut offsets, &m);
-                match schemas.get(m.topic()) {
-                    None => return Err(KafkaError::TopicNotDefined),
-                    Some((table_index, (schema, fields_map))) => {
-                        if let (Some(message), Some(key)) = (m.payload(), m.key()) {
-                            let new = match schema_registry_url {
-                                None => {
-                                    let value = std::str::from_utf8(message)
-                                        .map_err(KafkaError::BytesConvertError)?;
-                                    let key = std::str::from_utf8(key)
-                                        .map_err(KafkaError::BytesConvertError)?;
-
-                                    vec![
-                                        Field::String(key.to_string()),
-                                        Field::String(value.to_string()),
-                                    ]
-                                }
-                                Some(_) => {
-                                    let value_struct: Value = serde_json::from_str(
-                                        std::str::from_utf8(message)
-                                            .map_err(KafkaError::BytesConvertError)?,
-                                    )
-                                    .map_err(KafkaError::JsonDecodeError)?;
-                                    let _key_struct: Value = serde_json::from_str(
-                                        std::str::from_utf8(key)
-                                            .map_err(KafkaError::BytesConvertError)?,
-                                    )
-                                    .map_err(KafkaError::JsonDecodeError)?;
-
-                                    convert_value_to_schema(
-                                        value_struct,
-                                        &schema.schema,
-                                        fields_map,
-                                    )
-                                    .map_err(KafkaError::KafkaSchemaError)?
-                                }
-                            };
-
-                            if ingestor
-                                .handle_message(IngestionMessage::OperationEvent {
-                                    table_index: *table_index,
-                                    op: Operation::Insert {
-                                        new: Record {
-                                            values: new,
-                                            lifetime: None,
-                                        },
-                                    },
-                                    id: None,
-                                })
-                                .await
-                                .is_err()
-                            {
-                                // If receiving side is closed, we should stop the stream
-                                return Ok(());
-                            }
-                        }
+            let result = if uncommitted == 0 {
+                con.poll(None)
+            } else if uncommitted < MAX_UNCOMMITTED_MESSAGES {
+                con.poll(Duration::ZERO)
+            } else {
+                None
+            };
+            let Some(result) = result else {
+                // Commit once the messages that are immediately available have been ingested
+                if uncommitted > 0 {
+                    uncommitted = 0;
+                    commit += 1;
+                    if ingestor
+                        .handle_message(IngestionMessage::TransactionInfo(
+                            TransactionInfo::Commit {
+                                id: Some(OpIdentifier::new(commit, 0)),
+                                state: Some(StreamConsumerHelper::encode_offsets(&offsets)?),
+                            },
+                        ))
+                        .await
+                        .is_err()
+                    {
+                        return Ok(());
                     }
                 }
+                continue;
+            };
+
+            if matches!(result.as_ref(), Err(err) if is_network_failure(err)) {
+                con = StreamConsumerHelper::resume(&client_config, &topics, &offsets).await?;
+                continue;
+            }
+            let m = result
+                .map_err(|e| KafkaError::KafkaStreamError(KafkaStreamError::PollingError(e)))?;
+            StreamConsumerHelper::update_offsets(&mut offsets, &m);
+            uncommitted += 1;
+            let Some((table_index, decoder)) = decoders.get_mut(m.topic()) else {
+                return Err(KafkaError::TopicNotDefined);
+            };
+            let Some(op) = decoder.decode(m.key(), m.payload()).await? else {
+                continue;
+            };
+
+            if ingestor
+                .handle_message(IngestionMessage::OperationEvent {
+                    table_index: *table_index,
+                    op,
+                    id: None,
+                })
+                .await
+                .is_err()
+            {
+                // If receiving side is closed, we should stop the stream
+                return Ok(());
             }
         }
     }
@@ -1,17 +1,22 @@
-use dozer_ingestion_connector::{dozer_types, tokio};
+use dozer_ingestion_connector::{
+    dozer_types::{self, bincode},
+    tokio,
+};
 use rdkafka::{
     consumer::{BaseConsumer, Consumer},
     message::BorrowedMessage,
-    util::Timeout,
-    ClientConfig, Message, Offset,
+    ClientConfig, Message, Offset, TopicPartitionList,
 };
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use crate::KafkaError;
 
 pub struct StreamConsumerHelper;
 
-pub type OffsetsMap = HashMap<String, (i32, i64)>; // key: topic, value: (partition, offset)
+/// Next offset to read of every consumed partition. Key: (topic, partition).
+pub type OffsetsMap = HashMap<(String, i32), Offset>;
+
+const METADATA_TIMEOUT: Duration = Duration::from_secs(60);
 
 impl StreamConsumerHelper {
     pub async fn start(
@@ -21,6 +26,8 @@ impl StreamConsumerHelper {
         Self::resume_impl(client_config, topics, None).await
     }
 
+    /// Assigns all the partitions of `topics`, starting from `offsets`.
+    /// Partitions that aren't in `offsets` start from the committed offset of the group.
     pub async fn resume(
         client_config: &ClientConfig,
         topics: &[&str],
@@ -31,11 +38,39 @@ impl StreamConsumerHelper {
 
     pub fn update_offsets(offsets: &mut OffsetsMap, message: &BorrowedMessage<'_>) {
         let _ = offsets.insert(
-            message.topic().into(),
-            (message.partition(), message.offset()),
+            (message.topic().into(), message.partition()),
+            Offset::Offset(message.offset() + 1),
         );
     }
 
+    /// Serializes the next offset of every consumed partition, to be checkpointed with a commit.
+    pub fn encode_offsets(offsets: &OffsetsMap) -> Result<Vec<u8>, KafkaError> {
+        let mut offsets = offsets
+            .iter()
+            .filter_map(|((topic, partition), offset)| match offset {
+                Offset::Offset(offset) => Some((topic.clone(), *partition, *offset)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        offsets.sort();
+        bincode::encode_to_vec(&offsets, bincode::config::legacy())
+            .map_err(KafkaError::EncodeOffsets)
+    }
+
+    /// Deserializes the offsets serialized by `encode_offsets`.
+    ///
+    /// Partitions that aren't in the checkpoint, like the ones added since, resume from the committed offset of the group.
+    pub fn decode_offsets(state: &[u8]) -> Result<OffsetsMap, KafkaError> {
+        let offsets: Vec<(String, i32, i64)> =
+            bincode::decode_from_slice(state, bincode::config::legacy())
+                .map_err(KafkaError::DecodeOffsets)?
+                .0;
+        Ok(offsets
+            .into_iter()
+            .map(|(topic, partition, offset)| ((topic, partition), Offset::Offset(offset)))
+            .collect())
+    }
+
     async fn resume_impl(
         client_config: &ClientConfig,
         topics: &[&str],
@@ -63,12 +98,27 @@ impl StreamConsumerHelper {
         offsets: Option<&OffsetsMap>,
     ) -> Result<BaseConsumer, rdkafka::error::KafkaError> {
         let con: BaseConsumer = client_config.create()?;
-        con.subscribe(topics.iter().as_slice())?;
 
         if let Some(offsets) = offsets {
-            for (topic, &(partition, offset)) in offsets.iter() {
-                con.seek(topic, partition, Offset::Offset(offset), Timeout::Never)?;
+            // Partitions are assigned explicitly, because seeking is only possible once they're assigned.
+            let mut assignment = TopicPartitionList::new();
+            for topic in topics {
+                let metadata = con.fetch_metadata(Some(*topic), METADATA_TIMEOUT)?;
+                for partition in metadata
+                    .topics()
+                    .iter()
+                    .flat_map(|topic| topic.partitions())
+                {
+                    let offset = offsets
+                        .get(&(topic.to_string(), partition.id()))
+                        .copied()
+                        .unwrap_or(Offset::Stored);
+                    assignment.add_partition_offset(topic, partition.id(), offset)?;
+                }
             }
+            con.assign(&assignment)?;
+        } else {
+            con.subscribe(topics.iter().as_slice())?;
         }
 
         Ok(con)
@@ -113,3 +163,44 @@ pub fn is_network_failure(err: &rdkafka::error::KafkaError) -> bool {
             | NetworkException
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offsets_round_trip() {
+        // Hundreds of partitions with trillions of messages each.
+        let offsets = ["events", "users"]
+            .into_iter()
+            .flat_map(|topic| {
+                (0..256).map(move |partition| {
+                    let offset = 1_000_000_000_000 + partition as i64 * 123_456_789;
+                    ((topic.to_string(), partition), Offset::Offset(offset))
+                })
+            })
+            .collect::<OffsetsMap>();
+        let state = StreamConsumerHelper::encode_offsets(&offsets).unwrap();
+        assert_eq!(
+            StreamConsumerHelper::decode_offsets(&state).unwrap(),
+            offsets
+        );
+    }
+
+    #[test]
+    fn test_offsets_skip_unconsumed_partitions() {
+        let offsets = OffsetsMap::from([
+            (("a".to_string(), 0), Offset::Offset(42)),
+            (("a".to_string(), 1), Offset::Beginning),
+        ]);
+        let state = StreamConsumerHelper::encode_offsets(&offsets).unwrap();
+        assert_eq!(
+            StreamConsumerHelper::decode_offsets(&state).unwrap(),
+            OffsetsMap::from([(("a".to_string(), 0), Offset::Offset(42))])
+        );
+        assert!(matches!(
+            StreamConsumerHelper::decode_offsets(&state[..state.len() - 1]),
+            Err(KafkaError::DecodeOffsets(_))
+        ));
+    }
+}
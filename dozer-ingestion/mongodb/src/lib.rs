@@ -8,7 +8,7 @@ use dozer_ingestion_connector::{
         errors::{internal::BoxedError, types::DeserializationError},
         json_types::{serde_json_to_json_value, JsonValue},
         models::ingestion_types::{IngestionMessage, TransactionInfo},
-        node::OpIdentifier,
+        node::SourceCheckpoint,
         thiserror::{self, Error},
         types::{Field, FieldDefinition, FieldType, Operation, Record, SourceDefinition},
     },
@@ -597,7 +597,7 @@ impl Connector for MongodbConnector {
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        _last_checkpoint: Option<OpIdentifier>,
+        _last_checkpoint: Option<SourceCheckpoint>,
     ) -> Result<(), BoxedError> {
         // Snapshot: find
         //
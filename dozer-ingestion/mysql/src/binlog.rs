@@ -444,6 +444,7 @@ impl BinlogIngestor<'_, '_, '_> {
                         .handle_message(IngestionMessage::TransactionInfo(
                             TransactionInfo::Commit {
                                 id: Some(encode_state(&transaction_pos)),
+                                state: None,
                             },
                         ))
                         .await
@@ -15,7 +15,7 @@ use dozer_ingestion_connector::{
         log::info,
         models::ingestion_types::IngestionMessage,
         models::ingestion_types::TransactionInfo,
-        node::OpIdentifier,
+        node::SourceCheckpoint,
         types::{FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition},
     },
     utils::TableNotFound,
@@ -199,7 +199,7 @@ impl Connector for MySQLConnector {
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourceCheckpoint>,
     ) -> Result<(), BoxedError> {
         self.replicate(ingestor, tables, last_checkpoint)
             .await
@@ -222,7 +222,7 @@ impl MySQLConnector {
         &self,
         ingestor: &Ingestor,
         table_infos: Vec<TableInfo>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourceCheckpoint>,
     ) -> Result<(), MySQLConnectorError> {
         let mut table_definitions = self
             .schema_helper()
@@ -240,7 +240,7 @@ impl MySQLConnector {
             .await?;
 
         let binlog_position = last_checkpoint
-            .map(crate::binlog::BinlogPosition::try_from)
+            .map(|checkpoint| crate::binlog::BinlogPosition::try_from(checkpoint.id))
             .transpose()?;
 
         let binlog_positions = self
@@ -539,7 +539,7 @@ mod tests {
                 IngestionMessage::OperationEvent { id, .. } => {
                     *id = None;
                 }
-                IngestionMessage::TransactionInfo(TransactionInfo::Commit { id }) => {
+                IngestionMessage::TransactionInfo(TransactionInfo::Commit { id, .. }) => {
                     *id = None;
                 }
                 _ => {}
@@ -736,7 +736,10 @@ mod tests {
                 },
                 id: None,
             },
-            IngestionMessage::TransactionInfo(TransactionInfo::Commit { id: None }),
+            IngestionMessage::TransactionInfo(TransactionInfo::Commit {
+                id: None,
+                state: None,
+            }),
         ];
 
         check_ingestion_messages(&mut iterator, expected_ingestion_messages).await;
@@ -754,7 +757,10 @@ mod tests {
                 },
                 id: None,
             },
-            IngestionMessage::TransactionInfo(TransactionInfo::Commit { id: None }),
+            IngestionMessage::TransactionInfo(TransactionInfo::Commit {
+                id: None,
+                state: None,
+            }),
         ];
 
         check_ingestion_messages(&mut iterator, expected_ingestion_messages).await;
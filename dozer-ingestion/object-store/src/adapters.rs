@@ -75,6 +75,9 @@ impl DozerObjectStore for S3Storage {
         let folder = match &table.config {
             TableConfig::CSV(csv_config) => csv_config.path.clone(),
             TableConfig::Parquet(parquet_config) => parquet_config.path.clone(),
+            TableConfig::JSON(json_config) => json_config.path.clone(),
+            TableConfig::Avro(avro_config) => avro_config.path.clone(),
+            TableConfig::ORC(orc_config) => orc_config.path.clone(),
         };
 
         Ok(DozerObjectStoreParams {
@@ -109,6 +112,9 @@ impl DozerObjectStore for LocalStorage {
         let folder = match &table.config {
             TableConfig::CSV(csv_config) => csv_config.path.clone(),
             TableConfig::Parquet(parquet_config) => parquet_config.path.clone(),
+            TableConfig::JSON(json_config) => json_config.path.clone(),
+            TableConfig::Avro(avro_config) => avro_config.path.clone(),
+            TableConfig::ORC(orc_config) => orc_config.path.clone(),
         };
 
         Ok(DozerObjectStoreParams {
@@ -4,7 +4,10 @@ use dozer_ingestion_connector::{
     TableIdentifier,
 };
 
-use crate::{adapters::DozerObjectStore, ObjectStoreConnectorError};
+use crate::{
+    adapters::DozerObjectStore, file_selector::FileSelector, table::TableConfig,
+    ObjectStoreConnectorError,
+};
 
 pub enum Validations {
     Permissions,
@@ -48,6 +51,7 @@ fn validate_permissions<T: DozerObjectStore>(
             let params = config.table_params(&table.name)?;
             ListingTableUrl::parse(&params.table_path)
                 .map_err(ObjectStoreConnectorError::InternalDataFusionError)?;
+            FileSelector::new(params.data_fusion_table.config.selection())?;
         }
     }
 
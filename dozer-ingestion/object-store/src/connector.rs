@@ -3,7 +3,7 @@ use dozer_ingestion_connector::dozer_types::log::error;
 use dozer_ingestion_connector::dozer_types::models::ingestion_types::{
     IngestionMessage, TransactionInfo,
 };
-use dozer_ingestion_connector::dozer_types::node::OpIdentifier;
+use dozer_ingestion_connector::dozer_types::node::SourceCheckpoint;
 use dozer_ingestion_connector::dozer_types::types::FieldType;
 use dozer_ingestion_connector::futures::future::try_join_all;
 use dozer_ingestion_connector::tokio::sync::mpsc::channel;
@@ -103,7 +103,7 @@ impl<T: DozerObjectStore> Connector for ObjectStoreConnector<T> {
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourceCheckpoint>,
     ) -> Result<(), BoxedError> {
         assert!(last_checkpoint.is_none());
         let (sender, mut receiver) =
@@ -188,7 +188,10 @@ impl<T: DozerObjectStore> Connector for ObjectStoreConnector<T> {
             .unwrap();
         sender
             .send(Ok(Some(IngestionMessage::TransactionInfo(
-                TransactionInfo::Commit { id: None },
+                TransactionInfo::Commit {
+                    id: None,
+                    state: None,
+                },
             ))))
             .await
             .unwrap();
@@ -0,0 +1,163 @@
+use dozer_ingestion_connector::dozer_types::{
+    models::ingestion_types::{FileSelection, PartitionColumn, PartitionColumnType},
+    types::{Field, FieldDefinition, FieldType, SourceDefinition},
+};
+use glob::{MatchOptions, Pattern};
+use percent_encoding::percent_decode_str;
+
+use crate::{ObjectStoreObjectError, ObjectStoreTableReaderError};
+
+/// Value of a partition column in Hive-style paths when it's `null`.
+const HIVE_DEFAULT_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// `*` doesn't match `/`, so that patterns can select partitions, like `dt=*/region=eu/*`.
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Decides which files of a table are read, and the values of their partition columns.
+#[derive(Debug)]
+pub struct FileSelector {
+    partition_columns: Vec<PartitionColumn>,
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+    start_partition: Vec<Field>,
+}
+
+impl FileSelector {
+    pub fn new(selection: &FileSelection) -> Result<Self, ObjectStoreObjectError> {
+        let parse_patterns = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| {
+                    Pattern::new(pattern)
+                        .map_err(|e| ObjectStoreObjectError::InvalidGlobPattern(pattern.clone(), e))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        let mut start_partition = vec![];
+        if let Some(start) = &selection.start_partition {
+            let invalid = || ObjectStoreObjectError::InvalidStartPartition(start.clone());
+            for (index, segment) in start.trim_matches('/').split('/').enumerate() {
+                let (key, value) = segment.split_once('=').ok_or_else(invalid)?;
+                let column = selection
+                    .partition_columns
+                    .get(index)
+                    .filter(|column| column.name == key)
+                    .ok_or_else(invalid)?;
+                start_partition.push(parse_value(column, value).map_err(|_| invalid())?);
+            }
+        }
+
+        Ok(Self {
+            partition_columns: selection.partition_columns.clone(),
+            include: parse_patterns(&selection.include)?,
+            exclude: parse_patterns(&selection.exclude)?,
+            start_partition,
+        })
+    }
+
+    /// Returns the partition values of the file at `path`, relative to the table folder,
+    /// or `None` if it must not be read.
+    ///
+    /// Partition columns missing from the path are `null`.
+    pub fn select(&self, path: &str) -> Result<Option<Vec<Field>>, ObjectStoreTableReaderError> {
+        if !self.include.is_empty()
+            && !self
+                .include
+                .iter()
+                .any(|pattern| pattern.matches_with(path, MATCH_OPTIONS))
+        {
+            return Ok(None);
+        }
+        if self
+            .exclude
+            .iter()
+            .any(|pattern| pattern.matches_with(path, MATCH_OPTIONS))
+        {
+            return Ok(None);
+        }
+
+        let mut values = vec![Field::Null; self.partition_columns.len()];
+        let directories = path
+            .rsplit_once('/')
+            .map_or("", |(directories, _)| directories);
+        for segment in directories.split('/') {
+            let Some((key, value)) = segment.split_once('=') else {
+                continue;
+            };
+            if let Some(index) = self
+                .partition_columns
+                .iter()
+                .position(|column| column.name == key)
+            {
+                let column = &self.partition_columns[index];
+                values[index] = parse_value(column, value).map_err(|value| {
+                    ObjectStoreTableReaderError::InvalidPartitionValue {
+                        path: path.to_string(),
+                        column: column.name.clone(),
+                        value,
+                    }
+                })?;
+            }
+        }
+
+        if values[..self.start_partition.len()] < self.start_partition[..] {
+            return Ok(None);
+        }
+        Ok(Some(values))
+    }
+
+    /// Definitions of the partition columns, which follow the columns of the files.
+    pub fn field_definitions(&self) -> Vec<FieldDefinition> {
+        self.partition_columns
+            .iter()
+            .map(|column| FieldDefinition {
+                name: column.name.clone(),
+                typ: field_type(column.typ),
+                nullable: true,
+                source: SourceDefinition::Dynamic,
+            })
+            .collect()
+    }
+
+    pub fn is_partition_column(&self, name: &str) -> bool {
+        self.partition_columns
+            .iter()
+            .any(|column| column.name == name)
+    }
+
+    /// Keeps the values of the partition columns that are in `column_names`, or all of them if it's empty.
+    pub fn selected_values(&self, values: &[Field], column_names: &[String]) -> Vec<Field> {
+        self.partition_columns
+            .iter()
+            .zip(values)
+            .filter(|(column, _)| column_names.is_empty() || column_names.contains(&column.name))
+            .map(|(_, value)| value.clone())
+            .collect()
+    }
+}
+
+/// Parses a percent-encoded partition value. Returns the decoded value on failure.
+fn parse_value(column: &PartitionColumn, value: &str) -> Result<Field, String> {
+    let value = percent_decode_str(value).decode_utf8_lossy();
+    if value == HIVE_DEFAULT_PARTITION {
+        return Ok(Field::Null);
+    }
+    Field::from_str(&value, field_type(column.typ), true).map_err(|_| value.into_owned())
+}
+
+fn field_type(typ: PartitionColumnType) -> FieldType {
+    match typ {
+        PartitionColumnType::String => FieldType::String,
+        PartitionColumnType::Int => FieldType::Int,
+        PartitionColumnType::UInt => FieldType::UInt,
+        PartitionColumnType::Float => FieldType::Float,
+        PartitionColumnType::Boolean => FieldType::Boolean,
+        PartitionColumnType::Date => FieldType::Date,
+        PartitionColumnType::Timestamp => FieldType::Timestamp,
+    }
+}
@@ -1,11 +1,11 @@
 use datafusion::datasource::{
-    file_format::{csv::CsvFormat, parquet::ParquetFormat},
+    file_format::{avro::AvroFormat, csv::CsvFormat, json::JsonFormat, parquet::ParquetFormat},
     listing::ListingOptions,
 };
 use dozer_ingestion_connector::dozer_types::models::ingestion_types::{Table, TableConfig};
 use std::sync::Arc;
 
-use crate::{table_watcher::FileInfo, ObjectStoreObjectError};
+use crate::{orc::OrcFormat, table_watcher::FileInfo, ObjectStoreObjectError};
 
 pub fn map_listing_options(
     data_fusion_table: &Table,
@@ -22,6 +22,18 @@ pub fn map_listing_options(
                     .with_file_extension(parquet.extension.clone()),
             )
         }
+        TableConfig::JSON(json) => {
+            let format = JsonFormat::default();
+            Ok(ListingOptions::new(Arc::new(format)).with_file_extension(json.extension.clone()))
+        }
+        TableConfig::Avro(avro) => {
+            let format = AvroFormat;
+            Ok(ListingOptions::new(Arc::new(format)).with_file_extension(avro.extension.clone()))
+        }
+        TableConfig::ORC(orc) => {
+            let format = OrcFormat;
+            Ok(ListingOptions::new(Arc::new(format)).with_file_extension(orc.extension.clone()))
+        }
     }
 }
 
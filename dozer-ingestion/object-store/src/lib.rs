@@ -7,7 +7,9 @@ use dozer_ingestion_connector::dozer_types::{
 mod adapters;
 mod connection;
 pub mod connector;
+mod file_selector;
 mod helper;
+mod orc;
 mod schema_helper;
 pub mod schema_mapper;
 mod table;
@@ -77,6 +79,14 @@ pub enum ObjectStoreObjectError {
 
     #[error("Listing path {0} error: {1}")]
     ListingPathError(String, #[source] DataFusionError),
+
+    #[error("Invalid glob pattern {0}: {1}")]
+    InvalidGlobPattern(String, #[source] glob::PatternError),
+
+    #[error(
+        "Invalid start partition {0}, expected `key=value` pairs of the leading partition columns"
+    )]
+    InvalidStartPartition(String),
 }
 
 #[derive(Error, Debug)]
@@ -92,4 +102,11 @@ pub enum ObjectStoreTableReaderError {
 
     #[error("File {0} has a conflicting schema")]
     ConflictingSchema(ListingTableUrl),
+
+    #[error("File {path} has an invalid value {value:?} of partition column {column}")]
+    InvalidPartitionValue {
+        path: String,
+        column: String,
+        value: String,
+    },
 }
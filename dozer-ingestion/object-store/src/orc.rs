@@ -0,0 +1,282 @@
+//! ORC `FileFormat` for DataFusion, which doesn't have one.
+//!
+//! Files are decoded by `orc-rust`, which is built on a newer Arrow version than DataFusion,
+//! so its schemas and batches are converted through the Arrow IPC stream format.
+
+use std::any::Any;
+use std::io::{BufReader, Cursor};
+use std::sync::Arc;
+
+use datafusion::arrow::array::{new_null_array, ArrayRef};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{Schema, SchemaRef};
+use datafusion::arrow::error::ArrowError;
+use datafusion::arrow::ipc::reader::StreamReader;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::FileType;
+use datafusion::datasource::file_format::FileFormat;
+use datafusion::datasource::physical_plan::{
+    FileMeta, FileOpenFuture, FileOpener, FileScanConfig, FileStream,
+};
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::context::SessionState;
+use datafusion::execution::TaskContext;
+use datafusion::physical_expr::{
+    EquivalenceProperties, LexOrdering, PhysicalExpr, PhysicalSortExpr,
+};
+use datafusion::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::{
+    DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream,
+    Statistics,
+};
+use dozer_ingestion_connector::async_trait;
+use dozer_ingestion_connector::dozer_types::bytes::{buf::Reader, Buf, Bytes};
+use dozer_ingestion_connector::futures::StreamExt;
+use object_store::{ObjectMeta, ObjectStore};
+use orc_arrow::ipc::writer::StreamWriter;
+use orc_arrow::record_batch::RecordBatchReader;
+use orc_rust::reader::ChunkReader;
+use orc_rust::{ArrowReader, ArrowReaderBuilder};
+
+/// ORC `FileFormat` implementation.
+#[derive(Default, Debug)]
+pub struct OrcFormat;
+
+#[async_trait]
+impl FileFormat for OrcFormat {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn infer_schema(
+        &self,
+        _state: &SessionState,
+        store: &Arc<dyn ObjectStore>,
+        objects: &[ObjectMeta],
+    ) -> Result<SchemaRef> {
+        let mut schemas = vec![];
+        for object in objects {
+            let data = store.get(&object.location).await?.bytes().await?;
+            let reader = open(data, None)?;
+            let schema =
+                ipc_to_datafusion(|buf| StreamWriter::try_new(buf, &reader.schema())?.finish())?
+                    .schema();
+            schemas.push(schema.as_ref().clone());
+        }
+        Ok(Arc::new(Schema::try_merge(schemas)?))
+    }
+
+    async fn infer_stats(
+        &self,
+        _state: &SessionState,
+        _store: &Arc<dyn ObjectStore>,
+        table_schema: SchemaRef,
+        _object: &ObjectMeta,
+    ) -> Result<Statistics> {
+        Ok(Statistics::new_unknown(&table_schema))
+    }
+
+    async fn create_physical_plan(
+        &self,
+        _state: &SessionState,
+        conf: FileScanConfig,
+        _filters: Option<&Arc<dyn PhysicalExpr>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(OrcExec::new(conf)))
+    }
+
+    fn file_type(&self) -> FileType {
+        // DataFusion has no ORC file type. It's only used for writing, which isn't supported.
+        FileType::ARROW
+    }
+}
+
+/// Execution plan for scanning ORC files.
+#[derive(Debug, Clone)]
+struct OrcExec {
+    base_config: FileScanConfig,
+    projected_statistics: Statistics,
+    projected_schema: SchemaRef,
+    projected_output_ordering: Vec<LexOrdering>,
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl OrcExec {
+    fn new(base_config: FileScanConfig) -> Self {
+        let (projected_schema, projected_statistics, projected_output_ordering) =
+            base_config.project();
+        Self {
+            base_config,
+            projected_schema,
+            projected_statistics,
+            projected_output_ordering,
+            metrics: ExecutionPlanMetricsSet::new(),
+        }
+    }
+}
+
+impl DisplayAs for OrcExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "OrcExec: ")?;
+        self.base_config.fmt_as(t, f)
+    }
+}
+
+impl ExecutionPlan for OrcExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.projected_schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(self.base_config.file_groups.len())
+    }
+
+    fn unbounded_output(&self, _: &[bool]) -> Result<bool> {
+        Ok(self.base_config.infinite_source)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.projected_output_ordering
+            .first()
+            .map(|ordering| ordering.as_slice())
+    }
+
+    fn equivalence_properties(&self) -> EquivalenceProperties {
+        EquivalenceProperties::new_with_orderings(self.schema(), &self.projected_output_ordering)
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let object_store = context
+            .runtime_env()
+            .object_store(&self.base_config.object_store_url)?;
+        let file_schema = &self.base_config.file_schema;
+        let schema = match &self.base_config.projection {
+            Some(projection) => {
+                let indices = projection
+                    .iter()
+                    .copied()
+                    .filter(|index| *index < file_schema.fields().len())
+                    .collect::<Vec<_>>();
+                Arc::new(file_schema.project(&indices)?)
+            }
+            None => file_schema.clone(),
+        };
+        let opener = OrcOpener {
+            schema,
+            batch_size: context.session_config().batch_size(),
+            object_store,
+        };
+        let stream = FileStream::new(&self.base_config, partition, opener, &self.metrics)?;
+        Ok(Box::pin(stream))
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        Ok(self.projected_statistics.clone())
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+}
+
+/// Opens ORC files as streams of batches of `schema`, the projected file schema.
+struct OrcOpener {
+    schema: SchemaRef,
+    batch_size: usize,
+    object_store: Arc<dyn ObjectStore>,
+}
+
+impl FileOpener for OrcOpener {
+    fn open(&self, file_meta: FileMeta) -> Result<FileOpenFuture> {
+        let schema = self.schema.clone();
+        let batch_size = self.batch_size;
+        let object_store = self.object_store.clone();
+        Ok(Box::pin(async move {
+            let data = object_store
+                .get(file_meta.location())
+                .await?
+                .bytes()
+                .await?;
+            let reader = open(data, Some(batch_size))?;
+            let batches = reader.map(move |batch| {
+                let batch = batch.map_err(|e| ArrowError::ExternalError(Box::new(e)))?;
+                let batch = ipc_to_datafusion(|buf| {
+                    let mut writer = StreamWriter::try_new(buf, &batch.schema())?;
+                    writer.write(&batch)?;
+                    writer.finish()
+                })?
+                .next()
+                .unwrap_or_else(|| Ok(RecordBatch::new_empty(schema.clone())))?;
+                project(&batch, &schema)
+            });
+            Ok(dozer_ingestion_connector::futures::stream::iter(batches).boxed())
+        }))
+    }
+}
+
+/// An ORC file that has been fetched from the object store.
+struct OrcFile(Bytes);
+
+impl ChunkReader for OrcFile {
+    type T = Reader<Bytes>;
+
+    fn len(&self) -> u64 {
+        self.0.len() as u64
+    }
+
+    fn get_read(&self, offset_from_start: u64) -> std::io::Result<Self::T> {
+        Ok(self.0.slice(offset_from_start as usize..).reader())
+    }
+}
+
+fn open(data: Bytes, batch_size: Option<usize>) -> Result<ArrowReader<OrcFile>> {
+    let builder = ArrowReaderBuilder::try_new(OrcFile(data))
+        .map_err(|e| DataFusionError::External(Box::new(e)))?;
+    Ok(match batch_size {
+        Some(batch_size) => builder.with_batch_size(batch_size),
+        None => builder,
+    }
+    .build())
+}
+
+/// Reads back the IPC stream that `write` writes with `orc-rust`'s Arrow version.
+fn ipc_to_datafusion(
+    write: impl FnOnce(&mut Vec<u8>) -> Result<(), orc_arrow::error::ArrowError>,
+) -> Result<StreamReader<BufReader<Cursor<Vec<u8>>>>, ArrowError> {
+    let mut buf = vec![];
+    write(&mut buf).map_err(|e| ArrowError::ExternalError(Box::new(e)))?;
+    StreamReader::try_new(Cursor::new(buf), None)
+}
+
+/// Projects a batch read from a file onto `schema`. Columns that aren't in the file are filled with `null`.
+fn project(batch: &RecordBatch, schema: &SchemaRef) -> Result<RecordBatch, ArrowError> {
+    let mut columns: Vec<ArrayRef> = vec![];
+    for field in schema.fields() {
+        let column = match batch.column_by_name(field.name()) {
+            Some(column) if column.data_type() == field.data_type() => column.clone(),
+            Some(column) => cast(column, field.data_type())?,
+            None => new_null_array(field.data_type(), batch.num_rows()),
+        };
+        columns.push(column);
+    }
+    RecordBatch::try_new(schema.clone(), columns)
+}
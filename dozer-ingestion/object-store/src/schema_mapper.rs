@@ -1,17 +1,17 @@
 use datafusion::arrow::datatypes::SchemaRef;
-use datafusion::datasource::file_format::csv::CsvFormat;
-use datafusion::datasource::file_format::parquet::ParquetFormat;
 use datafusion::datasource::listing::{ListingOptions, ListingTableUrl};
 use datafusion::prelude::SessionContext;
 use dozer_ingestion_connector::dozer_types::log::error;
-use dozer_ingestion_connector::dozer_types::models::ingestion_types::TableConfig;
 use dozer_ingestion_connector::dozer_types::types::Schema;
 use dozer_ingestion_connector::utils::ListOrFilterColumns;
 use dozer_ingestion_connector::{CdcType, SourceSchema, SourceSchemaResult};
 use std::sync::Arc;
 
 use crate::adapters::DozerObjectStore;
+use crate::file_selector::FileSelector;
+use crate::helper::map_listing_options;
 use crate::schema_helper::map_schema_to_dozer;
+use crate::table::TableConfig;
 use crate::{ObjectStoreConnectorError, ObjectStoreObjectError, ObjectStoreSchemaError};
 
 pub fn map_schema(
@@ -52,27 +52,18 @@ async fn get_table_schema(
 ) -> SourceSchemaResult {
     let params = &config.table_params(&table.name)?;
 
-    match &params.data_fusion_table.config {
-        TableConfig::CSV(table_config) => {
-            let format = CsvFormat::default();
-            let listing_options = ListingOptions::new(Arc::new(format))
-                .with_file_extension(table_config.extension.clone());
-            get_object_schema(table, config, listing_options).await
-        }
-        TableConfig::Parquet(table_config) => {
-            let format = ParquetFormat::default();
-            let listing_options = ListingOptions::new(Arc::new(format))
-                .with_file_extension(table_config.extension.clone());
-
-            get_object_schema(table, config, listing_options).await
-        }
-    }
+    let listing_options = map_listing_options(&params.data_fusion_table)
+        .map_err(ObjectStoreConnectorError::DataFusionStorageObjectError)?;
+    let selector = FileSelector::new(params.data_fusion_table.config.selection())
+        .map_err(ObjectStoreConnectorError::DataFusionStorageObjectError)?;
+    get_object_schema(table, config, listing_options, &selector).await
 }
 
 async fn get_object_schema(
     table: &ListOrFilterColumns,
     store_config: &impl DozerObjectStore,
     listing_options: ListingOptions,
+    selector: &FileSelector,
 ) -> SourceSchemaResult {
     let params = store_config.table_params(&table.name)?;
 
@@ -95,7 +86,17 @@ async fn get_object_schema(
             ObjectStoreConnectorError::InternalDataFusionError(e)
         })?;
 
-    let schema = map_schema(resolved_schema, table)?;
+    let mut schema = map_schema(resolved_schema, table)?;
+    // Partition columns follow the columns of the files
+    let partition_fields =
+        selector
+            .field_definitions()
+            .into_iter()
+            .filter(|field| match &table.columns {
+                Some(columns) if !columns.is_empty() => columns.contains(&field.name),
+                _ => true,
+            });
+    schema.fields.extend(partition_fields);
 
     Ok(SourceSchema::new(schema, CdcType::Nothing))
 }
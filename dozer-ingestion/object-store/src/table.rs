@@ -5,7 +5,10 @@ use dozer_ingestion_connector::{
     dozer_types::{
         chrono::{DateTime, Utc},
         log::info,
-        models::ingestion_types::{self, CsvConfig, IngestionMessage, ParquetConfig},
+        models::ingestion_types::{
+            self, AvroConfig, CsvConfig, FileSelection, IngestionMessage, JsonConfig, OrcConfig,
+            ParquetConfig,
+        },
     },
     futures::StreamExt,
     tokio::{self, sync::mpsc::Sender},
@@ -16,6 +19,7 @@ use object_store::ObjectStore;
 
 use crate::{
     adapters::DozerObjectStore,
+    file_selector::FileSelector,
     helper::{is_marker_file_exist, map_listing_options},
     table_reader,
     table_watcher::FileInfo,
@@ -26,6 +30,7 @@ pub trait TableConfig {
     fn path(&self) -> &str;
     fn extension(&self) -> &str;
     fn marker_extension(&self) -> Option<&str>;
+    fn selection(&self) -> &FileSelection;
 }
 
 pub struct ObjectStoreTable<C: TableConfig, O: DozerObjectStore> {
@@ -54,6 +59,8 @@ impl<C: TableConfig, O: DozerObjectStore> ObjectStoreTable<C, O> {
 
         let listing_options = map_listing_options(&params.data_fusion_table)
             .map_err(ObjectStoreConnectorError::DataFusionStorageObjectError)?;
+        let selector = FileSelector::new(self.table_config.selection())
+            .map_err(ObjectStoreConnectorError::DataFusionStorageObjectError)?;
 
         let ctx = SessionContext::new();
 
@@ -98,6 +105,24 @@ impl<C: TableConfig, O: DozerObjectStore> ObjectStoreTable<C, O> {
                 }
 
                 if file_path.ends_with(self.table_config.extension()) {
+                    let relative_path = file_path
+                        .strip_prefix(&params.folder)
+                        .unwrap_or(&file_path)
+                        .trim_start_matches('/');
+                    let partition_values = match selector.select(relative_path) {
+                        Ok(Some(partition_values)) => partition_values,
+                        Ok(None) => {
+                            // Files that aren't selected are never read
+                            update_state.insert(object.location, object.last_modified);
+                            continue;
+                        }
+                        Err(e) => {
+                            update_state.insert(object.location, object.last_modified);
+                            sender.send(Err(e.into())).await.unwrap();
+                            continue;
+                        }
+                    };
+
                     // Scenario 2: New file added
                     info!(
                         "Source Object has been added: {:?}, {:?}",
@@ -114,6 +139,7 @@ impl<C: TableConfig, O: DozerObjectStore> ObjectStoreTable<C, O> {
                     new_files.push(FileInfo {
                         name: params.table_path.clone() + new_path_str,
                         last_modified: object.last_modified.timestamp(),
+                        partition_values,
                     });
                     if self.table_config.marker_extension().is_none() {
                         update_state.insert(object.location, object.last_modified);
@@ -136,6 +162,7 @@ impl<C: TableConfig, O: DozerObjectStore> ObjectStoreTable<C, O> {
                         new_marker_files.push(FileInfo {
                             name: params.table_path.clone() + new_path_str,
                             last_modified: object.last_modified.timestamp(),
+                            partition_values: vec![],
                         });
 
                         update_state.insert(object.location, object.last_modified);
@@ -172,6 +199,8 @@ impl<C: TableConfig, O: DozerObjectStore> ObjectStoreTable<C, O> {
                     table_info,
                     sender.clone(),
                     schema.as_ref(),
+                    &selector,
+                    &file.partition_values,
                 )
                 .await;
                 match result {
@@ -201,6 +230,8 @@ impl<C: TableConfig, O: DozerObjectStore> ObjectStoreTable<C, O> {
 
         let listing_options = map_listing_options(&params.data_fusion_table)
             .map_err(ObjectStoreConnectorError::DataFusionStorageObjectError)?;
+        let selector = FileSelector::new(self.table_config.selection())
+            .map_err(ObjectStoreConnectorError::DataFusionStorageObjectError)?;
 
         let ctx = SessionContext::new();
 
@@ -246,6 +277,24 @@ impl<C: TableConfig, O: DozerObjectStore> ObjectStoreTable<C, O> {
                     }
 
                     if file_path.ends_with(self.table_config.extension()) {
+                        let relative_path = file_path
+                            .strip_prefix(&source_folder)
+                            .unwrap_or(&file_path)
+                            .trim_start_matches('/');
+                        let partition_values = match selector.select(relative_path) {
+                            Ok(Some(partition_values)) => partition_values,
+                            Ok(None) => {
+                                // Files that aren't selected are never read
+                                update_state.insert(object.location, object.last_modified);
+                                continue;
+                            }
+                            Err(e) => {
+                                update_state.insert(object.location, object.last_modified);
+                                sender.send(Err(e.into())).await.unwrap();
+                                continue;
+                            }
+                        };
+
                         // Scenario 2: New file added
                         info!(
                             "Source Object has been added: {:?}, {:?}",
@@ -262,6 +311,7 @@ impl<C: TableConfig, O: DozerObjectStore> ObjectStoreTable<C, O> {
                         new_files.push(FileInfo {
                             name: base_path.clone() + new_path_str,
                             last_modified: object.last_modified.timestamp(),
+                            partition_values,
                         });
                         if self.table_config.marker_extension().is_none() {
                             update_state.insert(object.location, object.last_modified);
@@ -284,6 +334,7 @@ impl<C: TableConfig, O: DozerObjectStore> ObjectStoreTable<C, O> {
                             new_marker_files.push(FileInfo {
                                 name: base_path.clone() + new_path_str,
                                 last_modified: object.last_modified.timestamp(),
+                                partition_values: vec![],
                             });
                             update_state.insert(object.location, object.last_modified);
                         } else {
@@ -321,6 +372,8 @@ impl<C: TableConfig, O: DozerObjectStore> ObjectStoreTable<C, O> {
                         table_info,
                         sender.clone(),
                         schema,
+                        &selector,
+                        &file.partition_values,
                     )
                     .await;
                     if let Err(e) = result {
@@ -348,6 +401,10 @@ impl TableConfig for CsvConfig {
     fn marker_extension(&self) -> Option<&str> {
         self.marker_extension.as_deref()
     }
+
+    fn selection(&self) -> &FileSelection {
+        &self.selection
+    }
 }
 
 impl TableConfig for ParquetConfig {
@@ -362,6 +419,64 @@ impl TableConfig for ParquetConfig {
     fn marker_extension(&self) -> Option<&str> {
         self.marker_extension.as_deref()
     }
+
+    fn selection(&self) -> &FileSelection {
+        &self.selection
+    }
+}
+
+impl TableConfig for JsonConfig {
+    fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn extension(&self) -> &str {
+        &self.extension
+    }
+
+    fn marker_extension(&self) -> Option<&str> {
+        self.marker_extension.as_deref()
+    }
+
+    fn selection(&self) -> &FileSelection {
+        &self.selection
+    }
+}
+
+impl TableConfig for AvroConfig {
+    fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn extension(&self) -> &str {
+        &self.extension
+    }
+
+    fn marker_extension(&self) -> Option<&str> {
+        self.marker_extension.as_deref()
+    }
+
+    fn selection(&self) -> &FileSelection {
+        &self.selection
+    }
+}
+
+impl TableConfig for OrcConfig {
+    fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn extension(&self) -> &str {
+        &self.extension
+    }
+
+    fn marker_extension(&self) -> Option<&str> {
+        self.marker_extension.as_deref()
+    }
+
+    fn selection(&self) -> &FileSelection {
+        &self.selection
+    }
 }
 
 impl TableConfig for ingestion_types::TableConfig {
@@ -369,6 +484,9 @@ impl TableConfig for ingestion_types::TableConfig {
         match self {
             ingestion_types::TableConfig::CSV(csv_config) => csv_config.path(),
             ingestion_types::TableConfig::Parquet(parquet_config) => parquet_config.path(),
+            ingestion_types::TableConfig::JSON(json_config) => json_config.path(),
+            ingestion_types::TableConfig::Avro(avro_config) => avro_config.path(),
+            ingestion_types::TableConfig::ORC(orc_config) => orc_config.path(),
         }
     }
 
@@ -376,6 +494,9 @@ impl TableConfig for ingestion_types::TableConfig {
         match self {
             ingestion_types::TableConfig::CSV(csv_config) => csv_config.extension(),
             ingestion_types::TableConfig::Parquet(parquet_config) => parquet_config.extension(),
+            ingestion_types::TableConfig::JSON(json_config) => json_config.extension(),
+            ingestion_types::TableConfig::Avro(avro_config) => avro_config.extension(),
+            ingestion_types::TableConfig::ORC(orc_config) => orc_config.extension(),
         }
     }
 
@@ -385,6 +506,19 @@ impl TableConfig for ingestion_types::TableConfig {
             ingestion_types::TableConfig::Parquet(parquet_config) => {
                 parquet_config.marker_extension()
             }
+            ingestion_types::TableConfig::JSON(json_config) => json_config.marker_extension(),
+            ingestion_types::TableConfig::Avro(avro_config) => avro_config.marker_extension(),
+            ingestion_types::TableConfig::ORC(orc_config) => orc_config.marker_extension(),
+        }
+    }
+
+    fn selection(&self) -> &FileSelection {
+        match self {
+            ingestion_types::TableConfig::CSV(csv_config) => csv_config.selection(),
+            ingestion_types::TableConfig::Parquet(parquet_config) => parquet_config.selection(),
+            ingestion_types::TableConfig::JSON(json_config) => json_config.selection(),
+            ingestion_types::TableConfig::Avro(avro_config) => avro_config.selection(),
+            ingestion_types::TableConfig::ORC(orc_config) => orc_config.selection(),
         }
     }
 }
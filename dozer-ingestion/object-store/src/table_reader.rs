@@ -8,14 +8,16 @@ use dozer_ingestion_connector::dozer_types::arrow_types::from_arrow::{
 };
 use dozer_ingestion_connector::dozer_types::log::error;
 use dozer_ingestion_connector::dozer_types::models::ingestion_types::IngestionMessage;
-use dozer_ingestion_connector::dozer_types::types::{Operation, Record};
+use dozer_ingestion_connector::dozer_types::types::{Field, Operation, Record};
 use dozer_ingestion_connector::futures::StreamExt;
 use dozer_ingestion_connector::tokio::sync::mpsc::Sender;
 use dozer_ingestion_connector::{tokio, TableInfo};
 use std::sync::Arc;
 
+use crate::file_selector::FileSelector;
 use crate::{ObjectStoreConnectorError, ObjectStoreTableReaderError};
 
+#[allow(clippy::too_many_arguments)]
 pub async fn read(
     table_index: usize,
     ctx: SessionContext,
@@ -24,6 +26,8 @@ pub async fn read(
     table: &TableInfo,
     sender: Sender<Result<Option<IngestionMessage>, ObjectStoreConnectorError>>,
     schema: Option<&DFSchema>,
+    selector: &FileSelector,
+    partition_values: &[Field],
 ) -> Result<DFSchema, ObjectStoreConnectorError> {
     let resolved_schema = listing_options
         .infer_schema(&ctx.state(), &table_path)
@@ -44,8 +48,15 @@ pub async fn read(
     let cols: Vec<&str> = if table.column_names.is_empty() {
         fields.iter().map(|f| f.name().as_str()).collect()
     } else {
-        table.column_names.iter().map(|c| c.as_str()).collect()
+        // Partition columns aren't in the files, their values are appended to every record
+        table
+            .column_names
+            .iter()
+            .map(|c| c.as_str())
+            .filter(|c| !selector.is_partition_column(c))
+            .collect()
     };
+    let partition_values = selector.selected_values(partition_values, &table.column_names);
     let dataframe = ctx
         .read_table(provider.clone())
         .map_err(|e| {
@@ -89,7 +100,7 @@ pub async fn read(
         let dozer_schema = map_schema_to_dozer(&batch_schema)?;
 
         for row in 0..batch.num_rows() {
-            let fields = batch
+            let mut fields = batch
                 .columns()
                 .iter()
                 .enumerate()
@@ -102,6 +113,7 @@ pub async fn read(
                     )
                 })
                 .collect::<Result<Vec<_>, _>>()?;
+            fields.extend(partition_values.iter().cloned());
 
             let evt = Operation::Insert {
                 new: Record {
@@ -5,6 +5,7 @@ use dozer_ingestion_connector::{
     dozer_types::{
         chrono::{DateTime, Utc},
         models::ingestion_types::IngestionMessage,
+        types::Field,
     },
     tokio::{sync::mpsc::Sender, task::JoinHandle},
     TableInfo,
@@ -16,6 +17,8 @@ use crate::ObjectStoreConnectorError;
 pub struct FileInfo {
     pub name: String,
     pub last_modified: i64,
+    /// Values of the partition columns, for data files.
+    pub partition_values: Vec<Field>,
 }
 
 impl Ord for FileInfo {
@@ -1,13 +1,19 @@
 use dozer_ingestion_connector::{
     dozer_types::{
-        models::ingestion_types::{IngestionMessage, TransactionInfo},
+        chrono::NaiveDate,
+        models::ingestion_types::{
+            FileSelection, IngestionMessage, PartitionColumn, PartitionColumnType, TransactionInfo,
+        },
         types::{Field, FieldType, Operation},
     },
     test_util::create_runtime_and_spawn_connector_all_tables,
-    tokio, Connector,
+    tokio, Connector, TableIdentifier,
 };
 
-use crate::{connector::ObjectStoreConnector, tests::test_utils::get_local_storage_config};
+use crate::{
+    connector::ObjectStoreConnector,
+    tests::test_utils::{get_local_storage_config, get_partitioned_storage_config},
+};
 
 #[macro_export]
 macro_rules! test_type_conversion {
@@ -370,3 +376,254 @@ fn test_csv_read_no_marker() {
         panic!("Unexpected message");
     }
 }
+
+#[tokio::test]
+async fn test_get_schema_of_json() {
+    let local_storage = get_local_storage_config("json", "");
+
+    let mut connector = ObjectStoreConnector::new(local_storage);
+    let (_, schemas) = connector.list_all_schemas().await.unwrap();
+    let schema = schemas.first().unwrap();
+
+    let fields = schema.schema.fields.clone();
+    assert_eq!(fields.first().unwrap().typ, FieldType::Int);
+    assert_eq!(fields.get(1).unwrap().typ, FieldType::String);
+    assert_eq!(fields.get(2).unwrap().typ, FieldType::String);
+    assert_eq!(fields.get(3).unwrap().typ, FieldType::Int);
+    assert_eq!(fields.get(4).unwrap().typ, FieldType::Float);
+    assert_eq!(fields.get(5).unwrap().typ, FieldType::Float);
+    assert_eq!(fields.get(6).unwrap().typ, FieldType::Float);
+    assert_eq!(fields.get(7).unwrap().typ, FieldType::String);
+    assert_eq!(fields.get(8).unwrap().typ, FieldType::String);
+    assert_eq!(fields.get(9).unwrap().typ, FieldType::Float);
+}
+
+#[tokio::test]
+async fn test_get_schema_of_avro() {
+    let local_storage = get_local_storage_config("avro", "");
+
+    let mut connector = ObjectStoreConnector::new(local_storage);
+    let (_, schemas) = connector.list_all_schemas().await.unwrap();
+    let schema = schemas.first().unwrap();
+
+    let fields = schema.schema.fields.clone();
+    assert_eq!(fields.first().unwrap().typ, FieldType::Int);
+    assert_eq!(fields.get(1).unwrap().typ, FieldType::String);
+    assert_eq!(fields.get(2).unwrap().typ, FieldType::String);
+    assert_eq!(fields.get(3).unwrap().typ, FieldType::Int);
+    assert_eq!(fields.get(4).unwrap().typ, FieldType::Float);
+    assert_eq!(fields.get(5).unwrap().typ, FieldType::Float);
+    assert_eq!(fields.get(6).unwrap().typ, FieldType::Float);
+    assert_eq!(fields.get(7).unwrap().typ, FieldType::String);
+    assert_eq!(fields.get(8).unwrap().typ, FieldType::String);
+    assert_eq!(fields.get(9).unwrap().typ, FieldType::Float);
+    assert!(fields.get(9).unwrap().nullable);
+}
+
+#[tokio::test]
+async fn test_get_schema_of_orc() {
+    let local_storage = get_local_storage_config("orc", "");
+
+    let mut connector = ObjectStoreConnector::new(local_storage);
+    let (_, schemas) = connector.list_all_schemas().await.unwrap();
+    let schema = schemas.first().unwrap();
+
+    let fields = schema.schema.fields.clone();
+    assert_eq!(fields.first().unwrap().typ, FieldType::Int);
+    assert_eq!(fields.get(1).unwrap().typ, FieldType::String);
+    assert_eq!(fields.get(2).unwrap().typ, FieldType::String);
+    assert_eq!(fields.get(3).unwrap().typ, FieldType::Int);
+    assert_eq!(fields.get(4).unwrap().typ, FieldType::Float);
+    assert_eq!(fields.get(5).unwrap().typ, FieldType::Float);
+    assert_eq!(fields.get(6).unwrap().typ, FieldType::Float);
+    assert_eq!(fields.get(7).unwrap().typ, FieldType::String);
+    assert_eq!(fields.get(8).unwrap().typ, FieldType::String);
+    assert_eq!(fields.get(9).unwrap().typ, FieldType::Float);
+    assert!(fields.get(9).unwrap().nullable);
+}
+
+fn assert_sample_rows_read(typ: &str, prefix: &str) {
+    let local_storage = get_local_storage_config(typ, prefix);
+
+    let connector = ObjectStoreConnector::new(local_storage);
+
+    let (mut iterator, _) = create_runtime_and_spawn_connector_all_tables(connector);
+
+    let row = iterator.next();
+    if let Some(IngestionMessage::TransactionInfo(TransactionInfo::SnapshottingStarted)) = row {
+    } else {
+        panic!("Unexpected message");
+    }
+
+    let mut i = 1;
+    while i < 11 {
+        // no. of rows in the sample data
+        let row = iterator.next();
+        if let Some(IngestionMessage::OperationEvent {
+            op: Operation::Insert { new },
+            ..
+        }) = row
+        {
+            let values = new.values;
+
+            test_type_conversion!(values, 0, Field::Int(_));
+            test_type_conversion!(values, 1, Field::String(_));
+            test_type_conversion!(values, 2, Field::String(_));
+            test_type_conversion!(values, 3, Field::Int(_));
+            test_type_conversion!(values, 4, Field::Float(_));
+            test_type_conversion!(values, 5, Field::Float(_));
+            test_type_conversion!(values, 6, Field::Float(_));
+            test_type_conversion!(values, 7, Field::String(_));
+            test_type_conversion!(values, 8, Field::String(_));
+
+            if let Field::Int(id) = values.first().unwrap() {
+                if *id == 2 {
+                    test_type_conversion!(values, 9, Field::Float(_));
+                } else {
+                    test_type_conversion!(values, 9, Field::Null);
+                }
+            }
+        } else {
+            panic!("Unexpected message");
+        }
+
+        i += 1;
+    }
+
+    let row = iterator.next();
+    if let Some(IngestionMessage::TransactionInfo(TransactionInfo::SnapshottingDone { .. })) = row {
+    } else {
+        panic!("Unexpected message");
+    }
+}
+
+#[test]
+fn test_json_read() {
+    assert_sample_rows_read("json", "");
+}
+
+#[test]
+fn test_json_read_marker() {
+    assert_sample_rows_read("json", "marker");
+}
+
+#[test]
+fn test_avro_read() {
+    assert_sample_rows_read("avro", "");
+}
+
+#[test]
+fn test_orc_read() {
+    assert_sample_rows_read("orc", "");
+}
+
+#[test]
+fn test_orc_read_marker() {
+    assert_sample_rows_read("orc", "marker");
+}
+
+fn partition_columns() -> Vec<PartitionColumn> {
+    vec![
+        PartitionColumn {
+            name: "dt".to_string(),
+            typ: PartitionColumnType::Date,
+        },
+        PartitionColumn {
+            name: "region".to_string(),
+            typ: PartitionColumnType::String,
+        },
+    ]
+}
+
+#[tokio::test]
+async fn test_get_schema_with_partition_columns() {
+    let local_storage = get_partitioned_storage_config(FileSelection {
+        partition_columns: partition_columns(),
+        ..Default::default()
+    });
+
+    let mut connector = ObjectStoreConnector::new(local_storage);
+    let (_, schemas) = connector.list_all_schemas().await.unwrap();
+    let schema = schemas.first().unwrap();
+
+    let fields = schema
+        .schema
+        .fields
+        .iter()
+        .map(|field| (field.name.as_str(), field.typ))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fields,
+        vec![
+            ("id", FieldType::Int),
+            ("name", FieldType::String),
+            ("dt", FieldType::Date),
+            ("region", FieldType::String),
+        ]
+    );
+}
+
+#[test]
+fn test_read_partitions_from_start_partition() {
+    let local_storage = get_partitioned_storage_config(FileSelection {
+        partition_columns: partition_columns(),
+        exclude: vec!["*/region=us/*".to_string()],
+        start_partition: Some("dt=2024-01-01".to_string()),
+        ..Default::default()
+    });
+
+    let connector = ObjectStoreConnector::new(local_storage);
+
+    let (mut iterator, _) = create_runtime_and_spawn_connector_all_tables(connector);
+
+    let row = iterator.next();
+    if let Some(IngestionMessage::TransactionInfo(TransactionInfo::SnapshottingStarted)) = row {
+    } else {
+        panic!("Unexpected message");
+    }
+
+    let mut rows = vec![];
+    for _ in 0..2 {
+        if let Some(IngestionMessage::OperationEvent {
+            op: Operation::Insert { new },
+            ..
+        }) = iterator.next()
+        {
+            rows.push(new.values);
+        } else {
+            panic!("Unexpected message");
+        }
+    }
+    rows.sort();
+
+    let row = |id: i64, day: u32| {
+        vec![
+            Field::Int(id),
+            Field::String(format!("event {id}")),
+            Field::Date(NaiveDate::from_ymd_opt(2024, 1, day).unwrap()),
+            Field::String("eu".to_string()),
+        ]
+    };
+    assert_eq!(rows, vec![row(1, 1), row(2, 2)]);
+
+    let row = iterator.next();
+    if let Some(IngestionMessage::TransactionInfo(TransactionInfo::SnapshottingDone { .. })) = row {
+    } else {
+        panic!("Unexpected message");
+    }
+}
+
+#[tokio::test]
+async fn test_invalid_start_partition() {
+    let local_storage = get_partitioned_storage_config(FileSelection {
+        partition_columns: partition_columns(),
+        start_partition: Some("region=eu".to_string()),
+        ..Default::default()
+    });
+
+    let mut connector = ObjectStoreConnector::new(local_storage);
+    let tables = [TableIdentifier::from_table_name(
+        "partitioned_json".to_string(),
+    )];
+    assert!(connector.validate_tables(&tables).await.is_err());
+}
@@ -1,5 +1,6 @@
 use dozer_ingestion_connector::dozer_types::models::ingestion_types::{
-    CsvConfig, LocalDetails, LocalStorage, ParquetConfig, Table, TableConfig,
+    AvroConfig, CsvConfig, FileSelection, JsonConfig, LocalDetails, LocalStorage, OrcConfig,
+    ParquetConfig, Table, TableConfig,
 };
 use std::path::PathBuf;
 
@@ -16,6 +17,7 @@ pub fn get_local_storage_config(typ: &str, prefix: &str) -> LocalStorage {
                         extension: typ.to_string(),
                         path: format!("all_types_{typ}"),
                         marker_extension: None,
+                        selection: Default::default(),
                     }),
                     name: format!("all_types_{typ}"),
                 }],
@@ -29,6 +31,7 @@ pub fn get_local_storage_config(typ: &str, prefix: &str) -> LocalStorage {
                         extension: typ.to_string(),
                         path: format!("{prefix}_{typ}"),
                         marker_extension: Some(String::from(".marker")),
+                        selection: Default::default(),
                     }),
                     name: format!("{prefix}_{typ}"),
                 }],
@@ -44,6 +47,7 @@ pub fn get_local_storage_config(typ: &str, prefix: &str) -> LocalStorage {
                         extension: typ.to_string(),
                         path: format!("all_types_{typ}"),
                         marker_extension: None,
+                        selection: Default::default(),
                     }),
                     name: format!("all_types_{typ}"),
                 }],
@@ -57,6 +61,97 @@ pub fn get_local_storage_config(typ: &str, prefix: &str) -> LocalStorage {
                         extension: typ.to_string(),
                         path: format!("{prefix}_{typ}"),
                         marker_extension: Some(String::from(".marker")),
+                        selection: Default::default(),
+                    }),
+                    name: format!("{prefix}_{typ}"),
+                }],
+            },
+        },
+        "json" => match prefix {
+            "" => LocalStorage {
+                details: LocalDetails {
+                    path: p.to_str().unwrap().to_string(),
+                },
+                tables: vec![Table {
+                    config: TableConfig::JSON(JsonConfig {
+                        extension: typ.to_string(),
+                        path: format!("all_types_{typ}"),
+                        marker_extension: None,
+                        selection: Default::default(),
+                    }),
+                    name: format!("all_types_{typ}"),
+                }],
+            },
+            &_ => LocalStorage {
+                details: LocalDetails {
+                    path: p.to_str().unwrap().to_string(),
+                },
+                tables: vec![Table {
+                    config: TableConfig::JSON(JsonConfig {
+                        extension: typ.to_string(),
+                        path: format!("{prefix}_{typ}"),
+                        marker_extension: Some(String::from(".marker")),
+                        selection: Default::default(),
+                    }),
+                    name: format!("{prefix}_{typ}"),
+                }],
+            },
+        },
+        "avro" => match prefix {
+            "" => LocalStorage {
+                details: LocalDetails {
+                    path: p.to_str().unwrap().to_string(),
+                },
+                tables: vec![Table {
+                    config: TableConfig::Avro(AvroConfig {
+                        extension: typ.to_string(),
+                        path: format!("all_types_{typ}"),
+                        marker_extension: None,
+                        selection: Default::default(),
+                    }),
+                    name: format!("all_types_{typ}"),
+                }],
+            },
+            &_ => LocalStorage {
+                details: LocalDetails {
+                    path: p.to_str().unwrap().to_string(),
+                },
+                tables: vec![Table {
+                    config: TableConfig::Avro(AvroConfig {
+                        extension: typ.to_string(),
+                        path: format!("{prefix}_{typ}"),
+                        marker_extension: Some(String::from(".marker")),
+                        selection: Default::default(),
+                    }),
+                    name: format!("{prefix}_{typ}"),
+                }],
+            },
+        },
+        "orc" => match prefix {
+            "" => LocalStorage {
+                details: LocalDetails {
+                    path: p.to_str().unwrap().to_string(),
+                },
+                tables: vec![Table {
+                    config: TableConfig::ORC(OrcConfig {
+                        extension: typ.to_string(),
+                        path: format!("all_types_{typ}"),
+                        marker_extension: None,
+                        selection: Default::default(),
+                    }),
+                    name: format!("all_types_{typ}"),
+                }],
+            },
+            &_ => LocalStorage {
+                details: LocalDetails {
+                    path: p.to_str().unwrap().to_string(),
+                },
+                tables: vec![Table {
+                    config: TableConfig::ORC(OrcConfig {
+                        extension: typ.to_string(),
+                        path: format!("{prefix}_{typ}"),
+                        marker_extension: Some(String::from(".marker")),
+                        selection: Default::default(),
                     }),
                     name: format!("{prefix}_{typ}"),
                 }],
@@ -65,3 +160,22 @@ pub fn get_local_storage_config(typ: &str, prefix: &str) -> LocalStorage {
         other => panic!("Unsupported type: {}", other),
     }
 }
+
+/// Table of `partitioned_json`, whose files are under `dt=<date>/region=<region>/` directories.
+pub fn get_partitioned_storage_config(selection: FileSelection) -> LocalStorage {
+    let p = PathBuf::from("src/tests/files".to_string());
+    LocalStorage {
+        details: LocalDetails {
+            path: p.to_str().unwrap().to_string(),
+        },
+        tables: vec![Table {
+            config: TableConfig::JSON(JsonConfig {
+                extension: "json".to_string(),
+                path: "partitioned_json".to_string(),
+                marker_extension: None,
+                selection,
+            }),
+            name: "partitioned_json".to_string(),
+        }],
+    }
+}
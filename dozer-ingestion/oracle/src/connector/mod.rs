@@ -414,6 +414,7 @@ impl Connector {
                                 .blocking_handle_message(IngestionMessage::TransactionInfo(
                                     TransactionInfo::Commit {
                                         id: Some(OpIdentifier::new(checkpoint, 0)),
+                                        state: None,
                                     },
                                 ))
                                 .is_err()
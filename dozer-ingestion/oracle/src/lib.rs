@@ -4,7 +4,7 @@ use dozer_ingestion_connector::{
         errors::internal::BoxedError,
         log::info,
         models::ingestion_types::{IngestionMessage, OracleConfig, TransactionInfo},
-        node::OpIdentifier,
+        node::SourceCheckpoint,
         types::FieldType,
     },
     tokio, Connector, Ingestor, SourceSchemaResult, TableIdentifier, TableInfo,
@@ -150,10 +150,10 @@ impl Connector for OracleConnector {
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourceCheckpoint>,
     ) -> Result<(), BoxedError> {
         let checkpoint = if let Some(last_checkpoint) = last_checkpoint {
-            last_checkpoint.txid
+            last_checkpoint.id.txid
         } else {
             info!("No checkpoint passed, starting snapshotting");
 
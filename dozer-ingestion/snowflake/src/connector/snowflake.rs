@@ -4,7 +4,7 @@ use dozer_ingestion_connector::{
         errors::internal::BoxedError,
         log::{info, warn},
         models::ingestion_types::{default_snowflake_poll_interval, SnowflakeConfig},
-        node::OpIdentifier,
+        node::SourceCheckpoint,
         types::FieldType,
     },
     tokio, Connector, Ingestor, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
@@ -123,7 +123,7 @@ impl Connector for SnowflakeConnector {
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourceCheckpoint>,
     ) -> Result<(), BoxedError> {
         spawn_blocking({
             let name = self.name.clone();
@@ -140,7 +140,7 @@ fn run(
     name: String,
     config: SnowflakeConfig,
     tables: Vec<TableInfo>,
-    last_checkpoint: Option<OpIdentifier>,
+    last_checkpoint: Option<SourceCheckpoint>,
     ingestor: Ingestor,
 ) -> Result<(), SnowflakeError> {
     // SNAPSHOT part - run it when stream table doesn't exist
@@ -157,7 +157,7 @@ fn run(
             // We only check stream status on first iteration
             if iteration == 0 {
                 let state =
-                    last_checkpoint.map(|checkpoint| (checkpoint.txid, checkpoint.seq_in_tx));
+                    last_checkpoint.map(|checkpoint| (checkpoint.id.txid, checkpoint.id.seq_in_tx));
                 match state {
                     None | Some((0, _)) => {
                         info!("[{}][{}] Creating new stream", name, table.name);
@@ -96,6 +96,7 @@ fn create_connector(
                 path: table_name.to_string(),
                 extension: ".parquet".to_string(),
                 marker_extension: None,
+                selection: Default::default(),
             }),
             name: table_name,
         }],
@@ -2,20 +2,30 @@ use std::fmt::{Display, Formatter};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash, bincode::Encode, bincode::Decode)]
 pub enum AggregateFunctionType {
+    ApproxCountDistinct,
+    ApproxPercentile,
+    ArrayAgg,
     Avg,
     Count,
+    CountDistinct,
     Max,
     MaxAppendOnly,
     MaxValue,
     Min,
     MinAppendOnly,
     MinValue,
+    Stddev,
+    StringAgg,
     Sum,
+    Variance,
 }
 
 impl AggregateFunctionType {
     pub(crate) fn new(name: &str) -> Option<AggregateFunctionType> {
         match name {
+            "approx_count_distinct" => Some(AggregateFunctionType::ApproxCountDistinct),
+            "approx_percentile" => Some(AggregateFunctionType::ApproxPercentile),
+            "array_agg" => Some(AggregateFunctionType::ArrayAgg),
             "avg" => Some(AggregateFunctionType::Avg),
             "count" => Some(AggregateFunctionType::Count),
             "max" => Some(AggregateFunctionType::Max),
@@ -24,7 +34,23 @@ impl AggregateFunctionType {
             "min" => Some(AggregateFunctionType::Min),
             "min_append_only" => Some(AggregateFunctionType::MinAppendOnly),
             "min_value" => Some(AggregateFunctionType::MinValue),
+            "stddev" => Some(AggregateFunctionType::Stddev),
+            "string_agg" => Some(AggregateFunctionType::StringAgg),
             "sum" => Some(AggregateFunctionType::Sum),
+            "variance" => Some(AggregateFunctionType::Variance),
+            _ => None,
+        }
+    }
+
+    /// The variant to use when the function is called with `DISTINCT`, e.g. `COUNT(DISTINCT x)`.
+    pub(crate) fn distinct(self) -> Option<AggregateFunctionType> {
+        match self {
+            AggregateFunctionType::Count => Some(AggregateFunctionType::CountDistinct),
+            // Duplicates don't change the extremes
+            AggregateFunctionType::Max
+            | AggregateFunctionType::MaxAppendOnly
+            | AggregateFunctionType::Min
+            | AggregateFunctionType::MinAppendOnly => Some(self),
             _ => None,
         }
     }
@@ -33,15 +59,22 @@ impl AggregateFunctionType {
 impl Display for AggregateFunctionType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
+            AggregateFunctionType::ApproxCountDistinct => f.write_str("APPROX_COUNT_DISTINCT"),
+            AggregateFunctionType::ApproxPercentile => f.write_str("APPROX_PERCENTILE"),
+            AggregateFunctionType::ArrayAgg => f.write_str("ARRAY_AGG"),
             AggregateFunctionType::Avg => f.write_str("AVG"),
             AggregateFunctionType::Count => f.write_str("COUNT"),
+            AggregateFunctionType::CountDistinct => f.write_str("COUNT_DISTINCT"),
             AggregateFunctionType::Max => f.write_str("MAX"),
             AggregateFunctionType::MaxAppendOnly => f.write_str("MAX_APPEND_ONLY"),
             AggregateFunctionType::MaxValue => f.write_str("MAX_VALUE"),
             AggregateFunctionType::Min => f.write_str("MIN"),
             AggregateFunctionType::MinAppendOnly => f.write_str("MIN_APPEND_ONLY"),
             AggregateFunctionType::MinValue => f.write_str("MIN_VALUE"),
+            AggregateFunctionType::Stddev => f.write_str("STDDEV"),
+            AggregateFunctionType::StringAgg => f.write_str("STRING_AGG"),
             AggregateFunctionType::Sum => f.write_str("SUM"),
+            AggregateFunctionType::Variance => f.write_str("VARIANCE"),
         }
     }
 }
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use crate::aggregate::AggregateFunctionType;
-use crate::conditional::ConditionalExpressionType;
+use crate::conditional::{get_conditional_expr_type, ConditionalExpressionType};
 use crate::datetime::DateTimeFunctionType;
 use crate::error::Error;
 use dozer_types::models::udf_config::{UdfConfig, UdfType};
@@ -12,7 +12,7 @@ use dozer_types::{
 };
 use sqlparser::ast::{
     BinaryOperator as SqlBinaryOperator, DataType, DateTimeField, Expr as SqlExpr, Expr, Function,
-    FunctionArg, FunctionArgExpr, Ident, Interval, TrimWhereField,
+    FunctionArg, FunctionArgExpr, Ident, Interval, JsonOperator, TrimWhereField,
     UnaryOperator as SqlUnaryOperator, Value as SqlValue,
 };
 use tokio::runtime::Runtime;
@@ -102,6 +102,14 @@ impl ExpressionBuilder {
                 self.parse_sql_binary_op(parse_aggregations, left, op, right, schema, udfs)
                     .await
             }
+            SqlExpr::JsonAccess {
+                left,
+                operator,
+                right,
+            } => {
+                self.parse_sql_json_access(parse_aggregations, left, operator, right, schema, udfs)
+                    .await
+            }
             SqlExpr::Nested(expr) => {
                 self.parse_sql_expression(parse_aggregations, expr, schema, udfs)
                     .await
@@ -184,6 +192,56 @@ impl ExpressionBuilder {
                 )
                 .await
             }
+            SqlExpr::Substring {
+                expr,
+                substring_from,
+                substring_for,
+            } => {
+                let mut args = vec![
+                    self.parse_sql_expression(parse_aggregations, expr, schema, udfs)
+                        .await?,
+                    match substring_from {
+                        Some(from) => {
+                            self.parse_sql_expression(parse_aggregations, from, schema, udfs)
+                                .await?
+                        }
+                        None => Expression::Literal(Field::Int(1)),
+                    },
+                ];
+                if let Some(length) = substring_for {
+                    args.push(
+                        self.parse_sql_expression(parse_aggregations, length, schema, udfs)
+                            .await?,
+                    );
+                }
+                Ok(ScalarFunction {
+                    fun: ScalarFunctionType::Substring,
+                    args,
+                })
+            }
+            SqlExpr::AtTimeZone {
+                timestamp,
+                time_zone,
+            } => Ok(Expression::DateTimeFunction {
+                fun: DateTimeFunctionType::AtTimeZone,
+                args: vec![
+                    self.parse_sql_expression(parse_aggregations, timestamp, schema, udfs)
+                        .await?,
+                    Expression::Literal(Field::String(time_zone.clone())),
+                ],
+            }),
+            SqlExpr::Position { expr, r#in } => Ok(ScalarFunction {
+                fun: ScalarFunctionType::Position,
+                args: vec![
+                    self.parse_sql_expression(parse_aggregations, expr, schema, udfs)
+                        .await?,
+                    self.parse_sql_expression(parse_aggregations, r#in, schema, udfs)
+                        .await?,
+                ],
+            }),
+            SqlExpr::InSubquery { .. } | SqlExpr::Exists { .. } | SqlExpr::Subquery(_) => {
+                Err(Error::UnsupportedSubquery(expression.clone()))
+            }
             _ => Err(Error::UnsupportedExpression(expression.clone())),
         }
     }
@@ -302,7 +360,10 @@ impl ExpressionBuilder {
             return None;
         }
 
-        let aggr = AggregateFunctionType::new(function_name.as_str())?;
+        let mut aggr = AggregateFunctionType::new(function_name.as_str())?;
+        if sql_function.distinct {
+            aggr = aggr.distinct()?;
+        }
 
         let mut arg_expr: Vec<Expression> = Vec::new();
         for arg in &sql_function.args {
@@ -381,9 +442,32 @@ impl ExpressionBuilder {
         })
     }
 
-    fn datetime_expr_check(&mut self, function_name: String) -> Option<Expression> {
+    async fn datetime_expr_check(
+        &mut self,
+        function_name: String,
+        parse_aggregations: bool,
+        sql_function: &Function,
+        schema: &Schema,
+        udfs: &[UdfConfig],
+    ) -> Option<Expression> {
         let dtf = DateTimeFunctionType::new(function_name.as_str())?;
-        Some(Now { fun: dtf })
+        if dtf == DateTimeFunctionType::Now {
+            return Some(Now { fun: dtf });
+        }
+
+        let mut function_args: Vec<Expression> = Vec::new();
+        for arg in &sql_function.args {
+            function_args.push(
+                self.parse_sql_function_arg(parse_aggregations, arg, schema, udfs)
+                    .await
+                    .ok()?,
+            );
+        }
+
+        Some(Expression::DateTimeFunction {
+            fun: dtf,
+            args: function_args,
+        })
     }
 
     async fn json_func_check(
@@ -417,7 +501,7 @@ impl ExpressionBuilder {
         sql_function: &Function,
         schema: &Schema,
         udfs: &[UdfConfig],
-    ) -> Option<Expression> {
+    ) -> Option<Result<Expression, Error>> {
         let mut function_args: Vec<Expression> = Vec::new();
         for arg in &sql_function.args {
             function_args.push(
@@ -428,10 +512,15 @@ impl ExpressionBuilder {
         }
 
         let cet = ConditionalExpressionType::new(function_name.as_str())?;
-        Some(ConditionalExpression {
+        let return_type = match get_conditional_expr_type(&cet, &function_args, schema) {
+            Ok(expression_type) => expression_type.return_type,
+            Err(e) => return Some(Err(e)),
+        };
+        Some(Ok(ConditionalExpression {
             fun: cet,
             args: function_args,
-        })
+            return_type,
+        }))
     }
 
     async fn parse_sql_function(
@@ -501,10 +590,19 @@ impl ExpressionBuilder {
             )
             .await
         {
-            return Ok(conditional_check);
+            return conditional_check;
         }
 
-        if let Some(datetime_check) = self.datetime_expr_check(function_name.clone()) {
+        if let Some(datetime_check) = self
+            .datetime_expr_check(
+                function_name.clone(),
+                parse_aggregations,
+                sql_function,
+                schema,
+                udfs,
+            )
+            .await
+        {
             return Ok(datetime_check);
         }
 
@@ -652,7 +750,7 @@ impl ExpressionBuilder {
                 fun: DateTimeFunctionType::Interval {
                     field: *leading_field,
                 },
-                arg: Box::new(right),
+                args: vec![right],
             })
         } else {
             Err(Error::MissingLeadingFieldInInterval)
@@ -777,6 +875,55 @@ impl ExpressionBuilder {
         }
     }
 
+    async fn parse_sql_json_access(
+        &mut self,
+        parse_aggregations: bool,
+        left: &Expr,
+        operator: &JsonOperator,
+        right: &Expr,
+        schema: &Schema,
+        udfs: &[UdfConfig],
+    ) -> Result<Expression, Error> {
+        let mut result = self
+            .parse_sql_expression(parse_aggregations, left, schema, udfs)
+            .await?;
+        // The parser treats JSON operators as right associative, so `doc -> 'a' ->> 'b'` arrives
+        // as `doc -> ('a' ->> 'b')`. Walk down the right hand side to apply them left to right.
+        let (mut operator, mut right) = (operator, right);
+        loop {
+            let fun = match operator {
+                JsonOperator::Arrow => JsonFunctionType::JsonExtract,
+                JsonOperator::LongArrow => JsonFunctionType::JsonExtractText,
+                _ => {
+                    return Err(Error::UnsupportedExpression(Expr::JsonAccess {
+                        left: Box::new(left.clone()),
+                        operator: *operator,
+                        right: Box::new(right.clone()),
+                    }))
+                }
+            };
+            let (key, next) = match right {
+                Expr::JsonAccess {
+                    left: key,
+                    operator: next_operator,
+                    right: next_right,
+                } => (key.as_ref(), Some((next_operator, next_right.as_ref()))),
+                key => (key, None),
+            };
+            let key = self
+                .parse_sql_expression(parse_aggregations, key, schema, udfs)
+                .await?;
+            result = Expression::Json {
+                fun,
+                args: vec![result, key],
+            };
+            match next {
+                Some(next) => (operator, right) = next,
+                None => return Ok(result),
+            }
+        }
+    }
+
     async fn parse_sql_extract_operator(
         &mut self,
         parse_aggregations: bool,
@@ -790,7 +937,7 @@ impl ExpressionBuilder {
             .await?;
         Ok(Expression::DateTimeFunction {
             fun: DateTimeFunctionType::Extract { field: *field },
-            arg: Box::new(right),
+            args: vec![right],
         })
     }
 
@@ -1,3 +1,5 @@
+use crate::arg_utils::validate_num_arguments;
+use crate::cast::cast_field;
 use crate::error::Error;
 use crate::execution::{Expression, ExpressionType};
 use dozer_types::types::Record;
@@ -8,6 +10,9 @@ use std::fmt::{Display, Formatter};
 pub enum ConditionalExpressionType {
     Coalesce,
     NullIf,
+    Greatest,
+    Least,
+    IfNull,
 }
 
 pub(crate) fn get_conditional_expr_type(
@@ -17,7 +22,87 @@ pub(crate) fn get_conditional_expr_type(
 ) -> Result<ExpressionType, Error> {
     match function {
         ConditionalExpressionType::Coalesce => validate_coalesce(args, schema),
-        ConditionalExpressionType::NullIf => todo!(),
+        ConditionalExpressionType::NullIf | ConditionalExpressionType::IfNull => {
+            validate_num_arguments(2..3, args.len(), function)?;
+            validate_common_type(function, args, schema)
+        }
+        ConditionalExpressionType::Greatest | ConditionalExpressionType::Least => {
+            validate_num_arguments(1..usize::MAX, args.len(), function)?;
+            validate_common_type(function, args, schema)
+        }
+    }
+}
+
+/// Finds the type all arguments can be coerced to. Integers widen to larger integers, then to
+/// `Decimal` and `Float`, `Date` widens to `Timestamp` and `String` widens to `Text`. `NULL`
+/// literals don't take part in the decision.
+fn validate_common_type(
+    function: &ConditionalExpressionType,
+    args: &[Expression],
+    schema: &Schema,
+) -> Result<ExpressionType, Error> {
+    let mut common_type = None;
+    let mut nullable = false;
+    for (argument_index, arg) in args.iter().enumerate() {
+        if let Expression::Literal(Field::Null) = arg {
+            nullable = true;
+            continue;
+        }
+        let arg_type = arg.get_type(schema)?;
+        nullable |= arg_type.nullable;
+        common_type = Some(match common_type {
+            None => arg_type.return_type,
+            Some(common_type) => get_common_type(common_type, arg_type.return_type).ok_or(
+                Error::InvalidFunctionArgumentType {
+                    function_name: function.to_string(),
+                    argument_index,
+                    expected: vec![common_type],
+                    actual: arg_type.return_type,
+                },
+            )?,
+        });
+    }
+
+    let return_type = common_type.ok_or(Error::LiteralExpressionIsNull)?;
+    // NULLIF yields NULL whenever its arguments are equal, and GREATEST and LEAST are only
+    // non-nullable if all their arguments are.
+    let nullable = match function {
+        ConditionalExpressionType::NullIf => true,
+        ConditionalExpressionType::IfNull => match args.get(1) {
+            Some(Expression::Literal(Field::Null)) => true,
+            Some(fallback) => fallback.get_type(schema)?.nullable,
+            None => nullable,
+        },
+        _ => nullable,
+    };
+    Ok(ExpressionType::new(
+        return_type,
+        nullable,
+        dozer_types::types::SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
+fn get_common_type(left: FieldType, right: FieldType) -> Option<FieldType> {
+    use FieldType::*;
+
+    let is_numeric = |typ| matches!(typ, UInt | U128 | Int | I128 | Decimal | Float);
+    match (left, right) {
+        _ if left == right => Some(left),
+        (String, Text) | (Text, String) => Some(Text),
+        (Date, Timestamp) | (Timestamp, Date) => Some(Timestamp),
+        _ if is_numeric(left) && is_numeric(right) => Some(if left == Float || right == Float {
+            Float
+        } else if left == Decimal || right == Decimal {
+            Decimal
+        } else if matches!((left, right), (UInt, U128) | (U128, UInt)) {
+            U128
+        } else if [left, right].iter().any(|typ| matches!(typ, U128 | I128)) {
+            I128
+        } else {
+            Int
+        }),
+        _ => None,
     }
 }
 
@@ -26,6 +111,9 @@ impl ConditionalExpressionType {
         match name {
             "coalesce" => Some(ConditionalExpressionType::Coalesce),
             "nullif" => Some(ConditionalExpressionType::NullIf),
+            "greatest" => Some(ConditionalExpressionType::Greatest),
+            "least" => Some(ConditionalExpressionType::Least),
+            "ifnull" | "nvl" => Some(ConditionalExpressionType::IfNull),
             _ => None,
         }
     }
@@ -34,13 +122,70 @@ impl ConditionalExpressionType {
         &self,
         schema: &Schema,
         args: &mut [Expression],
+        return_type: FieldType,
         record: &Record,
     ) -> Result<Field, Error> {
         match self {
             ConditionalExpressionType::Coalesce => evaluate_coalesce(schema, args, record),
-            ConditionalExpressionType::NullIf => todo!(),
+            ConditionalExpressionType::NullIf => {
+                self.evaluate_nullif(schema, args, return_type, record)
+            }
+            ConditionalExpressionType::Greatest | ConditionalExpressionType::Least => {
+                self.evaluate_greatest_least(schema, args, return_type, record)
+            }
+            ConditionalExpressionType::IfNull => match evaluate_coalesce(schema, args, record)? {
+                Field::Null => Ok(Field::Null),
+                value => cast_field(&value, return_type),
+            },
         }
     }
+
+    /// `NULLIF(a, b)` returns `NULL` if `a` equals `b`, and `a` otherwise.
+    fn evaluate_nullif(
+        &self,
+        schema: &Schema,
+        args: &mut [Expression],
+        return_type: FieldType,
+        record: &Record,
+    ) -> Result<Field, Error> {
+        let value = args[0].evaluate(record, schema)?;
+        if value == Field::Null {
+            return Ok(Field::Null);
+        }
+        let value = cast_field(&value, return_type)?;
+        match args[1].evaluate(record, schema)? {
+            Field::Null => Ok(value),
+            other if cast_field(&other, return_type)? == value => Ok(Field::Null),
+            _ => Ok(value),
+        }
+    }
+
+    /// `GREATEST` and `LEAST` skip `NULL` arguments, and return `NULL` only if all of them are.
+    fn evaluate_greatest_least(
+        &self,
+        schema: &Schema,
+        args: &mut [Expression],
+        return_type: FieldType,
+        record: &Record,
+    ) -> Result<Field, Error> {
+        let mut result: Option<Field> = None;
+        for arg in args {
+            let value = match arg.evaluate(record, schema)? {
+                Field::Null => continue,
+                value => cast_field(&value, return_type)?,
+            };
+            result = Some(match result {
+                Some(current)
+                    if (*self == ConditionalExpressionType::Greatest && current >= value)
+                        || (*self == ConditionalExpressionType::Least && current <= value) =>
+                {
+                    current
+                }
+                _ => value,
+            });
+        }
+        Ok(result.unwrap_or(Field::Null))
+    }
 }
 
 pub(crate) fn validate_coalesce(
@@ -86,6 +231,9 @@ impl Display for ConditionalExpressionType {
         match self {
             ConditionalExpressionType::Coalesce => f.write_str("COALESCE"),
             ConditionalExpressionType::NullIf => f.write_str("NULLIF"),
+            ConditionalExpressionType::Greatest => f.write_str("GREATEST"),
+            ConditionalExpressionType::Least => f.write_str("LEAST"),
+            ConditionalExpressionType::IfNull => f.write_str("IFNULL"),
         }
     }
 }
@@ -308,6 +456,34 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_common_type() {
+        let cases = [
+            (FieldType::UInt, FieldType::UInt, Some(FieldType::UInt)),
+            (FieldType::UInt, FieldType::Int, Some(FieldType::Int)),
+            (FieldType::UInt, FieldType::U128, Some(FieldType::U128)),
+            (FieldType::Int, FieldType::U128, Some(FieldType::I128)),
+            (
+                FieldType::I128,
+                FieldType::Decimal,
+                Some(FieldType::Decimal),
+            ),
+            (FieldType::Decimal, FieldType::Float, Some(FieldType::Float)),
+            (
+                FieldType::Date,
+                FieldType::Timestamp,
+                Some(FieldType::Timestamp),
+            ),
+            (FieldType::String, FieldType::Text, Some(FieldType::Text)),
+            (FieldType::Int, FieldType::String, None),
+            (FieldType::Date, FieldType::Duration, None),
+        ];
+        for (left, right, expected) in cases {
+            assert_eq!(get_common_type(left, right), expected);
+            assert_eq!(get_common_type(right, left), expected);
+        }
+    }
+
     fn test_validate_coalesce(args: &[Expression], typ: FieldType) {
         let schema = Schema::default()
             .field(
@@ -1,10 +1,17 @@
-use crate::arg_utils::{extract_timestamp, extract_uint, validate_arg_type};
-use crate::error::Error;
+use crate::arg_utils::{
+    extract_timestamp, extract_uint, validate_arg_type, validate_num_arguments,
+};
+use crate::error::{Error, OperationError};
 use crate::execution::{Expression, ExpressionType};
 
-use dozer_types::chrono::{DateTime, Datelike, FixedOffset, Offset, Timelike, Utc};
+use chrono_tz::Tz;
+use dozer_types::chrono::format::{Item, StrftimeItems};
+use dozer_types::chrono::{
+    DateTime, Datelike, Days, Duration as ChronoDuration, FixedOffset, Months, NaiveDate,
+    NaiveDateTime, NaiveTime, Offset, TimeZone, Timelike, Utc,
+};
 use dozer_types::types::Record;
-use dozer_types::types::{DozerDuration, Field, FieldType, Schema, TimeUnit};
+use dozer_types::types::{DozerDuration, Field, FieldType, Schema, TimeUnit, DATE_FORMAT};
 use num_traits::ToPrimitive;
 use sqlparser::ast::DateTimeField;
 use std::fmt::{Display, Formatter};
@@ -18,6 +25,13 @@ pub enum DateTimeFunctionType {
         field: sqlparser::ast::DateTimeField,
     },
     Now,
+    DateTrunc,
+    DateAdd,
+    DateSub,
+    DateDiff,
+    ToTimestamp,
+    ToDate,
+    AtTimeZone,
 }
 
 impl Display for DateTimeFunctionType {
@@ -30,54 +44,195 @@ impl Display for DateTimeFunctionType {
                 f.write_str(format!("INTERVAL {field}").as_str())
             }
             DateTimeFunctionType::Now => f.write_str("NOW".to_string().as_str()),
+            DateTimeFunctionType::DateTrunc => f.write_str("DATE_TRUNC"),
+            DateTimeFunctionType::DateAdd => f.write_str("DATE_ADD"),
+            DateTimeFunctionType::DateSub => f.write_str("DATE_SUB"),
+            DateTimeFunctionType::DateDiff => f.write_str("DATE_DIFF"),
+            DateTimeFunctionType::ToTimestamp => f.write_str("TO_TIMESTAMP"),
+            DateTimeFunctionType::ToDate => f.write_str("TO_DATE"),
+            DateTimeFunctionType::AtTimeZone => f.write_str("AT TIME ZONE"),
         }
     }
 }
 
 pub(crate) fn get_datetime_function_type(
     function: &DateTimeFunctionType,
-    arg: &Expression,
+    args: &[Expression],
     schema: &Schema,
 ) -> Result<ExpressionType, Error> {
-    validate_arg_type(
-        arg,
-        vec![
-            FieldType::Date,
-            FieldType::Timestamp,
-            FieldType::Duration,
-            FieldType::String,
-            FieldType::Text,
-        ],
-        schema,
-        function,
-        0,
-    )?;
-    match function {
-        DateTimeFunctionType::Extract { field: _ } => Ok(ExpressionType::new(
-            FieldType::Int,
-            false,
-            dozer_types::types::SourceDefinition::Dynamic,
-            false,
-        )),
-        DateTimeFunctionType::Interval { field: _ } => Ok(ExpressionType::new(
-            FieldType::Duration,
-            false,
-            dozer_types::types::SourceDefinition::Dynamic,
-            false,
-        )),
-        DateTimeFunctionType::Now => Ok(ExpressionType::new(
-            FieldType::Timestamp,
-            false,
-            dozer_types::types::SourceDefinition::Dynamic,
-            false,
-        )),
-    }
+    let return_type = match function {
+        DateTimeFunctionType::Extract { field: _ }
+        | DateTimeFunctionType::Interval { field: _ } => {
+            validate_num_arguments(1..2, args.len(), function)?;
+            validate_arg_type(
+                &args[0],
+                vec![
+                    FieldType::Date,
+                    FieldType::Timestamp,
+                    FieldType::Duration,
+                    FieldType::String,
+                    FieldType::Text,
+                ],
+                schema,
+                function,
+                0,
+            )?;
+            let return_type = if let DateTimeFunctionType::Extract { .. } = function {
+                FieldType::Int
+            } else {
+                FieldType::Duration
+            };
+            return Ok(ExpressionType::new(
+                return_type,
+                false,
+                dozer_types::types::SourceDefinition::Dynamic,
+                false,
+            ));
+        }
+        DateTimeFunctionType::Now => {
+            return Ok(ExpressionType::new(
+                FieldType::Timestamp,
+                false,
+                dozer_types::types::SourceDefinition::Dynamic,
+                false,
+            ))
+        }
+        DateTimeFunctionType::DateTrunc => {
+            validate_num_arguments(2..3, args.len(), function)?;
+            validate_arg_type(
+                &args[0],
+                vec![FieldType::String, FieldType::Text],
+                schema,
+                function,
+                0,
+            )?;
+            validate_arg_type(
+                &args[1],
+                vec![FieldType::Timestamp, FieldType::Date, FieldType::Duration],
+                schema,
+                function,
+                1,
+            )?
+            .return_type
+        }
+        DateTimeFunctionType::DateAdd | DateTimeFunctionType::DateSub => {
+            validate_num_arguments(2..3, args.len(), function)?;
+            let value = validate_arg_type(
+                &args[0],
+                vec![FieldType::Timestamp, FieldType::Date, FieldType::Duration],
+                schema,
+                function,
+                0,
+            )?;
+            let amount = validate_arg_type(
+                &args[1],
+                vec![FieldType::Duration, FieldType::Int, FieldType::UInt],
+                schema,
+                function,
+                1,
+            )?;
+            match (value.return_type, amount.return_type) {
+                (FieldType::Date, FieldType::Int | FieldType::UInt) => FieldType::Date,
+                (FieldType::Timestamp | FieldType::Date, FieldType::Duration) => {
+                    FieldType::Timestamp
+                }
+                (FieldType::Duration, FieldType::Duration) => FieldType::Duration,
+                (_, actual) => {
+                    return Err(Error::InvalidFunctionArgumentType {
+                        function_name: function.to_string(),
+                        argument_index: 1,
+                        expected: vec![FieldType::Duration],
+                        actual,
+                    })
+                }
+            }
+        }
+        DateTimeFunctionType::DateDiff => {
+            validate_num_arguments(3..4, args.len(), function)?;
+            validate_arg_type(
+                &args[0],
+                vec![FieldType::String, FieldType::Text],
+                schema,
+                function,
+                0,
+            )?;
+            for (index, arg) in args.iter().enumerate().skip(1) {
+                validate_arg_type(
+                    arg,
+                    vec![FieldType::Timestamp, FieldType::Date],
+                    schema,
+                    function,
+                    index,
+                )?;
+            }
+            FieldType::Int
+        }
+        DateTimeFunctionType::ToTimestamp | DateTimeFunctionType::ToDate => {
+            validate_num_arguments(1..3, args.len(), function)?;
+            let value_types = if *function == DateTimeFunctionType::ToTimestamp && args.len() == 1 {
+                vec![
+                    FieldType::String,
+                    FieldType::Text,
+                    FieldType::Int,
+                    FieldType::UInt,
+                    FieldType::Float,
+                ]
+            } else {
+                vec![FieldType::String, FieldType::Text]
+            };
+            validate_arg_type(&args[0], value_types, schema, function, 0)?;
+            if let Some(format) = args.get(1) {
+                validate_arg_type(
+                    format,
+                    vec![FieldType::String, FieldType::Text],
+                    schema,
+                    function,
+                    1,
+                )?;
+            }
+            if *function == DateTimeFunctionType::ToTimestamp {
+                FieldType::Timestamp
+            } else {
+                FieldType::Date
+            }
+        }
+        DateTimeFunctionType::AtTimeZone => {
+            validate_num_arguments(2..3, args.len(), function)?;
+            validate_arg_type(
+                &args[0],
+                vec![FieldType::Timestamp, FieldType::Date],
+                schema,
+                function,
+                0,
+            )?;
+            validate_arg_type(
+                &args[1],
+                vec![FieldType::String, FieldType::Text],
+                schema,
+                function,
+                1,
+            )?;
+            FieldType::Timestamp
+        }
+    };
+    Ok(ExpressionType::new(
+        return_type,
+        true,
+        dozer_types::types::SourceDefinition::Dynamic,
+        false,
+    ))
 }
 
 impl DateTimeFunctionType {
     pub(crate) fn new(name: &str) -> Option<DateTimeFunctionType> {
         match name {
             "now" => Some(DateTimeFunctionType::Now),
+            "date_trunc" => Some(DateTimeFunctionType::DateTrunc),
+            "date_add" => Some(DateTimeFunctionType::DateAdd),
+            "date_sub" => Some(DateTimeFunctionType::DateSub),
+            "date_diff" | "datediff" => Some(DateTimeFunctionType::DateDiff),
+            "to_timestamp" => Some(DateTimeFunctionType::ToTimestamp),
+            "to_date" => Some(DateTimeFunctionType::ToDate),
             _ => None,
         }
     }
@@ -85,17 +240,42 @@ impl DateTimeFunctionType {
     pub(crate) fn evaluate(
         &self,
         schema: &Schema,
-        arg: &mut Expression,
+        args: &mut [Expression],
         record: &Record,
     ) -> Result<Field, Error> {
-        match self {
+        let values = match self {
             DateTimeFunctionType::Extract { field } => {
-                evaluate_date_part(schema, field, arg, record)
+                validate_num_arguments(1..2, args.len(), self)?;
+                return evaluate_date_part(schema, field, &mut args[0], record);
             }
             DateTimeFunctionType::Interval { field } => {
-                evaluate_interval(schema, field, arg, record)
+                validate_num_arguments(1..2, args.len(), self)?;
+                return evaluate_interval(schema, field, &mut args[0], record);
             }
-            DateTimeFunctionType::Now => self.evaluate_now(),
+            DateTimeFunctionType::Now => return self.evaluate_now(),
+            _ => {
+                let mut values = Vec::with_capacity(args.len());
+                for arg in args {
+                    match arg.evaluate(record, schema)? {
+                        Field::Null => return Ok(Field::Null),
+                        value => values.push(value),
+                    }
+                }
+                values
+            }
+        };
+
+        match self {
+            DateTimeFunctionType::DateTrunc => evaluate_date_trunc(self, &values),
+            DateTimeFunctionType::DateAdd => evaluate_date_add(self, &values, false),
+            DateTimeFunctionType::DateSub => evaluate_date_add(self, &values, true),
+            DateTimeFunctionType::DateDiff => evaluate_date_diff(self, &values),
+            DateTimeFunctionType::ToTimestamp => evaluate_to_timestamp(self, &values),
+            DateTimeFunctionType::ToDate => evaluate_to_date(self, &values),
+            DateTimeFunctionType::AtTimeZone => evaluate_at_time_zone(self, &values),
+            DateTimeFunctionType::Extract { .. }
+            | DateTimeFunctionType::Interval { .. }
+            | DateTimeFunctionType::Now => unreachable!(),
         }
     }
 
@@ -206,6 +386,369 @@ pub(crate) fn evaluate_interval(
     }
 }
 
+/// Unit accepted by `DATE_TRUNC` and `DATE_DIFF`, e.g. `'day'` or `'months'`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DateTimeUnit {
+    Millisecond,
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Quarter,
+    Year,
+}
+
+impl DateTimeUnit {
+    fn parse(value: &Field) -> Result<Self, Error> {
+        let name = match value {
+            Field::String(name) | Field::Text(name) => name,
+            other => return Err(Error::UnsupportedDateTimeUnit(other.to_string())),
+        };
+        match name.to_lowercase().as_str() {
+            "millisecond" | "milliseconds" | "ms" => Ok(DateTimeUnit::Millisecond),
+            "second" | "seconds" => Ok(DateTimeUnit::Second),
+            "minute" | "minutes" => Ok(DateTimeUnit::Minute),
+            "hour" | "hours" => Ok(DateTimeUnit::Hour),
+            "day" | "days" => Ok(DateTimeUnit::Day),
+            "week" | "weeks" => Ok(DateTimeUnit::Week),
+            "month" | "months" => Ok(DateTimeUnit::Month),
+            "quarter" | "quarters" => Ok(DateTimeUnit::Quarter),
+            "year" | "years" => Ok(DateTimeUnit::Year),
+            _ => Err(Error::UnsupportedDateTimeUnit(name.clone())),
+        }
+    }
+
+    /// Length of the unit in nanoseconds. Calendar units have no fixed length.
+    fn nanos(self) -> Option<u128> {
+        const SECOND: u128 = 1_000_000_000;
+        match self {
+            DateTimeUnit::Millisecond => Some(SECOND / 1000),
+            DateTimeUnit::Second => Some(SECOND),
+            DateTimeUnit::Minute => Some(60 * SECOND),
+            DateTimeUnit::Hour => Some(60 * 60 * SECOND),
+            DateTimeUnit::Day => Some(24 * 60 * 60 * SECOND),
+            DateTimeUnit::Week => Some(7 * 24 * 60 * 60 * SECOND),
+            DateTimeUnit::Month | DateTimeUnit::Quarter | DateTimeUnit::Year => None,
+        }
+    }
+}
+
+fn invalid_argument(
+    function: &DateTimeFunctionType,
+    argument_index: usize,
+    argument: &Field,
+) -> Error {
+    Error::InvalidFunctionArgument {
+        function_name: function.to_string(),
+        argument_index,
+        argument: argument.clone(),
+    }
+}
+
+fn truncate_date(date: NaiveDate, unit: DateTimeUnit) -> Option<NaiveDate> {
+    match unit {
+        DateTimeUnit::Millisecond
+        | DateTimeUnit::Second
+        | DateTimeUnit::Minute
+        | DateTimeUnit::Hour
+        | DateTimeUnit::Day => Some(date),
+        DateTimeUnit::Week => {
+            date.checked_sub_days(Days::new(date.weekday().num_days_from_monday() as u64))
+        }
+        DateTimeUnit::Month => date.with_day(1),
+        DateTimeUnit::Quarter => NaiveDate::from_ymd_opt(date.year(), date.month0() / 3 * 3 + 1, 1),
+        DateTimeUnit::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1),
+    }
+}
+
+fn truncate_datetime(datetime: NaiveDateTime, unit: DateTimeUnit) -> Option<NaiveDateTime> {
+    let (hour, minute, second) = (datetime.hour(), datetime.minute(), datetime.second());
+    let time = match unit {
+        DateTimeUnit::Millisecond => {
+            NaiveTime::from_hms_milli_opt(hour, minute, second, datetime.nanosecond() / 1_000_000)
+        }
+        DateTimeUnit::Second => NaiveTime::from_hms_opt(hour, minute, second),
+        DateTimeUnit::Minute => NaiveTime::from_hms_opt(hour, minute, 0),
+        DateTimeUnit::Hour => NaiveTime::from_hms_opt(hour, 0, 0),
+        _ => NaiveTime::from_hms_opt(0, 0, 0),
+    }?;
+    Some(truncate_date(datetime.date(), unit)?.and_time(time))
+}
+
+/// `DATE_TRUNC(unit, value)` rounds a timestamp or date down to the start of `unit`, keeping the
+/// timestamp's offset. Durations can only be truncated to units of fixed length.
+fn evaluate_date_trunc(function: &DateTimeFunctionType, values: &[Field]) -> Result<Field, Error> {
+    let unit = DateTimeUnit::parse(&values[0])?;
+    let result = match &values[1] {
+        Field::Timestamp(ts) => truncate_datetime(ts.naive_local(), unit)
+            .and_then(|datetime| ts.offset().from_local_datetime(&datetime).single())
+            .map(Field::Timestamp),
+        Field::Date(date) => truncate_date(*date, unit).map(Field::Date),
+        Field::Duration(DozerDuration(duration, time_unit)) => {
+            let unit_nanos = unit
+                .nanos()
+                .ok_or_else(|| Error::UnsupportedDateTimeUnit(values[0].to_string()))?;
+            let nanos = duration.as_nanos() / unit_nanos * unit_nanos;
+            u64::try_from(nanos / 1_000_000_000).ok().map(|seconds| {
+                Field::Duration(DozerDuration(
+                    std::time::Duration::new(seconds, (nanos % 1_000_000_000) as u32),
+                    *time_unit,
+                ))
+            })
+        }
+        _ => None,
+    };
+    result.ok_or_else(|| invalid_argument(function, 1, &values[1]))
+}
+
+/// `DATE_ADD(value, amount)` and `DATE_SUB(value, amount)`. Adding a duration to a date yields a
+/// timestamp at UTC midnight plus the duration, while adding an integer to a date adds days.
+fn evaluate_date_add(
+    function: &DateTimeFunctionType,
+    values: &[Field],
+    subtract: bool,
+) -> Result<Field, Error> {
+    let overflow = || {
+        Error::SqlError(if subtract {
+            OperationError::SubtractionOverflow
+        } else {
+            OperationError::AdditionOverflow
+        })
+    };
+
+    match (&values[0], &values[1]) {
+        (Field::Duration(DozerDuration(left, time_unit)), Field::Duration(right)) => {
+            let result = if subtract {
+                left.checked_sub(right.0)
+            } else {
+                left.checked_add(right.0)
+            };
+            result
+                .map(|duration| Field::Duration(DozerDuration(duration, *time_unit)))
+                .ok_or_else(overflow)
+        }
+        (Field::Date(date), days @ (Field::Int(_) | Field::UInt(_))) => {
+            let days = days
+                .to_i128()
+                .ok_or_else(|| invalid_argument(function, 1, days))?;
+            let days = if subtract { -days } else { days };
+            let count = u64::try_from(days.unsigned_abs()).map_err(|_| overflow())?;
+            if days >= 0 {
+                date.checked_add_days(Days::new(count))
+            } else {
+                date.checked_sub_days(Days::new(count))
+            }
+            .map(Field::Date)
+            .ok_or_else(overflow)
+        }
+        (value @ (Field::Timestamp(_) | Field::Date(_)), Field::Duration(duration)) => {
+            let ts = extract_timestamp(value.clone(), function, 0)?;
+            let delta = ChronoDuration::from_std(duration.0)
+                .map_err(|_| invalid_argument(function, 1, &values[1]))?;
+            if subtract {
+                ts.checked_sub_signed(delta)
+            } else {
+                ts.checked_add_signed(delta)
+            }
+            .map(Field::Timestamp)
+            .ok_or_else(overflow)
+        }
+        (_, amount) => Err(invalid_argument(function, 1, amount)),
+    }
+}
+
+/// `DATE_DIFF(unit, start, end)` counts the whole units elapsed from `start` to `end`, which is
+/// negative if `end` is before `start`. Calendar units are compared in UTC.
+fn evaluate_date_diff(function: &DateTimeFunctionType, values: &[Field]) -> Result<Field, Error> {
+    let unit = DateTimeUnit::parse(&values[0])?;
+    let start = extract_timestamp(values[1].clone(), function, 1)?;
+    let end = extract_timestamp(values[2].clone(), function, 2)?;
+
+    let elapsed = end.signed_duration_since(start);
+    let result = match unit {
+        DateTimeUnit::Millisecond => Some(elapsed.num_milliseconds()),
+        DateTimeUnit::Second => Some(elapsed.num_seconds()),
+        DateTimeUnit::Minute => Some(elapsed.num_minutes()),
+        DateTimeUnit::Hour => Some(elapsed.num_hours()),
+        DateTimeUnit::Day => Some(elapsed.num_days()),
+        DateTimeUnit::Week => Some(elapsed.num_weeks()),
+        DateTimeUnit::Month => whole_months_between(start.naive_utc(), end.naive_utc()),
+        DateTimeUnit::Quarter => {
+            whole_months_between(start.naive_utc(), end.naive_utc()).map(|months| months / 3)
+        }
+        DateTimeUnit::Year => {
+            whole_months_between(start.naive_utc(), end.naive_utc()).map(|months| months / 12)
+        }
+    };
+    result
+        .map(Field::Int)
+        .ok_or_else(|| invalid_argument(function, 2, &values[2]))
+}
+
+fn whole_months_between(start: NaiveDateTime, end: NaiveDateTime) -> Option<i64> {
+    let mut months =
+        (end.year() as i64 - start.year() as i64) * 12 + end.month() as i64 - start.month() as i64;
+    let shifted = if months >= 0 {
+        start.checked_add_months(Months::new(u32::try_from(months).ok()?))
+    } else {
+        start.checked_sub_months(Months::new(u32::try_from(-months).ok()?))
+    }?;
+    if months > 0 && shifted > end {
+        months -= 1;
+    } else if months < 0 && shifted < end {
+        months += 1;
+    }
+    Some(months)
+}
+
+/// `TO_TIMESTAMP(value [, format])` parses a string with a format (see `parse_format`), or an
+/// RFC 3339 string if no format is given. Numeric values are seconds since the Unix epoch.
+/// Timestamps without an offset in the format are assumed to be UTC.
+fn evaluate_to_timestamp(
+    function: &DateTimeFunctionType,
+    values: &[Field],
+) -> Result<Field, Error> {
+    let result = match (&values[0], values.get(1)) {
+        (Field::String(value) | Field::Text(value), Some(format)) => match format {
+            Field::String(format) | Field::Text(format) => {
+                parse_timestamp(value, &parse_format(format)?)
+            }
+            _ => return Err(invalid_argument(function, 1, format)),
+        },
+        (value @ (Field::String(_) | Field::Text(_)), None) => value.to_timestamp(),
+        (value, None) => value.to_float().and_then(timestamp_from_epoch_seconds),
+        _ => None,
+    };
+    result
+        .map(Field::Timestamp)
+        .ok_or_else(|| invalid_argument(function, 0, &values[0]))
+}
+
+fn parse_timestamp(value: &str, format: &str) -> Option<DateTime<FixedOffset>> {
+    if let Ok(ts) = DateTime::parse_from_str(value, format) {
+        return Some(ts);
+    }
+    let datetime = NaiveDateTime::parse_from_str(value, format)
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, format)
+                .ok()?
+                .and_hms_opt(0, 0, 0)
+        })?;
+    Some(Utc.from_utc_datetime(&datetime).into())
+}
+
+/// Template patterns of Postgres' `TO_TIMESTAMP` and `TO_DATE`, and their `strftime` equivalent.
+/// Longer patterns come first, so that e.g. `MONTH` isn't read as `MON` or `MM`.
+const FORMAT_PATTERNS: &[(&str, &str)] = &[
+    ("HH24", "%H"),
+    ("HH12", "%I"),
+    ("TZH:TZM", "%:z"),
+    ("MONTH", "%B"),
+    ("YYYY", "%Y"),
+    ("DDD", "%j"),
+    ("DAY", "%A"),
+    ("MON", "%b"),
+    ("HH", "%I"),
+    ("MI", "%M"),
+    ("SS", "%S"),
+    ("MS", "%3f"),
+    ("US", "%6f"),
+    ("AM", "%p"),
+    ("PM", "%p"),
+    ("YY", "%y"),
+    ("MM", "%m"),
+    ("DD", "%d"),
+    ("DY", "%a"),
+    ("OF", "%:z"),
+];
+
+/// Converts a Postgres template (e.g. `YYYY-MM-DD HH24:MI:SS`) to a `strftime` format.
+///
+/// Patterns are case insensitive, text in double quotes is copied as is, and so are characters
+/// other than letters. Any other letter is an error. Formats containing `%` are already `strftime`
+/// formats, and are only checked.
+fn parse_format(format: &str) -> Result<String, Error> {
+    let invalid = || Error::InvalidDateTimeFormat(format.to_string());
+    let result = if format.contains('%') {
+        format.to_string()
+    } else {
+        let mut result = String::with_capacity(format.len() * 2);
+        let mut rest = format;
+        while let Some(c) = rest.chars().next() {
+            if c == '"' {
+                let end = rest[1..].find('"').ok_or_else(invalid)?;
+                result.push_str(&rest[1..end + 1]);
+                rest = &rest[end + 2..];
+            } else if c.is_alphabetic() {
+                let (pattern, strftime) = FORMAT_PATTERNS
+                    .iter()
+                    .find(|(pattern, _)| {
+                        rest.get(..pattern.len())
+                            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(pattern))
+                    })
+                    .ok_or_else(invalid)?;
+                result.push_str(strftime);
+                rest = &rest[pattern.len()..];
+            } else {
+                result.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        result
+    };
+    if StrftimeItems::new(&result).any(|item| item == Item::Error) {
+        return Err(invalid());
+    }
+    Ok(result)
+}
+
+fn timestamp_from_epoch_seconds(seconds: f64) -> Option<DateTime<FixedOffset>> {
+    let whole = seconds.floor();
+    let nanos = (((seconds - whole) * 1e9).round() as u32).min(999_999_999);
+    let datetime = NaiveDateTime::from_timestamp_opt(whole.to_i64()?, nanos)?;
+    Some(Utc.from_utc_datetime(&datetime).into())
+}
+
+/// `TO_DATE(value [, format])` parses a string with a format (see `parse_format`), or `YYYY-MM-DD`
+/// if no format is given.
+fn evaluate_to_date(function: &DateTimeFunctionType, values: &[Field]) -> Result<Field, Error> {
+    let format = match values.get(1) {
+        Some(Field::String(format) | Field::Text(format)) => parse_format(format)?,
+        Some(format) => return Err(invalid_argument(function, 1, format)),
+        None => DATE_FORMAT.to_string(),
+    };
+    match &values[0] {
+        Field::String(value) | Field::Text(value) => NaiveDate::parse_from_str(value, &format)
+            .ok()
+            .map(Field::Date),
+        _ => None,
+    }
+    .ok_or_else(|| invalid_argument(function, 0, &values[0]))
+}
+
+/// `value AT TIME ZONE zone` converts a timestamp to the offset of an IANA time zone (e.g.
+/// `'Europe/Berlin'`) or a fixed offset (e.g. `'+05:30'`). The instant itself is unchanged.
+fn evaluate_at_time_zone(
+    function: &DateTimeFunctionType,
+    values: &[Field],
+) -> Result<Field, Error> {
+    let ts = extract_timestamp(values[0].clone(), function, 0)?;
+    let zone = match &values[1] {
+        Field::String(zone) | Field::Text(zone) => zone,
+        other => return Err(invalid_argument(function, 1, other)),
+    };
+    let offset = if let Ok(tz) = zone.parse::<Tz>() {
+        tz.offset_from_utc_datetime(&ts.naive_utc()).fix()
+    } else {
+        zone.parse::<FixedOffset>()
+            .map_err(|_| Error::InvalidTimeZone(zone.clone()))?
+    };
+    Ok(Field::Timestamp(ts.with_timezone(&offset)))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::tests::ArbitraryDateTime;
@@ -253,4 +796,85 @@ mod tests {
             assert_eq!(result, Field::Int(value));
         }
     }
+
+    #[test]
+    fn test_whole_months_between() {
+        let datetime = |y, m, d| {
+            NaiveDate::from_ymd_opt(y, m, d)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+        };
+        assert_eq!(
+            whole_months_between(datetime(2023, 1, 31), datetime(2023, 2, 28)),
+            Some(1)
+        );
+        assert_eq!(
+            whole_months_between(datetime(2023, 1, 15), datetime(2023, 3, 14)),
+            Some(1)
+        );
+        assert_eq!(
+            whole_months_between(datetime(2023, 3, 14), datetime(2023, 1, 15)),
+            Some(-1)
+        );
+        assert_eq!(
+            whole_months_between(datetime(2020, 2, 29), datetime(2024, 2, 29)),
+            Some(48)
+        );
+    }
+
+    #[test]
+    fn test_date_trunc_duration() {
+        let duration = Field::Duration(DozerDuration(
+            std::time::Duration::new(3 * 24 * 60 * 60 + 5, 999),
+            TimeUnit::Nanoseconds,
+        ));
+        assert_eq!(
+            evaluate_date_trunc(
+                &DateTimeFunctionType::DateTrunc,
+                &[Field::String("day".to_string()), duration.clone()]
+            )
+            .unwrap(),
+            Field::Duration(DozerDuration(
+                std::time::Duration::from_secs(3 * 24 * 60 * 60),
+                TimeUnit::Nanoseconds,
+            ))
+        );
+        assert!(matches!(
+            evaluate_date_trunc(
+                &DateTimeFunctionType::DateTrunc,
+                &[Field::String("month".to_string()), duration]
+            ),
+            Err(Error::UnsupportedDateTimeUnit(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(
+            parse_format("YYYY-MM-DD HH24:MI:SS").unwrap(),
+            "%Y-%m-%d %H:%M:%S"
+        );
+        assert_eq!(
+            parse_format("dd Mon yyyy\"T\"HH12:MI:SS.MS AM TZH:TZM").unwrap(),
+            "%d %b %YT%I:%M:%S.%3f %p %:z"
+        );
+        assert_eq!(parse_format("%d.%m.%Y").unwrap(), "%d.%m.%Y");
+        for format in ["%Q", "YYYY-QQ", "YYYY\"T"] {
+            assert!(
+                matches!(parse_format(format), Err(Error::InvalidDateTimeFormat(_))),
+                "{format}"
+            );
+        }
+        assert!(matches!(
+            evaluate_to_timestamp(
+                &DateTimeFunctionType::ToTimestamp,
+                &[
+                    Field::String("2023-05-17".to_string()),
+                    Field::String("YYYY-MM-DD HH24:MI:SS XX".to_string())
+                ]
+            ),
+            Err(Error::InvalidDateTimeFormat(_))
+        ));
+    }
 }
@@ -14,6 +14,8 @@ use crate::{aggregate::AggregateFunctionType, operator::BinaryOperatorType};
 pub enum Error {
     #[error("Unsupported SQL expression: {0:?}")]
     UnsupportedExpression(Expr),
+    #[error("Subqueries are only supported as AND-ed WHERE conditions: {0}")]
+    UnsupportedSubquery(Expr),
     #[error("Unsupported SQL function arg: {0:?}")]
     UnsupportedFunctionArg(FunctionArg),
     #[error("Invalid ident: {}", .0.iter().map(|ident| ident.value.as_str()).collect::<Vec<_>>().join("."))]
@@ -80,11 +82,21 @@ pub enum Error {
     InvalidLikeEscape(#[from] like::InvalidEscapeError),
     #[error("Invalid like pattern: {0}")]
     InvalidLikePattern(#[from] like::InvalidPatternError),
+    #[error("Invalid regular expression: {0}")]
+    InvalidRegex(#[from] regex::Error),
+    #[error("Invalid regular expression flag: {0}")]
+    InvalidRegexFlag(char),
 
     #[error("Unsupported extract: {0}")]
     UnsupportedExtract(DateTimeField),
     #[error("Unsupported interval: {0}")]
     UnsupportedInterval(DateTimeField),
+    #[error("Unsupported date time unit: {0}")]
+    UnsupportedDateTimeUnit(String),
+    #[error("Invalid time zone: {0}")]
+    InvalidTimeZone(String),
+    #[error("Invalid date time format: {0}")]
+    InvalidDateTimeFormat(String),
 
     #[error("Invalid json path: {0}")]
     InvalidJsonPath(String),
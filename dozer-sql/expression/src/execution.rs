@@ -4,7 +4,7 @@ use crate::conditional::{get_conditional_expr_type, ConditionalExpressionType};
 use crate::datetime::{get_datetime_function_type, DateTimeFunctionType};
 use crate::error::Error;
 use crate::geo::common::{get_geo_function_type, GeoFunctionType};
-use crate::json_functions::JsonFunctionType;
+use crate::json_functions::{get_json_function_type, JsonFunctionType};
 use crate::operator::{BinaryOperatorType, UnaryOperatorType};
 use crate::scalar::common::{get_scalar_function_type, ScalarFunctionType};
 use crate::scalar::string::{evaluate_trim, validate_trim, TrimType};
@@ -45,10 +45,12 @@ pub enum Expression {
     ConditionalExpression {
         fun: ConditionalExpressionType,
         args: Vec<Expression>,
+        /// The type the arguments are coerced to, resolved when the expression is built
+        return_type: FieldType,
     },
     DateTimeFunction {
         fun: DateTimeFunctionType,
-        arg: Box<Expression>,
+        args: Vec<Expression>,
     },
     AggregateFunction {
         fun: AggregateFunctionType,
@@ -129,7 +131,7 @@ impl Expression {
                         .as_str()
                     + ")"
             }
-            Expression::ConditionalExpression { fun, args } => {
+            Expression::ConditionalExpression { fun, args, .. } => {
                 fun.to_string()
                     + "("
                     + args
@@ -262,10 +264,28 @@ impl Expression {
                         .as_str()
                     + ")"
             }
-            Expression::DateTimeFunction { fun, arg } => {
-                fun.to_string() + "(" + arg.to_string(schema).as_str() + ")"
+            Expression::DateTimeFunction { fun, args } => {
+                fun.to_string()
+                    + "("
+                    + args
+                        .iter()
+                        .map(|e| e.to_string(schema))
+                        .collect::<Vec<String>>()
+                        .join(",")
+                        .as_str()
+                    + ")"
             }
             Expression::Now { fun } => fun.to_string() + "()",
+            Expression::Json {
+                fun: fun @ (JsonFunctionType::JsonExtract | JsonFunctionType::JsonExtractText),
+                args,
+            } if args.len() == 2 => {
+                args[0].to_string(schema)
+                    + " "
+                    + fun.to_string().as_str()
+                    + " "
+                    + args[1].to_string(schema).as_str()
+            }
             Expression::Json { fun, args } => {
                 fun.to_string()
                     + "("
@@ -355,8 +375,12 @@ impl Expression {
             } => evaluate_in_list(schema, expr, list, *negated, record),
             Expression::Cast { arg, typ } => typ.evaluate(schema, arg, record),
             Expression::GeoFunction { fun, args } => fun.evaluate(schema, args, record),
-            Expression::ConditionalExpression { fun, args } => fun.evaluate(schema, args, record),
-            Expression::DateTimeFunction { fun, arg } => fun.evaluate(schema, arg, record),
+            Expression::ConditionalExpression {
+                fun,
+                args,
+                return_type,
+            } => fun.evaluate(schema, args, *return_type, record),
+            Expression::DateTimeFunction { fun, args } => fun.evaluate(schema, args, record),
             Expression::Now { fun } => fun.evaluate_now(),
             Expression::Json { fun, args } => fun.evaluate(schema, args, record),
             Expression::Case {
@@ -402,7 +426,7 @@ impl Expression {
                 right,
             } => get_binary_operator_type(left, operator, right, schema),
             Expression::ScalarFunction { fun, args } => get_scalar_function_type(fun, args, schema),
-            Expression::ConditionalExpression { fun, args } => {
+            Expression::ConditionalExpression { fun, args, .. } => {
                 get_conditional_expr_type(fun, args, schema)
             }
             Expression::AggregateFunction { fun, args } => {
@@ -430,8 +454,8 @@ impl Expression {
             )),
             Expression::Cast { arg, typ } => typ.get_return_type(schema, arg),
             Expression::GeoFunction { fun, args } => get_geo_function_type(fun, args, schema),
-            Expression::DateTimeFunction { fun, arg } => {
-                get_datetime_function_type(fun, arg, schema)
+            Expression::DateTimeFunction { fun, args } => {
+                get_datetime_function_type(fun, args, schema)
             }
             Expression::Now { fun: _ } => Ok(ExpressionType::new(
                 FieldType::Timestamp,
@@ -439,12 +463,7 @@ impl Expression {
                 dozer_types::types::SourceDefinition::Dynamic,
                 false,
             )),
-            Expression::Json { fun: _, args: _ } => Ok(ExpressionType::new(
-                FieldType::Json,
-                false,
-                dozer_types::types::SourceDefinition::Dynamic,
-                false,
-            )),
+            Expression::Json { fun, args } => get_json_function_type(fun, args, schema),
             Expression::Case {
                 operand: _,
                 conditions: _,
@@ -505,7 +524,12 @@ impl Expression {
                 }
                 Ok(())
             }
-            Expression::DateTimeFunction { arg, .. } => arg.serialize_state(object),
+            Expression::DateTimeFunction { args, .. } => {
+                for arg in args {
+                    arg.serialize_state(object)?;
+                }
+                Ok(())
+            }
             Expression::AggregateFunction { args, .. } => {
                 for arg in args {
                     arg.serialize_state(object)?;
@@ -603,7 +627,12 @@ impl Expression {
                 }
                 Ok(())
             }
-            Expression::DateTimeFunction { arg, .. } => arg.deserialize_state(cursor),
+            Expression::DateTimeFunction { args, .. } => {
+                for arg in args {
+                    arg.deserialize_state(cursor)?;
+                }
+                Ok(())
+            }
             Expression::AggregateFunction { args, .. } => {
                 for arg in args {
                     arg.deserialize_state(cursor)?;
@@ -921,15 +950,26 @@ fn get_aggregate_function_type(
     schema: &Schema,
 ) -> Result<ExpressionType, Error> {
     match function {
+        AggregateFunctionType::ApproxCountDistinct => validate_approx_count_distinct(args, schema),
+        AggregateFunctionType::ApproxPercentile => validate_approx_percentile(args, schema),
+        AggregateFunctionType::ArrayAgg => validate_array_agg(args, schema),
         AggregateFunctionType::Avg => validate_avg(args, schema),
         AggregateFunctionType::Count => validate_count(args, schema),
+        AggregateFunctionType::CountDistinct => validate_count(args, schema),
         AggregateFunctionType::Max => validate_max(args, schema),
         AggregateFunctionType::MaxAppendOnly => validate_max_append_only(args, schema),
         AggregateFunctionType::MaxValue => validate_max_value(args, schema),
         AggregateFunctionType::Min => validate_min(args, schema),
         AggregateFunctionType::MinAppendOnly => validate_min_append_only(args, schema),
         AggregateFunctionType::MinValue => validate_min_value(args, schema),
+        AggregateFunctionType::Stddev => {
+            validate_variance(args, schema, AggregateFunctionType::Stddev)
+        }
+        AggregateFunctionType::StringAgg => validate_string_agg(args, schema),
         AggregateFunctionType::Sum => validate_sum(args, schema),
+        AggregateFunctionType::Variance => {
+            validate_variance(args, schema, AggregateFunctionType::Variance)
+        }
     }
 }
 
@@ -986,6 +1026,139 @@ fn validate_count(_args: &[Expression], _schema: &Schema) -> Result<ExpressionTy
     ))
 }
 
+fn validate_approx_count_distinct(
+    args: &[Expression],
+    schema: &Schema,
+) -> Result<ExpressionType, Error> {
+    validate_one_argument(args, schema, AggregateFunctionType::ApproxCountDistinct)?;
+    Ok(ExpressionType::new(
+        FieldType::Int,
+        false,
+        SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
+fn validate_approx_percentile(
+    args: &[Expression],
+    schema: &Schema,
+) -> Result<ExpressionType, Error> {
+    let (arg, percentile) =
+        validate_two_arguments(args, schema, AggregateFunctionType::ApproxPercentile)?;
+
+    for (argument_index, arg) in [&arg, &percentile].into_iter().enumerate() {
+        if !matches!(
+            arg.return_type,
+            FieldType::UInt
+                | FieldType::U128
+                | FieldType::Int
+                | FieldType::I128
+                | FieldType::Float
+                | FieldType::Decimal
+        ) {
+            return Err(Error::InvalidFunctionArgumentType {
+                function_name: AggregateFunctionType::ApproxPercentile.to_string(),
+                argument_index,
+                actual: arg.return_type,
+                expected: vec![
+                    FieldType::UInt,
+                    FieldType::U128,
+                    FieldType::Int,
+                    FieldType::I128,
+                    FieldType::Float,
+                    FieldType::Decimal,
+                ],
+            });
+        }
+    }
+
+    Ok(ExpressionType::new(
+        FieldType::Float,
+        true,
+        SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
+fn validate_array_agg(args: &[Expression], schema: &Schema) -> Result<ExpressionType, Error> {
+    validate_one_argument(args, schema, AggregateFunctionType::ArrayAgg)?;
+    Ok(ExpressionType::new(
+        FieldType::Json,
+        true,
+        SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
+fn validate_variance(
+    args: &[Expression],
+    schema: &Schema,
+    function: AggregateFunctionType,
+) -> Result<ExpressionType, Error> {
+    let arg = validate_one_argument(args, schema, &function)?;
+
+    match arg.return_type {
+        FieldType::UInt
+        | FieldType::U128
+        | FieldType::Int
+        | FieldType::I128
+        | FieldType::Float
+        | FieldType::Decimal => (),
+        FieldType::Boolean
+        | FieldType::String
+        | FieldType::Text
+        | FieldType::Date
+        | FieldType::Timestamp
+        | FieldType::Binary
+        | FieldType::Json
+        | FieldType::Point
+        | FieldType::Duration => {
+            return Err(Error::InvalidFunctionArgumentType {
+                function_name: function.to_string(),
+                argument_index: 0,
+                actual: arg.return_type,
+                expected: vec![
+                    FieldType::UInt,
+                    FieldType::U128,
+                    FieldType::Int,
+                    FieldType::I128,
+                    FieldType::Float,
+                    FieldType::Decimal,
+                ],
+            });
+        }
+    };
+
+    Ok(ExpressionType::new(
+        FieldType::Float,
+        true,
+        SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
+fn validate_string_agg(args: &[Expression], schema: &Schema) -> Result<ExpressionType, Error> {
+    let (arg, delimiter) = validate_two_arguments(args, schema, AggregateFunctionType::StringAgg)?;
+
+    for (argument_index, arg) in [&arg, &delimiter].into_iter().enumerate() {
+        if !matches!(arg.return_type, FieldType::String | FieldType::Text) {
+            return Err(Error::InvalidFunctionArgumentType {
+                function_name: AggregateFunctionType::StringAgg.to_string(),
+                argument_index,
+                actual: arg.return_type,
+                expected: vec![FieldType::String, FieldType::Text],
+            });
+        }
+    }
+
+    Ok(ExpressionType::new(
+        arg.return_type,
+        true,
+        SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
 fn validate_max(args: &[Expression], schema: &Schema) -> Result<ExpressionType, Error> {
     let arg = validate_one_argument(args, schema, AggregateFunctionType::Max)?;
 
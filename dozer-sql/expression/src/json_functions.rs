@@ -1,10 +1,12 @@
-use crate::arg_utils::validate_num_arguments;
+use crate::arg_utils::{validate_arg_type, validate_num_arguments};
 use crate::error::Error;
-use crate::execution::Expression;
+use crate::execution::{Expression, ExpressionType};
 
-use dozer_types::json_types::JsonValue;
+use dozer_types::json_types::{
+    field_to_json_value, json_to_string, DestructuredJsonRef, JsonArray, JsonObject, JsonValue,
+};
 use dozer_types::types::Record;
-use dozer_types::types::{Field, Schema};
+use dozer_types::types::{Field, FieldType, Schema, SourceDefinition};
 use jsonpath::{JsonPathFinder, JsonPathInst};
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
@@ -13,6 +15,17 @@ use std::str::FromStr;
 pub enum JsonFunctionType {
     JsonValue,
     JsonQuery,
+    JsonObject,
+    JsonArray,
+    JsonArrayLength,
+    JsonExists,
+    JsonSet,
+    JsonRemove,
+    JsonKeys,
+    /// The `->` operator.
+    JsonExtract,
+    /// The `->>` operator.
+    JsonExtractText,
 }
 
 impl Display for JsonFunctionType {
@@ -20,15 +33,126 @@ impl Display for JsonFunctionType {
         match self {
             JsonFunctionType::JsonValue => f.write_str("JSON_VALUE".to_string().as_str()),
             JsonFunctionType::JsonQuery => f.write_str("JSON_QUERY".to_string().as_str()),
+            JsonFunctionType::JsonObject => f.write_str("JSON_OBJECT"),
+            JsonFunctionType::JsonArray => f.write_str("JSON_ARRAY"),
+            JsonFunctionType::JsonArrayLength => f.write_str("JSON_ARRAY_LENGTH"),
+            JsonFunctionType::JsonExists => f.write_str("JSON_EXISTS"),
+            JsonFunctionType::JsonSet => f.write_str("JSON_SET"),
+            JsonFunctionType::JsonRemove => f.write_str("JSON_REMOVE"),
+            JsonFunctionType::JsonKeys => f.write_str("JSON_KEYS"),
+            JsonFunctionType::JsonExtract => f.write_str("->"),
+            JsonFunctionType::JsonExtractText => f.write_str("->>"),
         }
     }
 }
 
+pub(crate) fn get_json_function_type(
+    function: &JsonFunctionType,
+    args: &[Expression],
+    schema: &Schema,
+) -> Result<ExpressionType, Error> {
+    let string_types = || vec![FieldType::String, FieldType::Text];
+    let return_type = match function {
+        JsonFunctionType::JsonValue | JsonFunctionType::JsonQuery => {
+            return Ok(ExpressionType::new(
+                FieldType::Json,
+                false,
+                SourceDefinition::Dynamic,
+                false,
+            ))
+        }
+        JsonFunctionType::JsonObject => {
+            if args.len() % 2 != 0 {
+                return Err(Error::InvalidNumberOfArguments {
+                    function_name: function.to_string(),
+                    expected: args.len() + 1..args.len() + 2,
+                    actual: args.len(),
+                });
+            }
+            for (index, key) in args.iter().enumerate().step_by(2) {
+                validate_arg_type(key, string_types(), schema, function, index)?;
+            }
+            FieldType::Json
+        }
+        JsonFunctionType::JsonArray => FieldType::Json,
+        JsonFunctionType::JsonArrayLength | JsonFunctionType::JsonKeys => {
+            validate_num_arguments(1..3, args.len(), function)?;
+            if let Some(path) = args.get(1) {
+                validate_arg_type(path, string_types(), schema, function, 1)?;
+            }
+            if *function == JsonFunctionType::JsonKeys {
+                FieldType::Json
+            } else {
+                FieldType::UInt
+            }
+        }
+        JsonFunctionType::JsonExists => {
+            validate_num_arguments(2..3, args.len(), function)?;
+            validate_arg_type(&args[1], string_types(), schema, function, 1)?;
+            FieldType::Boolean
+        }
+        JsonFunctionType::JsonSet => {
+            if args.len() < 3 || args.len() % 2 == 0 {
+                let expected = args.len().max(2) + 1;
+                return Err(Error::InvalidNumberOfArguments {
+                    function_name: function.to_string(),
+                    expected: expected..expected + 1,
+                    actual: args.len(),
+                });
+            }
+            for (index, path) in args.iter().enumerate().skip(1).step_by(2) {
+                validate_arg_type(path, string_types(), schema, function, index)?;
+            }
+            FieldType::Json
+        }
+        JsonFunctionType::JsonRemove => {
+            validate_num_arguments(2..usize::MAX, args.len(), function)?;
+            for (index, path) in args.iter().enumerate().skip(1) {
+                validate_arg_type(path, string_types(), schema, function, index)?;
+            }
+            FieldType::Json
+        }
+        JsonFunctionType::JsonExtract | JsonFunctionType::JsonExtractText => {
+            validate_num_arguments(2..3, args.len(), function)?;
+            validate_arg_type(
+                &args[1],
+                vec![
+                    FieldType::String,
+                    FieldType::Text,
+                    FieldType::Int,
+                    FieldType::UInt,
+                ],
+                schema,
+                function,
+                1,
+            )?;
+            if *function == JsonFunctionType::JsonExtract {
+                FieldType::Json
+            } else {
+                FieldType::String
+            }
+        }
+    };
+    Ok(ExpressionType::new(
+        return_type,
+        true,
+        SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
 impl JsonFunctionType {
     pub(crate) fn new(name: &str) -> Option<JsonFunctionType> {
         match name {
             "json_value" => Some(JsonFunctionType::JsonValue),
             "json_query" => Some(JsonFunctionType::JsonQuery),
+            "json_object" => Some(JsonFunctionType::JsonObject),
+            "json_array" => Some(JsonFunctionType::JsonArray),
+            "json_array_length" => Some(JsonFunctionType::JsonArrayLength),
+            "json_exists" => Some(JsonFunctionType::JsonExists),
+            "json_set" => Some(JsonFunctionType::JsonSet),
+            "json_remove" => Some(JsonFunctionType::JsonRemove),
+            "json_keys" => Some(JsonFunctionType::JsonKeys),
             _ => None,
         }
     }
@@ -42,7 +166,212 @@ impl JsonFunctionType {
         match self {
             JsonFunctionType::JsonValue => self.evaluate_json_value(schema, args, record),
             JsonFunctionType::JsonQuery => self.evaluate_json_query(schema, args, record),
+            JsonFunctionType::JsonObject => self.evaluate_json_object(schema, args, record),
+            JsonFunctionType::JsonArray => {
+                let mut array = JsonArray::with_capacity(args.len());
+                for arg in args {
+                    array.push(field_to_json_value(arg.evaluate(record, schema)?));
+                }
+                Ok(Field::Json(array.into()))
+            }
+            JsonFunctionType::JsonArrayLength => {
+                let Some(value) = self.evaluate_json_target(schema, args, record)? else {
+                    return Ok(Field::Null);
+                };
+                Ok(value
+                    .as_array()
+                    .map_or(Field::Null, |array| Field::UInt(array.len() as u64)))
+            }
+            JsonFunctionType::JsonKeys => {
+                let Some(value) = self.evaluate_json_target(schema, args, record)? else {
+                    return Ok(Field::Null);
+                };
+                Ok(value.as_object().map_or(Field::Null, |object| {
+                    Field::Json(
+                        object
+                            .keys()
+                            .map(|key| JsonValue::from(key.as_str()))
+                            .collect::<JsonArray>()
+                            .into(),
+                    )
+                }))
+            }
+            JsonFunctionType::JsonExists => self.evaluate_json_exists(schema, args, record),
+            JsonFunctionType::JsonSet | JsonFunctionType::JsonRemove => {
+                self.evaluate_json_modify(schema, args, record)
+            }
+            JsonFunctionType::JsonExtract | JsonFunctionType::JsonExtractText => {
+                self.evaluate_json_extract(schema, args, record)
+            }
+        }
+    }
+
+    fn evaluate_json_object(
+        &self,
+        schema: &Schema,
+        args: &mut [Expression],
+        record: &Record,
+    ) -> Result<Field, Error> {
+        let mut object = JsonObject::with_capacity(args.len() / 2);
+        for (index, pair) in args.chunks_mut(2).enumerate() {
+            let [key, value] = pair else {
+                return Err(Error::InvalidNumberOfArguments {
+                    function_name: self.to_string(),
+                    expected: index * 2 + 2..index * 2 + 3,
+                    actual: index * 2 + 1,
+                });
+            };
+            let key = match key.evaluate(record, schema)? {
+                Field::String(key) | Field::Text(key) => key,
+                other => {
+                    return Err(Error::InvalidFunctionArgument {
+                        function_name: self.to_string(),
+                        argument_index: index * 2,
+                        argument: other,
+                    })
+                }
+            };
+            object.insert(key, field_to_json_value(value.evaluate(record, schema)?));
         }
+        Ok(Field::Json(object.into()))
+    }
+
+    /// Evaluates the JSON document in the first argument, narrowed down by the optional path in
+    /// the second argument. Returns `None` if the document is `NULL` or nothing matches the path.
+    fn evaluate_json_target(
+        &self,
+        schema: &Schema,
+        args: &mut [Expression],
+        record: &Record,
+    ) -> Result<Option<JsonValue>, Error> {
+        validate_num_arguments(1..3, args.len(), self)?;
+        let json_input = args[0].evaluate(record, schema)?;
+        if json_input == Field::Null {
+            return Ok(None);
+        }
+        let path = match args.get_mut(1) {
+            Some(path) => match path.evaluate(record, schema)? {
+                Field::Null => return Ok(None),
+                path => path.to_string(),
+            },
+            None => String::from("$"),
+        };
+        let value = self.evaluate_json(json_input, path)?;
+        Ok(if value.is_null() { None } else { Some(value) })
+    }
+
+    fn evaluate_json_exists(
+        &self,
+        schema: &Schema,
+        args: &mut [Expression],
+        record: &Record,
+    ) -> Result<Field, Error> {
+        validate_num_arguments(2..3, args.len(), self)?;
+        let json_input = args[0].evaluate(record, schema)?;
+        let path = args[1].evaluate(record, schema)?;
+        if json_input == Field::Null || path == Field::Null {
+            return Ok(Field::Null);
+        }
+
+        let finder = JsonPathFinder::new(
+            Box::from(json_input.to_json().unwrap_or(JsonValue::NULL)),
+            Box::from(
+                JsonPathInst::from_str(path.to_string().as_str())
+                    .map_err(Error::InvalidJsonPath)?,
+            ),
+        );
+        let exists = finder.find_slice().iter().any(|value| value.has_value());
+        Ok(Field::Boolean(exists))
+    }
+
+    /// `JSON_SET(json, path, value [, path, value ...])` replaces or adds the value at each path,
+    /// and `JSON_REMOVE(json, path [, path ...])` removes it. Paths whose parent doesn't exist are
+    /// ignored.
+    fn evaluate_json_modify(
+        &self,
+        schema: &Schema,
+        args: &mut [Expression],
+        record: &Record,
+    ) -> Result<Field, Error> {
+        validate_num_arguments(2..usize::MAX, args.len(), self)?;
+        let json_input = args[0].evaluate(record, schema)?;
+        let Some(mut json) = json_input.to_json().filter(|json| !json.is_null()) else {
+            return Ok(Field::Null);
+        };
+
+        let step = if *self == JsonFunctionType::JsonSet {
+            2
+        } else {
+            1
+        };
+        for (index, chunk) in args[1..].chunks_mut(step).enumerate() {
+            let path = match chunk[0].evaluate(record, schema)? {
+                Field::Null => return Ok(Field::Null),
+                path => path.to_string(),
+            };
+            let segments = parse_json_path_segments(&path)?;
+            if *self == JsonFunctionType::JsonSet {
+                let Some(value) = chunk.get_mut(1) else {
+                    return Err(Error::InvalidNumberOfArguments {
+                        function_name: self.to_string(),
+                        expected: index * 2 + 3..index * 2 + 4,
+                        actual: index * 2 + 2,
+                    });
+                };
+                let value = field_to_json_value(value.evaluate(record, schema)?);
+                set_json_path(&mut json, &segments, value);
+            } else if segments.is_empty() {
+                return Err(Error::InvalidJsonPath(path));
+            } else {
+                remove_json_path(&mut json, &segments);
+            }
+        }
+        Ok(Field::Json(json))
+    }
+
+    /// `json -> key` returns the member or array element as JSON, and `json ->> key` returns it as
+    /// a string. Negative indexes count from the end of an array.
+    fn evaluate_json_extract(
+        &self,
+        schema: &Schema,
+        args: &mut [Expression],
+        record: &Record,
+    ) -> Result<Field, Error> {
+        validate_num_arguments(2..3, args.len(), self)?;
+        let json_input = args[0].evaluate(record, schema)?;
+        let key = args[1].evaluate(record, schema)?;
+        let Some(json) = json_input.to_json() else {
+            return Ok(Field::Null);
+        };
+
+        let value = match (json.destructure_ref(), &key) {
+            (DestructuredJsonRef::Object(object), Field::String(key) | Field::Text(key)) => {
+                object.get(key.as_str())
+            }
+            (DestructuredJsonRef::Array(array), Field::Int(_) | Field::UInt(_)) => key
+                .to_i128()
+                .and_then(|index| {
+                    if index < 0 {
+                        array.len() as i128 + index
+                    } else {
+                        index
+                    }
+                    .try_into()
+                    .ok()
+                })
+                .and_then(|index: usize| array.get(index)),
+            _ => None,
+        };
+
+        Ok(match value {
+            None => Field::Null,
+            Some(value) if value.is_null() => Field::Null,
+            Some(value) if *self == JsonFunctionType::JsonExtract => Field::Json(value.clone()),
+            Some(value) => match value.as_string() {
+                Some(string) => Field::String(string.as_str().to_string()),
+                None => Field::String(json_to_string(value)),
+            },
+        })
     }
 
     pub(crate) fn evaluate_json_value(
@@ -116,3 +445,108 @@ impl JsonFunctionType {
         Ok(found)
     }
 }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum JsonPathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Parses the simple paths accepted by `JSON_SET` and `JSON_REMOVE`, e.g. `$.info.tags[0]` or
+/// `$['first name']`.
+fn parse_json_path_segments(path: &str) -> Result<Vec<JsonPathSegment>, Error> {
+    let invalid = || Error::InvalidJsonPath(path.to_string());
+    let mut rest = path.trim().strip_prefix('$').ok_or_else(invalid)?;
+    let mut segments = vec![];
+    while !rest.is_empty() {
+        if let Some(tail) = rest.strip_prefix('.') {
+            let end = tail.find(|c| c == '.' || c == '[').unwrap_or(tail.len());
+            if end == 0 {
+                return Err(invalid());
+            }
+            segments.push(JsonPathSegment::Key(tail[..end].to_string()));
+            rest = &tail[end..];
+        } else if let Some(tail) = rest.strip_prefix('[') {
+            let end = tail.find(']').ok_or_else(invalid)?;
+            let inner = tail[..end].trim();
+            let key = inner
+                .strip_prefix('\'')
+                .and_then(|key| key.strip_suffix('\''))
+                .or_else(|| {
+                    inner
+                        .strip_prefix('"')
+                        .and_then(|key| key.strip_suffix('"'))
+                });
+            segments.push(match key {
+                Some(key) => JsonPathSegment::Key(key.to_string()),
+                None => JsonPathSegment::Index(inner.parse().map_err(|_| invalid())?),
+            });
+            rest = &tail[end + 1..];
+        } else {
+            return Err(invalid());
+        }
+    }
+    Ok(segments)
+}
+
+fn get_json_path_mut<'a>(
+    mut json: &'a mut JsonValue,
+    segments: &[JsonPathSegment],
+) -> Option<&'a mut JsonValue> {
+    for segment in segments {
+        json = match segment {
+            JsonPathSegment::Key(key) => json.as_object_mut()?.get_mut(key.as_str())?,
+            JsonPathSegment::Index(index) => json.as_array_mut()?.get_mut(*index)?,
+        };
+    }
+    Some(json)
+}
+
+fn set_json_path(json: &mut JsonValue, segments: &[JsonPathSegment], value: JsonValue) {
+    let Some((last, parents)) = segments.split_last() else {
+        *json = value;
+        return;
+    };
+    let Some(parent) = get_json_path_mut(json, parents) else {
+        return;
+    };
+    match last {
+        JsonPathSegment::Key(key) => {
+            if let Some(object) = parent.as_object_mut() {
+                object.insert(key.as_str(), value);
+            }
+        }
+        JsonPathSegment::Index(index) => {
+            if let Some(array) = parent.as_array_mut() {
+                if *index < array.len() {
+                    array[*index] = value;
+                } else {
+                    array.push(value);
+                }
+            }
+        }
+    }
+}
+
+fn remove_json_path(json: &mut JsonValue, segments: &[JsonPathSegment]) {
+    let Some((last, parents)) = segments.split_last() else {
+        return;
+    };
+    let Some(parent) = get_json_path_mut(json, parents) else {
+        return;
+    };
+    match last {
+        JsonPathSegment::Key(key) => {
+            if let Some(object) = parent.as_object_mut() {
+                object.remove(key.as_str());
+            }
+        }
+        JsonPathSegment::Index(index) => {
+            if let Some(array) = parent.as_array_mut() {
+                if *index < array.len() {
+                    array.remove(*index);
+                }
+            }
+        }
+    }
+}
@@ -2,7 +2,7 @@ pub mod aggregate;
 mod arg_utils;
 pub mod builder;
 mod case;
-mod cast;
+pub mod cast;
 mod comparison;
 mod conditional;
 mod datetime;
@@ -3,8 +3,8 @@ use crate::error::Error;
 use crate::execution::{Expression, ExpressionType};
 use crate::scalar::number::{evaluate_abs, evaluate_round};
 use crate::scalar::string::{
-    evaluate_concat, evaluate_length, evaluate_to_char, evaluate_ucase, validate_concat,
-    validate_ucase,
+    evaluate_concat, evaluate_length, evaluate_string_function, evaluate_to_char, evaluate_ucase,
+    validate_concat, validate_string_function, validate_ucase,
 };
 use dozer_types::types::Record;
 use dozer_types::types::{Field, FieldType, Schema};
@@ -18,6 +18,22 @@ pub enum ScalarFunctionType {
     Concat,
     Length,
     ToChar,
+    Lower,
+    Ltrim,
+    Rtrim,
+    Substring,
+    Replace,
+    SplitPart,
+    Position,
+    Left,
+    Right,
+    Lpad,
+    Rpad,
+    RegexpReplace,
+    RegexpMatch,
+    Md5,
+    Sha256,
+    Initcap,
 }
 
 impl Display for ScalarFunctionType {
@@ -29,6 +45,22 @@ impl Display for ScalarFunctionType {
             ScalarFunctionType::Concat => f.write_str("CONCAT"),
             ScalarFunctionType::Length => f.write_str("LENGTH"),
             ScalarFunctionType::ToChar => f.write_str("TO_CHAR"),
+            ScalarFunctionType::Lower => f.write_str("LOWER"),
+            ScalarFunctionType::Ltrim => f.write_str("LTRIM"),
+            ScalarFunctionType::Rtrim => f.write_str("RTRIM"),
+            ScalarFunctionType::Substring => f.write_str("SUBSTRING"),
+            ScalarFunctionType::Replace => f.write_str("REPLACE"),
+            ScalarFunctionType::SplitPart => f.write_str("SPLIT_PART"),
+            ScalarFunctionType::Position => f.write_str("POSITION"),
+            ScalarFunctionType::Left => f.write_str("LEFT"),
+            ScalarFunctionType::Right => f.write_str("RIGHT"),
+            ScalarFunctionType::Lpad => f.write_str("LPAD"),
+            ScalarFunctionType::Rpad => f.write_str("RPAD"),
+            ScalarFunctionType::RegexpReplace => f.write_str("REGEXP_REPLACE"),
+            ScalarFunctionType::RegexpMatch => f.write_str("REGEXP_MATCH"),
+            ScalarFunctionType::Md5 => f.write_str("MD5"),
+            ScalarFunctionType::Sha256 => f.write_str("SHA256"),
+            ScalarFunctionType::Initcap => f.write_str("INITCAP"),
         }
     }
 }
@@ -73,6 +105,22 @@ pub(crate) fn get_scalar_function_type(
                 Ok(validate_two_arguments(args, schema, ScalarFunctionType::ToChar)?.0)
             }
         }
+        ScalarFunctionType::Lower
+        | ScalarFunctionType::Ltrim
+        | ScalarFunctionType::Rtrim
+        | ScalarFunctionType::Substring
+        | ScalarFunctionType::Replace
+        | ScalarFunctionType::SplitPart
+        | ScalarFunctionType::Position
+        | ScalarFunctionType::Left
+        | ScalarFunctionType::Right
+        | ScalarFunctionType::Lpad
+        | ScalarFunctionType::Rpad
+        | ScalarFunctionType::RegexpReplace
+        | ScalarFunctionType::RegexpMatch
+        | ScalarFunctionType::Md5
+        | ScalarFunctionType::Sha256
+        | ScalarFunctionType::Initcap => validate_string_function(function, args, schema),
     }
 }
 
@@ -81,10 +129,26 @@ impl ScalarFunctionType {
         match name {
             "abs" => Some(ScalarFunctionType::Abs),
             "round" => Some(ScalarFunctionType::Round),
-            "ucase" => Some(ScalarFunctionType::Ucase),
+            "ucase" | "upper" => Some(ScalarFunctionType::Ucase),
             "concat" => Some(ScalarFunctionType::Concat),
             "length" => Some(ScalarFunctionType::Length),
             "to_char" => Some(ScalarFunctionType::ToChar),
+            "lower" | "lcase" => Some(ScalarFunctionType::Lower),
+            "ltrim" => Some(ScalarFunctionType::Ltrim),
+            "rtrim" => Some(ScalarFunctionType::Rtrim),
+            "substring" | "substr" => Some(ScalarFunctionType::Substring),
+            "replace" => Some(ScalarFunctionType::Replace),
+            "split_part" => Some(ScalarFunctionType::SplitPart),
+            "position" => Some(ScalarFunctionType::Position),
+            "left" => Some(ScalarFunctionType::Left),
+            "right" => Some(ScalarFunctionType::Right),
+            "lpad" => Some(ScalarFunctionType::Lpad),
+            "rpad" => Some(ScalarFunctionType::Rpad),
+            "regexp_replace" => Some(ScalarFunctionType::RegexpReplace),
+            "regexp_match" => Some(ScalarFunctionType::RegexpMatch),
+            "md5" => Some(ScalarFunctionType::Md5),
+            "sha256" => Some(ScalarFunctionType::Sha256),
+            "initcap" => Some(ScalarFunctionType::Initcap),
             _ => None,
         }
     }
@@ -119,6 +183,22 @@ impl ScalarFunctionType {
                 let (arg0, arg1) = args.split_at_mut(1);
                 evaluate_to_char(schema, &mut arg0[0], &mut arg1[0], record)
             }
+            ScalarFunctionType::Lower
+            | ScalarFunctionType::Ltrim
+            | ScalarFunctionType::Rtrim
+            | ScalarFunctionType::Substring
+            | ScalarFunctionType::Replace
+            | ScalarFunctionType::SplitPart
+            | ScalarFunctionType::Position
+            | ScalarFunctionType::Left
+            | ScalarFunctionType::Right
+            | ScalarFunctionType::Lpad
+            | ScalarFunctionType::Rpad
+            | ScalarFunctionType::RegexpReplace
+            | ScalarFunctionType::RegexpMatch
+            | ScalarFunctionType::Md5
+            | ScalarFunctionType::Sha256
+            | ScalarFunctionType::Initcap => evaluate_string_function(self, schema, args, record),
         }
     }
 }
@@ -1,15 +1,20 @@
 use crate::error::Error;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::fmt::Write;
 use std::fmt::{Display, Formatter};
 
 use crate::execution::{Expression, ExpressionType};
 
-use crate::arg_utils::validate_arg_type;
+use crate::arg_utils::{validate_arg_type, validate_num_arguments};
 use crate::scalar::common::ScalarFunctionType;
 
+use dozer_types::json_types::JsonValue;
 use dozer_types::types::Record;
 use dozer_types::types::{Field, FieldType, Schema};
 use like::{Escape, Like};
+use regex::{Regex, RegexBuilder};
+use sha2::{Digest, Sha256};
 
 pub(crate) fn validate_ucase(arg: &Expression, schema: &Schema) -> Result<ExpressionType, Error> {
     validate_arg_type(
@@ -272,6 +277,401 @@ pub(crate) fn evaluate_to_char(
     Ok(Field::String(output))
 }
 
+const STRING_TYPES: &[FieldType] = &[FieldType::String, FieldType::Text];
+const INTEGER_TYPES: &[FieldType] = &[
+    FieldType::UInt,
+    FieldType::U128,
+    FieldType::Int,
+    FieldType::I128,
+];
+const BYTES_TYPES: &[FieldType] = &[FieldType::String, FieldType::Text, FieldType::Binary];
+
+/// Longest string, in characters, that `LPAD` and `RPAD` produce.
+const MAX_PAD_LENGTH: i64 = 10 * 1024 * 1024;
+
+/// Number of compiled regular expressions kept per thread before the cache is cleared.
+const REGEX_CACHE_SIZE: usize = 256;
+
+thread_local! {
+    /// Compiled regular expressions by pattern and flags, as patterns are usually literals.
+    static REGEX_CACHE: RefCell<HashMap<(String, String), (Regex, bool)>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Argument types of the string functions that return `NULL` when any argument is `NULL`.
+/// Trailing arguments past the minimum number are optional.
+fn string_function_signature(
+    function: &ScalarFunctionType,
+) -> (usize, &'static [&'static [FieldType]]) {
+    match function {
+        ScalarFunctionType::Lower | ScalarFunctionType::Initcap => (1, &[STRING_TYPES]),
+        ScalarFunctionType::Ltrim | ScalarFunctionType::Rtrim => (1, &[STRING_TYPES, STRING_TYPES]),
+        ScalarFunctionType::Substring => (2, &[STRING_TYPES, INTEGER_TYPES, INTEGER_TYPES]),
+        ScalarFunctionType::Replace => (3, &[STRING_TYPES, STRING_TYPES, STRING_TYPES]),
+        ScalarFunctionType::SplitPart => (3, &[STRING_TYPES, STRING_TYPES, INTEGER_TYPES]),
+        ScalarFunctionType::Position => (2, &[STRING_TYPES, STRING_TYPES]),
+        ScalarFunctionType::Left | ScalarFunctionType::Right => (2, &[STRING_TYPES, INTEGER_TYPES]),
+        ScalarFunctionType::Lpad | ScalarFunctionType::Rpad => {
+            (2, &[STRING_TYPES, INTEGER_TYPES, STRING_TYPES])
+        }
+        ScalarFunctionType::RegexpReplace => {
+            (3, &[STRING_TYPES, STRING_TYPES, STRING_TYPES, STRING_TYPES])
+        }
+        ScalarFunctionType::RegexpMatch => (2, &[STRING_TYPES, STRING_TYPES, STRING_TYPES]),
+        ScalarFunctionType::Md5 | ScalarFunctionType::Sha256 => (1, &[BYTES_TYPES]),
+        ScalarFunctionType::Abs
+        | ScalarFunctionType::Round
+        | ScalarFunctionType::Ucase
+        | ScalarFunctionType::Concat
+        | ScalarFunctionType::Length
+        | ScalarFunctionType::ToChar => unreachable!("{function} is not a string function"),
+    }
+}
+
+pub(crate) fn validate_string_function(
+    function: &ScalarFunctionType,
+    args: &[Expression],
+    schema: &Schema,
+) -> Result<ExpressionType, Error> {
+    let (min_args, arg_types) = string_function_signature(function);
+    validate_num_arguments(min_args..arg_types.len() + 1, args.len(), function)?;
+
+    let mut first_arg_type = None;
+    for (index, (arg, expected)) in args.iter().zip(arg_types.iter()).enumerate() {
+        let arg_type = validate_arg_type(arg, expected.to_vec(), schema, function, index)?;
+        first_arg_type.get_or_insert(arg_type.return_type);
+    }
+
+    let return_type = match function {
+        ScalarFunctionType::Position => FieldType::UInt,
+        ScalarFunctionType::RegexpMatch => FieldType::Json,
+        ScalarFunctionType::Md5 | ScalarFunctionType::Sha256 => FieldType::String,
+        _ => match first_arg_type {
+            Some(FieldType::Text) => FieldType::Text,
+            _ => FieldType::String,
+        },
+    };
+    Ok(ExpressionType::new(
+        return_type,
+        true,
+        dozer_types::types::SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
+pub(crate) fn evaluate_string_function(
+    function: &ScalarFunctionType,
+    schema: &Schema,
+    args: &mut [Expression],
+    record: &Record,
+) -> Result<Field, Error> {
+    let (min_args, arg_types) = string_function_signature(function);
+    validate_num_arguments(min_args..arg_types.len() + 1, args.len(), function)?;
+
+    let mut values = Vec::with_capacity(args.len());
+    for arg in args {
+        match arg.evaluate(record, schema)? {
+            Field::Null => return Ok(Field::Null),
+            value => values.push(value),
+        }
+    }
+
+    let string = |index: usize| -> Result<String, Error> {
+        match &values[index] {
+            Field::String(value) | Field::Text(value) => Ok(value.clone()),
+            other => Err(Error::InvalidFunctionArgument {
+                function_name: function.to_string(),
+                argument_index: index,
+                argument: other.clone(),
+            }),
+        }
+    };
+    let int = |index: usize| -> Result<i64, Error> {
+        values[index]
+            .to_int()
+            .ok_or_else(|| Error::InvalidFunctionArgument {
+                function_name: function.to_string(),
+                argument_index: index,
+                argument: values[index].clone(),
+            })
+    };
+    let is_text = matches!(values[0], Field::Text(_));
+    let text_or_string = |value: String| {
+        if is_text {
+            Field::Text(value)
+        } else {
+            Field::String(value)
+        }
+    };
+
+    let result = match function {
+        ScalarFunctionType::Lower => text_or_string(string(0)?.to_lowercase()),
+        ScalarFunctionType::Initcap => text_or_string(initcap(&string(0)?)),
+        ScalarFunctionType::Ltrim | ScalarFunctionType::Rtrim => {
+            let value = string(0)?;
+            let chars: Vec<char> = match values.get(1) {
+                Some(_) => string(1)?.chars().collect(),
+                None => vec![' '],
+            };
+            text_or_string(if function == &ScalarFunctionType::Ltrim {
+                value.trim_start_matches::<&[char]>(&chars).to_string()
+            } else {
+                value.trim_end_matches::<&[char]>(&chars).to_string()
+            })
+        }
+        ScalarFunctionType::Substring => {
+            let length = match values.get(2) {
+                Some(_) => match int(2)? {
+                    length if length < 0 => {
+                        return Err(Error::InvalidFunctionArgument {
+                            function_name: function.to_string(),
+                            argument_index: 2,
+                            argument: values[2].clone(),
+                        })
+                    }
+                    length => Some(length),
+                },
+                None => None,
+            };
+            text_or_string(substring(&string(0)?, int(1)?, length))
+        }
+        ScalarFunctionType::Replace => {
+            text_or_string(string(0)?.replace(string(1)?.as_str(), &string(2)?))
+        }
+        ScalarFunctionType::SplitPart => {
+            let index = int(2)?;
+            if index == 0 {
+                return Err(Error::InvalidFunctionArgument {
+                    function_name: function.to_string(),
+                    argument_index: 2,
+                    argument: values[2].clone(),
+                });
+            }
+            text_or_string(split_part(&string(0)?, &string(1)?, index))
+        }
+        ScalarFunctionType::Position => {
+            let (substring, value) = (string(0)?, string(1)?);
+            let position = value
+                .find(substring.as_str())
+                .map_or(0, |index| value[..index].chars().count() + 1);
+            Field::UInt(position as u64)
+        }
+        ScalarFunctionType::Left | ScalarFunctionType::Right => {
+            let (value, count) = (string(0)?, int(1)?);
+            let length = value.chars().count() as i64;
+            // A negative count removes characters from the other end
+            let keep = if count < 0 {
+                (length + count).max(0)
+            } else {
+                count.min(length)
+            } as usize;
+            text_or_string(if function == &ScalarFunctionType::Left {
+                value.chars().take(keep).collect()
+            } else {
+                value.chars().skip(length as usize - keep).collect()
+            })
+        }
+        ScalarFunctionType::Lpad | ScalarFunctionType::Rpad => {
+            let length = int(1)?;
+            if length > MAX_PAD_LENGTH {
+                return Err(Error::InvalidFunctionArgument {
+                    function_name: function.to_string(),
+                    argument_index: 1,
+                    argument: values[1].clone(),
+                });
+            }
+            let (value, length) = (string(0)?, length.max(0) as usize);
+            let fill = match values.get(2) {
+                Some(_) => string(2)?,
+                None => " ".to_string(),
+            };
+            text_or_string(pad(
+                &value,
+                length,
+                &fill,
+                function == &ScalarFunctionType::Lpad,
+            ))
+        }
+        ScalarFunctionType::RegexpReplace => {
+            let flags = match values.get(3) {
+                Some(_) => string(3)?,
+                None => String::new(),
+            };
+            let (regex, global) = build_regex(&string(1)?, &flags)?;
+            let value = string(0)?;
+            let replacement = convert_regex_replacement(&string(2)?);
+            text_or_string(if global {
+                regex.replace_all(&value, replacement.as_str()).into_owned()
+            } else {
+                regex.replace(&value, replacement.as_str()).into_owned()
+            })
+        }
+        ScalarFunctionType::RegexpMatch => {
+            let flags = match values.get(2) {
+                Some(_) => string(2)?,
+                None => String::new(),
+            };
+            let (regex, _) = build_regex(&string(1)?, &flags)?;
+            let value = string(0)?;
+            match regex.captures(&value) {
+                Some(captures) => {
+                    // Without capture groups, the whole match is returned
+                    let groups: Vec<JsonValue> = if captures.len() == 1 {
+                        vec![captures[0].into()]
+                    } else {
+                        captures
+                            .iter()
+                            .skip(1)
+                            .map(|group| {
+                                group.map_or(JsonValue::NULL, |group| group.as_str().into())
+                            })
+                            .collect()
+                    };
+                    Field::Json(groups.into())
+                }
+                None => Field::Null,
+            }
+        }
+        ScalarFunctionType::Md5 | ScalarFunctionType::Sha256 => {
+            let bytes = match &values[0] {
+                Field::Binary(bytes) => bytes.clone(),
+                _ => string(0)?.into_bytes(),
+            };
+            Field::String(if function == &ScalarFunctionType::Md5 {
+                format!("{:x}", md5::compute(bytes))
+            } else {
+                format!("{:x}", Sha256::digest(bytes))
+            })
+        }
+        ScalarFunctionType::Abs
+        | ScalarFunctionType::Round
+        | ScalarFunctionType::Ucase
+        | ScalarFunctionType::Concat
+        | ScalarFunctionType::Length
+        | ScalarFunctionType::ToChar => unreachable!("{function} is not a string function"),
+    };
+    Ok(result)
+}
+
+/// `start` is 1-based, and characters before the first one are counted but not returned.
+fn substring(value: &str, start: i64, length: Option<i64>) -> String {
+    let end = length.map(|length| start.saturating_add(length));
+    let start = start.max(1);
+    let take = match end {
+        Some(end) => (end - start).max(0) as usize,
+        None => usize::MAX,
+    };
+    value.chars().skip(start as usize - 1).take(take).collect()
+}
+
+/// `index` is 1-based, negative indexes count from the end.
+fn split_part(value: &str, delimiter: &str, index: i64) -> String {
+    let parts: Vec<&str> = if delimiter.is_empty() {
+        vec![value]
+    } else {
+        value.split(delimiter).collect()
+    };
+    let index = if index > 0 {
+        index - 1
+    } else {
+        parts.len() as i64 + index
+    };
+    usize::try_from(index)
+        .ok()
+        .and_then(|index| parts.get(index))
+        .map_or(String::new(), |part| part.to_string())
+}
+
+fn pad(value: &str, length: usize, fill: &str, left: bool) -> String {
+    let value_length = value.chars().count();
+    if value_length >= length {
+        return value.chars().take(length).collect();
+    }
+    if fill.is_empty() {
+        return value.to_string();
+    }
+    let padding: String = fill.chars().cycle().take(length - value_length).collect();
+    if left {
+        padding + value
+    } else {
+        value.to_string() + &padding
+    }
+}
+
+fn initcap(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut in_word = false;
+    for c in value.chars() {
+        if in_word {
+            result.extend(c.to_lowercase());
+        } else {
+            result.extend(c.to_uppercase());
+        }
+        in_word = c.is_alphanumeric();
+    }
+    result
+}
+
+/// Returns the regex and whether all matches should be replaced.
+fn build_regex(pattern: &str, flags: &str) -> Result<(Regex, bool), Error> {
+    let key = (pattern.to_string(), flags.to_string());
+    if let Some(regex) = REGEX_CACHE.with(|cache| cache.borrow().get(&key).cloned()) {
+        return Ok(regex);
+    }
+    let regex = compile_regex(pattern, flags)?;
+    REGEX_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.len() >= REGEX_CACHE_SIZE {
+            cache.clear();
+        }
+        cache.insert(key, regex.clone());
+    });
+    Ok(regex)
+}
+
+fn compile_regex(pattern: &str, flags: &str) -> Result<(Regex, bool), Error> {
+    let mut builder = RegexBuilder::new(pattern);
+    let mut global = false;
+    for flag in flags.chars() {
+        match flag {
+            'g' => global = true,
+            'i' => {
+                builder.case_insensitive(true);
+            }
+            'c' => {
+                builder.case_insensitive(false);
+            }
+            other => return Err(Error::InvalidRegexFlag(other)),
+        }
+    }
+    Ok((builder.build()?, global))
+}
+
+/// Converts Postgres style back references (`\1`, `\&`) to the `regex` crate syntax.
+fn convert_regex_replacement(replacement: &str) -> String {
+    let mut result = String::with_capacity(replacement.len());
+    let mut chars = replacement.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some(digit)) if digit.is_ascii_digit() => {
+                result.push_str(&format!("${{{digit}}}"));
+                chars.next();
+            }
+            ('\\', Some('&')) => {
+                result.push_str("${0}");
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                result.push('\\');
+                chars.next();
+            }
+            ('$', _) => result.push_str("$$"),
+            (c, _) => result.push(c),
+        }
+    }
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -291,6 +691,132 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_substring() {
+        assert_eq!(substring("Hello", 2, Some(3)), "ell");
+        assert_eq!(substring("Hello", 2, None), "ello");
+        assert_eq!(substring("Hello", 0, Some(2)), "H");
+        assert_eq!(substring("Hello", -3, Some(2)), "");
+        assert_eq!(substring("Hello", 10, None), "");
+        assert_eq!(substring("héllo", 2, Some(2)), "él");
+    }
+
+    #[test]
+    fn test_split_part() {
+        assert_eq!(split_part("a,b,c", ",", 1), "a");
+        assert_eq!(split_part("a,b,c", ",", 3), "c");
+        assert_eq!(split_part("a,b,c", ",", 4), "");
+        assert_eq!(split_part("a,b,c", ",", -1), "c");
+        assert_eq!(split_part("a,b,c", ",", -4), "");
+        assert_eq!(split_part("a,b,c", "", 1), "a,b,c");
+    }
+
+    #[test]
+    fn test_pad() {
+        assert_eq!(pad("hi", 5, "xy", true), "xyxhi");
+        assert_eq!(pad("hi", 5, "xy", false), "hixyx");
+        assert_eq!(pad("hello", 3, " ", true), "hel");
+        assert_eq!(pad("hi", 5, "", true), "hi");
+
+        let row = Record::new(vec![]);
+        let mut args = [
+            Literal(Field::String("hi".to_string())),
+            Literal(Field::Int(MAX_PAD_LENGTH + 1)),
+        ];
+        assert!(matches!(
+            evaluate_string_function(
+                &ScalarFunctionType::Lpad,
+                &Schema::default(),
+                &mut args,
+                &row
+            ),
+            Err(Error::InvalidFunctionArgument {
+                argument_index: 1,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_initcap() {
+        assert_eq!(initcap("hello wORLD"), "Hello World");
+        assert_eq!(initcap("o'neil-smith 2nd"), "O'Neil-Smith 2nd");
+    }
+
+    #[test]
+    fn test_convert_regex_replacement() {
+        assert_eq!(convert_regex_replacement(r"\1-\2"), "${1}-${2}");
+        assert_eq!(convert_regex_replacement(r"[\&]"), "[${0}]");
+        assert_eq!(convert_regex_replacement("$1"), "$$1");
+        assert_eq!(convert_regex_replacement(r"a\\b"), r"a\b");
+    }
+
+    #[test]
+    fn test_string_functions_null() {
+        let row = Record::new(vec![]);
+        let mut args = [
+            Literal(Field::String("Hello".to_string())),
+            Literal(Field::Null),
+        ];
+        assert_eq!(
+            evaluate_string_function(
+                &ScalarFunctionType::Left,
+                &Schema::default(),
+                &mut args,
+                &row
+            )
+            .unwrap(),
+            Field::Null
+        );
+    }
+
+    #[test]
+    fn test_regexp_functions() {
+        let row = Record::new(vec![]);
+        let mut args = [
+            Literal(Field::String("foo123bar45".to_string())),
+            Literal(Field::String("([a-z]+)(\\d+)".to_string())),
+        ];
+        assert_eq!(
+            evaluate_string_function(
+                &ScalarFunctionType::RegexpMatch,
+                &Schema::default(),
+                &mut args,
+                &row
+            )
+            .unwrap(),
+            Field::Json(vec![JsonValue::from("foo"), JsonValue::from("123")].into())
+        );
+
+        let mut args = [
+            Literal(Field::Text("foo123bar45".to_string())),
+            Literal(Field::String("\\d+".to_string())),
+            Literal(Field::String("<\\&>".to_string())),
+            Literal(Field::String("g".to_string())),
+        ];
+        assert_eq!(
+            evaluate_string_function(
+                &ScalarFunctionType::RegexpReplace,
+                &Schema::default(),
+                &mut args,
+                &row
+            )
+            .unwrap(),
+            Field::Text("foo<123>bar<45>".to_string())
+        );
+
+        args[3] = Literal(Field::String("x".to_string()));
+        assert!(matches!(
+            evaluate_string_function(
+                &ScalarFunctionType::RegexpReplace,
+                &Schema::default(),
+                &mut args,
+                &row
+            ),
+            Err(Error::InvalidRegexFlag('x'))
+        ));
+    }
+
     fn test_like(s_val: &str, c_val: char) {
         let row = Record::new(vec![]);
 
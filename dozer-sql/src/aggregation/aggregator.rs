@@ -1,10 +1,17 @@
 #![allow(clippy::enum_variant_names)]
 
+use crate::aggregation::approx_count_distinct::ApproxCountDistinctAggregator;
+use crate::aggregation::approx_percentile::ApproxPercentileAggregator;
+use crate::aggregation::array_agg::ArrayAggAggregator;
 use crate::aggregation::avg::AvgAggregator;
 use crate::aggregation::count::CountAggregator;
+use crate::aggregation::count_distinct::CountDistinctAggregator;
 use crate::aggregation::max::MaxAggregator;
 use crate::aggregation::min::MinAggregator;
+use crate::aggregation::stddev::StddevAggregator;
+use crate::aggregation::string_agg::StringAggAggregator;
 use crate::aggregation::sum::SumAggregator;
+use crate::aggregation::variance::VarianceAggregator;
 use crate::calculate_err;
 use crate::errors::PipelineError;
 use dozer_types::chrono::{DateTime, FixedOffset, NaiveDate};
@@ -46,19 +53,34 @@ pub enum AggregatorEnum {
     MaxValueAggregator,
     SumAggregator,
     CountAggregator,
+    CountDistinctAggregator,
+    StddevAggregator,
+    VarianceAggregator,
+    ArrayAggAggregator,
+    StringAggAggregator,
+    ApproxCountDistinctAggregator,
+    ApproxPercentileAggregator,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
 pub enum AggregatorType {
+    ApproxCountDistinct,
+    ApproxPercentile,
+    ArrayAgg,
     Avg,
     Count,
+    CountDistinct,
     Max,
     MaxAppendOnly,
     MaxValue,
     Min,
     MinAppendOnly,
     MinValue,
+    Stddev,
+    /// With its delimiter, which is fixed when the query is planned
+    StringAgg(String),
     Sum,
+    Variance,
 }
 
 #[derive(Debug, bincode::Encode, bincode::Decode)]
@@ -241,30 +263,44 @@ impl OrderedAggregatorState {
 impl Display for AggregatorType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
+            AggregatorType::ApproxCountDistinct => f.write_str("approx_count_distinct"),
+            AggregatorType::ApproxPercentile => f.write_str("approx_percentile"),
+            AggregatorType::ArrayAgg => f.write_str("array_agg"),
             AggregatorType::Avg => f.write_str("avg"),
             AggregatorType::Count => f.write_str("count"),
+            AggregatorType::CountDistinct => f.write_str("count_distinct"),
             AggregatorType::Max => f.write_str("max"),
             AggregatorType::MaxAppendOnly => f.write_str("max_append_only"),
             AggregatorType::MaxValue => f.write_str("max_value"),
             AggregatorType::Min => f.write_str("min"),
             AggregatorType::MinAppendOnly => f.write_str("min_append_only"),
             AggregatorType::MinValue => f.write_str("min_value"),
+            AggregatorType::Stddev => f.write_str("stddev"),
+            AggregatorType::StringAgg(_) => f.write_str("string_agg"),
             AggregatorType::Sum => f.write_str("sum"),
+            AggregatorType::Variance => f.write_str("variance"),
         }
     }
 }
 
-pub fn get_aggregator_from_aggregator_type(typ: AggregatorType) -> AggregatorEnum {
+pub fn get_aggregator_from_aggregator_type(typ: &AggregatorType) -> AggregatorEnum {
     match typ {
+        AggregatorType::ApproxCountDistinct => ApproxCountDistinctAggregator::new().into(),
+        AggregatorType::ApproxPercentile => ApproxPercentileAggregator::new().into(),
+        AggregatorType::ArrayAgg => ArrayAggAggregator::new().into(),
         AggregatorType::Avg => AvgAggregator::new().into(),
         AggregatorType::Count => CountAggregator::new().into(),
+        AggregatorType::CountDistinct => CountDistinctAggregator::new().into(),
         AggregatorType::Max => MaxAggregator::new().into(),
         AggregatorType::MaxAppendOnly => MaxAppendOnlyAggregator::new().into(),
         AggregatorType::MaxValue => MaxValueAggregator::new().into(),
         AggregatorType::Min => MinAggregator::new().into(),
         AggregatorType::MinAppendOnly => MinAppendOnlyAggregator::new().into(),
         AggregatorType::MinValue => MinValueAggregator::new().into(),
+        AggregatorType::Stddev => StddevAggregator::new().into(),
+        AggregatorType::StringAgg(delimiter) => StringAggAggregator::new(delimiter.clone()).into(),
         AggregatorType::Sum => SumAggregator::new().into(),
+        AggregatorType::Variance => VarianceAggregator::new().into(),
     }
 }
 
@@ -405,10 +441,127 @@ pub fn get_aggregator_type_from_aggregation_expression(
                 .clone()],
             AggregatorType::Count,
         )),
+        Expression::AggregateFunction {
+            fun: AggregateFunctionType::CountDistinct,
+            args,
+        } => Ok((
+            vec![args
+                .first()
+                .ok_or_else(|| {
+                    PipelineError::NotEnoughArguments(
+                        AggregateFunctionType::CountDistinct.to_string(),
+                    )
+                })?
+                .clone()],
+            AggregatorType::CountDistinct,
+        )),
+        Expression::AggregateFunction {
+            fun: AggregateFunctionType::Stddev,
+            args,
+        } => Ok((
+            vec![args
+                .first()
+                .ok_or_else(|| {
+                    PipelineError::NotEnoughArguments(AggregateFunctionType::Stddev.to_string())
+                })?
+                .clone()],
+            AggregatorType::Stddev,
+        )),
+        Expression::AggregateFunction {
+            fun: AggregateFunctionType::Variance,
+            args,
+        } => Ok((
+            vec![args
+                .first()
+                .ok_or_else(|| {
+                    PipelineError::NotEnoughArguments(AggregateFunctionType::Variance.to_string())
+                })?
+                .clone()],
+            AggregatorType::Variance,
+        )),
+        Expression::AggregateFunction {
+            fun: AggregateFunctionType::ArrayAgg,
+            args,
+        } => Ok((
+            vec![args
+                .first()
+                .ok_or_else(|| {
+                    PipelineError::NotEnoughArguments(AggregateFunctionType::ArrayAgg.to_string())
+                })?
+                .clone()],
+            AggregatorType::ArrayAgg,
+        )),
+        Expression::AggregateFunction {
+            fun: AggregateFunctionType::StringAgg,
+            args,
+        } => {
+            let (Some(value), Some(delimiter)) = (args.first(), args.get(1)) else {
+                return Err(PipelineError::NotEnoughArguments(
+                    AggregateFunctionType::StringAgg.to_string(),
+                ));
+            };
+            // The delimiter must be the same for every record, or the result would depend on which one came last
+            let delimiter = match delimiter {
+                Expression::Literal(Field::String(delimiter) | Field::Text(delimiter)) => {
+                    delimiter.clone()
+                }
+                _ => {
+                    return Err(PipelineError::NonLiteralArgument(
+                        AggregateFunctionType::StringAgg.to_string(),
+                        1,
+                    ))
+                }
+            };
+            Ok((vec![value.clone()], AggregatorType::StringAgg(delimiter)))
+        }
+        Expression::AggregateFunction {
+            fun: AggregateFunctionType::ApproxCountDistinct,
+            args,
+        } => Ok((
+            vec![args
+                .first()
+                .ok_or_else(|| {
+                    PipelineError::NotEnoughArguments(
+                        AggregateFunctionType::ApproxCountDistinct.to_string(),
+                    )
+                })?
+                .clone()],
+            AggregatorType::ApproxCountDistinct,
+        )),
+        Expression::AggregateFunction {
+            fun: AggregateFunctionType::ApproxPercentile,
+            args,
+        } => Ok((
+            vec![
+                args.first()
+                    .ok_or_else(|| {
+                        PipelineError::NotEnoughArguments(
+                            AggregateFunctionType::ApproxPercentile.to_string(),
+                        )
+                    })?
+                    .clone(),
+                args.get(1)
+                    .ok_or_else(|| {
+                        PipelineError::NotEnoughArguments(
+                            AggregateFunctionType::ApproxPercentile.to_string(),
+                        )
+                    })?
+                    .clone(),
+            ],
+            AggregatorType::ApproxPercentile,
+        )),
         _ => Err(PipelineError::InvalidFunction(e.to_string(schema))),
     }
 }
 
+/// Tracks how many times each non-null value is in the group, so values can be retracted.
+pub(crate) fn update_field_count(map: &mut BTreeMap<Field, u64>, field: &Field, incr: bool) {
+    if field == &Field::Null {
+        return;
+    }
+    OrderedAggregatorState::update_for_map(map, field.clone(), incr);
+}
+
 pub fn update_val_map(
     fields: &[Field],
     val_delta: u64,
@@ -0,0 +1,165 @@
+use crate::aggregation::aggregator::Aggregator;
+use crate::aggregation::count::get_count;
+use crate::errors::PipelineError;
+use ahash::RandomState;
+use dozer_types::types::{Field, FieldType};
+use std::collections::BTreeMap;
+use std::hash::BuildHasher;
+
+/// Estimates the number of distinct non-null values of the group with a HyperLogLog sketch.
+#[derive(Debug, bincode::Encode, bincode::Decode)]
+pub struct ApproxCountDistinctAggregator {
+    current_state: HyperLogLog,
+    return_type: Option<FieldType>,
+}
+
+impl ApproxCountDistinctAggregator {
+    pub fn new() -> Self {
+        Self {
+            current_state: HyperLogLog::new(),
+            return_type: None,
+        }
+    }
+}
+
+impl Aggregator for ApproxCountDistinctAggregator {
+    fn init(&mut self, return_type: FieldType) {
+        self.return_type = Some(return_type);
+    }
+
+    fn update(&mut self, old: &[Field], new: &[Field]) -> Result<Field, PipelineError> {
+        self.delete(old)?;
+        self.insert(new)
+    }
+
+    fn delete(&mut self, old: &[Field]) -> Result<Field, PipelineError> {
+        for field in old {
+            self.current_state.remove(field);
+        }
+        get_count(self.current_state.estimate(), self.return_type)
+    }
+
+    fn insert(&mut self, new: &[Field]) -> Result<Field, PipelineError> {
+        for field in new {
+            self.current_state.insert(field);
+        }
+        get_count(self.current_state.estimate(), self.return_type)
+    }
+}
+
+/// A HyperLogLog sketch that supports removals.
+///
+/// Instead of only the maximum rank per register, the sketch counts how many values hit every
+/// `(register, rank)` pair, so the register value can go down again when values are removed.
+/// Only the pairs that were hit are stored, which keeps small groups small.
+///
+/// The harmonic sum of the registers and the number of empty registers are kept up to date on
+/// every change, so estimating doesn't visit the registers.
+#[derive(Debug, bincode::Encode, bincode::Decode)]
+pub struct HyperLogLog {
+    /// Keyed by `register << 8 | rank`, so the last entry of a register holds its maximum rank.
+    counters: BTreeMap<u32, u64>,
+    /// Sum of `2^(64 - rank)` over all registers, which is exact in fixed point.
+    sum: u128,
+    num_zero_registers: u32,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self {
+            counters: BTreeMap::new(),
+            sum: Self::NUM_REGISTERS as u128 * Self::register_term(0),
+            num_zero_registers: Self::NUM_REGISTERS as u32,
+        }
+    }
+}
+
+impl HyperLogLog {
+    /// 2^12 registers give a standard error of about 1.6%.
+    const PRECISION: u32 = 12;
+    const NUM_REGISTERS: usize = 1 << Self::PRECISION;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, value: &Field) {
+        if let Some(key) = Self::counter_key(value) {
+            let register = key >> 8;
+            let old_rank = self.register_rank(register);
+            *self.counters.entry(key).or_insert(0) += 1;
+            self.update_register(old_rank, self.register_rank(register));
+        }
+    }
+
+    pub fn remove(&mut self, value: &Field) {
+        if let Some(key) = Self::counter_key(value) {
+            if let Some(count) = self.counters.get_mut(&key) {
+                *count -= 1;
+                if *count == 0 {
+                    let register = key >> 8;
+                    let old_rank = self.register_rank(register);
+                    self.counters.remove(&key);
+                    self.update_register(old_rank, self.register_rank(register));
+                }
+            }
+        }
+    }
+
+    pub fn estimate(&self) -> u64 {
+        let num_registers = Self::NUM_REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / num_registers);
+        let sum = self.sum as f64 / Self::register_term(0) as f64;
+        let estimate = alpha * num_registers * num_registers / sum;
+
+        let estimate = if estimate <= 2.5 * num_registers && self.num_zero_registers > 0 {
+            // Linear counting is more accurate for small cardinalities
+            num_registers * (num_registers / self.num_zero_registers as f64).ln()
+        } else {
+            estimate
+        };
+        estimate.round() as u64
+    }
+
+    /// Returns the maximum rank of `register`, 0 if no value hit it.
+    fn register_rank(&self, register: u32) -> u32 {
+        self.counters
+            .range(register << 8..(register + 1) << 8)
+            .next_back()
+            .map_or(0, |(key, _)| key & 0xff)
+    }
+
+    /// Updates the sum and the number of empty registers after a register rank changed.
+    fn update_register(&mut self, old_rank: u32, new_rank: u32) {
+        if old_rank == new_rank {
+            return;
+        }
+        self.sum = self.sum - Self::register_term(old_rank) + Self::register_term(new_rank);
+        if old_rank == 0 {
+            self.num_zero_registers -= 1;
+        } else if new_rank == 0 {
+            self.num_zero_registers += 1;
+        }
+    }
+
+    /// `2^-rank`, scaled by `2^64`.
+    fn register_term(rank: u32) -> u128 {
+        1 << (64 - rank)
+    }
+
+    fn counter_key(value: &Field) -> Option<u32> {
+        if value == &Field::Null {
+            return None;
+        }
+        let hash = hasher().hash_one(value);
+        let register = (hash >> (64 - Self::PRECISION)) as u32;
+        let rank = ((hash << Self::PRECISION).leading_zeros() + 1).min(64 - Self::PRECISION + 1);
+        Some(register << 8 | rank)
+    }
+}
+
+fn hasher() -> RandomState {
+    // Fixed seed, so the sketch stays valid after being restored from a checkpoint
+    const SEED: usize = 4586239412096138541;
+    RandomState::with_seed(SEED)
+}
@@ -0,0 +1,179 @@
+use crate::aggregation::aggregator::Aggregator;
+use crate::calculate_err;
+use crate::errors::PipelineError;
+use crate::errors::PipelineError::InvalidFunctionArgument;
+use dozer_sql_expression::aggregate::AggregateFunctionType::ApproxPercentile;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldType};
+use std::collections::BTreeMap;
+
+/// Estimates a percentile of the non-null values of the group with a DDSketch.
+#[derive(Debug, bincode::Encode, bincode::Decode)]
+pub struct ApproxPercentileAggregator {
+    current_state: DdSketch,
+    percentile: f64,
+    return_type: Option<FieldType>,
+}
+
+impl ApproxPercentileAggregator {
+    pub fn new() -> Self {
+        Self {
+            current_state: DdSketch::new(),
+            percentile: 0.5,
+            return_type: None,
+        }
+    }
+
+    fn get_percentile(&self) -> Field {
+        self.current_state
+            .quantile(self.percentile)
+            .map_or(Field::Null, |value| Field::Float(OrderedFloat(value)))
+    }
+}
+
+impl Aggregator for ApproxPercentileAggregator {
+    fn init(&mut self, return_type: FieldType) {
+        self.return_type = Some(return_type);
+    }
+
+    fn update(&mut self, old: &[Field], new: &[Field]) -> Result<Field, PipelineError> {
+        self.delete(old)?;
+        self.insert(new)
+    }
+
+    fn delete(&mut self, old: &[Field]) -> Result<Field, PipelineError> {
+        let (value, _) = get_arguments(old)?;
+        if value != &Field::Null {
+            self.current_state
+                .remove(calculate_err!(value.to_float(), ApproxPercentile));
+        }
+        Ok(self.get_percentile())
+    }
+
+    fn insert(&mut self, new: &[Field]) -> Result<Field, PipelineError> {
+        let (value, percentile) = get_arguments(new)?;
+        self.percentile = match percentile.to_float() {
+            Some(percentile) if (0.0..=1.0).contains(&percentile) => percentile,
+            _ => {
+                return Err(InvalidFunctionArgument(
+                    ApproxPercentile.to_string(),
+                    percentile.clone(),
+                    1,
+                ))
+            }
+        };
+        if value != &Field::Null {
+            self.current_state
+                .insert(calculate_err!(value.to_float(), ApproxPercentile));
+        }
+        Ok(self.get_percentile())
+    }
+}
+
+fn get_arguments(fields: &[Field]) -> Result<(&Field, &Field), PipelineError> {
+    match fields {
+        [value, percentile] => Ok((value, percentile)),
+        _ => Err(InvalidFunctionArgument(
+            ApproxPercentile.to_string(),
+            Field::Null,
+            fields.len(),
+        )),
+    }
+}
+
+/// A DDSketch: values are counted in logarithmically sized buckets, so every quantile
+/// is estimated within a fixed relative error. Removing a value just decrements its bucket.
+#[derive(Debug, Default, bincode::Encode, bincode::Decode)]
+pub struct DdSketch {
+    positive: BTreeMap<i32, u64>,
+    negative: BTreeMap<i32, u64>,
+    zero_count: u64,
+    count: u64,
+}
+
+impl DdSketch {
+    const RELATIVE_ACCURACY: f64 = 0.01;
+    /// Values closer to zero than this are counted as zero.
+    const MIN_INDEXABLE_VALUE: f64 = 1e-9;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, value: f64) {
+        if let Some((buckets, index)) = self.bucket(value) {
+            *buckets.entry(index).or_insert(0) += 1;
+        } else {
+            self.zero_count += 1;
+        }
+        self.count += 1;
+    }
+
+    pub fn remove(&mut self, value: f64) {
+        if let Some((buckets, index)) = self.bucket(value) {
+            let Some(count) = buckets.get_mut(&index) else {
+                return;
+            };
+            *count -= 1;
+            if *count == 0 {
+                buckets.remove(&index);
+            }
+        } else if self.zero_count > 0 {
+            self.zero_count -= 1;
+        } else {
+            return;
+        }
+        self.count -= 1;
+    }
+
+    /// Returns `None` if the sketch is empty.
+    pub fn quantile(&self, quantile: f64) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = (quantile * (self.count - 1) as f64).floor() as u64;
+
+        let mut seen = 0;
+        // Negative values, from the most negative one
+        for (index, count) in self.negative.iter().rev() {
+            seen += count;
+            if seen > rank {
+                return Some(-Self::value(*index));
+            }
+        }
+        seen += self.zero_count;
+        if seen > rank {
+            return Some(0.0);
+        }
+        for (index, count) in &self.positive {
+            seen += count;
+            if seen > rank {
+                return Some(Self::value(*index));
+            }
+        }
+        None
+    }
+
+    fn gamma() -> f64 {
+        (1.0 + Self::RELATIVE_ACCURACY) / (1.0 - Self::RELATIVE_ACCURACY)
+    }
+
+    fn bucket(&mut self, value: f64) -> Option<(&mut BTreeMap<i32, u64>, i32)> {
+        if value.abs() < Self::MIN_INDEXABLE_VALUE {
+            return None;
+        }
+        let index = (value.abs().ln() / Self::gamma().ln()).ceil() as i32;
+        let buckets = if value > 0.0 {
+            &mut self.positive
+        } else {
+            &mut self.negative
+        };
+        Some((buckets, index))
+    }
+
+    /// The value all the values of a bucket are estimated as.
+    fn value(index: i32) -> f64 {
+        let gamma = Self::gamma();
+        2.0 * gamma.powi(index) / (gamma + 1.0)
+    }
+}
@@ -0,0 +1,123 @@
+use crate::aggregation::aggregator::Aggregator;
+use crate::errors::PipelineError;
+use crate::errors::PipelineError::InvalidFunctionArgument;
+use dozer_sql_expression::aggregate::AggregateFunctionType::ArrayAgg;
+use dozer_types::json_types::{field_to_json_value, JsonValue};
+use dozer_types::types::{Field, FieldType};
+use std::collections::BTreeMap;
+
+/// Collects the non-null values of the group into a JSON array, in the order they were inserted.
+///
+/// Retracting a value removes its latest occurrence, and updating a value replaces its latest
+/// occurrence in place.
+#[derive(Debug, bincode::Encode, bincode::Decode)]
+pub struct ArrayAggAggregator {
+    /// Values by insertion sequence number
+    current_state: BTreeMap<u64, Field>,
+    /// Sequence numbers of the occurrences of every value, in insertion order
+    positions: BTreeMap<Field, Vec<u64>>,
+    next_seq: u64,
+    /// The array of `current_state`, only rebuilt when the values change
+    result: Field,
+    return_type: Option<FieldType>,
+}
+
+impl ArrayAggAggregator {
+    pub fn new() -> Self {
+        Self {
+            current_state: BTreeMap::new(),
+            positions: BTreeMap::new(),
+            next_seq: 0,
+            result: Field::Null,
+            return_type: None,
+        }
+    }
+}
+
+impl Aggregator for ArrayAggAggregator {
+    fn init(&mut self, return_type: FieldType) {
+        self.return_type = Some(return_type);
+    }
+
+    fn update(&mut self, old: &[Field], new: &[Field]) -> Result<Field, PipelineError> {
+        let (old, new) = (get_argument(old)?, get_argument(new)?);
+        if old == new {
+            return Ok(self.result.clone());
+        }
+        match self.remove(old) {
+            Some(seq) if new != &Field::Null => self.insert_at(seq, new.clone()),
+            _ => self.push(new),
+        }
+        self.update_result();
+        Ok(self.result.clone())
+    }
+
+    fn delete(&mut self, old: &[Field]) -> Result<Field, PipelineError> {
+        if self.remove(get_argument(old)?).is_some() {
+            self.update_result();
+        }
+        Ok(self.result.clone())
+    }
+
+    fn insert(&mut self, new: &[Field]) -> Result<Field, PipelineError> {
+        let value = get_argument(new)?;
+        if value != &Field::Null {
+            self.push(value);
+            self.update_result();
+        }
+        Ok(self.result.clone())
+    }
+}
+
+impl ArrayAggAggregator {
+    fn push(&mut self, value: &Field) {
+        if value == &Field::Null {
+            return;
+        }
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.insert_at(seq, value.clone());
+    }
+
+    fn insert_at(&mut self, seq: u64, value: Field) {
+        let positions = self.positions.entry(value.clone()).or_default();
+        let index = positions.partition_point(|position| *position < seq);
+        positions.insert(index, seq);
+        self.current_state.insert(seq, value);
+    }
+
+    /// Removes the latest occurrence of `value` and returns its sequence number.
+    fn remove(&mut self, value: &Field) -> Option<u64> {
+        let positions = self.positions.get_mut(value)?;
+        let seq = positions.pop()?;
+        if positions.is_empty() {
+            self.positions.remove(value);
+        }
+        self.current_state.remove(&seq);
+        Some(seq)
+    }
+
+    fn update_result(&mut self) {
+        if self.current_state.is_empty() {
+            self.result = Field::Null;
+            return;
+        }
+        let array: Vec<JsonValue> = self
+            .current_state
+            .values()
+            .map(|value| field_to_json_value(value.clone()))
+            .collect();
+        self.result = Field::Json(array.into());
+    }
+}
+
+fn get_argument(fields: &[Field]) -> Result<&Field, PipelineError> {
+    match fields {
+        [value] => Ok(value),
+        _ => Err(InvalidFunctionArgument(
+            ArrayAgg.to_string(),
+            Field::Null,
+            fields.len(),
+        )),
+    }
+}
@@ -43,7 +43,10 @@ impl Aggregator for CountAggregator {
     }
 }
 
-fn get_count(count: u64, return_type: Option<FieldType>) -> Result<Field, PipelineError> {
+pub(crate) fn get_count(
+    count: u64,
+    return_type: Option<FieldType>,
+) -> Result<Field, PipelineError> {
     match return_type {
         Some(typ) => match typ {
             FieldType::UInt => Ok(Field::UInt(count)),
@@ -1,14 +1,21 @@
 pub mod aggregator;
+pub mod approx_count_distinct;
+pub mod approx_percentile;
+pub mod array_agg;
 pub mod avg;
 pub mod count;
+pub mod count_distinct;
 pub mod factory;
 pub mod max;
 pub mod max_value;
 pub mod min;
 pub mod min_value;
 pub mod processor;
+pub mod stddev;
+pub mod string_agg;
 pub mod sum;
 mod tests;
+pub mod variance;
 
 pub mod max_append_only;
 pub mod min_append_only;
@@ -33,7 +33,7 @@ impl AggregationState {
     pub fn new(types: &[AggregatorType], ret_types: &[FieldType]) -> Self {
         let mut states: Vec<AggregatorEnum> = Vec::new();
         for (idx, typ) in types.iter().enumerate() {
-            let mut aggr = get_aggregator_from_aggregator_type(*typ);
+            let mut aggr = get_aggregator_from_aggregator_type(typ);
             aggr.init(ret_types[idx]);
             states.push(aggr);
         }
@@ -86,8 +86,12 @@ impl AggregationProcessor {
         let mut aggr_measures_ret_types = Vec::new();
 
         for measure in measures {
-            let (aggr_measure, aggr_type) =
+            let (aggr_measure, mut aggr_type) =
                 get_aggregator_type_from_aggregation_expression(&measure, &input_schema)?;
+            if enable_probabilistic_optimizations && aggr_type == AggregatorType::CountDistinct {
+                // Exact distinct counts keep every value of the group in memory
+                aggr_type = AggregatorType::ApproxCountDistinct;
+            }
             aggr_measures.push(aggr_measure);
             aggr_types.push(aggr_type);
             aggr_measures_ret_types.push(measure.get_type(&input_schema)?.return_type)
@@ -0,0 +1,52 @@
+use crate::aggregation::aggregator::Aggregator;
+use crate::aggregation::variance::VarianceAggregator;
+use crate::errors::PipelineError;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldType};
+
+/// Sample standard deviation of the non-null values of the group.
+#[derive(Debug, bincode::Encode, bincode::Decode)]
+pub struct StddevAggregator {
+    variance: VarianceAggregator,
+}
+
+impl StddevAggregator {
+    pub fn new() -> Self {
+        Self {
+            variance: VarianceAggregator::new(),
+        }
+    }
+
+    fn get_stddev(&self) -> Field {
+        self.variance
+            .get_variance()
+            .map_or(Field::Null, |variance| {
+                Field::Float(OrderedFloat(variance.sqrt()))
+            })
+    }
+}
+
+impl Aggregator for StddevAggregator {
+    fn init(&mut self, return_type: FieldType) {
+        self.variance.init(return_type);
+    }
+
+    fn update(&mut self, old: &[Field], new: &[Field]) -> Result<Field, PipelineError> {
+        self.delete(old)?;
+        self.insert(new)
+    }
+
+    fn delete(&mut self, old: &[Field]) -> Result<Field, PipelineError> {
+        for field in old {
+            self.variance.update_state(field, false)?;
+        }
+        Ok(self.get_stddev())
+    }
+
+    fn insert(&mut self, new: &[Field]) -> Result<Field, PipelineError> {
+        for field in new {
+            self.variance.update_state(field, true)?;
+        }
+        Ok(self.get_stddev())
+    }
+}
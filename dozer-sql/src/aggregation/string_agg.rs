@@ -0,0 +1,94 @@
+use crate::aggregation::aggregator::{update_field_count, Aggregator};
+use crate::errors::PipelineError;
+use crate::errors::PipelineError::InvalidFunctionArgument;
+use dozer_sql_expression::aggregate::AggregateFunctionType::StringAgg;
+use dozer_types::types::{Field, FieldType};
+use std::collections::BTreeMap;
+
+/// Concatenates the non-null values of the group, separated by the delimiter.
+///
+/// Values are kept with their number of occurrences, so the result is ordered by value
+/// and stays the same no matter in which order records were inserted or retracted.
+#[derive(Debug, bincode::Encode, bincode::Decode)]
+pub struct StringAggAggregator {
+    current_state: BTreeMap<Field, u64>,
+    delimiter: String,
+    /// The concatenation of `current_state`, only rebuilt when the values change
+    result: Field,
+    return_type: Option<FieldType>,
+}
+
+impl StringAggAggregator {
+    pub fn new(delimiter: String) -> Self {
+        Self {
+            current_state: BTreeMap::new(),
+            delimiter,
+            result: Field::Null,
+            return_type: None,
+        }
+    }
+}
+
+impl Aggregator for StringAggAggregator {
+    fn init(&mut self, return_type: FieldType) {
+        self.return_type = Some(return_type);
+    }
+
+    fn update(&mut self, old: &[Field], new: &[Field]) -> Result<Field, PipelineError> {
+        let (old, new) = (get_argument(old)?, get_argument(new)?);
+        if old != new {
+            update_field_count(&mut self.current_state, old, false);
+            update_field_count(&mut self.current_state, new, true);
+            self.update_result();
+        }
+        Ok(self.result.clone())
+    }
+
+    fn delete(&mut self, old: &[Field]) -> Result<Field, PipelineError> {
+        let value = get_argument(old)?;
+        if value != &Field::Null {
+            update_field_count(&mut self.current_state, value, false);
+            self.update_result();
+        }
+        Ok(self.result.clone())
+    }
+
+    fn insert(&mut self, new: &[Field]) -> Result<Field, PipelineError> {
+        let value = get_argument(new)?;
+        if value != &Field::Null {
+            update_field_count(&mut self.current_state, value, true);
+            self.update_result();
+        }
+        Ok(self.result.clone())
+    }
+}
+
+impl StringAggAggregator {
+    fn update_result(&mut self) {
+        if self.current_state.is_empty() {
+            self.result = Field::Null;
+            return;
+        }
+        let values: Vec<String> = self
+            .current_state
+            .iter()
+            .flat_map(|(value, count)| std::iter::repeat(value.to_text()).take(*count as usize))
+            .collect();
+        let result = values.join(&self.delimiter);
+        self.result = match self.return_type {
+            Some(FieldType::Text) => Field::Text(result),
+            _ => Field::String(result),
+        };
+    }
+}
+
+fn get_argument(fields: &[Field]) -> Result<&Field, PipelineError> {
+    match fields {
+        [value] => Ok(value),
+        _ => Err(InvalidFunctionArgument(
+            StringAgg.to_string(),
+            Field::Null,
+            fields.len(),
+        )),
+    }
+}
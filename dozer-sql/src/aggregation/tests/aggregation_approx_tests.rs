@@ -0,0 +1,123 @@
+use crate::aggregation::approx_count_distinct::HyperLogLog;
+use crate::aggregation::approx_percentile::DdSketch;
+use crate::aggregation::tests::aggregation_tests_utils::{
+    delete_field, init_input_schema, init_processor, insert_exp, insert_field, update_exp,
+    FIELD_100_INT, FIELD_1_INT, FIELD_200_INT, FIELD_2_INT, FIELD_300_INT, FIELD_NULL, ITALY,
+};
+use crate::output;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::types::FieldType::Int;
+use dozer_types::types::{Field, Operation};
+use std::collections::HashMap;
+
+#[test]
+fn test_approx_count_distinct_aggregation() {
+    let schema = init_input_schema(Int, "APPROX_COUNT_DISTINCT");
+    let mut processor = init_processor(
+        "SELECT Country, APPROX_COUNT_DISTINCT(Salary) \
+        FROM Users \
+        GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    // Small cardinalities are counted exactly
+    let mut out = output!(processor, insert_field(ITALY, FIELD_100_INT));
+    let mut exp = vec![insert_exp(ITALY, FIELD_1_INT)];
+    assert_eq!(out, exp);
+
+    out = output!(processor, insert_field(ITALY, FIELD_100_INT));
+    exp = vec![update_exp(ITALY, ITALY, FIELD_1_INT, FIELD_1_INT)];
+    assert_eq!(out, exp);
+
+    out = output!(processor, insert_field(ITALY, FIELD_200_INT));
+    exp = vec![update_exp(ITALY, ITALY, FIELD_1_INT, FIELD_2_INT)];
+    assert_eq!(out, exp);
+
+    out = output!(processor, delete_field(ITALY, FIELD_200_INT));
+    exp = vec![update_exp(ITALY, ITALY, FIELD_2_INT, FIELD_1_INT)];
+    assert_eq!(out, exp);
+}
+
+#[test]
+fn test_hyper_log_log() {
+    let mut sketch = HyperLogLog::new();
+    for i in 0..100_000 {
+        sketch.insert(&Field::Int(i));
+        sketch.insert(&Field::Int(i));
+    }
+    sketch.insert(&Field::Null);
+    assert!((sketch.estimate() as f64 - 100_000.0).abs() < 5_000.0);
+
+    for i in 50_000..100_000 {
+        sketch.remove(&Field::Int(i));
+        sketch.remove(&Field::Int(i));
+    }
+    assert!((sketch.estimate() as f64 - 50_000.0).abs() < 2_500.0);
+
+    // The sketch survives a checkpoint
+    let data = bincode::encode_to_vec(&sketch, bincode::config::legacy()).unwrap();
+    let (restored, _): (HyperLogLog, usize) =
+        bincode::decode_from_slice(&data, bincode::config::legacy()).unwrap();
+    assert_eq!(restored.estimate(), sketch.estimate());
+
+    // Removing every value brings the incremental state back to an empty sketch
+    for i in 0..50_000 {
+        sketch.remove(&Field::Int(i));
+        sketch.remove(&Field::Int(i));
+    }
+    assert_eq!(sketch.estimate(), 0);
+}
+
+#[test]
+fn test_approx_percentile_aggregation() {
+    let schema = init_input_schema(Int, "APPROX_PERCENTILE");
+    let mut processor = init_processor(
+        "SELECT Country, APPROX_PERCENTILE(Salary, 0.5) \
+        FROM Users \
+        GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    fn percentile(out: &[Operation]) -> f64 {
+        match out {
+            [Operation::Insert { new } | Operation::Update { new, .. }] => {
+                new.values[1].as_float().unwrap()
+            }
+            _ => panic!("Unexpected output {out:?}"),
+        }
+    }
+
+    output!(processor, insert_field(ITALY, FIELD_100_INT));
+    output!(processor, insert_field(ITALY, FIELD_300_INT));
+    output!(processor, insert_field(ITALY, FIELD_NULL));
+    let out = output!(processor, insert_field(ITALY, FIELD_200_INT));
+    assert!((percentile(&out) - 200.0).abs() <= 2.0);
+
+    let out = output!(processor, delete_field(ITALY, FIELD_100_INT));
+    assert!((percentile(&out) - 200.0).abs() <= 2.0);
+
+    let out = output!(processor, delete_field(ITALY, FIELD_200_INT));
+    assert!((percentile(&out) - 300.0).abs() <= 3.0);
+}
+
+#[test]
+fn test_dd_sketch() {
+    let mut sketch = DdSketch::new();
+    assert_eq!(sketch.quantile(0.5), None);
+
+    for i in -1000..=1000 {
+        sketch.insert(i as f64);
+    }
+    for (quantile, expected) in [(0.0, -1000.0), (0.25, -500.0), (0.5, 0.0), (0.99, 980.0)] {
+        let estimate = sketch.quantile(quantile).unwrap();
+        assert!((estimate - expected).abs() <= expected.abs() * 0.01);
+    }
+
+    for i in -1000..0 {
+        sketch.remove(i as f64);
+    }
+    let estimate = sketch.quantile(0.5).unwrap();
+    assert!((estimate - 500.0).abs() <= 5.0);
+}
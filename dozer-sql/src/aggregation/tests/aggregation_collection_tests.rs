@@ -0,0 +1,166 @@
+use crate::aggregation::tests::aggregation_tests_utils::{
+    delete_exp, delete_field, init_input_schema, init_processor, insert_exp, insert_field,
+    update_exp, update_field, FIELD_100_INT, FIELD_200_INT, FIELD_50_INT, FIELD_NULL, ITALY,
+};
+use crate::output;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::json_types::json;
+use dozer_types::types::Field;
+use dozer_types::types::FieldType;
+use std::collections::HashMap;
+
+#[test]
+fn test_array_agg_aggregation() {
+    let schema = init_input_schema(FieldType::Int, "ARRAY_AGG");
+    let mut processor = init_processor(
+        "SELECT Country, ARRAY_AGG(Salary) \
+        FROM Users \
+        GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    // Italy: 100
+    let mut out = output!(processor, insert_field(ITALY, FIELD_100_INT));
+    let mut exp = vec![insert_exp(ITALY, &Field::Json(json!([100])))];
+    assert_eq!(out, exp);
+
+    // Italy: 100, 50, NULL, values stay in insertion order
+    output!(processor, insert_field(ITALY, FIELD_50_INT));
+    out = output!(processor, insert_field(ITALY, FIELD_NULL));
+    exp = vec![update_exp(
+        ITALY,
+        ITALY,
+        &Field::Json(json!([100, 50])),
+        &Field::Json(json!([100, 50])),
+    )];
+    assert_eq!(out, exp);
+
+    // Italy: 200, 50, NULL, an updated value keeps its position
+    out = output!(
+        processor,
+        update_field(ITALY, ITALY, FIELD_100_INT, FIELD_200_INT)
+    );
+    exp = vec![update_exp(
+        ITALY,
+        ITALY,
+        &Field::Json(json!([100, 50])),
+        &Field::Json(json!([200, 50])),
+    )];
+    assert_eq!(out, exp);
+
+    // Italy: 200, 50, 100, NULL
+    out = output!(processor, insert_field(ITALY, FIELD_100_INT));
+    exp = vec![update_exp(
+        ITALY,
+        ITALY,
+        &Field::Json(json!([200, 50])),
+        &Field::Json(json!([200, 50, 100])),
+    )];
+    assert_eq!(out, exp);
+
+    // Italy: 100, NULL
+    output!(processor, delete_field(ITALY, FIELD_200_INT));
+    out = output!(processor, delete_field(ITALY, FIELD_50_INT));
+    exp = vec![update_exp(
+        ITALY,
+        ITALY,
+        &Field::Json(json!([50, 100])),
+        &Field::Json(json!([100])),
+    )];
+    assert_eq!(out, exp);
+
+    // Italy: NULL
+    out = output!(processor, delete_field(ITALY, FIELD_100_INT));
+    exp = vec![update_exp(
+        ITALY,
+        ITALY,
+        &Field::Json(json!([100])),
+        FIELD_NULL,
+    )];
+    assert_eq!(out, exp);
+
+    out = output!(processor, delete_field(ITALY, FIELD_NULL));
+    exp = vec![delete_exp(ITALY, FIELD_NULL)];
+    assert_eq!(out, exp);
+
+    // A new group starts from an empty array
+    out = output!(processor, insert_field(ITALY, FIELD_200_INT));
+    exp = vec![insert_exp(ITALY, &Field::Json(json!([200])))];
+    assert_eq!(out, exp);
+}
+
+#[test]
+fn test_string_agg_aggregation() {
+    let schema = init_input_schema(FieldType::String, "STRING_AGG");
+    let mut processor = init_processor(
+        "SELECT Country, STRING_AGG(Salary, ', ') \
+        FROM Users \
+        GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    let a = Field::String("a".to_string());
+    let b = Field::String("b".to_string());
+    let c = Field::String("c".to_string());
+
+    // Italy: b
+    let mut out = output!(processor, insert_field(ITALY, &b));
+    let mut exp = vec![insert_exp(ITALY, &b)];
+    assert_eq!(out, exp);
+
+    // Italy: b, a
+    out = output!(processor, insert_field(ITALY, &a));
+    exp = vec![update_exp(
+        ITALY,
+        ITALY,
+        &b,
+        &Field::String("a, b".to_string()),
+    )];
+    assert_eq!(out, exp);
+
+    // Italy: b, a, a
+    out = output!(processor, insert_field(ITALY, &a));
+    exp = vec![update_exp(
+        ITALY,
+        ITALY,
+        &Field::String("a, b".to_string()),
+        &Field::String("a, a, b".to_string()),
+    )];
+    assert_eq!(out, exp);
+
+    // Italy: c, a, a
+    out = output!(processor, update_field(ITALY, ITALY, &b, &c));
+    exp = vec![update_exp(
+        ITALY,
+        ITALY,
+        &Field::String("a, a, b".to_string()),
+        &Field::String("a, a, c".to_string()),
+    )];
+    assert_eq!(out, exp);
+
+    // Italy: c
+    output!(processor, delete_field(ITALY, &a));
+    out = output!(processor, delete_field(ITALY, &a));
+    exp = vec![update_exp(
+        ITALY,
+        ITALY,
+        &Field::String("a, c".to_string()),
+        &c,
+    )];
+    assert_eq!(out, exp);
+}
+
+#[test]
+#[should_panic(expected = "Argument 1 of function STRING_AGG() must be a literal")]
+fn test_string_agg_column_delimiter() {
+    // Delimiters could differ between records, so they must be literals
+    let schema = init_input_schema(FieldType::String, "STRING_AGG");
+    let _ = init_processor(
+        "SELECT Country, STRING_AGG(Salary, Country) \
+        FROM Users \
+        GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    );
+}
@@ -0,0 +1,86 @@
+use crate::aggregation::tests::aggregation_tests_utils::{
+    delete_exp, delete_field, init_input_schema, init_processor, insert_exp, insert_field,
+    update_exp, update_field, FIELD_100_INT, FIELD_1_INT, FIELD_200_INT, FIELD_2_INT, FIELD_NULL,
+    ITALY,
+};
+use crate::output;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::types::FieldType::Int;
+use std::collections::HashMap;
+
+#[test]
+fn test_count_distinct_aggregation() {
+    let schema = init_input_schema(Int, "COUNT");
+    let mut processor = init_processor(
+        "SELECT Country, COUNT(DISTINCT Salary) \
+        FROM Users \
+        GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    // Insert 100 for segment Italy
+    /*
+        Italy, 100
+        -------------
+        COUNT = 1
+    */
+    let mut out = output!(processor, insert_field(ITALY, FIELD_100_INT));
+    let mut exp = vec![insert_exp(ITALY, FIELD_1_INT)];
+    assert_eq!(out, exp);
+
+    // Insert another 100 for segment Italy
+    /*
+        Italy, 100
+        Italy, 100
+        -------------
+        COUNT = 1
+    */
+    out = output!(processor, insert_field(ITALY, FIELD_100_INT));
+    exp = vec![update_exp(ITALY, ITALY, FIELD_1_INT, FIELD_1_INT)];
+    assert_eq!(out, exp);
+
+    // Insert NULL for segment Italy
+    /*
+        Italy, 100
+        Italy, 100
+        Italy, NULL
+        -------------
+        COUNT = 1
+    */
+    out = output!(processor, insert_field(ITALY, FIELD_NULL));
+    exp = vec![update_exp(ITALY, ITALY, FIELD_1_INT, FIELD_1_INT)];
+    assert_eq!(out, exp);
+
+    // Update Italy value 100 -> 200
+    /*
+        Italy, 200
+        Italy, 100
+        Italy, NULL
+        -------------
+        COUNT = 2
+    */
+    out = output!(
+        processor,
+        update_field(ITALY, ITALY, FIELD_100_INT, FIELD_200_INT)
+    );
+    exp = vec![update_exp(ITALY, ITALY, FIELD_1_INT, FIELD_2_INT)];
+    assert_eq!(out, exp);
+
+    // Delete 200
+    /*
+        Italy, 100
+        Italy, NULL
+        -------------
+        COUNT = 1
+    */
+    out = output!(processor, delete_field(ITALY, FIELD_200_INT));
+    exp = vec![update_exp(ITALY, ITALY, FIELD_2_INT, FIELD_1_INT)];
+    assert_eq!(out, exp);
+
+    // Delete the remaining records
+    output!(processor, delete_field(ITALY, FIELD_NULL));
+    out = output!(processor, delete_field(ITALY, FIELD_100_INT));
+    exp = vec![delete_exp(ITALY, FIELD_1_INT)];
+    assert_eq!(out, exp);
+}
@@ -0,0 +1,94 @@
+use crate::aggregation::tests::aggregation_tests_utils::{
+    delete_field, init_input_schema, init_processor, insert_exp, insert_field, update_exp,
+    FIELD_100_INT, FIELD_200_INT, FIELD_300_INT, FIELD_NULL, ITALY,
+};
+use crate::output;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::Field;
+use dozer_types::types::FieldType::Int;
+use std::collections::HashMap;
+
+#[test]
+fn test_variance_aggregation() {
+    let schema = init_input_schema(Int, "VARIANCE");
+    let mut processor = init_processor(
+        "SELECT Country, VARIANCE(Salary) \
+        FROM Users \
+        GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    let variance_5000 = Field::Float(OrderedFloat(5000.0));
+    let variance_10000 = Field::Float(OrderedFloat(10000.0));
+
+    // Sample variance of a single value is NULL
+    let mut out = output!(processor, insert_field(ITALY, FIELD_100_INT));
+    let mut exp = vec![insert_exp(ITALY, FIELD_NULL)];
+    assert_eq!(out, exp);
+
+    // Italy: 100, 200
+    out = output!(processor, insert_field(ITALY, FIELD_200_INT));
+    exp = vec![update_exp(ITALY, ITALY, FIELD_NULL, &variance_5000)];
+    assert_eq!(out, exp);
+
+    // Italy: 100, 200, 300
+    out = output!(processor, insert_field(ITALY, FIELD_300_INT));
+    exp = vec![update_exp(ITALY, ITALY, &variance_5000, &variance_10000)];
+    assert_eq!(out, exp);
+
+    // Italy: 200, 300
+    out = output!(processor, delete_field(ITALY, FIELD_100_INT));
+    exp = vec![update_exp(ITALY, ITALY, &variance_10000, &variance_5000)];
+    assert_eq!(out, exp);
+
+    // Italy: 300
+    out = output!(processor, delete_field(ITALY, FIELD_200_INT));
+    exp = vec![update_exp(ITALY, ITALY, &variance_5000, FIELD_NULL)];
+    assert_eq!(out, exp);
+}
+
+#[test]
+fn test_stddev_aggregation() {
+    let schema = init_input_schema(Int, "STDDEV");
+    let mut processor = init_processor(
+        "SELECT Country, STDDEV(Salary) \
+        FROM Users \
+        GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    let stddev_100 = Field::Float(OrderedFloat(100.0));
+
+    // Italy: 100
+    let mut out = output!(processor, insert_field(ITALY, FIELD_100_INT));
+    let mut exp = vec![insert_exp(ITALY, FIELD_NULL)];
+    assert_eq!(out, exp);
+
+    // Italy: 100, NULL
+    out = output!(processor, insert_field(ITALY, FIELD_NULL));
+    exp = vec![update_exp(ITALY, ITALY, FIELD_NULL, FIELD_NULL)];
+    assert_eq!(out, exp);
+
+    // Italy: 100, NULL, 300
+    out = output!(processor, insert_field(ITALY, FIELD_300_INT));
+    exp = vec![update_exp(
+        ITALY,
+        ITALY,
+        FIELD_NULL,
+        &Field::Float(OrderedFloat(20000.0_f64.sqrt())),
+    )];
+    assert_eq!(out, exp);
+
+    // Italy: 100, NULL, 300, 200
+    out = output!(processor, insert_field(ITALY, FIELD_200_INT));
+    exp = vec![update_exp(
+        ITALY,
+        ITALY,
+        &Field::Float(OrderedFloat(20000.0_f64.sqrt())),
+        &stddev_100,
+    )];
+    assert_eq!(out, exp);
+}
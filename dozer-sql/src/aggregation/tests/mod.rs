@@ -1,6 +1,12 @@
 #[cfg(test)]
+mod aggregation_approx_tests;
+#[cfg(test)]
 mod aggregation_avg_tests;
 #[cfg(test)]
+mod aggregation_collection_tests;
+#[cfg(test)]
+mod aggregation_count_distinct_tests;
+#[cfg(test)]
 mod aggregation_count_tests;
 #[cfg(test)]
 mod aggregation_having_tests;
@@ -20,6 +26,8 @@ mod aggregation_sum_tests;
 mod aggregation_test_planner;
 #[cfg(test)]
 mod aggregation_tests_utils;
+#[cfg(test)]
+mod aggregation_variance_tests;
 
 #[cfg(test)]
 mod aggregation_max_append_only_tests;
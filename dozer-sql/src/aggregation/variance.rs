@@ -0,0 +1,91 @@
+use crate::aggregation::aggregator::Aggregator;
+use crate::calculate_err;
+use crate::errors::PipelineError;
+use dozer_sql_expression::aggregate::AggregateFunctionType::Variance;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldType};
+
+/// Sample variance of the non-null values of the group.
+///
+/// Keeps the count, mean and sum of squared deviations of the values (Welford's algorithm),
+/// which are updated in constant time when a value is added or retracted.
+#[derive(Debug, bincode::Encode, bincode::Decode)]
+pub struct VarianceAggregator {
+    count: u64,
+    mean: f64,
+    squares: f64,
+    return_type: Option<FieldType>,
+}
+
+impl VarianceAggregator {
+    pub fn new() -> Self {
+        Self {
+            count: 0,
+            mean: 0.0,
+            squares: 0.0,
+            return_type: None,
+        }
+    }
+
+    pub(crate) fn update_state(&mut self, field: &Field, incr: bool) -> Result<(), PipelineError> {
+        if field == &Field::Null {
+            return Ok(());
+        }
+        let value = calculate_err!(field.to_float(), Variance);
+        if incr {
+            self.count += 1;
+            let delta = value - self.mean;
+            self.mean += delta / self.count as f64;
+            self.squares += delta * (value - self.mean);
+        } else if self.count <= 1 {
+            // Start over from the exact empty state
+            self.count = 0;
+            self.mean = 0.0;
+            self.squares = 0.0;
+        } else {
+            self.count -= 1;
+            let delta = value - self.mean;
+            self.mean -= delta / self.count as f64;
+            // Rounding errors must not make the sum of squares negative
+            self.squares = (self.squares - delta * (value - self.mean)).max(0.0);
+        }
+        Ok(())
+    }
+
+    /// Returns `None` when the group has fewer than two values.
+    pub(crate) fn get_variance(&self) -> Option<f64> {
+        if self.count < 2 {
+            return None;
+        }
+        Some(self.squares / (self.count - 1) as f64)
+    }
+}
+
+impl Aggregator for VarianceAggregator {
+    fn init(&mut self, return_type: FieldType) {
+        self.return_type = Some(return_type);
+    }
+
+    fn update(&mut self, old: &[Field], new: &[Field]) -> Result<Field, PipelineError> {
+        self.delete(old)?;
+        self.insert(new)
+    }
+
+    fn delete(&mut self, old: &[Field]) -> Result<Field, PipelineError> {
+        for field in old {
+            self.update_state(field, false)?;
+        }
+        Ok(self
+            .get_variance()
+            .map_or(Field::Null, |variance| Field::Float(OrderedFloat(variance))))
+    }
+
+    fn insert(&mut self, new: &[Field]) -> Result<Field, PipelineError> {
+        for field in new {
+            self.update_state(field, true)?;
+        }
+        Ok(self
+            .get_variance()
+            .map_or(Field::Null, |variance| Field::Float(OrderedFloat(variance))))
+    }
+}
@@ -8,24 +8,34 @@ use dozer_core::node::PortHandle;
 use dozer_core::DEFAULT_PORT_HANDLE;
 use dozer_sql_expression::builder::{ExpressionBuilder, NameOrAlias};
 use dozer_sql_expression::sqlparser::ast::{
-    Join, SetOperator, SetQuantifier, TableFactor, TableWithJoins,
+    BinaryOperator, Expr as SqlExpr, FunctionArg, FunctionArgExpr, Ident, Join, ObjectName,
+    OrderByExpr, SelectItem, SetOperator, SetQuantifier, TableFactor, TableWithJoins, Value,
+    WindowSpec, WindowType,
 };
 use dozer_types::models::udf_config::UdfConfig;
 
 use dozer_sql_expression::sqlparser::{
     ast::{Query, Select, SetExpr, Statement},
     dialect::DozerDialect,
-    parser::Parser,
 };
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::sync::Arc;
 use tokio::runtime::Runtime;
 
-use super::errors::UnsupportedSqlError;
-use super::pipeline_builder::from_builder::insert_from_to_pipeline;
+use super::errors::{TopNError, UnsupportedSqlError};
+use super::pipeline_builder::from_builder::{insert_from_to_pipeline, string_from_sql_object_name};
+use super::pipeline_builder::subquery_builder::insert_subqueries_to_pipeline;
 
+use super::product::join::temporal;
 use super::product::set::set_factory::SetProcessorFactory;
+use super::recursive_cte::{
+    factory::RecursionGuardProcessorFactory, processor::DEPTH_EXCEEDED_PORT,
+};
+use super::top_n::factory::TopNProcessorFactory;
+
+/// Upper bound of the `max_recursive_cte_depth` flag.
+pub const MAX_RECURSIVE_CTE_DEPTH: u32 = 64;
 
 #[derive(Debug, Clone)]
 pub struct OutputNodeInfo {
@@ -102,7 +112,7 @@ pub fn statement_to_pipeline(
     let dialect = DozerDialect {};
     let mut ctx = QueryContext::new(udfs, runtime);
     let is_top_select = true;
-    let ast = Parser::parse_sql(&dialect, sql)
+    let ast = temporal::parse_sql(&dialect, sql)
         .map_err(|err| PipelineError::InternalError(Box::new(err)))?;
     let query_name = NameOrAlias(format!("query_{}", ctx.get_next_processor_id()), None);
 
@@ -134,7 +144,7 @@ pub fn statement_to_pipeline(
     Ok(ctx)
 }
 
-fn query_to_pipeline(
+pub(crate) fn query_to_pipeline(
     table_info: &TableInfo,
     query: &Query,
     pipeline: &mut AppPipeline,
@@ -144,26 +154,20 @@ fn query_to_pipeline(
     is_top_select: bool,
 ) -> Result<(), PipelineError> {
     // return error if there is unsupported syntax
-    if !query.order_by.is_empty() {
+    if query.order_by.is_empty() && (query.limit.is_some() || query.offset.is_some()) {
         return Err(PipelineError::UnsupportedSqlError(
-            UnsupportedSqlError::OrderByError,
+            UnsupportedSqlError::LimitOffsetError,
         ));
     }
 
-    if query.limit.is_some() || query.offset.is_some() {
+    if !query.order_by.is_empty() && query.limit.is_none() {
         return Err(PipelineError::UnsupportedSqlError(
-            UnsupportedSqlError::LimitOffsetError,
+            UnsupportedSqlError::OrderByError,
         ));
     }
 
     // Attach the first pipeline if there is with clause
     if let Some(with) = &query.with {
-        if with.recursive {
-            return Err(PipelineError::UnsupportedSqlError(
-                UnsupportedSqlError::Recursive,
-            ));
-        }
-
         for table in &with.cte_tables {
             if table.from.is_some() {
                 return Err(PipelineError::UnsupportedSqlError(
@@ -179,23 +183,79 @@ fn query_to_pipeline(
                     "WITH query name {table_name:?} specified more than once"
                 )));
             }
-            query_to_pipeline(
-                &TableInfo {
-                    name: NameOrAlias(table_name.clone(), Some(table_name)),
-                    is_derived: true,
-                    override_name: None,
-                },
-                &table.query,
-                pipeline,
-                query_ctx,
-                true,
-                pipeline_idx,
-                false, //Inside a with clause, so not top select
-            )?;
+            let cte_table_info = TableInfo {
+                name: NameOrAlias(table_name.clone(), Some(table_name)),
+                is_derived: true,
+                override_name: None,
+            };
+            if with.recursive {
+                recursive_cte_to_pipeline(
+                    &cte_table_info,
+                    &table.query,
+                    pipeline,
+                    query_ctx,
+                    pipeline_idx,
+                )?;
+            } else {
+                query_to_pipeline(
+                    &cte_table_info,
+                    &table.query,
+                    pipeline,
+                    query_ctx,
+                    true,
+                    pipeline_idx,
+                    false, //Inside a with clause, so not top select
+                )?;
+            }
         }
     };
 
-    match *query.body.clone() {
+    let mut body = *query.body.clone();
+    if let Some(row_number) = take_row_number_top_n(&mut body, query_ctx)? {
+        row_number_top_n_to_pipeline(row_number, pipeline, query_ctx, pipeline_idx)?;
+    }
+
+    set_expr_to_pipeline(
+        table_info,
+        body,
+        pipeline,
+        query_ctx,
+        stateful,
+        pipeline_idx,
+        is_top_select,
+    )?;
+
+    if let Some(limit) = &query.limit {
+        let limit = parse_top_n_count(limit).ok_or(TopNError::InvalidLimit(limit.to_string()))?;
+        let offset = match &query.offset {
+            Some(offset) => parse_top_n_count(&offset.value)
+                .ok_or(TopNError::InvalidOffset(offset.value.to_string()))?,
+            None => 0,
+        };
+        top_n_to_pipeline(
+            table_info,
+            vec![],
+            query.order_by.clone(),
+            offset,
+            limit,
+            pipeline,
+            query_ctx,
+            pipeline_idx,
+        )?;
+    }
+    Ok(())
+}
+
+fn set_expr_to_pipeline(
+    table_info: &TableInfo,
+    set_expr: SetExpr,
+    pipeline: &mut AppPipeline,
+    query_ctx: &mut QueryContext,
+    stateful: bool,
+    pipeline_idx: usize,
+    is_top_select: bool,
+) -> Result<(), PipelineError> {
+    match set_expr {
         SetExpr::Select(select) => {
             select_to_pipeline(
                 table_info,
@@ -229,22 +289,20 @@ fn query_to_pipeline(
             set_quantifier,
             left,
             right,
-        } => match op {
-            SetOperator::Union => {
-                set_to_pipeline(
-                    table_info,
-                    left,
-                    right,
-                    set_quantifier,
-                    pipeline,
-                    query_ctx,
-                    stateful,
-                    pipeline_idx,
-                    is_top_select,
-                )?;
-            }
-            _ => return Err(PipelineError::InvalidOperator(op.to_string())),
-        },
+        } => {
+            set_to_pipeline(
+                table_info,
+                left,
+                right,
+                op,
+                set_quantifier,
+                pipeline,
+                query_ctx,
+                stateful,
+                pipeline_idx,
+                is_top_select,
+            )?;
+        }
         _ => {
             return Err(PipelineError::UnsupportedSqlError(
                 UnsupportedSqlError::GenericError("Unsupported query body structure".to_string()),
@@ -254,6 +312,442 @@ fn query_to_pipeline(
     Ok(())
 }
 
+/// Adds a `WITH RECURSIVE` query of the form `<anchor> UNION [ALL] <recursive term>` to the pipeline.
+///
+/// The pipeline must stay acyclic, so the recursive term is expanded `max_recursive_cte_depth`
+/// times, every copy reading from the output of the previous one, and all of them are unioned
+/// with the anchor. Results are maintained incrementally for hierarchies up to that depth.
+/// One more copy feeds a [`RecursionGuardProcessorFactory`], which fails the pipeline as soon as
+/// a deeper hierarchy would be truncated.
+///
+/// Every copy adds the processors of the recursive term to the pipeline, so depths above
+/// [`MAX_RECURSIVE_CTE_DEPTH`] are rejected.
+fn recursive_cte_to_pipeline(
+    table_info: &TableInfo,
+    query: &Query,
+    pipeline: &mut AppPipeline,
+    query_ctx: &mut QueryContext,
+    pipeline_idx: usize,
+) -> Result<(), PipelineError> {
+    let table_name = table_info.name.0.clone();
+    let (anchor, recursive_term, set_quantifier) = match query.body.as_ref() {
+        SetExpr::SetOperation {
+            op: SetOperator::Union,
+            set_quantifier,
+            left,
+            right,
+        } if set_expr_references_table(right, &table_name) => (left, right, *set_quantifier),
+        body => {
+            // Queries that don't reference themselves are allowed in `WITH RECURSIVE`
+            if set_expr_references_table(body, &table_name) {
+                return Err(PipelineError::UnsupportedSqlError(
+                    UnsupportedSqlError::Recursive,
+                ));
+            }
+            return query_to_pipeline(
+                table_info,
+                query,
+                pipeline,
+                query_ctx,
+                true,
+                pipeline_idx,
+                false,
+            );
+        }
+    };
+    if set_expr_references_table(anchor, &table_name)
+        || query.with.is_some()
+        || !query.order_by.is_empty()
+        || query.limit.is_some()
+        || query.offset.is_some()
+    {
+        return Err(PipelineError::UnsupportedSqlError(
+            UnsupportedSqlError::Recursive,
+        ));
+    }
+
+    let max_depth = pipeline.flags().max_recursive_cte_depth;
+    if max_depth > MAX_RECURSIVE_CTE_DEPTH {
+        return Err(PipelineError::RecursionDepthTooLarge {
+            max_depth,
+            limit: MAX_RECURSIVE_CTE_DEPTH,
+        });
+    }
+    let mut level_output =
+        recursive_level_to_pipeline(&table_name, 0, anchor, pipeline, query_ctx, pipeline_idx)?;
+    let mut union_output = level_output.clone();
+    for depth in 1..=max_depth {
+        // The recursive term reads the rows produced by the previous level
+        query_ctx
+            .pipeline_map
+            .insert((pipeline_idx, table_name.clone()), level_output);
+        level_output = recursive_level_to_pipeline(
+            &table_name,
+            depth,
+            recursive_term,
+            pipeline,
+            query_ctx,
+            pipeline_idx,
+        )?;
+
+        let gen_set_name = format!("set_{}", query_ctx.get_next_processor_id());
+        add_set_processor_to_pipeline(
+            &gen_set_name,
+            SetOperator::Union,
+            set_quantifier,
+            &union_output,
+            &level_output,
+            pipeline,
+        );
+        union_output = OutputNodeInfo {
+            node: gen_set_name,
+            port: DEFAULT_PORT_HANDLE,
+            is_derived: true,
+        };
+    }
+
+    query_ctx
+        .pipeline_map
+        .insert((pipeline_idx, table_name.clone()), level_output);
+    let exceeded_output = recursive_level_to_pipeline(
+        &table_name,
+        max_depth + 1,
+        recursive_term,
+        pipeline,
+        query_ctx,
+        pipeline_idx,
+    )?;
+    let gen_guard_name = format!("recursion_guard--{}", query_ctx.get_next_processor_id());
+    pipeline.add_processor(
+        Box::new(RecursionGuardProcessorFactory::new(
+            gen_guard_name.clone(),
+            table_name.clone(),
+            max_depth,
+        )),
+        &gen_guard_name,
+        vec![],
+    );
+    pipeline.connect_nodes(
+        &union_output.node,
+        union_output.port,
+        &gen_guard_name,
+        DEFAULT_PORT_HANDLE,
+    );
+    pipeline.connect_nodes(
+        &exceeded_output.node,
+        exceeded_output.port,
+        &gen_guard_name,
+        DEPTH_EXCEEDED_PORT,
+    );
+    union_output = OutputNodeInfo {
+        node: gen_guard_name,
+        port: DEFAULT_PORT_HANDLE,
+        is_derived: true,
+    };
+
+    query_ctx.pipeline_map.insert(
+        (pipeline_idx, table_name),
+        OutputNodeInfo {
+            is_derived: table_info.is_derived,
+            ..union_output
+        },
+    );
+    Ok(())
+}
+
+fn recursive_level_to_pipeline(
+    table_name: &str,
+    depth: u32,
+    set_expr: &SetExpr,
+    pipeline: &mut AppPipeline,
+    query_ctx: &mut QueryContext,
+    pipeline_idx: usize,
+) -> Result<OutputNodeInfo, PipelineError> {
+    let level_name = format!("{table_name}_level_{depth}");
+    set_expr_to_pipeline(
+        &TableInfo {
+            name: NameOrAlias(level_name.clone(), None),
+            is_derived: true,
+            override_name: None,
+        },
+        set_expr.clone(),
+        pipeline,
+        query_ctx,
+        true,
+        pipeline_idx,
+        false,
+    )?;
+    query_ctx
+        .pipeline_map
+        .get(&(pipeline_idx, level_name))
+        .cloned()
+        .ok_or_else(|| InvalidQuery(format!("Invalid recursive query {table_name:?}")))
+}
+
+/// Returns true if `table_name` is read anywhere in the FROM clauses of `set_expr`.
+fn set_expr_references_table(set_expr: &SetExpr, table_name: &str) -> bool {
+    match set_expr {
+        SetExpr::Select(select) => select.from.iter().any(|from| {
+            table_factor_references_table(&from.relation, table_name)
+                || from
+                    .joins
+                    .iter()
+                    .any(|join| table_factor_references_table(&join.relation, table_name))
+        }),
+        SetExpr::Query(query) => set_expr_references_table(&query.body, table_name),
+        SetExpr::SetOperation { left, right, .. } => {
+            set_expr_references_table(left, table_name)
+                || set_expr_references_table(right, table_name)
+        }
+        _ => false,
+    }
+}
+
+fn table_factor_references_table(relation: &TableFactor, table_name: &str) -> bool {
+    match relation {
+        TableFactor::Table { name, args, .. } => {
+            string_from_sql_object_name(name) == table_name
+                || args.iter().flatten().any(|arg| match arg {
+                    FunctionArg::Unnamed(FunctionArgExpr::Expr(SqlExpr::Identifier(ident))) => {
+                        ExpressionBuilder::normalize_ident(ident) == table_name
+                    }
+                    _ => false,
+                })
+        }
+        TableFactor::Derived { subquery, .. } => {
+            set_expr_references_table(&subquery.body, table_name)
+        }
+        _ => false,
+    }
+}
+
+/// A subquery whose rows are filtered by their row number in every partition, like
+/// `SELECT region, player, score FROM (SELECT region, player, score, ROW_NUMBER() OVER (PARTITION BY region ORDER BY score DESC) AS rn FROM scores) WHERE rn <= 3`.
+struct RowNumberTopN {
+    /// The subquery, without its `ROW_NUMBER()` column
+    subquery: Query,
+    /// Name the subquery output is registered with
+    name: String,
+    partition_by: Vec<SqlExpr>,
+    order_by: Vec<OrderByExpr>,
+    limit: usize,
+}
+
+/// Takes the `ROW_NUMBER()` subquery out of a `SELECT ... FROM (<subquery>) WHERE <row number> <= <count>`
+/// query, which then reads from the top-N processor of the subquery under the returned name.
+///
+/// The row number is only allowed in the filter, because the top-N processor doesn't output it.
+fn take_row_number_top_n(
+    body: &mut SetExpr,
+    query_ctx: &mut QueryContext,
+) -> Result<Option<RowNumberTopN>, PipelineError> {
+    let SetExpr::Select(select) = body else {
+        return Ok(None);
+    };
+    let [TableWithJoins {
+        relation: TableFactor::Derived {
+            subquery, alias, ..
+        },
+        joins,
+    }] = select.from.as_slice()
+    else {
+        return Ok(None);
+    };
+    let SetExpr::Select(inner) = subquery.body.as_ref() else {
+        return Ok(None);
+    };
+    let Some((position, row_number, function)) =
+        inner
+            .projection
+            .iter()
+            .enumerate()
+            .find_map(|(position, item)| match item {
+                SelectItem::ExprWithAlias {
+                    expr: SqlExpr::Function(function),
+                    alias,
+                } if string_from_sql_object_name(&function.name) == "row_number" => Some((
+                    position,
+                    ExpressionBuilder::normalize_ident(alias),
+                    function,
+                )),
+                _ => None,
+            })
+    else {
+        return Ok(None);
+    };
+
+    let Some(WindowType::WindowSpec(WindowSpec {
+        partition_by,
+        order_by,
+        window_frame: None,
+    })) = &function.over
+    else {
+        return Err(TopNError::RowNumberWindow(function.to_string()).into());
+    };
+    if !function.args.is_empty() || order_by.is_empty() {
+        return Err(TopNError::RowNumberWindow(function.to_string()).into());
+    }
+
+    let is_row_number = |expr: &SqlExpr| match expr {
+        SqlExpr::Identifier(ident) => ExpressionBuilder::normalize_ident(ident) == row_number,
+        SqlExpr::CompoundIdentifier(idents) => idents
+            .last()
+            .is_some_and(|ident| ExpressionBuilder::normalize_ident(ident) == row_number),
+        _ => false,
+    };
+    let limit = match &select.selection {
+        Some(SqlExpr::BinaryOp { left, op, right }) => match (left.as_ref(), op, right.as_ref()) {
+            (column, BinaryOperator::LtEq, count) | (count, BinaryOperator::GtEq, column)
+                if is_row_number(column) =>
+            {
+                parse_top_n_count(count)
+            }
+            (column, BinaryOperator::Lt, count) | (count, BinaryOperator::Gt, column)
+                if is_row_number(column) =>
+            {
+                parse_top_n_count(count).map(|count| count.saturating_sub(1))
+            }
+            (column, BinaryOperator::Eq, count) | (count, BinaryOperator::Eq, column)
+                if is_row_number(column) && parse_top_n_count(count) == Some(1) =>
+            {
+                Some(1)
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+    .ok_or_else(|| TopNError::RowNumberFilter(row_number.clone()))?;
+
+    if select.projection.iter().any(|item| {
+        matches!(
+            item,
+            SelectItem::Wildcard(_) | SelectItem::QualifiedWildcard(..)
+        )
+    }) {
+        return Err(TopNError::RowNumberWildcard.into());
+    }
+    if !joins.is_empty() {
+        return Err(PipelineError::UnsupportedSqlError(
+            UnsupportedSqlError::GenericError(
+                "A ROW_NUMBER() subquery can't be joined".to_string(),
+            ),
+        ));
+    }
+
+    let mut subquery = subquery.as_ref().clone();
+    let SetExpr::Select(inner) = subquery.body.as_mut() else {
+        unreachable!("subquery body must be a SELECT");
+    };
+    inner.projection.remove(position);
+    let (partition_by, order_by, alias) = (partition_by.clone(), order_by.clone(), alias.clone());
+
+    let name = format!("derived_{}", query_ctx.get_next_processor_id());
+    select.from[0].relation = TableFactor::Table {
+        name: ObjectName(vec![Ident::new(&name)]),
+        alias,
+        args: None,
+        with_hints: vec![],
+    };
+    select.selection = None;
+
+    Ok(Some(RowNumberTopN {
+        subquery,
+        name,
+        partition_by,
+        order_by,
+        limit,
+    }))
+}
+
+fn row_number_top_n_to_pipeline(
+    row_number: RowNumberTopN,
+    pipeline: &mut AppPipeline,
+    query_ctx: &mut QueryContext,
+    pipeline_idx: usize,
+) -> Result<(), PipelineError> {
+    let table_info = TableInfo {
+        name: NameOrAlias(row_number.name.clone(), Some(row_number.name)),
+        is_derived: true,
+        override_name: None,
+    };
+    query_to_pipeline(
+        &table_info,
+        &row_number.subquery,
+        pipeline,
+        query_ctx,
+        false,
+        pipeline_idx,
+        false, //Inside a FROM clause, so not top select
+    )?;
+    top_n_to_pipeline(
+        &table_info,
+        row_number.partition_by,
+        row_number.order_by,
+        0,
+        row_number.limit,
+        pipeline,
+        query_ctx,
+        pipeline_idx,
+    )
+}
+
+fn top_n_to_pipeline(
+    table_info: &TableInfo,
+    partition_by: Vec<SqlExpr>,
+    order_by: Vec<OrderByExpr>,
+    offset: usize,
+    limit: usize,
+    pipeline: &mut AppPipeline,
+    query_ctx: &mut QueryContext,
+    pipeline_idx: usize,
+) -> Result<(), PipelineError> {
+    let key = (pipeline_idx, table_info.name.0.to_string());
+    let input = query_ctx
+        .pipeline_map
+        .get(&key)
+        .cloned()
+        .ok_or_else(|| InvalidQuery("ORDER BY ... LIMIT must follow a SELECT".to_string()))?;
+
+    let gen_top_n_name = format!("top_n--{}", query_ctx.get_next_processor_id());
+    let top_n = TopNProcessorFactory::new(
+        gen_top_n_name.clone(),
+        partition_by,
+        order_by,
+        offset,
+        limit,
+        query_ctx.udfs.clone(),
+        query_ctx.runtime.clone(),
+    );
+    pipeline.add_processor(Box::new(top_n), &gen_top_n_name, vec![]);
+    pipeline.connect_nodes(
+        &input.node,
+        input.port,
+        &gen_top_n_name,
+        DEFAULT_PORT_HANDLE,
+    );
+
+    // Everything that was reading the query output now reads the top N rows
+    for output in query_ctx
+        .pipeline_map
+        .values_mut()
+        .chain(query_ctx.output_tables_map.values_mut())
+    {
+        if output.node == input.node && output.port == input.port {
+            output.node = gen_top_n_name.clone();
+            output.port = DEFAULT_PORT_HANDLE;
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_top_n_count(expr: &SqlExpr) -> Option<usize> {
+    match expr {
+        SqlExpr::Value(Value::Number(n, _)) => n.parse().ok(),
+        _ => None,
+    }
+}
+
 fn select_to_pipeline(
     table_info: &TableInfo,
     select: Select,
@@ -288,7 +782,17 @@ fn select_to_pipeline(
     let gen_agg_name = format!("agg--{}", query_ctx.get_next_processor_id());
 
     let gen_selection_name = format!("select--{}", query_ctx.get_next_processor_id());
-    let (gen_product_name, product_output_port) = output_node;
+    let ((gen_product_name, product_output_port), selection) = match select.selection.clone() {
+        Some(selection) => insert_subqueries_to_pipeline(
+            selection,
+            &select.from[0],
+            output_node,
+            pipeline,
+            pipeline_idx,
+            query_ctx,
+        )?,
+        None => (output_node, None),
+    };
 
     for (source_name, processor_name, processor_port) in input_nodes.iter() {
         if let Some(table_info) = query_ctx
@@ -323,7 +827,7 @@ fn select_to_pipeline(
     pipeline.add_processor(Box::new(aggregation), &gen_agg_name, vec![]);
 
     // Where clause
-    if let Some(selection) = select.selection {
+    if let Some(selection) = selection {
         let selection = SelectionProcessorFactory::new(
             gen_selection_name.to_owned(),
             selection,
@@ -397,6 +901,7 @@ fn set_to_pipeline(
     table_info: &TableInfo,
     left_select: Box<SetExpr>,
     right_select: Box<SetExpr>,
+    set_operator: SetOperator,
     set_quantifier: SetQuantifier,
     pipeline: &mut AppPipeline,
     query_ctx: &mut QueryContext,
@@ -404,6 +909,15 @@ fn set_to_pipeline(
     pipeline_idx: usize,
     is_top_select: bool,
 ) -> Result<String, PipelineError> {
+    if !matches!(
+        set_quantifier,
+        SetQuantifier::All | SetQuantifier::Distinct | SetQuantifier::None
+    ) {
+        return Err(PipelineError::InvalidOperator(format!(
+            "{set_operator} {set_quantifier}"
+        )));
+    }
+
     let gen_left_set_name = format!("set_left_{}", query_ctx.get_next_processor_id());
     let left_table_info = TableInfo {
         name: NameOrAlias(gen_left_set_name.clone(), None),
@@ -428,7 +942,7 @@ fn set_to_pipeline(
             is_top_select,
         )?,
         SetExpr::SetOperation {
-            op: _,
+            op,
             set_quantifier,
             left,
             right,
@@ -436,6 +950,7 @@ fn set_to_pipeline(
             &left_table_info,
             left,
             right,
+            op,
             set_quantifier,
             pipeline,
             query_ctx,
@@ -444,9 +959,9 @@ fn set_to_pipeline(
             is_top_select,
         )?,
         _ => {
-            return Err(PipelineError::InvalidQuery(
-                "Invalid UNION left Query".to_string(),
-            ))
+            return Err(PipelineError::InvalidQuery(format!(
+                "Invalid {set_operator} left Query"
+            )))
         }
     };
 
@@ -461,7 +976,7 @@ fn set_to_pipeline(
             is_top_select,
         )?,
         SetExpr::SetOperation {
-            op: _,
+            op,
             set_quantifier,
             left,
             right,
@@ -469,6 +984,7 @@ fn set_to_pipeline(
             &right_table_info,
             left,
             right,
+            op,
             set_quantifier,
             pipeline,
             query_ctx,
@@ -477,9 +993,9 @@ fn set_to_pipeline(
             is_top_select,
         )?,
         _ => {
-            return Err(PipelineError::InvalidQuery(
-                "Invalid UNION right Query".to_string(),
-            ))
+            return Err(PipelineError::InvalidQuery(format!(
+                "Invalid {set_operator} right Query"
+            )))
         }
     };
 
@@ -491,9 +1007,9 @@ fn set_to_pipeline(
     {
         Some(pipeline) => pipeline,
         None => {
-            return Err(PipelineError::InvalidQuery(
-                "Invalid UNION left Query".to_string(),
-            ))
+            return Err(PipelineError::InvalidQuery(format!(
+                "Invalid {set_operator} left Query"
+            )))
         }
     };
 
@@ -503,9 +1019,9 @@ fn set_to_pipeline(
     {
         Some(pipeline) => pipeline,
         None => {
-            return Err(PipelineError::InvalidQuery(
-                "Invalid UNION Right Query".to_string(),
-            ))
+            return Err(PipelineError::InvalidQuery(format!(
+                "Invalid {set_operator} right Query"
+            )))
         }
     };
 
@@ -513,30 +1029,13 @@ fn set_to_pipeline(
         gen_set_name = table_info.override_name.to_owned().unwrap();
     }
 
-    let set_proc_fac = SetProcessorFactory::new(
-        gen_set_name.clone(),
-        set_quantifier,
-        pipeline
-            .flags()
-            .enable_probabilistic_optimizations
-            .in_sets
-            .unwrap_or(false),
-    );
-
-    pipeline.add_processor(Box::new(set_proc_fac), &gen_set_name, vec![]);
-
-    pipeline.connect_nodes(
-        &left_pipeline_output_node.node,
-        left_pipeline_output_node.port,
-        &gen_set_name,
-        0 as PortHandle,
-    );
-
-    pipeline.connect_nodes(
-        &right_pipeline_output_node.node,
-        right_pipeline_output_node.port,
+    add_set_processor_to_pipeline(
         &gen_set_name,
-        1 as PortHandle,
+        set_operator,
+        set_quantifier,
+        left_pipeline_output_node,
+        right_pipeline_output_node,
+        pipeline,
     );
 
     for (_, table_name) in query_ctx.pipeline_map.keys() {
@@ -555,6 +1054,42 @@ fn set_to_pipeline(
     Ok(gen_set_name)
 }
 
+fn add_set_processor_to_pipeline(
+    gen_set_name: &str,
+    set_operator: SetOperator,
+    set_quantifier: SetQuantifier,
+    left_output_node: &OutputNodeInfo,
+    right_output_node: &OutputNodeInfo,
+    pipeline: &mut AppPipeline,
+) {
+    let set_proc_fac = SetProcessorFactory::new(
+        gen_set_name.to_string(),
+        set_operator,
+        set_quantifier,
+        pipeline
+            .flags()
+            .enable_probabilistic_optimizations
+            .in_sets
+            .unwrap_or(false),
+    );
+
+    pipeline.add_processor(Box::new(set_proc_fac), gen_set_name, vec![]);
+
+    pipeline.connect_nodes(
+        &left_output_node.node,
+        left_output_node.port,
+        gen_set_name,
+        0 as PortHandle,
+    );
+
+    pipeline.connect_nodes(
+        &right_output_node.node,
+        right_output_node.port,
+        gen_set_name,
+        1 as PortHandle,
+    );
+}
+
 /// Returns a vector of input port handles and relative table name
 ///
 /// # Errors
@@ -675,9 +1210,12 @@ pub fn get_from_source(
 
 #[cfg(test)]
 mod tests {
-    use super::statement_to_pipeline;
-    use crate::{errors::PipelineError, tests::utils::create_test_runtime};
-    use dozer_core::app::AppPipeline;
+    use super::{statement_to_pipeline, MAX_RECURSIVE_CTE_DEPTH};
+    use crate::{
+        errors::{PipelineError, TopNError, UnsupportedSqlError},
+        tests::utils::create_test_runtime,
+    };
+    use dozer_core::app::{AppPipeline, PipelineFlags};
     #[test]
     #[should_panic]
     fn disallow_zero_outgoing_ndes() {
@@ -792,6 +1330,258 @@ mod tests {
         assert_eq!(output_keys, expected_keys);
     }
 
+    #[test]
+    fn test_order_by_limit() {
+        let sql = r#"SELECT name, SUM(score) AS total INTO leaderboard FROM scores GROUP BY name ORDER BY total DESC LIMIT 10 OFFSET 5"#;
+        let runtime = create_test_runtime();
+        let context = statement_to_pipeline(
+            sql,
+            &mut AppPipeline::new_with_default_flags(),
+            None,
+            vec![],
+            runtime,
+        )
+        .unwrap();
+        assert!(context.output_tables_map["leaderboard"]
+            .node
+            .starts_with("top_n--"));
+    }
+
+    #[test]
+    fn test_top_n_per_partition() {
+        let build = |sql: &str| {
+            statement_to_pipeline(
+                sql,
+                &mut AppPipeline::new_with_default_flags(),
+                None,
+                vec![],
+                create_test_runtime(),
+            )
+        };
+
+        let context = build(
+            r#"SELECT region, player, score INTO leaderboard
+                FROM (SELECT region, player, score, ROW_NUMBER() OVER (PARTITION BY region ORDER BY score DESC) AS rn FROM scores) ranked
+                WHERE rn <= 3"#,
+        )
+        .unwrap();
+        // The outer query reads the top 3 rows of the subquery
+        assert!(context
+            .pipeline_map
+            .values()
+            .any(|output| output.node.starts_with("top_n--")));
+        assert!(!context.output_tables_map["leaderboard"]
+            .node
+            .starts_with("top_n--"));
+
+        // The row number must be filtered
+        assert!(matches!(
+            build("SELECT player INTO t FROM (SELECT player, ROW_NUMBER() OVER (ORDER BY score) AS rn FROM scores) WHERE score > 3"),
+            Err(PipelineError::TopNError(TopNError::RowNumberFilter(_)))
+        ));
+        // The row number can't be selected
+        assert!(matches!(
+            build("SELECT * INTO t FROM (SELECT player, ROW_NUMBER() OVER (ORDER BY score) AS rn FROM scores) WHERE rn <= 3"),
+            Err(PipelineError::TopNError(TopNError::RowNumberWildcard))
+        ));
+        // The ranking must be ordered
+        assert!(matches!(
+            build("SELECT player INTO t FROM (SELECT player, ROW_NUMBER() OVER (PARTITION BY region) AS rn FROM scores) WHERE rn <= 3"),
+            Err(PipelineError::TopNError(TopNError::RowNumberWindow(_)))
+        ));
+    }
+
+    #[test]
+    fn test_order_by_without_limit() {
+        for (sql, expected_order_by_error) in [
+            ("SELECT a INTO c FROM b ORDER BY a", true),
+            ("SELECT a INTO c FROM b LIMIT 10", false),
+        ] {
+            let runtime = create_test_runtime();
+            let result = statement_to_pipeline(
+                sql,
+                &mut AppPipeline::new_with_default_flags(),
+                None,
+                vec![],
+                runtime,
+            );
+            if expected_order_by_error {
+                assert!(matches!(
+                    result,
+                    Err(PipelineError::UnsupportedSqlError(
+                        UnsupportedSqlError::OrderByError
+                    ))
+                ));
+            } else {
+                assert!(matches!(
+                    result,
+                    Err(PipelineError::UnsupportedSqlError(
+                        UnsupportedSqlError::LimitOffsetError
+                    ))
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_cte_referenced_more_than_once() {
+        let sql = r#"
+                WITH tbl AS (SELECT id, manager_id FROM employees)
+                SELECT e.id, m.id
+                INTO managers
+                FROM tbl e JOIN tbl m ON e.manager_id = m.id;
+            "#;
+        let runtime = create_test_runtime();
+        let mut pipeline = AppPipeline::new_with_default_flags();
+        let context = statement_to_pipeline(sql, &mut pipeline, None, vec![], runtime).unwrap();
+        assert_eq!(context.used_sources, vec!["employees".to_string()]);
+        // Both references read the same subgraph
+        assert_eq!(
+            pipeline.get_entry_points_sources_names(),
+            vec!["employees".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_recursive_cte() {
+        let sql = r#"
+                WITH RECURSIVE org AS (
+                    SELECT id, manager_id FROM employees WHERE manager_id IS NULL
+                    UNION ALL
+                    SELECT e.id, e.manager_id FROM employees e JOIN org o ON e.manager_id = o.id
+                )
+                SELECT id
+                INTO org_chart
+                FROM org;
+            "#;
+        let runtime = create_test_runtime();
+        let mut pipeline = AppPipeline::new(PipelineFlags {
+            max_recursive_cte_depth: 3,
+            ..Default::default()
+        });
+        let context = statement_to_pipeline(sql, &mut pipeline, None, vec![], runtime).unwrap();
+
+        assert!(context.output_tables_map.contains_key("org_chart"));
+        // The level after the last one is read by the recursion guard
+        for depth in 0..=4 {
+            assert!(context
+                .pipeline_map
+                .contains_key(&(0, format!("org_level_{depth}"))));
+        }
+        assert!(!context
+            .pipeline_map
+            .contains_key(&(0, "org_level_5".to_string())));
+        assert!(context.pipeline_map[&(0, "org".to_string())]
+            .node
+            .starts_with("recursion_guard--"));
+        assert!(context
+            .used_sources
+            .iter()
+            .all(|source| source == "employees"));
+
+        let mut pipeline = AppPipeline::new(PipelineFlags {
+            max_recursive_cte_depth: MAX_RECURSIVE_CTE_DEPTH + 1,
+            ..Default::default()
+        });
+        assert!(matches!(
+            statement_to_pipeline(sql, &mut pipeline, None, vec![], create_test_runtime()),
+            Err(PipelineError::RecursionDepthTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_recursive_cte() {
+        for sql in [
+            "WITH RECURSIVE t AS (SELECT id FROM t UNION ALL SELECT id FROM a) SELECT id INTO c FROM t",
+            "WITH RECURSIVE t AS (SELECT id FROM a JOIN t ON a.id = t.id) SELECT id INTO c FROM t",
+        ] {
+            let runtime = create_test_runtime();
+            let result = statement_to_pipeline(
+                sql,
+                &mut AppPipeline::new_with_default_flags(),
+                None,
+                vec![],
+                runtime,
+            );
+            assert!(matches!(
+                result,
+                Err(PipelineError::UnsupportedSqlError(
+                    UnsupportedSqlError::Recursive
+                ))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_cross_join_unnest() {
+        let sql = r#"
+                SELECT o.id, item
+                INTO order_items
+                FROM orders o CROSS JOIN UNNEST(JSON_QUERY(o.payload, '$.items')) AS item;
+            "#;
+        let runtime = create_test_runtime();
+        let context = statement_to_pipeline(
+            sql,
+            &mut AppPipeline::new_with_default_flags(),
+            None,
+            vec![],
+            runtime,
+        )
+        .unwrap();
+        assert_eq!(context.used_sources, vec!["orders".to_string()]);
+        assert!(context.output_tables_map.contains_key("order_items"));
+    }
+
+    #[test]
+    fn test_where_subqueries() {
+        let sql = r#"
+                SELECT o.id
+                INTO vip_orders
+                FROM orders o
+                WHERE o.customer_id IN (SELECT id FROM vip_customers)
+                    AND NOT EXISTS (SELECT 1 FROM refunds r WHERE r.order_id = o.id)
+                    AND o.amount > (SELECT AVG(amount) FROM orders);
+            "#;
+        let runtime = create_test_runtime();
+        let context = statement_to_pipeline(
+            sql,
+            &mut AppPipeline::new_with_default_flags(),
+            None,
+            vec![],
+            runtime,
+        )
+        .unwrap();
+        let mut used_sources = context.used_sources;
+        used_sources.sort();
+        used_sources.dedup();
+        assert_eq!(used_sources, vec!["orders", "refunds", "vip_customers"]);
+        assert!(context.output_tables_map.contains_key("vip_orders"));
+    }
+
+    #[test]
+    fn test_multiple_scalar_subqueries_in_condition() {
+        let sql = r#"
+                SELECT id
+                INTO results
+                FROM orders
+                WHERE amount BETWEEN (SELECT MIN(amount) FROM refunds) AND (SELECT MAX(amount) FROM refunds);
+            "#;
+        let runtime = create_test_runtime();
+        let result = statement_to_pipeline(
+            sql,
+            &mut AppPipeline::new_with_default_flags(),
+            None,
+            vec![],
+            runtime,
+        );
+        assert!(matches!(
+            result,
+            Err(PipelineError::UnsupportedSqlError(
+                UnsupportedSqlError::Subquery(_)
+            ))
+        ));
+    }
+
     #[test]
     fn test_missing_into_in_simple_from_clause() {
         let sql = r#"SELECT a FROM B "#;
@@ -47,6 +47,8 @@ pub enum PipelineError {
     InvalidFunctionArgument(String, Field, usize),
     #[error("Not enough arguments for function {0}()")]
     NotEnoughArguments(String),
+    #[error("Argument {1} of function {0}() must be a literal")]
+    NonLiteralArgument(String, usize),
     #[error("Missing INTO clause for top-level SELECT statement")]
     MissingIntoClause,
     #[error("Duplicate INTO table name found: {0:?}")]
@@ -73,13 +75,19 @@ pub enum PipelineError {
     #[error("Set: {0}")]
     SetError(#[from] SetError),
 
+    #[error("Semi join: {0}")]
+    SemiJoinError(#[from] SemiJoinError),
+
     #[error("Window: {0}")]
     WindowError(#[from] WindowError),
 
+    #[error("Top N: {0}")]
+    TopNError(#[from] TopNError),
+
     #[error("Table Function is not supported")]
     UnsupportedTableFunction,
 
-    #[error("UNNEST not supported")]
+    #[error("UNNEST is only supported in a CROSS JOIN, as `FROM table CROSS JOIN UNNEST(<expression>) AS alias`")]
     UnsupportedUnnest,
 
     #[error("Nested Join is not supported")]
@@ -94,7 +102,7 @@ pub enum PipelineError {
     #[error("Invalid JOIN: {0}")]
     InvalidJoin(String),
 
-    #[error("The JOIN clause is not supported. In this version only INNER, LEFT and RIGHT OUTER JOINs are supported")]
+    #[error("The JOIN clause is not supported. In this version only INNER, LEFT, RIGHT and FULL OUTER JOINs and CROSS JOINs are supported")]
     UnsupportedJoinType,
 
     #[error(
@@ -124,11 +132,19 @@ pub enum PipelineError {
 
     #[error("Duplicated Processor name: {0}")]
     ProcessorAlreadyExists(String),
+
+    #[error(
+        "Recursive query {table_name:?} is deeper than `max_recursive_cte_depth` ({max_depth})"
+    )]
+    RecursionDepthExceeded { table_name: String, max_depth: u32 },
+
+    #[error("`max_recursive_cte_depth` is {max_depth}, but recursive queries can't be expanded more than {limit} times")]
+    RecursionDepthTooLarge { max_depth: u32, limit: u32 },
 }
 
 #[derive(Error, Debug)]
 pub enum UnsupportedSqlError {
-    #[error("Recursive CTE is not supported. Please refer to the documentation(https://getdozer.io/docs/reference/sql/introduction) for more information. ")]
+    #[error("Recursive CTEs must have the form `<anchor query> UNION [ALL] <recursive query>`, where only the recursive query references the CTE, without ORDER BY, LIMIT or nested WITH clauses")]
     Recursive,
     #[error("Currently this syntax is not supported for CTEs")]
     CteFromError,
@@ -139,15 +155,18 @@ pub enum UnsupportedSqlError {
 
     #[error("FROM clause doesn't support \"Comma Syntax\"")]
     FromCommaSyntax,
-    #[error("ORDER BY is not supported in SQL. You could achieve the same by using the ORDER BY operator in the cache and APIs")]
+    #[error("ORDER BY is only supported together with LIMIT. Otherwise, you could achieve the same by using the ORDER BY operator in the cache and APIs")]
     OrderByError,
-    #[error("Limit and Offset are not supported in SQL. You could achieve the same by using the LIMIT and OFFSET operators in the cache and APIs")]
+    #[error("LIMIT and OFFSET are only supported together with ORDER BY. Otherwise, you could achieve the same by using the LIMIT and OFFSET operators in the cache and APIs")]
     LimitOffsetError,
     #[error("Select statements should specify INTO for creating output tables")]
     IntoError,
 
     #[error("Unsupported SQL statement {0}")]
     GenericError(String),
+
+    #[error("Unsupported subquery {0}. Only uncorrelated subqueries and EXISTS subqueries correlated with `=` conditions are supported")]
+    Subquery(String),
 }
 
 #[derive(Error, Debug)]
@@ -160,6 +179,26 @@ pub enum SetError {
     HistoryUnavailable(u16),
     #[error("Deserialization error: {0}")]
     Deserialization(#[from] DeserializationError),
+    #[error("Unsupported checkpoint version {0}")]
+    UnsupportedCheckpointVersion(u64),
+}
+
+#[derive(Error, Debug)]
+pub enum SemiJoinError {
+    #[error("The subquery returns {actual} columns, but {expected} are expected")]
+    InvalidSubqueryColumns { expected: usize, actual: usize },
+    #[error("The scalar subquery returns {0} rows, but at most one is expected")]
+    ScalarSubqueryRows(u64),
+    #[error("The subquery column {index} of type {right} can't be compared with {left}")]
+    IncompatibleKeyTypes {
+        index: usize,
+        left: FieldType,
+        right: FieldType,
+    },
+    #[error("Expression error: {0}")]
+    Expression(#[from] dozer_sql_expression::error::Error),
+    #[error("Deserialization error: {0}")]
+    Deserialization(#[from] DeserializationError),
 }
 
 #[derive(Error, Debug)]
@@ -185,6 +224,12 @@ pub enum JoinError {
     #[error("Unsupported Join type")]
     UnsupportedJoinType,
 
+    #[error("Invalid AS OF join {0}, expected an INNER JOIN on `<table> FOR SYSTEM_TIME AS OF <left time column>`, where the table has a single timestamp or date column or the ON constraint has a `<right time column> <= <left time column>` condition")]
+    InvalidAsOfJoin(String),
+
+    #[error("AS OF join times must be both timestamps or both dates, found {0} and {1}")]
+    InvalidAsOfJoinTimeType(FieldType, FieldType),
+
     #[error("Overflow error computing the eviction time in the TTL reference field")]
     EvictionTimeOverflow,
 
@@ -271,6 +316,26 @@ pub enum WindowError {
     #[error("Error in Hop Windowing function:\n{0}")]
     HopRoundingError(#[source] RoundingError),
 
+    #[error("Invalid column specified in Session Windowing function.\nOnly Timestamp types are supported")]
+    SessionInvalidColumnType(),
+    #[error("Invalid column specified in Session Windowing function.")]
+    SessionInvalidColumnIndex(),
+
+    #[error("Gap not specified in the session window function")]
+    WindowMissingGapArgument,
+
+    #[error("Invalid session gap '{0}' specified in the window function")]
+    WindowInvalidGap(String),
+
+    #[error("Invalid session lateness '{0}' specified in the window function")]
+    WindowInvalidLateness(String),
+
+    #[error("Record at {0} is later than the allowed lateness of its session window")]
+    SessionRecordTooLate(String),
+
+    #[error("Can't retract record at {0}: its session is closed, increase the allowed lateness of the session window")]
+    SessionRetractionTooLate(String),
+
     #[error("Invalid WINDOW function")]
     InvalidWindow(),
 
@@ -288,6 +353,33 @@ pub enum WindowError {
 
     #[error("WINDOW functions require alias")]
     NoAlias,
+
+    #[error("Deserialization error: {0}")]
+    Deserialization(#[from] DeserializationError),
+}
+
+#[derive(Error, Debug)]
+pub enum TopNError {
+    #[error("Invalid LIMIT '{0}', only non-negative integers are supported")]
+    InvalidLimit(String),
+
+    #[error("Invalid OFFSET '{0}', only non-negative integers are supported")]
+    InvalidOffset(String),
+
+    #[error("Invalid window '{0}', only ROW_NUMBER() OVER ([PARTITION BY ...] ORDER BY ...) is supported")]
+    RowNumberWindow(String),
+
+    #[error("Row number '{0}' must be filtered by '{0} <= <count>', and can't be selected")]
+    RowNumberFilter(String),
+
+    #[error("Columns of a ROW_NUMBER() subquery must be selected explicitly, the row number can't be selected")]
+    RowNumberWildcard,
+
+    #[error("Expression error: {0}")]
+    Expression(#[from] dozer_sql_expression::error::Error),
+
+    #[error("Deserialization error: {0}")]
+    Deserialization(#[from] DeserializationError),
 }
 
 #[derive(Error, Debug)]
@@ -315,4 +407,7 @@ pub enum TableOperatorError {
 
     #[error("TTL input must evaluate to timestamp, but it evaluates to {0}")]
     InvalidTtlInputType(Field),
+
+    #[error("UNNEST input must evaluate to a JSON array, but it evaluates to {0}")]
+    InvalidUnnestInputType(Field),
 }
@@ -1,5 +1,6 @@
 use crate::expression::tests::test_common::*;
 use dozer_types::{
+    chrono::{DateTime, NaiveDate},
     ordered_float::OrderedFloat,
     types::{Field, FieldDefinition, FieldType, Schema, SourceDefinition},
 };
@@ -94,3 +95,136 @@ fn test_coalesce_logic_null() {
     );
     assert_eq!(f, Field::Null);
 }
+
+fn conditional_schema() -> Schema {
+    Schema::default()
+        .field(
+            FieldDefinition::new(
+                String::from("uint_field"),
+                FieldType::UInt,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("float_field"),
+                FieldType::Float,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("date_field"),
+                FieldType::Date,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("ts_field"),
+                FieldType::Timestamp,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn conditional_input() -> Vec<Field> {
+    vec![
+        Field::UInt(3),
+        Field::Float(OrderedFloat(2.5)),
+        Field::Date(NaiveDate::from_ymd_opt(2023, 5, 17).unwrap()),
+        Field::Timestamp(DateTime::parse_from_rfc3339("2023-05-16T12:00:00Z").unwrap()),
+    ]
+}
+
+#[test]
+fn test_nullif() {
+    let cases = vec![
+        ("SELECT NULLIF(uint_field, 3) FROM users", Field::Null),
+        ("SELECT NULLIF(uint_field, 4) FROM users", Field::Int(3)),
+        ("SELECT NULLIF(uint_field, NULL) FROM users", Field::UInt(3)),
+        ("SELECT NULLIF(float_field, 2.5) FROM users", Field::Null),
+    ];
+    for (sql, expected) in cases {
+        assert_eq!(
+            run_fct(sql, conditional_schema(), conditional_input()),
+            expected,
+            "{sql}"
+        );
+    }
+}
+
+#[test]
+fn test_greatest_least() {
+    let cases = vec![
+        (
+            "SELECT GREATEST(uint_field, float_field, 1) FROM users",
+            Field::Float(OrderedFloat(3.0)),
+        ),
+        (
+            "SELECT LEAST(uint_field, float_field, NULL) FROM users",
+            Field::Float(OrderedFloat(2.5)),
+        ),
+        ("SELECT GREATEST(uint_field, 7) FROM users", Field::Int(7)),
+        (
+            "SELECT GREATEST(date_field, ts_field) FROM users",
+            Field::Timestamp(DateTime::parse_from_rfc3339("2023-05-17T00:00:00Z").unwrap()),
+        ),
+        (
+            "SELECT LEAST(date_field, ts_field) FROM users",
+            Field::Timestamp(DateTime::parse_from_rfc3339("2023-05-16T12:00:00Z").unwrap()),
+        ),
+        (
+            "SELECT LEAST(NULL, NULL, uint_field) FROM users",
+            Field::UInt(3),
+        ),
+    ];
+    for (sql, expected) in cases {
+        assert_eq!(
+            run_fct(sql, conditional_schema(), conditional_input()),
+            expected,
+            "{sql}"
+        );
+    }
+}
+
+#[test]
+fn test_ifnull() {
+    let input = vec![
+        Field::Null,
+        Field::Null,
+        Field::Null,
+        Field::Timestamp(DateTime::parse_from_rfc3339("2023-05-16T12:00:00Z").unwrap()),
+    ];
+    let cases = vec![
+        ("SELECT IFNULL(uint_field, 5) FROM users", Field::Int(5)),
+        (
+            "SELECT NVL(float_field, uint_field) FROM users",
+            Field::Null,
+        ),
+        (
+            "SELECT NVL(float_field, 1) FROM users",
+            Field::Float(OrderedFloat(1.0)),
+        ),
+        (
+            "SELECT IFNULL(date_field, ts_field) FROM users",
+            Field::Timestamp(DateTime::parse_from_rfc3339("2023-05-16T12:00:00Z").unwrap()),
+        ),
+    ];
+    for (sql, expected) in cases {
+        assert_eq!(
+            run_fct(sql, conditional_schema(), input.clone()),
+            expected,
+            "{sql}"
+        );
+    }
+}
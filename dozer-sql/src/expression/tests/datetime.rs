@@ -180,3 +180,218 @@ fn test_now() {
     );
     assert!(f.to_timestamp().is_some())
 }
+
+fn timestamp_schema() -> Schema {
+    Schema::default()
+        .field(
+            FieldDefinition::new(
+                String::from("ts1"),
+                FieldType::Timestamp,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("d1"),
+                FieldType::Date,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn timestamp_input() -> Vec<Field> {
+    vec![
+        Field::Timestamp(DateTime::parse_from_rfc3339("2023-05-17T13:45:30.250+02:00").unwrap()),
+        Field::Date(NaiveDate::from_ymd_opt(2023, 5, 17).unwrap()),
+    ]
+}
+
+#[test]
+fn test_date_trunc() {
+    let cases = vec![
+        (
+            "SELECT DATE_TRUNC('second', ts1) FROM users",
+            Field::Timestamp(DateTime::parse_from_rfc3339("2023-05-17T13:45:30+02:00").unwrap()),
+        ),
+        (
+            "SELECT DATE_TRUNC('hour', ts1) FROM users",
+            Field::Timestamp(DateTime::parse_from_rfc3339("2023-05-17T13:00:00+02:00").unwrap()),
+        ),
+        (
+            "SELECT DATE_TRUNC('week', ts1) FROM users",
+            Field::Timestamp(DateTime::parse_from_rfc3339("2023-05-15T00:00:00+02:00").unwrap()),
+        ),
+        (
+            "SELECT DATE_TRUNC('quarter', ts1) FROM users",
+            Field::Timestamp(DateTime::parse_from_rfc3339("2023-04-01T00:00:00+02:00").unwrap()),
+        ),
+        (
+            "SELECT DATE_TRUNC('month', d1) FROM users",
+            Field::Date(NaiveDate::from_ymd_opt(2023, 5, 1).unwrap()),
+        ),
+        (
+            "SELECT DATE_TRUNC('year', d1) FROM users",
+            Field::Date(NaiveDate::from_ymd_opt(2023, 1, 1).unwrap()),
+        ),
+        (
+            "SELECT DATE_TRUNC('minute', INTERVAL '150' SECOND) FROM users",
+            Field::Duration(DozerDuration(
+                std::time::Duration::from_secs(120),
+                TimeUnit::Seconds,
+            )),
+        ),
+    ];
+    for (sql, expected) in cases {
+        assert_eq!(
+            run_fct(sql, timestamp_schema(), timestamp_input()),
+            expected,
+            "{sql}"
+        );
+    }
+}
+
+#[test]
+fn test_date_add_sub() {
+    let cases = vec![
+        (
+            "SELECT DATE_ADD(ts1, INTERVAL '1' DAY) FROM users",
+            Field::Timestamp(
+                DateTime::parse_from_rfc3339("2023-05-18T13:45:30.250+02:00").unwrap(),
+            ),
+        ),
+        (
+            "SELECT DATE_SUB(ts1, INTERVAL '30' SECOND) FROM users",
+            Field::Timestamp(
+                DateTime::parse_from_rfc3339("2023-05-17T13:45:00.250+02:00").unwrap(),
+            ),
+        ),
+        (
+            "SELECT DATE_ADD(d1, INTERVAL '2' DAY) FROM users",
+            Field::Timestamp(DateTime::parse_from_rfc3339("2023-05-19T00:00:00Z").unwrap()),
+        ),
+        (
+            "SELECT DATE_ADD(d1, 20) FROM users",
+            Field::Date(NaiveDate::from_ymd_opt(2023, 6, 6).unwrap()),
+        ),
+        (
+            "SELECT DATE_SUB(d1, 17) FROM users",
+            Field::Date(NaiveDate::from_ymd_opt(2023, 4, 30).unwrap()),
+        ),
+    ];
+    for (sql, expected) in cases {
+        assert_eq!(
+            run_fct(sql, timestamp_schema(), timestamp_input()),
+            expected,
+            "{sql}"
+        );
+    }
+}
+
+#[test]
+fn test_date_diff() {
+    let cases = vec![
+        ("SELECT DATE_DIFF('day', d1, ts1) FROM users", 0),
+        ("SELECT DATE_DIFF('hour', d1, ts1) FROM users", 11),
+        ("SELECT DATE_DIFF('minute', ts1, d1) FROM users", -705),
+        (
+            "SELECT DATE_DIFF('month', TO_DATE('2023-01-31'), d1) FROM users",
+            3,
+        ),
+        (
+            "SELECT DATE_DIFF('year', TO_DATE('2020-05-18'), d1) FROM users",
+            2,
+        ),
+    ];
+    for (sql, expected) in cases {
+        assert_eq!(
+            run_fct(sql, timestamp_schema(), timestamp_input()),
+            Field::Int(expected),
+            "{sql}"
+        );
+    }
+}
+
+#[test]
+fn test_to_timestamp_and_date() {
+    let cases = vec![
+        (
+            "SELECT TO_TIMESTAMP('2023-05-17 13:45:30', '%Y-%m-%d %H:%M:%S') FROM users",
+            Field::Timestamp(DateTime::parse_from_rfc3339("2023-05-17T13:45:30Z").unwrap()),
+        ),
+        (
+            "SELECT TO_TIMESTAMP('17/05/2023 13:45 +0530', '%d/%m/%Y %H:%M %z') FROM users",
+            Field::Timestamp(DateTime::parse_from_rfc3339("2023-05-17T13:45:00+05:30").unwrap()),
+        ),
+        (
+            "SELECT TO_TIMESTAMP('17 May 2023 01:45:30 PM', 'DD Mon YYYY HH12:MI:SS AM') FROM users",
+            Field::Timestamp(DateTime::parse_from_rfc3339("2023-05-17T13:45:30Z").unwrap()),
+        ),
+        (
+            "SELECT TO_TIMESTAMP('2023-05-17T13:45:30.250+05:30', 'YYYY-MM-DD\"T\"HH24:MI:SS.MSTZH:TZM') FROM users",
+            Field::Timestamp(
+                DateTime::parse_from_rfc3339("2023-05-17T13:45:30.250+05:30").unwrap(),
+            ),
+        ),
+        (
+            "SELECT TO_TIMESTAMP('2023-05-17T13:45:30+02:00') FROM users",
+            Field::Timestamp(DateTime::parse_from_rfc3339("2023-05-17T13:45:30+02:00").unwrap()),
+        ),
+        (
+            "SELECT TO_TIMESTAMP(1684331130) FROM users",
+            Field::Timestamp(DateTime::parse_from_rfc3339("2023-05-17T13:45:30Z").unwrap()),
+        ),
+        (
+            "SELECT TO_DATE('17.05.2023', '%d.%m.%Y') FROM users",
+            Field::Date(NaiveDate::from_ymd_opt(2023, 5, 17).unwrap()),
+        ),
+        (
+            "SELECT TO_DATE('May 17, 2023', 'Month DD, YYYY') FROM users",
+            Field::Date(NaiveDate::from_ymd_opt(2023, 5, 17).unwrap()),
+        ),
+        (
+            "SELECT TO_DATE('2023-05-17') FROM users",
+            Field::Date(NaiveDate::from_ymd_opt(2023, 5, 17).unwrap()),
+        ),
+    ];
+    for (sql, expected) in cases {
+        assert_eq!(
+            run_fct(sql, timestamp_schema(), timestamp_input()),
+            expected,
+            "{sql}"
+        );
+    }
+}
+
+#[test]
+fn test_at_time_zone() {
+    let cases = vec![
+        (
+            "SELECT ts1 AT TIME ZONE 'UTC' FROM users",
+            "2023-05-17T11:45:30.250+00:00",
+        ),
+        (
+            "SELECT ts1 AT TIME ZONE 'America/New_York' FROM users",
+            "2023-05-17T07:45:30.250-04:00",
+        ),
+        (
+            "SELECT ts1 AT TIME ZONE '+05:30' FROM users",
+            "2023-05-17T17:15:30.250+05:30",
+        ),
+        (
+            "SELECT d1 AT TIME ZONE 'Asia/Tokyo' FROM users",
+            "2023-05-17T09:00:00+09:00",
+        ),
+    ];
+    for (sql, expected) in cases {
+        let result = run_fct(sql, timestamp_schema(), timestamp_input());
+        let Field::Timestamp(ts) = result else {
+            panic!("{sql} returned {result:?}");
+        };
+        assert_eq!(ts.to_rfc3339(), expected, "{sql}");
+    }
+}
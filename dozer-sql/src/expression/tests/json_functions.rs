@@ -820,3 +820,137 @@ fn test_json_value_diff_5() {
 
     assert_eq!(f, Field::Json(0.into()));
 }
+
+fn document_schema() -> Schema {
+    Schema::default()
+        .field(
+            FieldDefinition::new(
+                String::from("doc"),
+                FieldType::Json,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("name"),
+                FieldType::String,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn document_input() -> Vec<Field> {
+    vec![
+        Field::Json(json!({
+            "info": { "town": "Bristol" },
+            "tags": ["Sport", "Water polo"]
+        })),
+        Field::String(String::from("Monty")),
+    ]
+}
+
+#[test]
+fn test_json_object_and_array() {
+    let f = run_fct(
+        "SELECT JSON_OBJECT('name', name, 'age', 42, 'tags', JSON_ARRAY('a', 1, NULL)) FROM users",
+        document_schema(),
+        document_input(),
+    );
+    assert_eq!(
+        f,
+        Field::Json(json!({ "name": "Monty", "age": 42, "tags": ["a", 1, null] }))
+    );
+}
+
+#[test]
+fn test_json_inspection_functions() {
+    let cases = vec![
+        (
+            "SELECT JSON_ARRAY_LENGTH(doc, '$.tags') FROM users",
+            Field::UInt(2),
+        ),
+        ("SELECT JSON_ARRAY_LENGTH(doc) FROM users", Field::Null),
+        (
+            "SELECT JSON_EXISTS(doc, '$.info.town') FROM users",
+            Field::Boolean(true),
+        ),
+        (
+            "SELECT JSON_EXISTS(doc, '$.info.county') FROM users",
+            Field::Boolean(false),
+        ),
+        (
+            "SELECT JSON_KEYS(doc, '$.info') FROM users",
+            Field::Json(json!(["town"])),
+        ),
+        ("SELECT JSON_KEYS(doc, '$.tags') FROM users", Field::Null),
+    ];
+    for (sql, expected) in cases {
+        assert_eq!(
+            run_fct(sql, document_schema(), document_input()),
+            expected,
+            "{sql}"
+        );
+    }
+}
+
+#[test]
+fn test_json_set_and_remove() {
+    let f = run_fct(
+        "SELECT JSON_SET(doc, '$.info.town', 'Bath', '$.info.zip', 'BA1', '$.tags[5]', name, '$.missing.key', 1) FROM users",
+        document_schema(),
+        document_input(),
+    );
+    assert_eq!(
+        f,
+        Field::Json(json!({
+            "info": { "town": "Bath", "zip": "BA1" },
+            "tags": ["Sport", "Water polo", "Monty"]
+        }))
+    );
+
+    let f = run_fct(
+        "SELECT JSON_REMOVE(doc, '$.tags[0]', '$.info') FROM users",
+        document_schema(),
+        document_input(),
+    );
+    assert_eq!(f, Field::Json(json!({ "tags": ["Water polo"] })));
+}
+
+#[test]
+fn test_json_arrow_operators() {
+    let cases = vec![
+        (
+            "SELECT doc -> 'info' FROM users",
+            Field::Json(json!({ "town": "Bristol" })),
+        ),
+        (
+            "SELECT doc -> 'info' -> 'town' FROM users",
+            Field::Json("Bristol".into()),
+        ),
+        (
+            "SELECT doc -> 'info' ->> 'town' FROM users",
+            Field::String(String::from("Bristol")),
+        ),
+        (
+            "SELECT doc -> 'tags' ->> -1 FROM users",
+            Field::String(String::from("Water polo")),
+        ),
+        (
+            "SELECT doc ->> 'info' FROM users",
+            Field::String(String::from("{\"town\":\"Bristol\"}")),
+        ),
+        ("SELECT doc -> 'missing' FROM users", Field::Null),
+    ];
+    for (sql, expected) in cases {
+        assert_eq!(
+            run_fct(sql, document_schema(), document_input()),
+            expected,
+            "{sql}"
+        );
+    }
+}
@@ -479,3 +479,161 @@ fn test_to_char() {
     );
     assert_eq!(f, Field::String("%H:%M".to_string()));
 }
+
+fn string_schema(field_type: FieldType) -> Schema {
+    Schema::default()
+        .field(
+            FieldDefinition::new(
+                String::from("fn"),
+                field_type,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+#[test]
+fn test_lower() {
+    let f = run_fct(
+        "SELECT LOWER(fn) FROM USERS",
+        string_schema(FieldType::Text),
+        vec![Field::Text("JoHn".to_string())],
+    );
+    assert_eq!(f, Field::Text("john".to_string()));
+}
+
+#[test]
+fn test_substring() {
+    let schema = string_schema(FieldType::String);
+    let input = vec![Field::String("Johnathan".to_string())];
+
+    let f = run_fct(
+        "SELECT SUBSTRING(fn FROM 2 FOR 3) FROM USERS",
+        schema.clone(),
+        input.clone(),
+    );
+    assert_eq!(f, Field::String("ohn".to_string()));
+
+    let f = run_fct(
+        "SELECT SUBSTRING(fn, 5) FROM USERS",
+        schema.clone(),
+        input.clone(),
+    );
+    assert_eq!(f, Field::String("athan".to_string()));
+
+    let f = run_fct("SELECT SUBSTR(fn, 1, 4) FROM USERS", schema, input);
+    assert_eq!(f, Field::String("John".to_string()));
+}
+
+#[test]
+fn test_position() {
+    let f = run_fct(
+        "SELECT POSITION('@' IN fn) FROM USERS",
+        string_schema(FieldType::String),
+        vec![Field::String("john@doe.com".to_string())],
+    );
+    assert_eq!(f, Field::UInt(5));
+}
+
+#[test]
+fn test_split_part_and_replace() {
+    let schema = string_schema(FieldType::String);
+    let input = vec![Field::String("john@doe.com".to_string())];
+
+    let f = run_fct(
+        "SELECT SPLIT_PART(fn, '@', 2) FROM USERS",
+        schema.clone(),
+        input.clone(),
+    );
+    assert_eq!(f, Field::String("doe.com".to_string()));
+
+    let f = run_fct(
+        "SELECT REPLACE(fn, '.com', '.org') FROM USERS",
+        schema,
+        input,
+    );
+    assert_eq!(f, Field::String("john@doe.org".to_string()));
+}
+
+#[test]
+fn test_left_right_pad() {
+    let schema = string_schema(FieldType::String);
+    let input = vec![Field::String("42".to_string())];
+
+    let f = run_fct(
+        "SELECT LPAD(fn, 5, '0') FROM USERS",
+        schema.clone(),
+        input.clone(),
+    );
+    assert_eq!(f, Field::String("00042".to_string()));
+
+    let f = run_fct("SELECT RPAD(fn, 4) FROM USERS", schema.clone(), input);
+    assert_eq!(f, Field::String("42  ".to_string()));
+
+    let input = vec![Field::String("Johnathan".to_string())];
+    let f = run_fct(
+        "SELECT LEFT(fn, -5) FROM USERS",
+        schema.clone(),
+        input.clone(),
+    );
+    assert_eq!(f, Field::String("John".to_string()));
+
+    let f = run_fct("SELECT RIGHT(fn, 3) FROM USERS", schema, input);
+    assert_eq!(f, Field::String("han".to_string()));
+}
+
+#[test]
+fn test_regexp_replace() {
+    let f = run_fct(
+        "SELECT REGEXP_REPLACE(fn, '(\\w+)@(\\w+)', '\\2 at \\1') FROM USERS",
+        string_schema(FieldType::String),
+        vec![Field::String("john@doe".to_string())],
+    );
+    assert_eq!(f, Field::String("doe at john".to_string()));
+}
+
+#[test]
+fn test_hashes() {
+    let schema = string_schema(FieldType::String);
+    let input = vec![Field::String("dozer".to_string())];
+
+    let f = run_fct("SELECT MD5(fn) FROM USERS", schema.clone(), input.clone());
+    assert_eq!(
+        f,
+        Field::String("8dcc92ed28112ba6048ffb983ca637f5".to_string())
+    );
+
+    let f = run_fct("SELECT SHA256(fn) FROM USERS", schema, input);
+    let Field::String(hash) = f else {
+        panic!("SHA256 should return a string");
+    };
+    assert_eq!(hash.len(), 64);
+}
+
+#[test]
+fn test_initcap_and_trim_functions() {
+    let schema = string_schema(FieldType::String);
+
+    let f = run_fct(
+        "SELECT INITCAP(fn) FROM USERS",
+        schema.clone(),
+        vec![Field::String("jOHN doe".to_string())],
+    );
+    assert_eq!(f, Field::String("John Doe".to_string()));
+
+    let f = run_fct(
+        "SELECT LTRIM(fn, 'x') FROM USERS",
+        schema.clone(),
+        vec![Field::String("xxJohnxx".to_string())],
+    );
+    assert_eq!(f, Field::String("Johnxx".to_string()));
+
+    let f = run_fct(
+        "SELECT RTRIM(fn) FROM USERS",
+        schema,
+        vec![Field::String("  John  ".to_string())],
+    );
+    assert_eq!(f, Field::String("  John".to_string()));
+}
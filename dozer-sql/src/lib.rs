@@ -6,8 +6,10 @@ mod pipeline_builder;
 mod planner;
 mod product;
 mod projection;
+mod recursive_cte;
 mod selection;
 mod table_operator;
+mod top_n;
 mod utils;
 mod window;
 
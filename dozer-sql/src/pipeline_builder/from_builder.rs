@@ -10,8 +10,8 @@ use dozer_sql_expression::{
 
 use crate::{
     builder::{get_from_source, QueryContext},
-    errors::PipelineError,
-    product::table::factory::TableProcessorFactory,
+    errors::{JoinError, PipelineError},
+    product::{join::temporal::get_system_time_as_of, table::factory::TableProcessorFactory},
     table_operator::factory::{get_source_name, TableOperatorProcessorFactory},
     window::factory::WindowProcessorFactory,
 };
@@ -42,6 +42,13 @@ pub fn insert_from_to_pipeline(
     pipeline_idx: usize,
     query_context: &mut QueryContext,
 ) -> Result<ConnectionInfo, PipelineError> {
+    // Only the right side of a join can be read as of the time of the left side
+    if let Some(as_of) = get_system_time_as_of(&from.relation) {
+        return Err(PipelineError::JoinError(JoinError::InvalidAsOfJoin(
+            as_of.to_string(),
+        )));
+    }
+
     if from.joins.is_empty() {
         insert_table_to_pipeline(&from.relation, pipeline, pipeline_idx, query_context)
     } else {
@@ -169,6 +176,7 @@ pub fn insert_table_operator_processor_to_pipeline(
                         pipeline_idx,
                         query_context,
                     )?;
+                    input_nodes.extend(connection_info.input_nodes);
                     connection_info.output_node.0
                 }
             };
@@ -209,7 +217,10 @@ pub fn insert_table_operator_processor_to_pipeline(
                 operator.name.clone(),
             ))
         }
-    } else if operator.name.to_uppercase() == "TUMBLE" || operator.name.to_uppercase() == "HOP" {
+    } else if matches!(
+        operator.name.to_uppercase().as_str(),
+        "TUMBLE" | "HOP" | "SESSION"
+    ) {
         let mut entry_points = vec![];
 
         let processor_name = generate_name("WIN", operator, query_context);
@@ -228,6 +239,7 @@ pub fn insert_table_operator_processor_to_pipeline(
                         pipeline_idx,
                         query_context,
                     )?;
+                    input_nodes.extend(connection_info.input_nodes);
                     connection_info.output_node.0
                 }
             };
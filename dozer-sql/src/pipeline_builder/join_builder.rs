@@ -1,21 +1,32 @@
 use dozer_core::{
     app::{AppPipeline, PipelineEntryPoint},
+    node::PortHandle,
     DEFAULT_PORT_HANDLE,
 };
-use dozer_sql_expression::sqlparser::ast::TableWithJoins;
+use dozer_sql_expression::{
+    builder::ExpressionBuilder,
+    sqlparser::ast::{
+        Expr, FunctionArg, FunctionArgExpr, Ident, JoinOperator, TableAlias, TableFactor,
+        TableWithJoins,
+    },
+};
 
 use crate::{
     builder::{get_from_source, QueryContext},
     errors::PipelineError,
     product::{
-        join::factory::{JoinProcessorFactory, LEFT_JOIN_PORT, RIGHT_JOIN_PORT},
-        table::factory::get_name_or_alias,
+        join::{
+            factory::{JoinProcessorFactory, LEFT_JOIN_PORT, RIGHT_JOIN_PORT},
+            temporal::get_system_time_as_of,
+        },
+        table::factory::{get_name_or_alias, TableProcessorFactory},
     },
+    table_operator::factory::TableOperatorProcessorFactory,
 };
 
 use super::from_builder::{
     insert_table_operator_processor_to_pipeline, is_an_entry_point, is_table_operator,
-    ConnectionInfo,
+    ConnectionInfo, TableOperatorArg, TableOperatorDescriptor,
 };
 
 #[derive(Clone, Debug)]
@@ -40,6 +51,26 @@ pub(crate) fn insert_join_to_pipeline(
 
     for join in &from.joins {
         let right_table = &join.relation;
+        if let TableFactor::UNNEST {
+            alias, array_expr, ..
+        } = right_table
+        {
+            left_join_source = insert_unnest_to_pipeline(
+                &from.relation,
+                left_join_source,
+                &join.join_operator,
+                array_expr,
+                alias.as_ref(),
+                &mut input_nodes,
+                pipeline,
+                pipeline_idx,
+                query_context,
+            )?;
+            // The unnested records keep the names and aliases of the left table
+            left_name_or_alias = None;
+            continue;
+        }
+
         let right_name_or_alias = Some(get_name_or_alias(right_table)?);
         let right_join_source = insert_join_source_to_pipeline(
             right_table.clone(),
@@ -60,6 +91,7 @@ pub(crate) fn insert_join_to_pipeline(
             left_name_or_alias.clone(),
             right_name_or_alias,
             join.join_operator.clone(),
+            get_system_time_as_of(right_table).cloned(),
             pipeline
                 .flags()
                 .enable_probabilistic_optimizations
@@ -67,6 +99,13 @@ pub(crate) fn insert_join_to_pipeline(
                 .unwrap_or(false),
         );
 
+        // Table operators may read from other queries of the pipeline
+        for join_source in [&left_join_source, &right_join_source] {
+            if let JoinSource::Operator(connection_info) = join_source {
+                input_nodes.extend(connection_info.input_nodes.iter().cloned());
+            }
+        }
+
         let mut pipeline_entry_points = vec![];
         if let JoinSource::Table(ref source_table) = left_join_source {
             if is_an_entry_point(source_table, query_context, pipeline_idx) {
@@ -155,6 +194,124 @@ pub(crate) fn insert_join_to_pipeline(
     }
 }
 
+/// Explodes every record of the left side of a `CROSS JOIN UNNEST(<expression>)` into one record
+/// per item of the array the expression evaluates to.
+#[allow(clippy::too_many_arguments)]
+fn insert_unnest_to_pipeline(
+    left_table: &TableFactor,
+    left_join_source: JoinSource,
+    join_operator: &JoinOperator,
+    array_expr: &Expr,
+    alias: Option<&TableAlias>,
+    input_nodes: &mut Vec<(String, String, PortHandle)>,
+    pipeline: &mut AppPipeline,
+    pipeline_idx: usize,
+    query_context: &mut QueryContext,
+) -> Result<JoinSource, PipelineError> {
+    if !matches!(
+        join_operator,
+        JoinOperator::CrossJoin | JoinOperator::CrossApply
+    ) {
+        return Err(PipelineError::UnsupportedUnnest);
+    }
+
+    let table_name = alias.map(|alias| ExpressionBuilder::normalize_ident(&alias.name));
+    let column_name = alias
+        .and_then(|alias| alias.columns.first())
+        .map(ExpressionBuilder::normalize_ident)
+        .or(table_name.clone())
+        .unwrap_or_else(|| "unnest".to_string());
+    let mut args = vec![
+        array_expr.clone(),
+        Expr::Identifier(Ident::new(column_name)),
+    ];
+    if let Some(table_name) = table_name {
+        args.push(Expr::Identifier(Ident::new(table_name)));
+    }
+    let descriptor = TableOperatorDescriptor {
+        name: "UNNEST".to_string(),
+        args: args
+            .into_iter()
+            .map(|arg| TableOperatorArg::Argument(FunctionArg::Unnamed(FunctionArgExpr::Expr(arg))))
+            .collect(),
+    };
+
+    let processor_name = format!("unnest_{}", query_context.get_next_processor_id());
+    if !query_context.processors_list.insert(processor_name.clone()) {
+        return Err(PipelineError::ProcessorAlreadyExists(processor_name));
+    }
+    let processor = TableOperatorProcessorFactory::new(
+        processor_name.clone(),
+        descriptor,
+        query_context.udfs.to_owned(),
+        query_context.runtime.clone(),
+    );
+    pipeline.add_processor(Box::new(processor), &processor_name, vec![]);
+
+    let (source_node, source_port) = match left_join_source {
+        JoinSource::Table(source_table) => {
+            // The join processor would apply the table name and alias to its input schema,
+            // so they have to be applied before evaluating the UNNEST expression
+            if is_an_entry_point(&source_table, query_context, pipeline_idx) {
+                let entry_point =
+                    PipelineEntryPoint::new(source_table.clone(), DEFAULT_PORT_HANDLE);
+                query_context.used_sources.push(source_table);
+                let table_processor_name =
+                    insert_table_processor(left_table, vec![entry_point], pipeline, query_context)?;
+                (table_processor_name, DEFAULT_PORT_HANDLE)
+            } else {
+                let table_processor_name =
+                    insert_table_processor(left_table, vec![], pipeline, query_context)?;
+                input_nodes.push((
+                    source_table,
+                    table_processor_name.clone(),
+                    DEFAULT_PORT_HANDLE,
+                ));
+                (table_processor_name, DEFAULT_PORT_HANDLE)
+            }
+        }
+        JoinSource::Operator(connection_info) => {
+            let table_processor_name =
+                insert_table_processor(left_table, vec![], pipeline, query_context)?;
+            input_nodes.extend(connection_info.input_nodes);
+            pipeline.connect_nodes(
+                &connection_info.output_node.0,
+                connection_info.output_node.1,
+                &table_processor_name,
+                DEFAULT_PORT_HANDLE,
+            );
+            (table_processor_name, DEFAULT_PORT_HANDLE)
+        }
+        JoinSource::Join(connection_info) => connection_info.output_node,
+    };
+    pipeline.connect_nodes(
+        &source_node,
+        source_port,
+        &processor_name,
+        DEFAULT_PORT_HANDLE,
+    );
+
+    Ok(JoinSource::Join(ConnectionInfo {
+        input_nodes: input_nodes.clone(),
+        output_node: (processor_name, DEFAULT_PORT_HANDLE),
+    }))
+}
+
+fn insert_table_processor(
+    relation: &TableFactor,
+    entry_points: Vec<PipelineEntryPoint>,
+    pipeline: &mut AppPipeline,
+    query_context: &mut QueryContext,
+) -> Result<String, PipelineError> {
+    let processor_name = format!("from--{}", query_context.get_next_processor_id());
+    if !query_context.processors_list.insert(processor_name.clone()) {
+        return Err(PipelineError::ProcessorAlreadyExists(processor_name));
+    }
+    let processor = TableProcessorFactory::new(processor_name.clone(), relation.clone());
+    pipeline.add_processor(Box::new(processor), &processor_name, entry_points);
+    Ok(processor_name)
+}
+
 // TODO: refactor this
 fn insert_join_source_to_pipeline(
     source: dozer_sql_expression::sqlparser::ast::TableFactor,
@@ -1,2 +1,3 @@
 pub(crate) mod from_builder;
 pub(crate) mod join_builder;
+pub(crate) mod subquery_builder;
@@ -0,0 +1,289 @@
+use std::collections::HashSet;
+
+use dozer_core::{app::AppPipeline, node::PortHandle, DEFAULT_PORT_HANDLE};
+use dozer_sql_expression::{
+    builder::{ExpressionBuilder, NameOrAlias},
+    sqlparser::ast::{
+        BinaryOperator, Expr as SqlExpr, FunctionArg, FunctionArgExpr, Ident, Query, SelectItem,
+        SetExpr, TableFactor, TableWithJoins,
+    },
+};
+
+use crate::{
+    builder::{query_to_pipeline, QueryContext, TableInfo},
+    errors::{PipelineError, UnsupportedSqlError},
+    product::semi_join::factory::{
+        SemiJoinProcessorFactory, SubqueryCondition, LEFT_SEMI_JOIN_PORT, RIGHT_SEMI_JOIN_PORT,
+    },
+};
+
+/// Name of the column that replaces a scalar subquery in the condition using it.
+const SCALAR_SUBQUERY_COLUMN: &str = "__scalar_subquery";
+
+/// Turns the subquery conditions of a WHERE clause into semi-joins after `input`.
+///
+/// Returns the output node of the last semi-join, and the rest of the WHERE clause.
+pub fn insert_subqueries_to_pipeline(
+    selection: SqlExpr,
+    from: &TableWithJoins,
+    input: (String, PortHandle),
+    pipeline: &mut AppPipeline,
+    pipeline_idx: usize,
+    query_ctx: &mut QueryContext,
+) -> Result<((String, PortHandle), Option<SqlExpr>), PipelineError> {
+    let mut conditions = vec![];
+    let mut remaining = vec![];
+    for conjunct in split_conjunction(selection) {
+        match parse_subquery_condition(conjunct, from)? {
+            Ok(condition) => conditions.push(condition),
+            Err(conjunct) => remaining.push(conjunct),
+        }
+    }
+
+    let mut output = input;
+    for (condition, subquery) in conditions {
+        let subquery_name = format!("subquery_{}", query_ctx.get_next_processor_id());
+        query_to_pipeline(
+            &TableInfo {
+                name: NameOrAlias(subquery_name.clone(), None),
+                is_derived: true,
+                override_name: None,
+            },
+            &subquery,
+            pipeline,
+            query_ctx,
+            false,
+            pipeline_idx,
+            false, // inside WHERE clause, so not top select
+        )?;
+        let subquery_output = query_ctx
+            .pipeline_map
+            .get(&(pipeline_idx, subquery_name.clone()))
+            .cloned()
+            .ok_or_else(|| PipelineError::InvalidQuery(subquery_name))?;
+
+        let gen_semi_join_name = format!("semi_join--{}", query_ctx.get_next_processor_id());
+        let semi_join = SemiJoinProcessorFactory::new(
+            gen_semi_join_name.clone(),
+            condition,
+            query_ctx.udfs.clone(),
+            query_ctx.runtime.clone(),
+        );
+        pipeline.add_processor(Box::new(semi_join), &gen_semi_join_name, vec![]);
+        pipeline.connect_nodes(
+            &output.0,
+            output.1,
+            &gen_semi_join_name,
+            LEFT_SEMI_JOIN_PORT,
+        );
+        pipeline.connect_nodes(
+            &subquery_output.node,
+            subquery_output.port,
+            &gen_semi_join_name,
+            RIGHT_SEMI_JOIN_PORT,
+        );
+        output = (gen_semi_join_name, DEFAULT_PORT_HANDLE);
+    }
+
+    let remaining = join_conjunction(remaining);
+    Ok((output, remaining))
+}
+
+fn split_conjunction(expr: SqlExpr) -> Vec<SqlExpr> {
+    match expr {
+        SqlExpr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            let mut conjuncts = split_conjunction(*left);
+            conjuncts.extend(split_conjunction(*right));
+            conjuncts
+        }
+        expr => vec![expr],
+    }
+}
+
+fn join_conjunction(conjuncts: Vec<SqlExpr>) -> Option<SqlExpr> {
+    conjuncts
+        .into_iter()
+        .reduce(|left, right| SqlExpr::BinaryOp {
+            left: Box::new(left),
+            op: BinaryOperator::And,
+            right: Box::new(right),
+        })
+}
+
+/// Returns the conjunct back if it doesn't use a subquery.
+#[allow(clippy::type_complexity)]
+fn parse_subquery_condition(
+    conjunct: SqlExpr,
+    from: &TableWithJoins,
+) -> Result<Result<(SubqueryCondition, Query), SqlExpr>, PipelineError> {
+    match conjunct {
+        SqlExpr::InSubquery {
+            expr,
+            subquery,
+            negated,
+        } => Ok(Ok((
+            SubqueryCondition::In {
+                expr: *expr,
+                negated,
+            },
+            *subquery,
+        ))),
+        SqlExpr::Exists { subquery, negated } => {
+            let (keys, subquery) = decorrelate_exists_subquery(*subquery, from)?;
+            Ok(Ok((SubqueryCondition::Exists { keys, negated }, subquery)))
+        }
+        mut conjunct => {
+            let mut subqueries = vec![];
+            replace_scalar_subqueries(&mut conjunct, &mut subqueries);
+            match subqueries.len() {
+                0 => Ok(Err(conjunct)),
+                1 => Ok(Ok((
+                    SubqueryCondition::Scalar {
+                        condition: conjunct,
+                        column: SCALAR_SUBQUERY_COLUMN.to_string(),
+                    },
+                    subqueries.remove(0),
+                ))),
+                _ => Err(UnsupportedSqlError::Subquery(conjunct.to_string()).into()),
+            }
+        }
+    }
+}
+
+/// Replaces the scalar subqueries in `expr` with [`SCALAR_SUBQUERY_COLUMN`], and collects them.
+fn replace_scalar_subqueries(expr: &mut SqlExpr, subqueries: &mut Vec<Query>) {
+    match expr {
+        SqlExpr::Subquery(subquery) => {
+            subqueries.push(*subquery.clone());
+            *expr = SqlExpr::Identifier(Ident::new(SCALAR_SUBQUERY_COLUMN));
+        }
+        SqlExpr::BinaryOp { left, right, .. } => {
+            replace_scalar_subqueries(left, subqueries);
+            replace_scalar_subqueries(right, subqueries);
+        }
+        SqlExpr::UnaryOp { expr, .. }
+        | SqlExpr::Nested(expr)
+        | SqlExpr::IsNull(expr)
+        | SqlExpr::IsNotNull(expr)
+        | SqlExpr::Cast { expr, .. } => replace_scalar_subqueries(expr, subqueries),
+        SqlExpr::Between {
+            expr, low, high, ..
+        } => {
+            replace_scalar_subqueries(expr, subqueries);
+            replace_scalar_subqueries(low, subqueries);
+            replace_scalar_subqueries(high, subqueries);
+        }
+        SqlExpr::InList { expr, list, .. } => {
+            replace_scalar_subqueries(expr, subqueries);
+            for item in list {
+                replace_scalar_subqueries(item, subqueries);
+            }
+        }
+        SqlExpr::Function(function) => {
+            for arg in &mut function.args {
+                if let FunctionArg::Unnamed(FunctionArgExpr::Expr(arg))
+                | FunctionArg::Named {
+                    arg: FunctionArgExpr::Expr(arg),
+                    ..
+                } = arg
+                {
+                    replace_scalar_subqueries(arg, subqueries);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Moves the `<outer column> = <subquery expression>` conditions out of the WHERE clause of an
+/// EXISTS subquery, so it returns the subquery expressions to match against the outer columns.
+///
+/// Returns the outer columns and the rewritten subquery.
+fn decorrelate_exists_subquery(
+    subquery: Query,
+    outer: &TableWithJoins,
+) -> Result<(Vec<SqlExpr>, Query), PipelineError> {
+    let SetExpr::Select(select) = subquery.body.as_ref() else {
+        return Ok((vec![], subquery));
+    };
+    let Some(selection) = select.selection.clone() else {
+        return Ok((vec![], subquery));
+    };
+
+    let inner_names = select
+        .from
+        .iter()
+        .flat_map(table_names)
+        .collect::<HashSet<_>>();
+    let outer_names = table_names(outer)
+        .into_iter()
+        .filter(|name| !inner_names.contains(name))
+        .collect::<HashSet<_>>();
+    let is_outer_column = |expr: &SqlExpr| match expr {
+        SqlExpr::CompoundIdentifier(ident) if ident.len() == 2 => {
+            outer_names.contains(&ExpressionBuilder::normalize_ident(&ident[0]))
+        }
+        _ => false,
+    };
+
+    let mut outer_keys = vec![];
+    let mut inner_keys = vec![];
+    let mut remaining = vec![];
+    for conjunct in split_conjunction(selection) {
+        match conjunct {
+            SqlExpr::BinaryOp {
+                left,
+                op: BinaryOperator::Eq,
+                right,
+            } if is_outer_column(&left) != is_outer_column(&right) => {
+                let (outer_key, inner_key) = if is_outer_column(&left) {
+                    (left, right)
+                } else {
+                    (right, left)
+                };
+                outer_keys.push(*outer_key);
+                inner_keys.push(*inner_key);
+            }
+            conjunct => remaining.push(conjunct),
+        }
+    }
+    if outer_keys.is_empty() {
+        return Ok((vec![], subquery));
+    }
+    if !select.group_by.is_empty() || select.having.is_some() {
+        return Err(UnsupportedSqlError::Subquery(subquery.to_string()).into());
+    }
+
+    let mut select = select.clone();
+    select.projection = inner_keys
+        .into_iter()
+        .map(SelectItem::UnnamedExpr)
+        .collect();
+    select.selection = join_conjunction(remaining);
+    let mut subquery = subquery;
+    subquery.body = Box::new(SetExpr::Select(select));
+    Ok((outer_keys, subquery))
+}
+
+/// The names that the columns of the tables in `from` can be qualified with.
+fn table_names(from: &TableWithJoins) -> Vec<String> {
+    std::iter::once(&from.relation)
+        .chain(from.joins.iter().map(|join| &join.relation))
+        .filter_map(|relation| match relation {
+            TableFactor::Table {
+                alias: Some(alias), ..
+            }
+            | TableFactor::Derived {
+                alias: Some(alias), ..
+            } => Some(ExpressionBuilder::normalize_ident(&alias.name)),
+            TableFactor::Table { name, .. } => {
+                name.0.last().map(ExpressionBuilder::normalize_ident)
+            }
+            _ => None,
+        })
+        .collect()
+}
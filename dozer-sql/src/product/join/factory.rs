@@ -15,7 +15,7 @@ use dozer_sql_expression::{
 use dozer_types::{
     errors::internal::BoxedError,
     tonic::async_trait,
-    types::{FieldDefinition, Schema},
+    types::{FieldDefinition, FieldType, Schema},
 };
 
 use crate::errors::JoinError;
@@ -36,6 +36,8 @@ pub struct JoinProcessorFactory {
     left: Option<NameOrAlias>,
     right: Option<NameOrAlias>,
     join_operator: SqlJoinOperator,
+    /// The left time of `JOIN <right> FOR SYSTEM_TIME AS OF <left time>`
+    as_of: Option<SqlExpr>,
     enable_probabilistic_optimizations: bool,
 }
 
@@ -45,6 +47,7 @@ impl JoinProcessorFactory {
         left: Option<NameOrAlias>,
         right: Option<NameOrAlias>,
         join_operator: SqlJoinOperator,
+        as_of: Option<SqlExpr>,
         enable_probabilistic_optimizations: bool,
     ) -> Self {
         Self {
@@ -52,6 +55,7 @@ impl JoinProcessorFactory {
             left,
             right,
             join_operator,
+            as_of,
             enable_probabilistic_optimizations,
         }
     }
@@ -113,21 +117,14 @@ impl ProcessorFactory for JoinProcessorFactory {
         checkpoint_data: Option<Vec<u8>>,
     ) -> Result<Box<dyn Processor>, BoxedError> {
         let (join_type, join_constraint) = match &self.join_operator {
-            SqlJoinOperator::Inner(constraint) => (JoinType::Inner, constraint),
-            SqlJoinOperator::LeftOuter(constraint) => (JoinType::LeftOuter, constraint),
-            SqlJoinOperator::RightOuter(constraint) => (JoinType::RightOuter, constraint),
+            SqlJoinOperator::Inner(constraint) => (JoinType::Inner, Some(constraint)),
+            SqlJoinOperator::LeftOuter(constraint) => (JoinType::LeftOuter, Some(constraint)),
+            SqlJoinOperator::RightOuter(constraint) => (JoinType::RightOuter, Some(constraint)),
+            SqlJoinOperator::FullOuter(constraint) => (JoinType::FullOuter, Some(constraint)),
+            SqlJoinOperator::CrossJoin => (JoinType::Cross, None),
             _ => return Err(PipelineError::JoinError(JoinError::UnsupportedJoinType).into()),
         };
 
-        let expression = match join_constraint {
-            SqlJoinConstraint::On(expression) => expression,
-            _ => {
-                return Err(
-                    PipelineError::JoinError(JoinError::UnsupportedJoinConstraintType).into(),
-                )
-            }
-        };
-
         let mut left_schema = input_schemas
             .get(&LEFT_JOIN_PORT)
             .ok_or(PipelineError::InternalError(
@@ -148,8 +145,54 @@ impl ProcessorFactory for JoinProcessorFactory {
             right_schema = extend_schema_source_def(&right_schema, right_table_name);
         }
 
-        let (left_join_key_indexes, right_join_key_indexes) =
-            parse_join_constraint(expression, &left_schema, &right_schema)?;
+        let (join_type, join_constraint) = match &self.as_of {
+            None => (join_type, join_constraint.cloned()),
+            Some(as_of) => {
+                if join_type != JoinType::Inner {
+                    return Err(PipelineError::JoinError(JoinError::InvalidAsOfJoin(
+                        as_of.to_string(),
+                    ))
+                    .into());
+                }
+                let (join_constraint, right_time_index) = match join_constraint {
+                    Some(SqlJoinConstraint::On(expression)) => {
+                        let (expression, right_time_index) =
+                            parse_as_of_constraint(expression, as_of, &left_schema, &right_schema)?;
+                        (expression.map(SqlJoinConstraint::On), right_time_index)
+                    }
+                    Some(_) => {
+                        return Err(PipelineError::JoinError(
+                            JoinError::UnsupportedJoinConstraintType,
+                        )
+                        .into())
+                    }
+                    None => (None, None),
+                };
+                let (left_time_index, right_time_index) =
+                    get_as_of_time_indexes(as_of, right_time_index, &left_schema, &right_schema)?;
+                (
+                    JoinType::AsOf {
+                        left_time_index,
+                        right_time_index,
+                    },
+                    join_constraint,
+                )
+            }
+        };
+
+        let (left_join_key_indexes, right_join_key_indexes) = match &join_constraint {
+            Some(SqlJoinConstraint::On(expression)) => {
+                parse_join_constraint(expression, &left_schema, &right_schema)?
+            }
+            // CROSS JOIN matches all records, so every record gets the same, empty, join key.
+            // So does an AS OF join without equality conditions.
+            None => (vec![], vec![]),
+            _ => {
+                return Err(
+                    PipelineError::JoinError(JoinError::UnsupportedJoinConstraintType).into(),
+                )
+            }
+        };
 
         let join_operator = JoinOperator::new(
             join_type,
@@ -190,6 +233,100 @@ fn append_schema(left_schema: &Schema, right_schema: &Schema) -> Schema {
     output_schema
 }
 
+/// Splits the `<right time column> <= <as of time>` condition off the constraint of an AS OF join.
+///
+/// Returns the remaining constraint, if any, and the index of the right time column.
+fn parse_as_of_constraint(
+    expression: &SqlExpr,
+    as_of: &SqlExpr,
+    left_join_table: &Schema,
+    right_join_table: &Schema,
+) -> Result<(Option<SqlExpr>, Option<usize>), JoinError> {
+    match expression {
+        SqlExpr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            let (left_expression, left_time_index) =
+                parse_as_of_constraint(left, as_of, left_join_table, right_join_table)?;
+            let (right_expression, right_time_index) =
+                parse_as_of_constraint(right, as_of, left_join_table, right_join_table)?;
+            let time_index = match (left_time_index, right_time_index) {
+                (Some(_), Some(_)) => {
+                    return Err(JoinError::InvalidAsOfJoin(expression.to_string()))
+                }
+                (time_index, None) | (None, time_index) => time_index,
+            };
+            let expression = match (left_expression, right_expression) {
+                (Some(left), Some(right)) => Some(SqlExpr::BinaryOp {
+                    left: Box::new(left),
+                    op: BinaryOperator::And,
+                    right: Box::new(right),
+                }),
+                (expression, None) | (None, expression) => expression,
+            };
+            Ok((expression, time_index))
+        }
+        SqlExpr::BinaryOp {
+            left: version,
+            op: BinaryOperator::LtEq,
+            right: time,
+        }
+        | SqlExpr::BinaryOp {
+            left: time,
+            op: BinaryOperator::GtEq,
+            right: version,
+        } if time.as_ref() == as_of => {
+            let time_index = parse_join_eq_expression(version, left_join_table, right_join_table)?
+                .1
+                .pop()
+                .ok_or_else(|| JoinError::InvalidAsOfJoin(expression.to_string()))?;
+            Ok((None, Some(time_index)))
+        }
+        _ => Ok((Some(expression.clone()), None)),
+    }
+}
+
+/// Returns the indexes of the left and right time columns of an AS OF join.
+///
+/// Without a time condition in the constraint, the right time column is the only timestamp or
+/// date column of the right table.
+fn get_as_of_time_indexes(
+    as_of: &SqlExpr,
+    right_time_index: Option<usize>,
+    left_join_table: &Schema,
+    right_join_table: &Schema,
+) -> Result<(usize, usize), JoinError> {
+    let invalid_as_of_join = || JoinError::InvalidAsOfJoin(as_of.to_string());
+    let left_time_index = parse_join_eq_expression(as_of, left_join_table, right_join_table)?
+        .0
+        .pop()
+        .ok_or_else(invalid_as_of_join)?;
+    let right_time_index = match right_time_index {
+        Some(right_time_index) => right_time_index,
+        None => {
+            let mut time_indexes = right_join_table
+                .fields
+                .iter()
+                .enumerate()
+                .filter(|(_, field)| matches!(field.typ, FieldType::Timestamp | FieldType::Date))
+                .map(|(index, _)| index);
+            match (time_indexes.next(), time_indexes.next()) {
+                (Some(index), None) => index,
+                _ => return Err(invalid_as_of_join()),
+            }
+        }
+    };
+
+    let left_type = left_join_table.fields[left_time_index].typ;
+    let right_type = right_join_table.fields[right_time_index].typ;
+    if left_type != right_type || !matches!(left_type, FieldType::Timestamp | FieldType::Date) {
+        return Err(JoinError::InvalidAsOfJoinTimeType(left_type, right_type));
+    }
+    Ok((left_time_index, right_time_index))
+}
+
 fn parse_join_constraint(
     expression: &dozer_sql_expression::sqlparser::ast::Expr,
     left_join_table: &Schema,
@@ -4,5 +4,6 @@ pub mod factory;
 
 pub(crate) mod operator;
 mod processor;
+pub(crate) mod temporal;
 
 type JoinResult<T> = Result<T, JoinError>;
@@ -1,8 +1,8 @@
 use dozer_core::{
-    checkpoint::serialize::{Cursor, SerializationError},
+    checkpoint::serialize::{deserialize_bincode, serialize_bincode, Cursor, SerializationError},
     dozer_log::storage::Object,
 };
-use dozer_types::types::{Record, Schema, Timestamp};
+use dozer_types::types::{Field, Record, Schema, Timestamp};
 
 use crate::errors::JoinError;
 
@@ -21,6 +21,19 @@ pub enum JoinType {
     Inner,
     LeftOuter,
     RightOuter,
+    FullOuter,
+    /// Joins every record with every record of the other side, using an empty join key
+    Cross,
+    /// Joins every left record with the version of the matching right records that was valid at
+    /// the left record time, i.e. the one with the latest time not after it.
+    /// Changes to the right side don't retract joins that were already emitted.
+    ///
+    /// Right versions that were superseded before the latest left time are dropped, so late left
+    /// records don't join with them anymore.
+    AsOf {
+        left_time_index: usize,
+        right_time_index: usize,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -37,6 +50,9 @@ pub struct JoinOperator {
 
     left: JoinTable,
     right: JoinTable,
+
+    /// Latest left record time seen by an AS OF join
+    watermark: Option<Field>,
 }
 
 impl JoinOperator {
@@ -61,10 +77,15 @@ impl JoinOperator {
             accurate_keys,
             cursor.as_mut(),
         )?;
+        let watermark = match (&join_type, cursor.as_mut()) {
+            (JoinType::AsOf { .. }, Some(cursor)) => deserialize_bincode(cursor)?,
+            _ => None,
+        };
         Ok(Self {
             join_type,
             left,
             right,
+            watermark,
         })
     }
 
@@ -158,6 +179,80 @@ impl JoinOperator {
         output_records
     }
 
+    fn full_outer_join(
+        &self,
+        action: JoinAction,
+        join_key: &JoinKey,
+        record: &Record,
+        record_branch: JoinBranch,
+    ) -> Vec<(JoinAction, Record)> {
+        let output_records = self.outer_join(action, join_key, record, record_branch);
+        if !output_records.is_empty() {
+            return output_records;
+        }
+        // No record of the other side matches, so this record is joined with the default record
+        self.inner_join(action, join_key, record, record_branch, true)
+    }
+
+    fn as_of_join(&self, join_key: &JoinKey, record: &Record) -> Option<Record> {
+        let JoinType::AsOf {
+            left_time_index,
+            right_time_index,
+        } = self.join_type
+        else {
+            return None;
+        };
+        let time = &record.values[left_time_index];
+        if time == &Field::Null {
+            return None;
+        }
+
+        let version = self
+            .right
+            .get_matching_records(join_key, false)
+            .filter(|version| {
+                let version_time = &version.values[right_time_index];
+                version_time != &Field::Null && version_time <= time
+            })
+            // Versions valid from the same time are ordered by their values, so the same one is
+            // picked whatever the order they are stored in.
+            .max_by(|a, b| {
+                a.values[right_time_index]
+                    .cmp(&b.values[right_time_index])
+                    .then_with(|| a.values.cmp(&b.values))
+            })?;
+        Some(create_join_records_fn(record, JoinBranch::Left)(version))
+    }
+
+    /// Drops the right versions of `join_key` that no left record at or after the watermark can
+    /// join with anymore.
+    fn prune_versions(&mut self, join_key: &JoinKey) {
+        let (
+            JoinType::AsOf {
+                right_time_index, ..
+            },
+            Some(watermark),
+        ) = (&self.join_type, &self.watermark)
+        else {
+            return;
+        };
+        let right_time_index = *right_time_index;
+        let Some(oldest) = self
+            .right
+            .get_matching_records(join_key, false)
+            .map(|version| &version.values[right_time_index])
+            .filter(|time| *time != &Field::Null && *time <= watermark)
+            .max()
+            .cloned()
+        else {
+            return;
+        };
+        self.right.retain_matching_records(join_key, |version| {
+            let time = &version.values[right_time_index];
+            time == &Field::Null || time >= &oldest
+        });
+    }
+
     fn join(
         &self,
         action: JoinAction,
@@ -166,7 +261,9 @@ impl JoinOperator {
         record_branch: JoinBranch,
     ) -> Vec<(JoinAction, Record)> {
         match (&self.join_type, record_branch) {
-            (JoinType::Inner, _) => self.inner_join(action, join_key, record, record_branch, false),
+            (JoinType::Inner | JoinType::Cross, _) => {
+                self.inner_join(action, join_key, record, record_branch, false)
+            }
             (JoinType::LeftOuter, JoinBranch::Left) => {
                 self.inner_join(action, join_key, record, JoinBranch::Left, true)
             }
@@ -179,6 +276,12 @@ impl JoinOperator {
             (JoinType::RightOuter, JoinBranch::Right) => {
                 self.inner_join(action, join_key, record, JoinBranch::Right, true)
             }
+            (JoinType::FullOuter, _) => {
+                self.full_outer_join(action, join_key, record, record_branch)
+            }
+            (JoinType::AsOf { .. }, _) => {
+                unreachable!("AS OF joins are handled when inserting and deleting records")
+            }
         }
     }
 
@@ -188,7 +291,23 @@ impl JoinOperator {
         old: &Record,
         old_decoded: &Record,
     ) -> Vec<(JoinAction, Record)> {
-        let join_key = match from {
+        if let JoinType::AsOf { .. } = self.join_type {
+            return match from {
+                // The left table keeps the joined records that were emitted
+                JoinBranch::Left => self
+                    .left
+                    .remove(old_decoded)
+                    .1
+                    .map(|joined| vec![(JoinAction::Delete, joined)])
+                    .unwrap_or_default(),
+                JoinBranch::Right => {
+                    self.right.remove(old_decoded);
+                    vec![]
+                }
+            };
+        }
+
+        let (join_key, _) = match from {
             JoinBranch::Left => self.left.remove(old_decoded),
             JoinBranch::Right => self.right.remove(old_decoded),
         };
@@ -202,6 +321,34 @@ impl JoinOperator {
         new: &Record,
         new_decoded: &Record,
     ) -> JoinResult<Vec<(JoinAction, Record)>> {
+        if let JoinType::AsOf { .. } = self.join_type {
+            return match from {
+                JoinBranch::Left => {
+                    let join_key = self.left.get_join_key(new_decoded);
+                    if let JoinType::AsOf {
+                        left_time_index, ..
+                    } = self.join_type
+                    {
+                        let time = &new.values[left_time_index];
+                        if time != &Field::Null && self.watermark.as_ref() < Some(time) {
+                            self.watermark = Some(time.clone());
+                        }
+                    }
+                    self.prune_versions(&join_key);
+                    let Some(joined) = self.as_of_join(&join_key, new) else {
+                        return Ok(vec![]);
+                    };
+                    self.left.insert(joined.clone(), new_decoded)?;
+                    Ok(vec![(JoinAction::Insert, joined)])
+                }
+                JoinBranch::Right => {
+                    let join_key = self.right.insert(new.clone(), new_decoded)?;
+                    self.prune_versions(&join_key);
+                    Ok(vec![])
+                }
+            };
+        }
+
         let join_key = match from {
             JoinBranch::Left => self.left.insert(new.clone(), new_decoded)?,
             JoinBranch::Right => self.right.insert(new.clone(), new_decoded)?,
@@ -210,6 +357,26 @@ impl JoinOperator {
         Ok(self.join(JoinAction::Insert, &join_key, new, from))
     }
 
+    pub fn update(
+        &mut self,
+        from: JoinBranch,
+        old: &Record,
+        new: &Record,
+    ) -> JoinResult<Vec<(JoinAction, Record)>> {
+        // Updates add a new version of the right record, and keep the previous ones for left
+        // records that come late
+        if matches!(
+            (&self.join_type, from),
+            (JoinType::AsOf { .. }, JoinBranch::Right)
+        ) {
+            return self.insert(from, new, new);
+        }
+
+        let mut records = self.delete(from, old, old);
+        records.extend(self.insert(from, new, new)?);
+        Ok(records)
+    }
+
     pub fn evict_index(&mut self, now: &Timestamp) {
         self.left.evict_index(now);
         self.right.evict_index(now);
@@ -218,6 +385,9 @@ impl JoinOperator {
     pub fn serialize(&self, mut object: Object) -> Result<(), SerializationError> {
         self.left.serialize(&mut object)?;
         self.right.serialize(&mut object)?;
+        if let JoinType::AsOf { .. } = self.join_type {
+            serialize_bincode(&self.watermark, &mut object)?;
+        }
         Ok(())
     }
 }
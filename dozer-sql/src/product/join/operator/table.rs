@@ -130,13 +130,32 @@ impl JoinTable {
         Ok(join_key)
     }
 
-    pub fn remove(&mut self, record: &Record) -> JoinKey {
+    /// Removes the last record inserted with the primary key of `record`, and returns it.
+    pub fn remove(&mut self, record: &Record) -> (JoinKey, Option<Record>) {
         let join_key = self.get_join_key(record);
+        let mut removed = None;
         if let hash_map::Entry::Occupied(record_map) = self.map.entry(join_key.clone()) {
             let primary_key = get_record_key_hash(record, &self.primary_key_indexes);
-            remove_record_using_primary_key(record_map, primary_key);
+            removed = remove_record_using_primary_key(record_map, primary_key);
+        }
+        (join_key, removed)
+    }
+
+    /// Removes the records of `join_key` for which `keep` returns false.
+    pub fn retain_matching_records(
+        &mut self,
+        join_key: &JoinKey,
+        mut keep: impl FnMut(&Record) -> bool,
+    ) {
+        if let hash_map::Entry::Occupied(mut record_map) = self.map.entry(join_key.clone()) {
+            record_map.get_mut().retain(|_, records| {
+                records.retain(|record| keep(record));
+                !records.is_empty()
+            });
+            if record_map.get().is_empty() {
+                record_map.remove();
+            }
         }
-        join_key
     }
 
     pub fn evict_index(&mut self, now: &Timestamp) {
@@ -167,7 +186,7 @@ impl JoinTable {
         Ok(())
     }
 
-    fn get_join_key(&self, record: &Record) -> JoinKey {
+    pub fn get_join_key(&self, record: &Record) -> JoinKey {
         if self.accurate_keys {
             JoinKey::Accurate(get_record_key_fields(record, &self.join_key_indexes))
         } else {
@@ -210,9 +229,10 @@ fn get_record_key_fields(record: &Record, key_indexes: &[usize]) -> Vec<Field> {
 fn remove_record_using_primary_key(
     mut record_map: hash_map::OccupiedEntry<JoinKey, HashMap<u64, Vec<Record>>>,
     primary_key: u64,
-) {
+) -> Option<Record> {
+    let mut removed = None;
     if let hash_map::Entry::Occupied(mut record_vec) = record_map.get_mut().entry(primary_key) {
-        record_vec.get_mut().pop();
+        removed = record_vec.get_mut().pop();
         if record_vec.get().is_empty() {
             record_vec.remove();
         }
@@ -221,6 +241,7 @@ fn remove_record_using_primary_key(
     if record_map.get().is_empty() {
         record_map.remove();
     }
+    removed
 }
 
 fn serialize_join_map(
@@ -316,7 +337,8 @@ mod tests {
         assert_eq!(table.get_matching_records(&join_key, true).count(), 1);
         assert_eq!(table.get_matching_records(&join_key, false).count(), 1);
 
-        let join_key = table.remove(&record);
+        let (join_key, removed) = table.remove(&record);
+        assert_eq!(removed, Some(record.clone()));
         assert_eq!(table.get_matching_records(&join_key, true).count(), 1);
         assert_eq!(table.get_matching_records(&join_key, false).count(), 0);
     }
@@ -106,15 +106,9 @@ impl Processor for ProductProcessor {
                     self.update_eviction_index(lifetime);
                 }
 
-                let mut old_records = self.join_operator.delete(from_branch, &old, &old);
-
-                let new_records = self
-                    .join_operator
-                    .insert(from_branch, &new, &new)
-                    .map_err(PipelineError::JoinError)?;
-
-                old_records.extend(new_records);
-                old_records
+                self.join_operator
+                    .update(from_branch, &old, &new)
+                    .map_err(PipelineError::JoinError)?
             }
             Operation::BatchInsert { new } => {
                 for record in &new {
@@ -183,11 +177,15 @@ mod tests {
     use dozer_core::node::ProcessorFactory;
     use dozer_sql_expression::builder::NameOrAlias;
     use dozer_sql_expression::sqlparser::ast::JoinOperator as SqlJoinOperator;
-    use dozer_types::types::{Field, FieldDefinition, Record, Schema};
+    use dozer_types::{
+        chrono::{DateTime, TimeZone, Utc},
+        types::{Field, FieldDefinition, FieldType, Record, Schema},
+    };
 
     use crate::product::join::{
         factory::{LEFT_JOIN_PORT, RIGHT_JOIN_PORT},
         operator::JoinType,
+        temporal::get_system_time_as_of,
     };
     use crate::{product::join::factory::JoinProcessorFactory, tests::utils::get_select};
 
@@ -203,7 +201,7 @@ mod tests {
         }
     }
 
-    fn create_schema(table_name: &'static str) -> Schema {
+    fn create_schema(table_name: &'static str, data_type: FieldType) -> Schema {
         let mut schema = Schema::new();
         schema
             .field(
@@ -221,7 +219,7 @@ mod tests {
             .field(
                 FieldDefinition {
                     name: "data".into(),
-                    typ: dozer_types::types::FieldType::UInt,
+                    typ: data_type,
                     nullable: false,
                     source: dozer_types::types::SourceDefinition::Table {
                         connection: "test".into(),
@@ -245,13 +243,14 @@ mod tests {
 
     impl Executor {
         async fn new(kind: JoinType) -> Self {
-            let left_schema = create_schema("left");
-            let right_schema = create_schema("right");
+            let (sql, data_type) = match kind {
+                JoinType::AsOf { .. } => ("SELECT left.joinkey FROM left INNER JOIN right FOR SYSTEM_TIME AS OF left.data ON left.joinkey = right.joinkey", FieldType::Timestamp),
+                _ => ("SELECT left.joinkey FROM left INNER JOIN right ON left.joinkey = right.joinkey", FieldType::UInt),
+            };
+            let left_schema = create_schema("left", data_type);
+            let right_schema = create_schema("right", data_type);
 
-            let stmt = get_select(
-                "SELECT left.joinkey FROM left INNER JOIN right ON left.joinkey = right.joinkey",
-            )
-            .unwrap();
+            let stmt = get_select(sql).unwrap();
             let join = &stmt.from[0].joins[0];
             let join_op = join.join_operator.clone();
             let SqlJoinOperator::Inner(constraint) = join_op else {
@@ -261,12 +260,16 @@ mod tests {
                 JoinType::Inner => SqlJoinOperator::Inner(constraint),
                 JoinType::LeftOuter => SqlJoinOperator::LeftOuter(constraint),
                 JoinType::RightOuter => SqlJoinOperator::RightOuter(constraint),
+                JoinType::FullOuter => SqlJoinOperator::FullOuter(constraint),
+                JoinType::Cross => SqlJoinOperator::CrossJoin,
+                JoinType::AsOf { .. } => SqlJoinOperator::Inner(constraint),
             };
             let factory = JoinProcessorFactory::new(
                 "test".into(),
                 Some(NameOrAlias("left".into(), None)),
                 Some(NameOrAlias("right".into(), None)),
                 join_op,
+                get_system_time_as_of(&join.relation).cloned(),
                 false,
             );
 
@@ -330,6 +333,10 @@ mod tests {
         }
     }
 
+    fn timestamp(seconds: i64) -> Field {
+        Field::Timestamp(DateTime::from(Utc.timestamp_opt(seconds, 0).unwrap()))
+    }
+
     fn join_record(left: Record, right: Record) -> Record {
         let mut values = left.values;
         values.extend(right.values);
@@ -498,4 +505,164 @@ mod tests {
             },]
         );
     }
+
+    #[tokio::test]
+    async fn test_full_outer_join() {
+        let mut exec = Executor::new(JoinType::FullOuter).await;
+
+        let null_record = Record::new(vec![Field::Null, Field::Null]);
+
+        let (left_record, ops) = exec.insert(JoinSide::Left, &[Field::UInt(0), Field::UInt(1)]);
+        assert_eq!(
+            ops,
+            &[Operation::Insert {
+                new: join_record(left_record.clone(), null_record.clone())
+            }]
+        );
+
+        let (right_record, ops) = exec.insert(JoinSide::Right, &[Field::UInt(0), Field::UInt(2)]);
+        assert_eq!(
+            ops,
+            &[
+                Operation::Delete {
+                    old: join_record(left_record.clone(), null_record.clone()),
+                },
+                Operation::Insert {
+                    new: join_record(left_record.clone(), right_record.clone())
+                }
+            ]
+        );
+
+        assert_eq!(
+            exec.delete(JoinSide::Left, left_record.clone()),
+            &[
+                Operation::Delete {
+                    old: join_record(left_record.clone(), right_record.clone())
+                },
+                Operation::Insert {
+                    new: join_record(null_record.clone(), right_record.clone())
+                },
+            ]
+        );
+
+        assert_eq!(
+            exec.delete(JoinSide::Right, right_record.clone()),
+            &[Operation::Delete {
+                old: join_record(null_record.clone(), right_record.clone())
+            },]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cross_join() {
+        let mut exec = Executor::new(JoinType::Cross).await;
+
+        let (first_left_record, ops) =
+            exec.insert(JoinSide::Left, &[Field::UInt(0), Field::UInt(1)]);
+        assert_eq!(ops, &[]);
+
+        let (right_record, ops) = exec.insert(JoinSide::Right, &[Field::UInt(5), Field::UInt(6)]);
+        assert_eq!(
+            ops,
+            &[Operation::Insert {
+                new: join_record(first_left_record.clone(), right_record.clone())
+            }]
+        );
+
+        let (second_left_record, ops) =
+            exec.insert(JoinSide::Left, &[Field::UInt(1), Field::UInt(1)]);
+        assert_eq!(
+            ops,
+            &[Operation::Insert {
+                new: join_record(second_left_record.clone(), right_record.clone())
+            }]
+        );
+
+        assert_eq!(
+            exec.delete(JoinSide::Left, first_left_record.clone()),
+            &[Operation::Delete {
+                old: join_record(first_left_record.clone(), right_record.clone())
+            },]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_as_of_join() {
+        let mut exec = Executor::new(JoinType::AsOf {
+            left_time_index: 1,
+            right_time_index: 1,
+        })
+        .await;
+
+        let (first_version, ops) = exec.insert(JoinSide::Right, &[Field::UInt(0), timestamp(10)]);
+        assert_eq!(ops, &[]);
+        let (second_version, ops) = exec.insert(JoinSide::Right, &[Field::UInt(0), timestamp(20)]);
+        assert_eq!(ops, &[]);
+
+        let (fact, ops) = exec.insert(JoinSide::Left, &[Field::UInt(0), timestamp(15)]);
+        assert_eq!(
+            ops,
+            &[Operation::Insert {
+                new: join_record(fact.clone(), first_version.clone())
+            }]
+        );
+        assert_eq!(
+            exec.delete(JoinSide::Left, fact.clone()),
+            &[Operation::Delete {
+                old: join_record(fact.clone(), first_version.clone())
+            }]
+        );
+
+        // Facts coming before any version don't join
+        let (early_fact, ops) = exec.insert(JoinSide::Left, &[Field::UInt(0), timestamp(5)]);
+        assert_eq!(ops, &[]);
+        assert_eq!(exec.delete(JoinSide::Left, early_fact), &[]);
+
+        // A late version is used by facts coming after it
+        let (late_version, ops) = exec.insert(JoinSide::Right, &[Field::UInt(0), timestamp(12)]);
+        assert_eq!(ops, &[]);
+        let (fact, ops) = exec.insert(JoinSide::Left, &[Field::UInt(0), timestamp(15)]);
+        assert_eq!(
+            ops,
+            &[Operation::Insert {
+                new: join_record(fact.clone(), late_version.clone())
+            }]
+        );
+
+        let (new_fact, ops) = exec.update(
+            JoinSide::Left,
+            fact.clone(),
+            &[Field::UInt(0), timestamp(25)],
+        );
+        assert_eq!(
+            ops,
+            &[
+                Operation::Delete {
+                    old: join_record(fact.clone(), late_version.clone())
+                },
+                Operation::Insert {
+                    new: join_record(new_fact.clone(), second_version.clone())
+                }
+            ]
+        );
+
+        // Updating the right side doesn't retract the joined records
+        let (_, ops) = exec.update(
+            JoinSide::Right,
+            second_version.clone(),
+            &[Field::UInt(0), timestamp(30)],
+        );
+        assert_eq!(ops, &[]);
+        assert_eq!(
+            exec.delete(JoinSide::Left, new_fact.clone()),
+            &[Operation::Delete {
+                old: join_record(new_fact.clone(), second_version.clone())
+            }]
+        );
+
+        // Versions superseded before the latest fact time are dropped, so late facts can't join
+        // with them anymore
+        let (_, ops) = exec.insert(JoinSide::Left, &[Field::UInt(0), timestamp(15)]);
+        assert_eq!(ops, &[]);
+    }
 }
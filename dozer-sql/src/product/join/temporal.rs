@@ -0,0 +1,144 @@
+use dozer_sql_expression::{
+    builder::ExpressionBuilder,
+    sqlparser::{
+        ast::{Expr as SqlExpr, FunctionArg, FunctionArgExpr, Statement, TableFactor},
+        dialect::Dialect,
+        keywords::{Keyword, RESERVED_FOR_TABLE_ALIAS},
+        parser::{Parser, ParserError},
+        tokenizer::{Token, Tokenizer, Whitespace},
+    },
+};
+
+/// Name of the table hint that `FOR SYSTEM_TIME AS OF <expr>` is rewritten to.
+const SYSTEM_TIME_AS_OF_HINT: &str = "SYSTEM_TIME_AS_OF";
+
+const SYSTEM_TIME_AS_OF: [Keyword; 4] =
+    [Keyword::FOR, Keyword::SYSTEM_TIME, Keyword::AS, Keyword::OF];
+
+/// Parses `sql`, accepting `<table> FOR SYSTEM_TIME AS OF <expr> [[AS] <alias>]` table factors.
+///
+/// The parser doesn't support the clause, so it is rewritten to the
+/// `<table> [[AS] <alias>] WITH (SYSTEM_TIME_AS_OF(<expr>))` table hint first.
+/// The rewrite can go once our sqlparser fork parses table versions with `DozerDialect`, which
+/// upstream only does for BigQuery and MSSQL since 0.37.
+pub fn parse_sql(dialect: &dyn Dialect, sql: &str) -> Result<Vec<Statement>, ParserError> {
+    let mut tokens = Tokenizer::new(dialect, sql).tokenize()?;
+    let mut position = 0;
+    while let Some((start, expr_start)) = find_system_time_as_of(&tokens, position) {
+        let mut parser = Parser::new(dialect).with_tokens(tokens[expr_start..].to_vec());
+        parser.parse_expr()?;
+        let expr_end = expr_start + parser.index();
+        let alias_end = find_alias_end(&tokens, expr_end);
+
+        let mut rewritten = tokens[..start].to_vec();
+        rewritten.extend_from_slice(&tokens[expr_end..alias_end]);
+        rewritten.extend([
+            Token::Whitespace(Whitespace::Space),
+            Token::make_keyword("WITH"),
+            Token::LParen,
+            Token::make_word(SYSTEM_TIME_AS_OF_HINT, None),
+            Token::LParen,
+        ]);
+        rewritten.extend_from_slice(&tokens[expr_start..expr_end]);
+        rewritten.extend([Token::RParen, Token::RParen]);
+        position = rewritten.len();
+        rewritten.extend_from_slice(&tokens[alias_end..]);
+        tokens = rewritten;
+    }
+    Parser::new(dialect).with_tokens(tokens).parse_statements()
+}
+
+/// Returns the `<expr>` of the `FOR SYSTEM_TIME AS OF <expr>` hint of `table`, if any.
+pub fn get_system_time_as_of(table: &TableFactor) -> Option<&SqlExpr> {
+    let TableFactor::Table { with_hints, .. } = table else {
+        return None;
+    };
+    with_hints.iter().find_map(|hint| match hint {
+        SqlExpr::Function(function)
+            if ExpressionBuilder::fullname_from_ident(&function.name.0)
+                .eq_ignore_ascii_case(SYSTEM_TIME_AS_OF_HINT) =>
+        {
+            match function.args.as_slice() {
+                [FunctionArg::Unnamed(FunctionArgExpr::Expr(expr))] => Some(expr),
+                _ => None,
+            }
+        }
+        _ => None,
+    })
+}
+
+/// Returns the index of the `FOR` keyword and the index after the `OF` keyword of the first
+/// `FOR SYSTEM_TIME AS OF` at or after `position`.
+fn find_system_time_as_of(tokens: &[Token], position: usize) -> Option<(usize, usize)> {
+    (position..tokens.len()).find_map(|start| {
+        let mut index = start;
+        for keyword in SYSTEM_TIME_AS_OF {
+            index = skip_whitespace(tokens, index);
+            match tokens.get(index) {
+                Some(Token::Word(word)) if word.keyword == keyword => index += 1,
+                _ => return None,
+            }
+        }
+        Some((start, index))
+    })
+}
+
+/// Returns the index after the optional `[AS] <alias>` starting at `position`.
+fn find_alias_end(tokens: &[Token], position: usize) -> usize {
+    let index = skip_whitespace(tokens, position);
+    match tokens.get(index) {
+        Some(Token::Word(word)) if word.keyword == Keyword::AS => {
+            let index = skip_whitespace(tokens, index + 1);
+            match tokens.get(index) {
+                Some(Token::Word(_)) => index + 1,
+                _ => position,
+            }
+        }
+        Some(Token::Word(word)) if !RESERVED_FOR_TABLE_ALIAS.contains(&word.keyword) => index + 1,
+        _ => position,
+    }
+}
+
+fn skip_whitespace(tokens: &[Token], mut index: usize) -> usize {
+    while let Some(Token::Whitespace(_)) = tokens.get(index) {
+        index += 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_sql_expression::sqlparser::{ast::SetExpr, dialect::DozerDialect};
+
+    use super::*;
+
+    fn get_right_table(sql: &str) -> TableFactor {
+        let statement = parse_sql(&DozerDialect {}, sql).unwrap().remove(0);
+        let Statement::Query(query) = statement else {
+            panic!("expected a query");
+        };
+        let SetExpr::Select(select) = *query.body else {
+            panic!("expected a select");
+        };
+        select.from[0].joins[0].relation.clone()
+    }
+
+    #[test]
+    fn test_parse_system_time_as_of() {
+        let table =
+            get_right_table("SELECT * FROM e JOIN dim FOR SYSTEM_TIME AS OF e.ts ON e.k = dim.k");
+        assert_eq!(get_system_time_as_of(&table).unwrap().to_string(), "e.ts");
+
+        let table = get_right_table(
+            "SELECT * FROM e JOIN dim FOR SYSTEM_TIME AS OF e.ts AS d ON e.k = d.k WHERE e.k > 0",
+        );
+        assert_eq!(get_system_time_as_of(&table).unwrap().to_string(), "e.ts");
+        let TableFactor::Table { alias, .. } = &table else {
+            panic!("expected a table");
+        };
+        assert_eq!(alias.as_ref().unwrap().name.value, "d");
+
+        let table = get_right_table("SELECT * FROM e JOIN dim d ON e.k = d.k");
+        assert_eq!(get_system_time_as_of(&table), None);
+    }
+}
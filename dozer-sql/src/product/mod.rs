@@ -1,4 +1,5 @@
 pub(crate) mod join;
+pub(crate) mod semi_join;
 pub(crate) mod set;
 pub(crate) mod table;
 pub mod tests;
@@ -0,0 +1,225 @@
+use std::{collections::HashMap, sync::Arc};
+
+use dozer_core::{
+    node::{PortHandle, Processor, ProcessorFactory},
+    DEFAULT_PORT_HANDLE,
+};
+use dozer_sql_expression::{
+    builder::ExpressionBuilder, execution::Expression, sqlparser::ast::Expr as SqlExpr,
+};
+use dozer_types::{
+    errors::internal::BoxedError,
+    models::udf_config::UdfConfig,
+    tonic::async_trait,
+    types::{FieldDefinition, FieldType, Schema, SourceDefinition},
+};
+use tokio::runtime::Runtime;
+
+use crate::errors::{PipelineError, SemiJoinError};
+
+use super::{
+    operator::{KeyCasts, SemiJoinOperator, SubqueryPredicate},
+    processor::SemiJoinProcessor,
+};
+
+pub(crate) const LEFT_SEMI_JOIN_PORT: PortHandle = 0;
+pub(crate) const RIGHT_SEMI_JOIN_PORT: PortHandle = 1;
+
+/// A WHERE condition on a subquery, whose records come from the right port.
+#[derive(Debug, Clone)]
+pub enum SubqueryCondition {
+    /// `<expr> [NOT] IN (<subquery>)`
+    In { expr: SqlExpr, negated: bool },
+    /// `[NOT] EXISTS (<subquery>)`, where the subquery returns the values matching `keys`
+    /// as its first columns.
+    Exists { keys: Vec<SqlExpr>, negated: bool },
+    /// A condition where the single-row subquery has been replaced by the identifier `column`.
+    Scalar { condition: SqlExpr, column: String },
+}
+
+/// Filters the records of the left port by a [`SubqueryCondition`] on the records of the right
+/// port, as a semi-join, or an anti-join for `NOT IN` and `NOT EXISTS`.
+#[derive(Debug)]
+pub struct SemiJoinProcessorFactory {
+    id: String,
+    condition: SubqueryCondition,
+    udfs: Vec<UdfConfig>,
+    runtime: Arc<Runtime>,
+}
+
+impl SemiJoinProcessorFactory {
+    pub fn new(
+        id: String,
+        condition: SubqueryCondition,
+        udfs: Vec<UdfConfig>,
+        runtime: Arc<Runtime>,
+    ) -> Self {
+        Self {
+            id,
+            condition,
+            udfs,
+            runtime,
+        }
+    }
+
+    async fn build_expression(
+        &self,
+        expression: &SqlExpr,
+        schema: &Schema,
+    ) -> Result<Expression, SemiJoinError> {
+        ExpressionBuilder::new(schema.fields.len(), self.runtime.clone())
+            .build(false, expression, schema, &self.udfs)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn build_predicate(
+        &self,
+        left_schema: &Schema,
+        right_schema: &Schema,
+    ) -> Result<SubqueryPredicate, SemiJoinError> {
+        let check_columns = |expected: usize, exact: bool| {
+            let actual = right_schema.fields.len();
+            if actual < expected || (exact && actual != expected) {
+                Err(SemiJoinError::InvalidSubqueryColumns { expected, actual })
+            } else {
+                Ok(())
+            }
+        };
+
+        match &self.condition {
+            SubqueryCondition::In { expr, negated } => {
+                check_columns(1, true)?;
+                let key = self.build_expression(expr, left_schema).await?;
+                Ok(SubqueryPredicate::In {
+                    casts: get_key_casts(std::slice::from_ref(&key), left_schema, right_schema)?,
+                    key,
+                    negated: *negated,
+                })
+            }
+            SubqueryCondition::Exists { keys, negated } => {
+                check_columns(keys.len(), false)?;
+                let mut key_expressions = vec![];
+                for key in keys {
+                    key_expressions.push(self.build_expression(key, left_schema).await?);
+                }
+                Ok(SubqueryPredicate::Exists {
+                    casts: get_key_casts(&key_expressions, left_schema, right_schema)?,
+                    keys: key_expressions,
+                    negated: *negated,
+                })
+            }
+            SubqueryCondition::Scalar { condition, column } => {
+                check_columns(1, true)?;
+                let mut schema = left_schema.clone();
+                schema.field(
+                    FieldDefinition::new(
+                        column.clone(),
+                        right_schema.fields[0].typ,
+                        true,
+                        SourceDefinition::Dynamic,
+                    ),
+                    false,
+                );
+                Ok(SubqueryPredicate::Scalar {
+                    condition: self.build_expression(condition, &schema).await?,
+                    schema,
+                })
+            }
+        }
+    }
+}
+
+/// Returns the casts that make every key comparable with the matching subquery column.
+fn get_key_casts(
+    keys: &[Expression],
+    left_schema: &Schema,
+    right_schema: &Schema,
+) -> Result<KeyCasts, SemiJoinError> {
+    let mut casts = KeyCasts::default();
+    for (index, key) in keys.iter().enumerate() {
+        let left = key.get_type(left_schema)?.return_type;
+        let right = right_schema.fields[index].typ;
+        let common = get_common_key_type(left, right)
+            .ok_or(SemiJoinError::IncompatibleKeyTypes { index, left, right })?;
+        casts.left.push((left != common).then_some(common));
+        casts.right.push((right != common).then_some(common));
+    }
+    Ok(casts)
+}
+
+/// The type both sides of a key are compared as, if they can be compared.
+fn get_common_key_type(left: FieldType, right: FieldType) -> Option<FieldType> {
+    use FieldType::*;
+    match (left, right) {
+        _ if left == right => Some(left),
+        (String | Text, String | Text) => Some(String),
+        (Timestamp | Date, Timestamp | Date) => Some(Timestamp),
+        (
+            UInt | U128 | Int | I128 | Float | Decimal,
+            UInt | U128 | Int | I128 | Float | Decimal,
+        ) => Some(if left == Float || right == Float {
+            Float
+        } else if left == Decimal || right == Decimal {
+            Decimal
+        } else if matches!((left, right), (UInt | U128, UInt | U128)) {
+            U128
+        } else {
+            I128
+        }),
+        _ => None,
+    }
+}
+
+#[async_trait]
+impl ProcessorFactory for SemiJoinProcessorFactory {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn type_name(&self) -> String {
+        "SemiJoin".to_string()
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![LEFT_SEMI_JOIN_PORT, RIGHT_SEMI_JOIN_PORT]
+    }
+
+    fn get_output_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    async fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, Schema>,
+    ) -> Result<Schema, BoxedError> {
+        let schema = input_schemas
+            .get(&LEFT_SEMI_JOIN_PORT)
+            .ok_or(PipelineError::InvalidPortHandle(LEFT_SEMI_JOIN_PORT))?;
+        Ok(schema.clone())
+    }
+
+    async fn build(
+        &self,
+        input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+        checkpoint_data: Option<Vec<u8>>,
+    ) -> Result<Box<dyn Processor>, BoxedError> {
+        let left_schema = input_schemas
+            .get(&LEFT_SEMI_JOIN_PORT)
+            .ok_or(PipelineError::InvalidPortHandle(LEFT_SEMI_JOIN_PORT))?;
+        let right_schema = input_schemas
+            .get(&RIGHT_SEMI_JOIN_PORT)
+            .ok_or(PipelineError::InvalidPortHandle(RIGHT_SEMI_JOIN_PORT))?;
+
+        let predicate = self
+            .build_predicate(left_schema, right_schema)
+            .await
+            .map_err(PipelineError::SemiJoinError)?;
+        let operator = SemiJoinOperator::new(predicate, left_schema.clone(), checkpoint_data)
+            .map_err(PipelineError::SemiJoinError)?;
+
+        Ok(Box::new(SemiJoinProcessor::new(operator)))
+    }
+}
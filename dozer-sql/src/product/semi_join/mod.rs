@@ -0,0 +1,4 @@
+pub mod factory;
+
+pub(crate) mod operator;
+mod processor;
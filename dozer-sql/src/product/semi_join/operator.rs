@@ -0,0 +1,347 @@
+use std::collections::{hash_map, HashMap};
+
+use dozer_core::{
+    checkpoint::serialize::{
+        deserialize_record, deserialize_u64, serialize_record, serialize_u64, Cursor,
+        SerializationError,
+    },
+    dozer_log::storage::Object,
+};
+use dozer_sql_expression::{cast::cast_field, execution::Expression};
+use dozer_types::types::{Field, FieldType, Record, Schema};
+
+use crate::errors::SemiJoinError;
+use crate::product::join::operator::JoinAction;
+
+/// The condition a record of the outer query must fulfill against the records of the subquery.
+#[derive(Debug)]
+pub enum SubqueryPredicate {
+    /// `<key> [NOT] IN (<subquery>)`, matching the first column of the subquery.
+    In {
+        key: Expression,
+        casts: KeyCasts,
+        negated: bool,
+    },
+    /// `[NOT] EXISTS (<subquery>)`, where every key is matched against the corresponding column of
+    /// the subquery. Uncorrelated subqueries have no keys.
+    Exists {
+        keys: Vec<Expression>,
+        casts: KeyCasts,
+        negated: bool,
+    },
+    /// A condition on the value of a single-row subquery, which is the last field of `schema`.
+    Scalar {
+        condition: Expression,
+        schema: Schema,
+    },
+}
+
+/// The types the keys of each side are cast to, so they compare with the other side.
+/// `None` keeps the type of the key.
+#[derive(Debug, Default)]
+pub struct KeyCasts {
+    pub left: Vec<Option<FieldType>>,
+    pub right: Vec<Option<FieldType>>,
+}
+
+#[derive(Debug)]
+pub struct SemiJoinOperator {
+    predicate: SubqueryPredicate,
+    left_schema: Schema,
+    /// Records of the outer query, by key.
+    left: HashMap<Vec<Field>, Vec<Record>>,
+    /// Number of records of the subquery, by key.
+    right: HashMap<Vec<Field>, u64>,
+    right_count: u64,
+}
+
+impl SemiJoinOperator {
+    pub fn new(
+        predicate: SubqueryPredicate,
+        left_schema: Schema,
+        checkpoint_data: Option<Vec<u8>>,
+    ) -> Result<Self, SemiJoinError> {
+        let mut operator = Self {
+            predicate,
+            left_schema,
+            left: Default::default(),
+            right: Default::default(),
+            right_count: 0,
+        };
+
+        if let Some(data) = checkpoint_data {
+            let mut cursor = Cursor::new(&data);
+            let left_len = deserialize_u64(&mut cursor)?;
+            for _ in 0..left_len {
+                let record = deserialize_record(&mut cursor)?;
+                let key = operator.left_key(&record)?;
+                operator.left.entry(key).or_default().push(record);
+            }
+            let right_len = deserialize_u64(&mut cursor)?;
+            for _ in 0..right_len {
+                let key = deserialize_record(&mut cursor)?.values;
+                let count = deserialize_u64(&mut cursor)?;
+                operator.right.insert(key, count);
+                operator.right_count += count;
+            }
+        }
+
+        Ok(operator)
+    }
+
+    /// Adds a record of the outer query, and returns whether it fulfills the predicate.
+    pub fn insert_left(&mut self, record: &Record) -> Result<bool, SemiJoinError> {
+        let key = self.left_key(record)?;
+        let scalar = self.scalar_value()?;
+        let matches = self.matches(&key, record, &scalar)?;
+        self.left.entry(key).or_default().push(record.clone());
+        Ok(matches)
+    }
+
+    /// Removes a record of the outer query, and returns whether it fulfilled the predicate.
+    pub fn delete_left(&mut self, record: &Record) -> Result<bool, SemiJoinError> {
+        let key = self.left_key(record)?;
+        let scalar = self.scalar_value()?;
+        let matches = self.matches(&key, record, &scalar)?;
+        if let hash_map::Entry::Occupied(mut entry) = self.left.entry(key) {
+            let records = entry.get_mut();
+            if let Some(index) = records.iter().position(|existing| existing == record) {
+                records.swap_remove(index);
+            }
+            if records.is_empty() {
+                entry.remove();
+            }
+        }
+        Ok(matches)
+    }
+
+    /// Replaces the `old` record of the subquery with the `new` one, and returns the changes of
+    /// the outer query records that fulfill the predicate.
+    pub fn update_right(
+        &mut self,
+        old: Option<&Record>,
+        new: Option<&Record>,
+    ) -> Result<Vec<(JoinAction, Record)>, SemiJoinError> {
+        let old_key = old.map(|record| self.right_key(record)).transpose()?;
+        let new_key = new.map(|record| self.right_key(record)).transpose()?;
+        if old_key.is_some() && old_key == new_key {
+            return Ok(vec![]);
+        }
+
+        if let SubqueryPredicate::Scalar { .. } = self.predicate {
+            return self.update_scalar(old_key, new_key);
+        }
+
+        // A NULL in the subquery makes `NOT IN` unknown for every record of the outer query.
+        let affects_all_keys =
+            matches!(self.predicate, SubqueryPredicate::In { negated: true, .. })
+                && [&old_key, &new_key]
+                    .into_iter()
+                    .flatten()
+                    .any(|key| key.contains(&Field::Null));
+        let affected_keys = if affects_all_keys {
+            self.left.keys().cloned().collect::<Vec<_>>()
+        } else {
+            [old_key.clone(), new_key.clone()]
+                .into_iter()
+                .flatten()
+                .filter(|key| self.left.contains_key(key))
+                .collect()
+        };
+
+        let before = affected_keys
+            .iter()
+            .map(|key| self.key_matches(key))
+            .collect::<Vec<_>>();
+        self.apply_right(old_key, new_key);
+
+        let mut output = vec![];
+        for (key, before) in affected_keys.iter().zip(before) {
+            let after = self.key_matches(key);
+            if before == after {
+                continue;
+            }
+            let action = if after {
+                JoinAction::Insert
+            } else {
+                JoinAction::Delete
+            };
+            if let Some(records) = self.left.get(key) {
+                output.extend(records.iter().map(|record| (action, record.clone())));
+            }
+        }
+        Ok(output)
+    }
+
+    /// Applies a change of the scalar subquery. If the scalar value changes, the condition is
+    /// re-evaluated on every stored record of the outer query.
+    fn update_scalar(
+        &mut self,
+        old_key: Option<Vec<Field>>,
+        new_key: Option<Vec<Field>>,
+    ) -> Result<Vec<(JoinAction, Record)>, SemiJoinError> {
+        let before = self.scalar_value()?;
+        self.apply_right(old_key, new_key);
+        let after = self.scalar_value()?;
+        if before == after {
+            return Ok(vec![]);
+        }
+
+        // The outer query records are taken out while evaluating the condition on them
+        let left = std::mem::take(&mut self.left);
+        let output = self.scalar_changes(&left, &before, &after);
+        self.left = left;
+        output
+    }
+
+    fn scalar_changes(
+        &mut self,
+        left: &HashMap<Vec<Field>, Vec<Record>>,
+        before: &Field,
+        after: &Field,
+    ) -> Result<Vec<(JoinAction, Record)>, SemiJoinError> {
+        let mut output = vec![];
+        for record in left.values().flatten() {
+            match (
+                self.scalar_matches(record, before)?,
+                self.scalar_matches(record, after)?,
+            ) {
+                (false, true) => output.push((JoinAction::Insert, record.clone())),
+                (true, false) => output.push((JoinAction::Delete, record.clone())),
+                _ => {}
+            }
+        }
+        Ok(output)
+    }
+
+    fn apply_right(&mut self, old_key: Option<Vec<Field>>, new_key: Option<Vec<Field>>) {
+        if let Some(key) = old_key {
+            if let hash_map::Entry::Occupied(mut entry) = self.right.entry(key) {
+                *entry.get_mut() -= 1;
+                if *entry.get() == 0 {
+                    entry.remove();
+                }
+                self.right_count -= 1;
+            }
+        }
+        if let Some(key) = new_key {
+            *self.right.entry(key).or_default() += 1;
+            self.right_count += 1;
+        }
+    }
+
+    /// The value of a scalar subquery, which is NULL if the subquery returns no rows.
+    /// Returning more than one row is an error, like in Postgres.
+    fn scalar_value(&self) -> Result<Field, SemiJoinError> {
+        if !matches!(self.predicate, SubqueryPredicate::Scalar { .. }) {
+            return Ok(Field::Null);
+        }
+        if self.right_count > 1 {
+            return Err(SemiJoinError::ScalarSubqueryRows(self.right_count));
+        }
+        Ok(self
+            .right
+            .keys()
+            .next()
+            .and_then(|key| key.first())
+            .cloned()
+            .unwrap_or(Field::Null))
+    }
+
+    fn matches(
+        &mut self,
+        key: &[Field],
+        record: &Record,
+        scalar: &Field,
+    ) -> Result<bool, SemiJoinError> {
+        match self.predicate {
+            SubqueryPredicate::Scalar { .. } => self.scalar_matches(record, scalar),
+            _ => Ok(self.key_matches(key)),
+        }
+    }
+
+    fn key_matches(&self, key: &[Field]) -> bool {
+        match &self.predicate {
+            SubqueryPredicate::In { negated, .. } => {
+                if key.contains(&Field::Null) {
+                    return false;
+                }
+                if *negated {
+                    !self.right.contains_key(key)
+                        && !self.right.contains_key([Field::Null].as_slice())
+                } else {
+                    self.right.contains_key(key)
+                }
+            }
+            SubqueryPredicate::Exists { keys, negated, .. } => {
+                let exists = if keys.is_empty() {
+                    self.right_count > 0
+                } else {
+                    !key.contains(&Field::Null) && self.right.contains_key(key)
+                };
+                exists != *negated
+            }
+            SubqueryPredicate::Scalar { .. } => false,
+        }
+    }
+
+    fn scalar_matches(&mut self, record: &Record, scalar: &Field) -> Result<bool, SemiJoinError> {
+        let SubqueryPredicate::Scalar { condition, schema } = &mut self.predicate else {
+            return Ok(false);
+        };
+        let mut values = record.values.clone();
+        values.push(scalar.clone());
+        Ok(condition.evaluate(&Record::new(values), schema)? == Field::Boolean(true))
+    }
+
+    fn left_key(&mut self, record: &Record) -> Result<Vec<Field>, SemiJoinError> {
+        let (keys, casts) = match &mut self.predicate {
+            SubqueryPredicate::In { key, casts, .. } => (std::slice::from_mut(key), &casts.left),
+            SubqueryPredicate::Exists { keys, casts, .. } => (keys.as_mut_slice(), &casts.left),
+            SubqueryPredicate::Scalar { .. } => return Ok(vec![]),
+        };
+        let mut values = keys
+            .iter_mut()
+            .map(|key| key.evaluate(record, &self.left_schema))
+            .collect::<Result<Vec<_>, _>>()?;
+        cast_key(&mut values, casts)?;
+        Ok(values)
+    }
+
+    fn right_key(&self, record: &Record) -> Result<Vec<Field>, SemiJoinError> {
+        let (len, casts) = match &self.predicate {
+            SubqueryPredicate::In { casts, .. } => (1, casts.right.as_slice()),
+            SubqueryPredicate::Exists { keys, casts, .. } => (keys.len(), casts.right.as_slice()),
+            SubqueryPredicate::Scalar { .. } => (1, [].as_slice()),
+        };
+        let mut values = record.values[..len].to_vec();
+        cast_key(&mut values, casts)?;
+        Ok(values)
+    }
+
+    pub fn serialize(&self, mut object: Object) -> Result<(), SerializationError> {
+        let left_len = self.left.values().map(Vec::len).sum::<usize>();
+        serialize_u64(left_len as u64, &mut object)?;
+        for record in self.left.values().flatten() {
+            serialize_record(record, &mut object)?;
+        }
+        serialize_u64(self.right.len() as u64, &mut object)?;
+        for (key, count) in &self.right {
+            serialize_record(&Record::new(key.clone()), &mut object)?;
+            serialize_u64(*count, &mut object)?;
+        }
+        Ok(())
+    }
+}
+
+/// Casts the fields of `key` that have a cast. NULLs stay NULL, as they never match.
+fn cast_key(key: &mut [Field], casts: &[Option<FieldType>]) -> Result<(), SemiJoinError> {
+    for (field, cast) in key.iter_mut().zip(casts) {
+        if let Some(typ) = cast {
+            if field != &Field::Null {
+                *field = cast_field(field, *typ)?;
+            }
+        }
+    }
+    Ok(())
+}
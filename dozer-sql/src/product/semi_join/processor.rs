@@ -0,0 +1,448 @@
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::dozer_log::storage::Object;
+use dozer_core::epoch::Epoch;
+use dozer_core::node::{PortHandle, Processor};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::types::{Operation, OperationWithId, Record};
+
+use crate::errors::PipelineError;
+use crate::product::join::operator::JoinAction;
+
+use super::factory::{LEFT_SEMI_JOIN_PORT, RIGHT_SEMI_JOIN_PORT};
+use super::operator::SemiJoinOperator;
+
+#[derive(Debug)]
+pub struct SemiJoinProcessor {
+    operator: SemiJoinOperator,
+}
+
+impl SemiJoinProcessor {
+    pub fn new(operator: SemiJoinOperator) -> Self {
+        Self { operator }
+    }
+
+    fn process_left(
+        &mut self,
+        op: OperationWithId,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), PipelineError> {
+        let id = op.id;
+        let op = match op.op {
+            Operation::Delete { old } => {
+                if !self.operator.delete_left(&old)? {
+                    return Ok(());
+                }
+                Operation::Delete { old }
+            }
+            Operation::Insert { new } => {
+                if !self.operator.insert_left(&new)? {
+                    return Ok(());
+                }
+                Operation::Insert { new }
+            }
+            Operation::Update { old, new } => {
+                let old_fulfilled = self.operator.delete_left(&old)?;
+                let new_fulfilled = self.operator.insert_left(&new)?;
+                match (old_fulfilled, new_fulfilled) {
+                    (true, true) => Operation::Update { old, new },
+                    (true, false) => Operation::Delete { old },
+                    (false, true) => Operation::Insert { new },
+                    (false, false) => return Ok(()),
+                }
+            }
+            Operation::BatchInsert { new } => {
+                let mut records = vec![];
+                for record in new {
+                    if self.operator.insert_left(&record)? {
+                        records.push(record);
+                    }
+                }
+                if records.is_empty() {
+                    return Ok(());
+                }
+                Operation::BatchInsert { new: records }
+            }
+        };
+        fw.send(OperationWithId { id, op }, DEFAULT_PORT_HANDLE);
+        Ok(())
+    }
+
+    fn process_right(
+        &mut self,
+        op: Operation,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), PipelineError> {
+        let records = match op {
+            Operation::Delete { old } => self.operator.update_right(Some(&old), None)?,
+            Operation::Insert { new } => self.operator.update_right(None, Some(&new))?,
+            Operation::Update { old, new } => self.operator.update_right(Some(&old), Some(&new))?,
+            Operation::BatchInsert { new } => {
+                let mut records = vec![];
+                for record in &new {
+                    records.extend(self.operator.update_right(None, Some(record))?);
+                }
+                records
+            }
+        };
+
+        for (action, record) in records {
+            fw.send(
+                OperationWithId::without_id(to_operation(action, record)),
+                DEFAULT_PORT_HANDLE,
+            );
+        }
+        Ok(())
+    }
+}
+
+fn to_operation(action: JoinAction, record: Record) -> Operation {
+    match action {
+        JoinAction::Insert => Operation::Insert { new: record },
+        JoinAction::Delete => Operation::Delete { old: record },
+    }
+}
+
+impl Processor for SemiJoinProcessor {
+    fn commit(&self, _epoch: &Epoch) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        from_port: PortHandle,
+        op: OperationWithId,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), BoxedError> {
+        match from_port {
+            LEFT_SEMI_JOIN_PORT => self.process_left(op, fw)?,
+            RIGHT_SEMI_JOIN_PORT => self.process_right(op.op, fw)?,
+            _ => return Err(PipelineError::InvalidPortHandle(from_port).into()),
+        }
+        Ok(())
+    }
+
+    fn serialize(&mut self, object: Object) -> Result<(), BoxedError> {
+        self.operator.serialize(object).map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dozer_core::node::ProcessorFactory;
+    use dozer_sql_expression::sqlparser::ast::{Expr as SqlExpr, Ident};
+    use dozer_types::types::{Field, FieldDefinition, FieldType, Schema, SourceDefinition};
+
+    use crate::product::semi_join::factory::{SemiJoinProcessorFactory, SubqueryCondition};
+    use crate::tests::utils::{create_test_runtime, get_select};
+
+    use super::*;
+
+    struct TestChannelForwarder {
+        operations: Vec<OperationWithId>,
+    }
+
+    impl ProcessorChannelForwarder for TestChannelForwarder {
+        fn send(&mut self, op: OperationWithId, _port: PortHandle) {
+            self.operations.push(op);
+        }
+    }
+
+    fn create_schema(table_name: &str, fields: &[(&str, FieldType)]) -> Schema {
+        let mut schema = Schema::new();
+        for (name, typ) in fields {
+            schema.field(
+                FieldDefinition::new(
+                    name.to_string(),
+                    *typ,
+                    true,
+                    SourceDefinition::Table {
+                        connection: "test".into(),
+                        name: table_name.into(),
+                    },
+                ),
+                false,
+            );
+        }
+        schema
+    }
+
+    struct Executor {
+        processor: Box<dyn Processor>,
+        forwarder: TestChannelForwarder,
+    }
+
+    impl Executor {
+        fn new(condition: SubqueryCondition, right_type: FieldType) -> Self {
+            Self::try_new(condition, right_type).unwrap()
+        }
+
+        fn try_new(
+            condition: SubqueryCondition,
+            right_type: FieldType,
+        ) -> Result<Self, BoxedError> {
+            let left_schema = create_schema(
+                "orders",
+                &[("customer_id", FieldType::UInt), ("amount", FieldType::Int)],
+            );
+            let right_schema = create_schema("customers", &[("id", right_type)]);
+
+            let runtime = create_test_runtime();
+            let factory =
+                SemiJoinProcessorFactory::new("test".into(), condition, vec![], runtime.clone());
+            let schemas = [
+                (LEFT_SEMI_JOIN_PORT, left_schema),
+                (RIGHT_SEMI_JOIN_PORT, right_schema),
+            ]
+            .into_iter()
+            .collect();
+            let processor = runtime.block_on(factory.build(schemas, HashMap::new(), None))?;
+
+            Ok(Executor {
+                processor,
+                forwarder: TestChannelForwarder { operations: vec![] },
+            })
+        }
+
+        fn do_op(&mut self, port: PortHandle, operation: Operation) -> Vec<Operation> {
+            self.processor
+                .process(
+                    port,
+                    OperationWithId::without_id(operation),
+                    &mut self.forwarder,
+                )
+                .unwrap();
+            std::mem::take(&mut self.forwarder.operations)
+                .into_iter()
+                .map(|op| op.op)
+                .collect()
+        }
+
+        fn insert(&mut self, port: PortHandle, values: &[Field]) -> (Record, Vec<Operation>) {
+            let record = Record::new(values.to_vec());
+            let op = Operation::Insert {
+                new: record.clone(),
+            };
+            (record, self.do_op(port, op))
+        }
+
+        fn delete(&mut self, port: PortHandle, old: Record) -> Vec<Operation> {
+            self.do_op(port, Operation::Delete { old })
+        }
+    }
+
+    fn customer_id() -> SqlExpr {
+        SqlExpr::Identifier(Ident::new("customer_id"))
+    }
+
+    #[test]
+    fn test_in_subquery() {
+        let condition = SubqueryCondition::In {
+            expr: customer_id(),
+            negated: false,
+        };
+        let mut exec = Executor::new(condition, FieldType::UInt);
+
+        let (order, ops) = exec.insert(LEFT_SEMI_JOIN_PORT, &[Field::UInt(1), Field::Int(10)]);
+        assert_eq!(ops, &[]);
+
+        let (customer, ops) = exec.insert(RIGHT_SEMI_JOIN_PORT, &[Field::UInt(1)]);
+        assert_eq!(ops, &[Operation::Insert { new: order.clone() }]);
+
+        // A duplicate subquery record doesn't change the result
+        let (_, ops) = exec.insert(RIGHT_SEMI_JOIN_PORT, &[Field::UInt(1)]);
+        assert_eq!(ops, &[]);
+        assert_eq!(exec.delete(RIGHT_SEMI_JOIN_PORT, customer.clone()), &[]);
+
+        let (other_order, ops) =
+            exec.insert(LEFT_SEMI_JOIN_PORT, &[Field::UInt(1), Field::Int(20)]);
+        assert_eq!(
+            ops,
+            &[Operation::Insert {
+                new: other_order.clone()
+            }]
+        );
+
+        assert_eq!(
+            exec.delete(RIGHT_SEMI_JOIN_PORT, customer),
+            &[
+                Operation::Delete { old: order },
+                Operation::Delete { old: other_order }
+            ]
+        );
+
+        let (_, ops) = exec.insert(LEFT_SEMI_JOIN_PORT, &[Field::Null, Field::Int(30)]);
+        assert_eq!(ops, &[]);
+    }
+
+    #[test]
+    fn test_in_subquery_key_types() {
+        let condition = SubqueryCondition::In {
+            expr: customer_id(),
+            negated: false,
+        };
+        // Integer keys are compared as the same type
+        let mut exec = Executor::new(condition.clone(), FieldType::Int);
+        let (order, ops) = exec.insert(LEFT_SEMI_JOIN_PORT, &[Field::UInt(1), Field::Int(10)]);
+        assert_eq!(ops, &[]);
+        let (_, ops) = exec.insert(RIGHT_SEMI_JOIN_PORT, &[Field::Int(1)]);
+        assert_eq!(ops, &[Operation::Insert { new: order }]);
+
+        // Keys that can't be compared are rejected
+        assert!(Executor::try_new(condition, FieldType::Boolean).is_err());
+    }
+
+    #[test]
+    fn test_not_in_subquery() {
+        let condition = SubqueryCondition::In {
+            expr: customer_id(),
+            negated: true,
+        };
+        let mut exec = Executor::new(condition, FieldType::UInt);
+
+        let (order, ops) = exec.insert(LEFT_SEMI_JOIN_PORT, &[Field::UInt(1), Field::Int(10)]);
+        assert_eq!(ops, &[Operation::Insert { new: order.clone() }]);
+
+        let (_, ops) = exec.insert(RIGHT_SEMI_JOIN_PORT, &[Field::UInt(2)]);
+        assert_eq!(ops, &[]);
+
+        let (customer, ops) = exec.insert(RIGHT_SEMI_JOIN_PORT, &[Field::UInt(1)]);
+        assert_eq!(ops, &[Operation::Delete { old: order.clone() }]);
+
+        let (other_order, ops) =
+            exec.insert(LEFT_SEMI_JOIN_PORT, &[Field::UInt(3), Field::Int(20)]);
+        assert_eq!(
+            ops,
+            &[Operation::Insert {
+                new: other_order.clone()
+            }]
+        );
+
+        // A NULL in the subquery makes NOT IN unknown
+        let (null_customer, ops) = exec.insert(RIGHT_SEMI_JOIN_PORT, &[Field::Null]);
+        assert_eq!(
+            ops,
+            &[Operation::Delete {
+                old: other_order.clone()
+            }]
+        );
+
+        assert_eq!(exec.delete(RIGHT_SEMI_JOIN_PORT, customer), &[]);
+        let ops = exec.delete(RIGHT_SEMI_JOIN_PORT, null_customer);
+        assert_eq!(ops.len(), 2);
+        assert!(ops.contains(&Operation::Insert { new: order }));
+        assert!(ops.contains(&Operation::Insert { new: other_order }));
+    }
+
+    #[test]
+    fn test_exists_subquery() {
+        let condition = SubqueryCondition::Exists {
+            keys: vec![],
+            negated: false,
+        };
+        let mut exec = Executor::new(condition, FieldType::UInt);
+
+        let (order, ops) = exec.insert(LEFT_SEMI_JOIN_PORT, &[Field::UInt(1), Field::Int(10)]);
+        assert_eq!(ops, &[]);
+
+        let (customer, ops) = exec.insert(RIGHT_SEMI_JOIN_PORT, &[Field::UInt(5)]);
+        assert_eq!(ops, &[Operation::Insert { new: order.clone() }]);
+
+        assert_eq!(
+            exec.delete(LEFT_SEMI_JOIN_PORT, order.clone()),
+            &[Operation::Delete { old: order }]
+        );
+        assert_eq!(exec.delete(RIGHT_SEMI_JOIN_PORT, customer), &[]);
+    }
+
+    #[test]
+    fn test_correlated_not_exists_subquery() {
+        let condition = SubqueryCondition::Exists {
+            keys: vec![customer_id()],
+            negated: true,
+        };
+        let mut exec = Executor::new(condition, FieldType::UInt);
+
+        let (order, ops) = exec.insert(LEFT_SEMI_JOIN_PORT, &[Field::UInt(1), Field::Int(10)]);
+        assert_eq!(ops, &[Operation::Insert { new: order.clone() }]);
+
+        // Unlike NOT IN, NULLs don't match anything
+        let (_, ops) = exec.insert(RIGHT_SEMI_JOIN_PORT, &[Field::Null]);
+        assert_eq!(ops, &[]);
+
+        let (customer, ops) = exec.insert(RIGHT_SEMI_JOIN_PORT, &[Field::UInt(1)]);
+        assert_eq!(ops, &[Operation::Delete { old: order.clone() }]);
+
+        assert_eq!(
+            exec.delete(RIGHT_SEMI_JOIN_PORT, customer),
+            &[Operation::Insert { new: order }]
+        );
+    }
+
+    #[test]
+    fn test_scalar_subquery() {
+        let condition = get_select("SELECT * FROM orders WHERE amount > __subquery")
+            .unwrap()
+            .selection
+            .unwrap();
+        let condition = SubqueryCondition::Scalar {
+            condition,
+            column: "__subquery".to_string(),
+        };
+        let mut exec = Executor::new(condition, FieldType::Int);
+
+        let (small_order, ops) =
+            exec.insert(LEFT_SEMI_JOIN_PORT, &[Field::UInt(1), Field::Int(10)]);
+        assert_eq!(ops, &[]);
+        let (big_order, ops) = exec.insert(LEFT_SEMI_JOIN_PORT, &[Field::UInt(2), Field::Int(20)]);
+        assert_eq!(ops, &[]);
+
+        let (average, ops) = exec.insert(RIGHT_SEMI_JOIN_PORT, &[Field::Int(15)]);
+        assert_eq!(
+            ops,
+            &[Operation::Insert {
+                new: big_order.clone()
+            }]
+        );
+
+        let ops = exec.do_op(
+            RIGHT_SEMI_JOIN_PORT,
+            Operation::Update {
+                old: average,
+                new: Record::new(vec![Field::Int(5)]),
+            },
+        );
+        assert_eq!(
+            ops,
+            &[Operation::Insert {
+                new: small_order.clone()
+            }]
+        );
+
+        let (_, ops) = exec.insert(LEFT_SEMI_JOIN_PORT, &[Field::UInt(3), Field::Int(1)]);
+        assert_eq!(ops, &[]);
+        assert_eq!(
+            exec.delete(LEFT_SEMI_JOIN_PORT, small_order.clone()),
+            &[Operation::Delete { old: small_order }]
+        );
+        assert_eq!(
+            exec.delete(RIGHT_SEMI_JOIN_PORT, Record::new(vec![Field::Int(5)])),
+            &[Operation::Delete { old: big_order }]
+        );
+
+        // More than one row is an error
+        exec.insert(RIGHT_SEMI_JOIN_PORT, &[Field::Int(5)]);
+        assert!(exec
+            .processor
+            .process(
+                RIGHT_SEMI_JOIN_PORT,
+                OperationWithId::without_id(Operation::Insert {
+                    new: Record::new(vec![Field::Int(6)]),
+                }),
+                &mut exec.forwarder,
+            )
+            .is_err());
+    }
+}
@@ -10,6 +10,13 @@ pub enum SetAction {
     // Update,
 }
 
+/// The input of the set operation a record is coming from.
+#[derive(Clone, Debug, PartialEq, Eq, Copy)]
+pub enum SetSide {
+    Left,
+    Right,
+}
+
 #[derive(Clone, Debug)]
 pub struct SetOperation {
     pub op: SetOperator,
@@ -27,70 +34,178 @@ impl SetOperation {
     pub fn execute(
         &self,
         action: SetAction,
+        side: SetSide,
         record: Record,
-        record_map: &mut CountingRecordMapEnum,
+        left_map: &mut CountingRecordMapEnum,
+        right_map: &mut CountingRecordMapEnum,
     ) -> Result<Vec<(SetAction, Record)>, PipelineError> {
-        match (self.op, self.quantifier) {
-            (SetOperator::Union, SetQuantifier::All) => Ok(vec![(action, record)]),
-            (SetOperator::Union, SetQuantifier::None) => {
-                self.execute_union(action, record, record_map)
+        let is_all = match self.quantifier {
+            SetQuantifier::All => true,
+            SetQuantifier::None | SetQuantifier::Distinct => false,
+            _ => {
+                return Err(PipelineError::InvalidOperator(format!(
+                    "{} {}",
+                    self.op, self.quantifier
+                )))
             }
-            _ => Err(PipelineError::InvalidOperandType(self.op.to_string())),
+        };
+
+        // UNION ALL doesn't need to keep any state
+        if self.op == SetOperator::Union && is_all {
+            return Ok(vec![(action, record)]);
         }
-    }
 
-    fn execute_union(
-        &self,
-        action: SetAction,
-        record: Record,
-        record_map: &mut CountingRecordMapEnum,
-    ) -> Result<Vec<(SetAction, Record)>, PipelineError> {
+        let before = self.output_count(
+            is_all,
+            left_map.estimate_count(&record),
+            right_map.estimate_count(&record),
+        );
+        let map = match side {
+            SetSide::Left => &mut *left_map,
+            SetSide::Right => &mut *right_map,
+        };
         match action {
-            SetAction::Insert => self.union_insert(action, record, record_map),
-            SetAction::Delete => self.union_delete(action, record, record_map),
+            SetAction::Insert => map.insert(&record),
+            SetAction::Delete => map.remove(&record),
         }
-    }
+        let after = self.output_count(
+            is_all,
+            left_map.estimate_count(&record),
+            right_map.estimate_count(&record),
+        );
 
-    fn union_insert(
-        &self,
-        action: SetAction,
-        record: Record,
-        record_map: &mut CountingRecordMapEnum,
-    ) -> Result<Vec<(SetAction, Record)>, PipelineError> {
-        let _count = self.update_map(record.clone(), false, record_map);
-        if _count == 1 {
-            Ok(vec![(action, record)])
+        Ok(if after > before {
+            vec![(SetAction::Insert, record); (after - before) as usize]
         } else {
-            Ok(vec![])
-        }
+            vec![(SetAction::Delete, record); (before - after) as usize]
+        })
     }
 
-    fn union_delete(
-        &self,
-        action: SetAction,
-        record: Record,
-        record_map: &mut CountingRecordMapEnum,
-    ) -> Result<Vec<(SetAction, Record)>, PipelineError> {
-        let _count = self.update_map(record.clone(), true, record_map);
-        if _count == 0 {
-            Ok(vec![(action, record)])
+    /// Returns how many times a record appears in the output of the set operation,
+    /// given the number of times it appears in the left and right inputs.
+    fn output_count(&self, is_all: bool, left: u64, right: u64) -> u64 {
+        let count = match self.op {
+            SetOperator::Union => left.saturating_add(right),
+            SetOperator::Intersect => left.min(right),
+            SetOperator::Except => {
+                if is_all {
+                    left.saturating_sub(right)
+                } else if right == 0 {
+                    left
+                } else {
+                    0
+                }
+            }
+        };
+
+        if is_all {
+            count
         } else {
-            Ok(vec![])
+            count.min(1)
         }
     }
+}
 
-    fn update_map(
-        &self,
-        record: Record,
-        decr: bool,
-        record_map: &mut CountingRecordMapEnum,
-    ) -> u64 {
-        if decr {
-            record_map.remove(&record);
-        } else {
-            record_map.insert(&record);
+#[cfg(test)]
+mod tests {
+    use dozer_sql_expression::sqlparser::ast::{SetOperator, SetQuantifier};
+    use dozer_types::types::{Field, Record};
+
+    use super::{SetAction, SetOperation, SetSide};
+    use crate::product::set::record_map::{AccurateCountingRecordMap, CountingRecordMapEnum};
+
+    struct TestSet {
+        operation: SetOperation,
+        left_map: CountingRecordMapEnum,
+        right_map: CountingRecordMapEnum,
+    }
+
+    impl TestSet {
+        fn new(op: SetOperator, quantifier: SetQuantifier) -> Self {
+            Self {
+                operation: SetOperation { op, quantifier },
+                left_map: AccurateCountingRecordMap::new(None).unwrap().into(),
+                right_map: AccurateCountingRecordMap::new(None).unwrap().into(),
+            }
+        }
+
+        fn execute(&mut self, action: SetAction, side: SetSide, value: &str) -> Vec<SetAction> {
+            self.operation
+                .execute(
+                    action,
+                    side,
+                    Record::new(vec![Field::String(value.to_string())]),
+                    &mut self.left_map,
+                    &mut self.right_map,
+                )
+                .unwrap()
+                .into_iter()
+                .map(|(action, _)| action)
+                .collect()
         }
+    }
+
+    use SetAction::{Delete, Insert};
+    use SetSide::{Left, Right};
+
+    #[test]
+    fn test_union_distinct() {
+        let mut set = TestSet::new(SetOperator::Union, SetQuantifier::None);
+        assert_eq!(set.execute(Insert, Left, "a"), vec![Insert]);
+        assert_eq!(set.execute(Insert, Right, "a"), vec![]);
+        assert_eq!(set.execute(Delete, Left, "a"), vec![]);
+        assert_eq!(set.execute(Delete, Right, "a"), vec![Delete]);
+    }
+
+    #[test]
+    fn test_except_distinct() {
+        let mut set = TestSet::new(SetOperator::Except, SetQuantifier::None);
+        assert_eq!(set.execute(Insert, Left, "a"), vec![Insert]);
+        assert_eq!(set.execute(Insert, Left, "a"), vec![]);
+        assert_eq!(set.execute(Insert, Right, "a"), vec![Delete]);
+        assert_eq!(set.execute(Insert, Right, "b"), vec![]);
+        assert_eq!(set.execute(Insert, Left, "b"), vec![]);
+        assert_eq!(set.execute(Delete, Right, "a"), vec![Insert]);
+        assert_eq!(set.execute(Delete, Left, "a"), vec![]);
+        assert_eq!(set.execute(Delete, Left, "a"), vec![Delete]);
+        assert_eq!(set.execute(Delete, Right, "b"), vec![Insert]);
+    }
+
+    #[test]
+    fn test_except_all() {
+        let mut set = TestSet::new(SetOperator::Except, SetQuantifier::All);
+        assert_eq!(set.execute(Insert, Left, "a"), vec![Insert]);
+        assert_eq!(set.execute(Insert, Left, "a"), vec![Insert]);
+        assert_eq!(set.execute(Insert, Right, "a"), vec![Delete]);
+        assert_eq!(set.execute(Insert, Right, "a"), vec![Delete]);
+        assert_eq!(set.execute(Insert, Right, "a"), vec![]);
+        assert_eq!(set.execute(Delete, Right, "a"), vec![]);
+        assert_eq!(set.execute(Delete, Right, "a"), vec![Insert]);
+        assert_eq!(set.execute(Delete, Left, "a"), vec![Delete]);
+    }
+
+    #[test]
+    fn test_intersect_distinct() {
+        let mut set = TestSet::new(SetOperator::Intersect, SetQuantifier::Distinct);
+        assert_eq!(set.execute(Insert, Left, "a"), vec![]);
+        assert_eq!(set.execute(Insert, Left, "a"), vec![]);
+        assert_eq!(set.execute(Insert, Right, "a"), vec![Insert]);
+        assert_eq!(set.execute(Insert, Right, "a"), vec![]);
+        assert_eq!(set.execute(Delete, Left, "a"), vec![]);
+        assert_eq!(set.execute(Delete, Left, "a"), vec![Delete]);
+    }
 
-        record_map.estimate_count(&record)
+    #[test]
+    fn test_intersect_all() {
+        let mut set = TestSet::new(SetOperator::Intersect, SetQuantifier::All);
+        assert_eq!(set.execute(Insert, Left, "a"), vec![]);
+        assert_eq!(set.execute(Insert, Left, "a"), vec![]);
+        assert_eq!(set.execute(Insert, Right, "a"), vec![Insert]);
+        assert_eq!(set.execute(Insert, Right, "a"), vec![Insert]);
+        assert_eq!(set.execute(Insert, Right, "a"), vec![]);
+        assert_eq!(set.execute(Delete, Left, "a"), vec![Delete]);
+        assert_eq!(set.execute(Delete, Right, "a"), vec![]);
+        assert_eq!(set.execute(Delete, Right, "a"), vec![]);
+        assert_eq!(set.execute(Delete, Left, "a"), vec![Delete]);
     }
 }
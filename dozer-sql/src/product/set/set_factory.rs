@@ -18,6 +18,7 @@ use super::set_processor::SetProcessor;
 #[derive(Debug)]
 pub struct SetProcessorFactory {
     id: String,
+    set_operator: SetOperator,
     set_quantifier: SetQuantifier,
     enable_probabilistic_optimizations: bool,
 }
@@ -26,11 +27,13 @@ impl SetProcessorFactory {
     /// Creates a new [`FromProcessorFactory`].
     pub fn new(
         id: String,
+        set_operator: SetOperator,
         set_quantifier: SetQuantifier,
         enable_probabilistic_optimizations: bool,
     ) -> Self {
         Self {
             id,
+            set_operator,
             set_quantifier,
             enable_probabilistic_optimizations,
         }
@@ -78,7 +81,7 @@ impl ProcessorFactory for SetProcessorFactory {
         Ok(Box::new(SetProcessor::new(
             self.id.clone(),
             SetOperation {
-                op: SetOperator::Union,
+                op: self.set_operator,
                 quantifier: self.set_quantifier,
             },
             self.enable_probabilistic_optimizations,
@@ -1,15 +1,16 @@
-use super::operator::{SetAction, SetOperation};
+use super::operator::{SetAction, SetOperation, SetSide};
 use super::record_map::{
     AccurateCountingRecordMap, CountingRecordMap, CountingRecordMapEnum,
     ProbabilisticCountingRecordMap,
 };
 use crate::errors::{PipelineError, ProductError, SetError};
 use dozer_core::channels::ProcessorChannelForwarder;
-use dozer_core::checkpoint::serialize::Cursor;
+use dozer_core::checkpoint::serialize::{deserialize_u64, serialize_u64, Cursor};
 use dozer_core::dozer_log::storage::Object;
 use dozer_core::epoch::Epoch;
 use dozer_core::node::{PortHandle, Processor};
 use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_sql_expression::sqlparser::ast::SetOperator;
 use dozer_types::errors::internal::BoxedError;
 use dozer_types::types::{Operation, OperationWithId, Record};
 use std::fmt::{Debug, Formatter};
@@ -18,10 +19,17 @@ pub struct SetProcessor {
     _id: String,
     /// Set operations
     operator: SetOperation,
-    /// Hashmap containing records with its occurrence
-    record_map: CountingRecordMapEnum,
+    /// Hashmap containing records from the left input with their occurrence
+    left_record_map: CountingRecordMapEnum,
+    /// Hashmap containing records from the right input with their occurrence
+    right_record_map: CountingRecordMapEnum,
 }
 
+/// Checkpoints written before the set processor kept a record map per input only contain the map of UNION.
+/// Versioned checkpoints start with this marker, which can't be the length a legacy checkpoint starts with.
+const CHECKPOINT_MARKER: u64 = u64::MAX;
+const CHECKPOINT_VERSION: u64 = 1;
+
 impl SetProcessor {
     /// Creates a new [`SetProcessor`].
     pub fn new(
@@ -30,53 +38,96 @@ impl SetProcessor {
         enable_probabilistic_optimizations: bool,
         checkpoint_data: Option<Vec<u8>>,
     ) -> Result<Self, SetError> {
-        let mut cursor = checkpoint_data.as_deref().map(Cursor::new);
+        // EXCEPT and INTERSECT compare the counts of both inputs, so false positives would emit wrong records.
+        let probabilistic = enable_probabilistic_optimizations && operator.op == SetOperator::Union;
+        let new_record_map =
+            |cursor: Option<&mut Cursor>| -> Result<CountingRecordMapEnum, SetError> {
+                Ok(if probabilistic {
+                    ProbabilisticCountingRecordMap::new(cursor)?.into()
+                } else {
+                    AccurateCountingRecordMap::new(cursor)?.into()
+                })
+            };
+
+        let (left_record_map, right_record_map) = match checkpoint_data.as_deref() {
+            Some(data) => {
+                let mut cursor = Cursor::new(data);
+                if deserialize_u64(&mut Cursor::new(data))? == CHECKPOINT_MARKER {
+                    deserialize_u64(&mut cursor)?;
+                    let version = deserialize_u64(&mut cursor)?;
+                    if version != CHECKPOINT_VERSION {
+                        return Err(SetError::UnsupportedCheckpointVersion(version));
+                    }
+                    (
+                        new_record_map(Some(&mut cursor))?,
+                        new_record_map(Some(&mut cursor))?,
+                    )
+                } else {
+                    // The legacy map counts the records of both inputs of UNION, which is what the left map does.
+                    (new_record_map(Some(&mut cursor))?, new_record_map(None)?)
+                }
+            }
+            None => (new_record_map(None)?, new_record_map(None)?),
+        };
         Ok(Self {
             _id: id,
             operator,
-            record_map: if enable_probabilistic_optimizations {
-                ProbabilisticCountingRecordMap::new(cursor.as_mut())?.into()
-            } else {
-                AccurateCountingRecordMap::new(cursor.as_mut())?.into()
-            },
+            left_record_map,
+            right_record_map,
         })
     }
 
-    fn delete(&mut self, record: Record) -> Result<Vec<(SetAction, Record)>, ProductError> {
-        self.operator
-            .execute(SetAction::Delete, record, &mut self.record_map)
-            .map_err(|err| {
-                ProductError::DeleteError("UNION query error:".to_string(), Box::new(err))
-            })
+    fn error_context(&self) -> String {
+        format!("{} query error:", self.operator.op)
+    }
+
+    fn execute(
+        &mut self,
+        action: SetAction,
+        side: SetSide,
+        record: Record,
+    ) -> Result<Vec<(SetAction, Record)>, PipelineError> {
+        self.operator.execute(
+            action,
+            side,
+            record,
+            &mut self.left_record_map,
+            &mut self.right_record_map,
+        )
+    }
+
+    fn delete(
+        &mut self,
+        side: SetSide,
+        record: Record,
+    ) -> Result<Vec<(SetAction, Record)>, ProductError> {
+        self.execute(SetAction::Delete, side, record)
+            .map_err(|err| ProductError::DeleteError(self.error_context(), Box::new(err)))
     }
 
-    fn insert(&mut self, record: Record) -> Result<Vec<(SetAction, Record)>, ProductError> {
-        self.operator
-            .execute(SetAction::Insert, record, &mut self.record_map)
-            .map_err(|err| {
-                ProductError::InsertError("UNION query error:".to_string(), Box::new(err))
-            })
+    fn insert(
+        &mut self,
+        side: SetSide,
+        record: Record,
+    ) -> Result<Vec<(SetAction, Record)>, ProductError> {
+        self.execute(SetAction::Insert, side, record)
+            .map_err(|err| ProductError::InsertError(self.error_context(), Box::new(err)))
     }
 
     #[allow(clippy::type_complexity)]
     fn update(
         &mut self,
+        side: SetSide,
         old: Record,
         new: Record,
     ) -> Result<(Vec<(SetAction, Record)>, Vec<(SetAction, Record)>), ProductError> {
         let old_records = self
-            .operator
-            .execute(SetAction::Delete, old, &mut self.record_map)
-            .map_err(|err| {
-                ProductError::UpdateOldError("UNION query error:".to_string(), Box::new(err))
-            })?;
+            .execute(SetAction::Delete, side, old)
+            .map_err(|err| ProductError::UpdateOldError(self.error_context(), Box::new(err)))?;
 
         let new_records = self
-            .operator
-            .execute(SetAction::Insert, new, &mut self.record_map)
-            .map_err(|err| {
-                ProductError::UpdateNewError("UNION query error:".to_string(), Box::new(err))
-            })?;
+            .execute(SetAction::Insert, side, new)
+            .map_err(|err| ProductError::UpdateNewError(self.error_context(), Box::new(err)))?;
 
         Ok((old_records, new_records))
     }
@@ -95,13 +146,21 @@ impl Processor for SetProcessor {
 
     fn process(
         &mut self,
-        _from_port: PortHandle,
+        from_port: PortHandle,
         op: OperationWithId,
         fw: &mut dyn ProcessorChannelForwarder,
     ) -> Result<(), BoxedError> {
+        let side = match from_port {
+            0 => SetSide::Left,
+            1 => SetSide::Right,
+            port => return Err(PipelineError::InvalidPortHandle(port).into()),
+        };
+
         match op.op {
             Operation::Delete { old } => {
-                let records = self.delete(old).map_err(PipelineError::ProductError)?;
+                let records = self
+                    .delete(side, old)
+                    .map_err(PipelineError::ProductError)?;
 
                 for (action, record) in records.into_iter() {
                     match action {
@@ -121,7 +180,9 @@ impl Processor for SetProcessor {
                 }
             }
             Operation::Insert { new } => {
-                let records = self.insert(new).map_err(PipelineError::ProductError)?;
+                let records = self
+                    .insert(side, new)
+                    .map_err(PipelineError::ProductError)?;
 
                 for (action, record) in records.into_iter() {
                     match action {
@@ -141,8 +202,9 @@ impl Processor for SetProcessor {
                 }
             }
             Operation::Update { old, new } => {
-                let (old_records, new_records) =
-                    self.update(old, new).map_err(PipelineError::ProductError)?;
+                let (old_records, new_records) = self
+                    .update(side, old, new)
+                    .map_err(PipelineError::ProductError)?;
 
                 for (action, old) in old_records.into_iter() {
                     match action {
@@ -181,7 +243,7 @@ impl Processor for SetProcessor {
             Operation::BatchInsert { new } => {
                 for record in new {
                     self.process(
-                        _from_port,
+                        from_port,
                         OperationWithId::without_id(Operation::Insert { new: record }),
                         fw,
                     )?;
@@ -192,6 +254,73 @@ impl Processor for SetProcessor {
     }
 
     fn serialize(&mut self, mut object: Object) -> Result<(), BoxedError> {
-        self.record_map.serialize(&mut object).map_err(Into::into)
+        serialize_u64(CHECKPOINT_MARKER, &mut object)?;
+        serialize_u64(CHECKPOINT_VERSION, &mut object)?;
+        self.left_record_map.serialize(&mut object)?;
+        self.right_record_map.serialize(&mut object)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_sql_expression::sqlparser::ast::{SetOperator, SetQuantifier};
+
+    use super::*;
+
+    fn processor(
+        op: SetOperator,
+        enable_probabilistic_optimizations: bool,
+        checkpoint_data: Option<Vec<u8>>,
+    ) -> Result<SetProcessor, SetError> {
+        SetProcessor::new(
+            "set".to_string(),
+            SetOperation {
+                op,
+                quantifier: SetQuantifier::None,
+            },
+            enable_probabilistic_optimizations,
+            checkpoint_data,
+        )
+    }
+
+    fn is_accurate(map: &CountingRecordMapEnum) -> bool {
+        matches!(map, CountingRecordMapEnum::AccurateCountingRecordMap(_))
+    }
+
+    #[test]
+    fn test_probabilistic_maps_only_for_union() {
+        let set = processor(SetOperator::Union, true, None).unwrap();
+        assert!(!is_accurate(&set.left_record_map));
+        let set = processor(SetOperator::Except, true, None).unwrap();
+        assert!(is_accurate(&set.left_record_map) && is_accurate(&set.right_record_map));
+        let set = processor(SetOperator::Intersect, true, None).unwrap();
+        assert!(is_accurate(&set.left_record_map) && is_accurate(&set.right_record_map));
+    }
+
+    #[test]
+    fn test_checkpoint_versions() {
+        let empty_map = 0u64.to_le_bytes().to_vec();
+
+        // Legacy checkpoints only have the map of UNION.
+        assert!(processor(SetOperator::Union, false, Some(empty_map.clone())).is_ok());
+
+        let versioned = |version: u64| {
+            let mut data = CHECKPOINT_MARKER.to_le_bytes().to_vec();
+            data.extend(version.to_le_bytes());
+            data.extend(&empty_map);
+            data.extend(&empty_map);
+            data
+        };
+        assert!(processor(
+            SetOperator::Except,
+            false,
+            Some(versioned(CHECKPOINT_VERSION))
+        )
+        .is_ok());
+        assert!(matches!(
+            processor(SetOperator::Except, false, Some(versioned(2))),
+            Err(SetError::UnsupportedCheckpointVersion(2))
+        ));
     }
 }
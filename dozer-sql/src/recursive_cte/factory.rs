@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use crate::errors::PipelineError;
+use dozer_core::{
+    node::{PortHandle, Processor, ProcessorFactory},
+    DEFAULT_PORT_HANDLE,
+};
+use dozer_types::{errors::internal::BoxedError, tonic::async_trait, types::Schema};
+
+use super::processor::{RecursionGuardProcessor, DEPTH_EXCEEDED_PORT};
+
+/// Forwards the rows of a recursive CTE, and fails if the level after the last expanded one produces any row.
+#[derive(Debug)]
+pub struct RecursionGuardProcessorFactory {
+    id: String,
+    table_name: String,
+    max_depth: u32,
+}
+
+impl RecursionGuardProcessorFactory {
+    /// Creates a new [`RecursionGuardProcessorFactory`].
+    pub fn new(id: String, table_name: String, max_depth: u32) -> Self {
+        Self {
+            id,
+            table_name,
+            max_depth,
+        }
+    }
+}
+
+#[async_trait]
+impl ProcessorFactory for RecursionGuardProcessorFactory {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn type_name(&self) -> String {
+        "RecursionGuard".to_string()
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE, DEPTH_EXCEEDED_PORT]
+    }
+
+    fn get_output_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    async fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, Schema>,
+    ) -> Result<Schema, BoxedError> {
+        let schema = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(PipelineError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+        Ok(schema.clone())
+    }
+
+    async fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+        _checkpoint_data: Option<Vec<u8>>,
+    ) -> Result<Box<dyn Processor>, BoxedError> {
+        Ok(Box::new(RecursionGuardProcessor::new(
+            self.table_name.clone(),
+            self.max_depth,
+        )))
+    }
+}
@@ -0,0 +1,2 @@
+pub mod factory;
+pub mod processor;
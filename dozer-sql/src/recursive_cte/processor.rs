@@ -0,0 +1,101 @@
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::dozer_log::storage::Object;
+use dozer_core::epoch::Epoch;
+use dozer_core::node::{PortHandle, Processor};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::types::{Operation, OperationWithId};
+
+use crate::errors::PipelineError;
+
+/// Port of the rows of the level after the last expanded one.
+pub const DEPTH_EXCEEDED_PORT: PortHandle = 1;
+
+#[derive(Debug)]
+pub struct RecursionGuardProcessor {
+    table_name: String,
+    max_depth: u32,
+}
+
+impl RecursionGuardProcessor {
+    pub fn new(table_name: String, max_depth: u32) -> Self {
+        Self {
+            table_name,
+            max_depth,
+        }
+    }
+}
+
+impl Processor for RecursionGuardProcessor {
+    fn commit(&self, _epoch: &Epoch) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        from_port: PortHandle,
+        op: OperationWithId,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), BoxedError> {
+        if from_port != DEPTH_EXCEEDED_PORT {
+            fw.send(op, DEFAULT_PORT_HANDLE);
+            return Ok(());
+        }
+
+        match op.op {
+            // Rows can only be deleted after they've been inserted
+            Operation::Delete { .. } => Ok(()),
+            Operation::BatchInsert { new } if new.is_empty() => Ok(()),
+            Operation::Insert { .. } | Operation::Update { .. } | Operation::BatchInsert { .. } => {
+                Err(PipelineError::RecursionDepthExceeded {
+                    table_name: self.table_name.clone(),
+                    max_depth: self.max_depth,
+                }
+                .into())
+            }
+        }
+    }
+
+    fn serialize(&mut self, _object: Object) -> Result<(), BoxedError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_core::channels::ProcessorChannelForwarder;
+    use dozer_types::types::{Field, Record};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct TestForwarder(Vec<OperationWithId>);
+
+    impl ProcessorChannelForwarder for TestForwarder {
+        fn send(&mut self, op: OperationWithId, _port: PortHandle) {
+            self.0.push(op);
+        }
+    }
+
+    #[test]
+    fn test_recursion_guard() {
+        let mut guard = RecursionGuardProcessor::new("org".to_string(), 3);
+        let mut fw = TestForwarder::default();
+        let insert = || {
+            OperationWithId::without_id(Operation::Insert {
+                new: Record::new(vec![Field::Int(1)]),
+            })
+        };
+
+        guard
+            .process(DEFAULT_PORT_HANDLE, insert(), &mut fw)
+            .unwrap();
+        assert_eq!(fw.0.len(), 1);
+
+        let error = guard
+            .process(DEPTH_EXCEEDED_PORT, insert(), &mut fw)
+            .unwrap_err();
+        assert!(error.to_string().contains("org"));
+        assert_eq!(fw.0.len(), 1);
+    }
+}
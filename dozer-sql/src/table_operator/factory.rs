@@ -22,6 +22,7 @@ use super::{
     lifetime::LifetimeTableOperator,
     operator::{TableOperator, TableOperatorType},
     processor::TableOperatorProcessor,
+    unnest::UnnestTableOperator,
 };
 
 const _SOURCE_TABLE_ARGUMENT: usize = 0;
@@ -142,6 +143,10 @@ pub(crate) async fn operator_from_descriptor(
     if &descriptor.name.to_uppercase() == "TTL" {
         let operator = lifetime_from_descriptor(descriptor, schema, udfs, runtime).await?;
 
+        Ok(Some(operator.into()))
+    } else if &descriptor.name.to_uppercase() == "UNNEST" {
+        let operator = unnest_from_descriptor(descriptor, schema, udfs, runtime).await?;
+
         Ok(Some(operator.into()))
     } else {
         Err(PipelineError::InternalError(descriptor.name.clone().into()))
@@ -201,6 +206,59 @@ async fn lifetime_from_descriptor(
     Ok(operator)
 }
 
+/// Builds an UNNEST operator from its arguments: the array expression, the name of the output
+/// column and, optionally, the alias of the unnested table.
+async fn unnest_from_descriptor(
+    descriptor: &TableOperatorDescriptor,
+    schema: &Schema,
+    udfs: &[UdfConfig],
+    runtime: Arc<Runtime>,
+) -> Result<UnnestTableOperator, TableOperatorError> {
+    let expression_arg = match descriptor.args.first() {
+        Some(TableOperatorArg::Argument(argument)) => argument,
+        Some(other) => {
+            return Err(TableOperatorError::InvalidReference(
+                descriptor.name.to_owned(),
+                format!("{:?}", other),
+            ))
+        }
+        None => {
+            return Err(TableOperatorError::MissingArgument(
+                descriptor.name.to_owned(),
+            ))
+        }
+    };
+    let expression = get_expression(
+        descriptor.name.to_owned(),
+        expression_arg,
+        schema,
+        udfs,
+        runtime,
+    )
+    .await?;
+
+    let column_name = match descriptor.args.get(1) {
+        Some(TableOperatorArg::Argument(argument)) => get_source_name(&descriptor.name, argument)?,
+        _ => {
+            return Err(TableOperatorError::MissingArgument(
+                descriptor.name.to_owned(),
+            ))
+        }
+    };
+    let table_name = match descriptor.args.get(2) {
+        Some(TableOperatorArg::Argument(argument)) => {
+            Some(get_source_name(&descriptor.name, argument)?)
+        }
+        _ => None,
+    };
+
+    Ok(UnnestTableOperator::new(
+        expression,
+        column_name,
+        table_name,
+    ))
+}
+
 fn get_interval(
     function_name: String,
     interval_arg: &FunctionArg,
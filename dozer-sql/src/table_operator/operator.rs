@@ -1,4 +1,4 @@
-use crate::table_operator::lifetime::LifetimeTableOperator;
+use crate::table_operator::{lifetime::LifetimeTableOperator, unnest::UnnestTableOperator};
 use dozer_types::types::{Record, Schema};
 use enum_dispatch::enum_dispatch;
 
@@ -19,4 +19,5 @@ pub trait TableOperator: Send + Sync {
 #[derive(Debug)]
 pub enum TableOperatorType {
     LifetimeTableOperator,
+    UnnestTableOperator,
 }
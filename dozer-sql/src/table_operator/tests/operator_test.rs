@@ -3,10 +3,13 @@ use std::time::Duration;
 use dozer_sql_expression::execution::Expression;
 use dozer_types::{
     chrono::DateTime,
+    json_types::json,
     types::{Field, FieldDefinition, FieldType, Lifetime, Record, Schema, SourceDefinition},
 };
 
-use crate::table_operator::{lifetime::LifetimeTableOperator, operator::TableOperator};
+use crate::table_operator::{
+    lifetime::LifetimeTableOperator, operator::TableOperator, unnest::UnnestTableOperator,
+};
 
 #[test]
 fn test_lifetime() {
@@ -59,3 +62,68 @@ fn test_lifetime() {
 
     assert_eq!(lifetime_record, &expected_record);
 }
+
+#[test]
+fn test_unnest() {
+    let schema = Schema::default()
+        .field(
+            FieldDefinition::new(
+                "id".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            true,
+        )
+        .field(
+            FieldDefinition::new(
+                "items".to_string(),
+                FieldType::Json,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .to_owned();
+
+    let mut table_operator = UnnestTableOperator::new(
+        Expression::Column { index: 1 },
+        "item".to_string(),
+        Some("i".to_string()),
+    );
+
+    let output_schema = table_operator.get_output_schema(&schema).unwrap();
+    assert_eq!(output_schema.fields.len(), 3);
+    assert_eq!(output_schema.fields[2].name, "item");
+    assert_eq!(output_schema.fields[2].typ, FieldType::Json);
+    assert!(output_schema.primary_index.is_empty());
+
+    let record = Record::new(vec![Field::Int(0), Field::Json(json!([1, "a"]))]);
+    assert_eq!(
+        table_operator.execute(&record, &schema).unwrap(),
+        vec![
+            Record::new(vec![
+                Field::Int(0),
+                Field::Json(json!([1, "a"])),
+                Field::Json(json!(1))
+            ]),
+            Record::new(vec![
+                Field::Int(0),
+                Field::Json(json!([1, "a"])),
+                Field::Json(json!("a"))
+            ]),
+        ]
+    );
+
+    for empty in [
+        Field::Null,
+        Field::Json(json!(null)),
+        Field::Json(json!([])),
+    ] {
+        let record = Record::new(vec![Field::Int(0), empty]);
+        assert_eq!(table_operator.execute(&record, &schema).unwrap(), vec![]);
+    }
+
+    let record = Record::new(vec![Field::Int(0), Field::Int(1)]);
+    assert!(table_operator.execute(&record, &schema).is_err());
+}
@@ -0,0 +1,74 @@
+use dozer_sql_expression::execution::Expression;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition};
+
+use crate::errors::TableOperatorError;
+
+use super::operator::TableOperator;
+
+/// Explodes the JSON array an expression evaluates to, into one record per array item.
+///
+/// The output records are the input record with the item appended as a JSON column.
+/// The operator is stateless: deleting a record explodes it again, so the same items are retracted.
+/// The output has no primary key, as every item of a record shares the record's key.
+#[derive(Debug)]
+pub struct UnnestTableOperator {
+    expression: Expression,
+    column_name: String,
+    table_name: Option<String>,
+}
+
+impl UnnestTableOperator {
+    pub fn new(expression: Expression, column_name: String, table_name: Option<String>) -> Self {
+        Self {
+            expression,
+            column_name,
+            table_name,
+        }
+    }
+}
+
+impl TableOperator for UnnestTableOperator {
+    fn get_name(&self) -> String {
+        "UNNEST".to_owned()
+    }
+
+    fn execute(
+        &mut self,
+        record: &Record,
+        schema: &Schema,
+    ) -> Result<Vec<Record>, TableOperatorError> {
+        let items = match self
+            .expression
+            .evaluate(record, schema)
+            .map_err(|err| TableOperatorError::InternalError(Box::new(err)))?
+        {
+            Field::Null => vec![],
+            Field::Json(json) if json.is_null() => vec![],
+            // Values that are not arrays are unnested as a single item
+            Field::Json(json) => match json.into_array() {
+                Ok(array) => array.into_iter().collect(),
+                Err(json) => vec![json],
+            },
+            other => return Err(TableOperatorError::InvalidUnnestInputType(other)),
+        };
+
+        Ok(items
+            .into_iter()
+            .map(|item| Record::appended(record, &[Field::Json(item)]))
+            .collect())
+    }
+
+    fn get_output_schema(&self, schema: &Schema) -> Result<Schema, TableOperatorError> {
+        let source = match &self.table_name {
+            Some(name) => SourceDefinition::Alias { name: name.clone() },
+            None => SourceDefinition::Dynamic,
+        };
+        let mut output_schema = schema.clone();
+        output_schema.primary_index.clear();
+        output_schema.field(
+            FieldDefinition::new(self.column_name.clone(), FieldType::Json, true, source),
+            false,
+        );
+        Ok(output_schema)
+    }
+}
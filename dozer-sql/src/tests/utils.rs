@@ -1,17 +1,16 @@
 use std::sync::Arc;
 
-use crate::errors::PipelineError;
+use crate::{errors::PipelineError, product::join::temporal};
 use dozer_sql_expression::sqlparser::{
     ast::{Query, Select, SetExpr, Statement},
     dialect::DozerDialect,
-    parser::Parser,
 };
 use tokio::runtime::Runtime;
 
 pub fn get_select(sql: &str) -> Result<Box<Select>, PipelineError> {
     let dialect = DozerDialect {};
 
-    let ast = Parser::parse_sql(&dialect, sql).unwrap();
+    let ast = temporal::parse_sql(&dialect, sql).unwrap();
 
     let statement = ast.first().expect("First statement is missing").to_owned();
     if let Statement::Query(query) = statement {
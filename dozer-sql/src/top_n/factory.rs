@@ -0,0 +1,129 @@
+use std::{collections::HashMap, sync::Arc};
+
+use dozer_core::{
+    node::{PortHandle, Processor, ProcessorFactory},
+    DEFAULT_PORT_HANDLE,
+};
+use dozer_sql_expression::{
+    builder::ExpressionBuilder,
+    sqlparser::ast::{Expr, OrderByExpr},
+};
+use dozer_types::{
+    errors::internal::BoxedError, models::udf_config::UdfConfig, tonic::async_trait, types::Schema,
+};
+use tokio::runtime::Runtime;
+
+use crate::errors::PipelineError;
+
+use super::processor::{OrderByExpression, TopNProcessor};
+
+#[derive(Debug)]
+pub struct TopNProcessorFactory {
+    id: String,
+    partition_by: Vec<Expr>,
+    order_by: Vec<OrderByExpr>,
+    offset: usize,
+    limit: usize,
+    udfs: Vec<UdfConfig>,
+    runtime: Arc<Runtime>,
+}
+
+impl TopNProcessorFactory {
+    /// Creates a new [`TopNProcessorFactory`].
+    pub fn new(
+        id: String,
+        partition_by: Vec<Expr>,
+        order_by: Vec<OrderByExpr>,
+        offset: usize,
+        limit: usize,
+        udfs: Vec<UdfConfig>,
+        runtime: Arc<Runtime>,
+    ) -> Self {
+        Self {
+            id,
+            partition_by,
+            order_by,
+            offset,
+            limit,
+            udfs,
+            runtime,
+        }
+    }
+}
+
+#[async_trait]
+impl ProcessorFactory for TopNProcessorFactory {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn type_name(&self) -> String {
+        "TopN".to_string()
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    async fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, Schema>,
+    ) -> Result<Schema, BoxedError> {
+        let schema = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(PipelineError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+        Ok(schema.clone())
+    }
+
+    async fn build(
+        &self,
+        input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+        checkpoint_data: Option<Vec<u8>>,
+    ) -> Result<Box<dyn Processor>, BoxedError> {
+        let schema = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(PipelineError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+
+        let mut partition_by = Vec::with_capacity(self.partition_by.len());
+        for expr in &self.partition_by {
+            partition_by.push(
+                ExpressionBuilder::new(schema.fields.len(), self.runtime.clone())
+                    .build(false, expr, schema, &self.udfs)
+                    .await?,
+            );
+        }
+
+        let mut order_by = Vec::with_capacity(self.order_by.len());
+        for expr in &self.order_by {
+            let expression = ExpressionBuilder::new(schema.fields.len(), self.runtime.clone())
+                .build(false, &expr.expr, schema, &self.udfs)
+                .await?;
+            // Like in PostgreSQL, nulls are sorted as if they were larger than any other value
+            let descending = expr.asc == Some(false);
+            order_by.push(OrderByExpression {
+                expression,
+                descending,
+                nulls_first: expr.nulls_first.unwrap_or(descending),
+            });
+        }
+
+        Ok(Box::new(
+            TopNProcessor::new(
+                self.id.clone(),
+                schema.clone(),
+                partition_by,
+                order_by,
+                self.offset,
+                self.limit,
+                checkpoint_data,
+            )
+            .map_err(PipelineError::TopNError)?,
+        ))
+    }
+}
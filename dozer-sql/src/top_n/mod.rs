@@ -0,0 +1,4 @@
+pub(crate) mod factory;
+mod operator;
+mod processor;
+pub mod tests;
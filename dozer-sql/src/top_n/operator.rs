@@ -0,0 +1,279 @@
+use std::cmp::Ordering;
+use std::collections::{hash_map::Entry, BTreeMap, HashMap};
+
+use dozer_core::{
+    checkpoint::serialize::{
+        deserialize_bincode, deserialize_record, deserialize_u64, serialize_bincode,
+        serialize_record, serialize_u64, Cursor, DeserializationError, SerializationError,
+    },
+    dozer_log::storage::Object,
+};
+use dozer_types::types::{Field, Operation, Record};
+
+/// A value of an `ORDER BY` expression, carrying the direction it must be sorted in.
+#[derive(Debug, Clone, PartialEq, Eq, bincode::Encode, bincode::Decode)]
+pub struct SortField {
+    pub value: Field,
+    pub descending: bool,
+    pub nulls_first: bool,
+}
+
+impl Ord for SortField {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (&self.value, &other.value) {
+            (Field::Null, Field::Null) => Ordering::Equal,
+            (Field::Null, _) if self.nulls_first => Ordering::Less,
+            (Field::Null, _) => Ordering::Greater,
+            (_, Field::Null) if self.nulls_first => Ordering::Greater,
+            (_, Field::Null) => Ordering::Less,
+            (left, right) if self.descending => right.cmp(left),
+            (left, right) => left.cmp(right),
+        }
+    }
+}
+
+impl PartialOrd for SortField {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+pub type SortKey = Vec<SortField>;
+
+pub type PartitionKey = Vec<Field>;
+
+/// Ranks the records of every partition separately, like the partitions of
+/// `ROW_NUMBER() OVER (PARTITION BY region ORDER BY score DESC)`.
+///
+/// Queries without partitions put all records in the same partition.
+#[derive(Debug)]
+pub struct PartitionedTopN {
+    offset: usize,
+    limit: usize,
+    partitions: HashMap<PartitionKey, TopN>,
+}
+
+impl PartitionedTopN {
+    pub fn new(
+        offset: usize,
+        limit: usize,
+        cursor: Option<&mut Cursor>,
+    ) -> Result<Self, DeserializationError> {
+        let mut partitions = HashMap::new();
+        if let Some(cursor) = cursor {
+            let len = deserialize_u64(cursor)?;
+            for _ in 0..len {
+                let partition = deserialize_bincode(cursor)?;
+                partitions.insert(partition, TopN::new(offset, limit, Some(&mut *cursor))?);
+            }
+        }
+        Ok(Self {
+            offset,
+            limit,
+            partitions,
+        })
+    }
+
+    pub fn insert(
+        &mut self,
+        partition: PartitionKey,
+        key: SortKey,
+        record: Record,
+    ) -> Vec<Operation> {
+        let (offset, limit) = (self.offset, self.limit);
+        self.partitions
+            .entry(partition)
+            .or_insert_with(|| TopN::empty(offset, limit))
+            .insert(key, record)
+    }
+
+    pub fn delete(
+        &mut self,
+        partition: PartitionKey,
+        key: SortKey,
+        record: &Record,
+    ) -> Vec<Operation> {
+        let Entry::Occupied(mut entry) = self.partitions.entry(partition) else {
+            return vec![];
+        };
+        let ops = entry.get_mut().delete(key, record);
+        if entry.get().is_empty() {
+            entry.remove();
+        }
+        ops
+    }
+
+    pub fn serialize(&self, object: &mut Object) -> Result<(), SerializationError> {
+        serialize_u64(self.partitions.len() as u64, object)?;
+        for (partition, top_n) in &self.partitions {
+            serialize_bincode(partition, object)?;
+            top_n.serialize(object)?;
+        }
+        Ok(())
+    }
+}
+
+/// Sort key of a record, followed by its insertion sequence number to keep records with the
+/// same sort key in insertion order.
+type RankKey = (SortKey, u64);
+
+/// Keeps every input record sorted, and emits the changes to the rows ranked
+/// between `offset` and `offset + limit`.
+///
+/// Records are split in three sorted tiers at those ranks, so a change only moves the first or last
+/// records of the tiers, and ranks never have to be counted.
+#[derive(Debug)]
+pub struct TopN {
+    offset: usize,
+    limit: usize,
+    next_seq: u64,
+    /// The first `offset` records
+    skipped: BTreeMap<RankKey, Record>,
+    /// The next `limit` records, which are the output
+    visible: BTreeMap<RankKey, Record>,
+    /// All the records after them
+    rest: BTreeMap<RankKey, Record>,
+}
+
+impl TopN {
+    pub fn new(
+        offset: usize,
+        limit: usize,
+        cursor: Option<&mut Cursor>,
+    ) -> Result<Self, DeserializationError> {
+        let mut top_n = Self::empty(offset, limit);
+        if let Some(cursor) = cursor {
+            top_n.next_seq = deserialize_u64(cursor)?;
+            let len = deserialize_u64(cursor)?;
+            for _ in 0..len {
+                let key = deserialize_bincode(cursor)?;
+                let seq = deserialize_u64(cursor)?;
+                let record = deserialize_record(cursor)?;
+                top_n.push_back((key, seq), record);
+            }
+        }
+        Ok(top_n)
+    }
+
+    fn empty(offset: usize, limit: usize) -> Self {
+        Self {
+            offset,
+            limit,
+            next_seq: 0,
+            skipped: BTreeMap::new(),
+            visible: BTreeMap::new(),
+            rest: BTreeMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, key: SortKey, record: Record) -> Vec<Operation> {
+        let (mut key, mut record) = ((key, self.next_seq), record);
+        self.next_seq += 1;
+
+        if fits(&self.skipped, self.offset, &key) {
+            self.skipped.insert(key, record);
+            if self.skipped.len() <= self.offset {
+                return vec![];
+            }
+            (key, record) = self
+                .skipped
+                .pop_last()
+                .expect("skipped records can't be empty");
+        }
+
+        let mut ops = vec![];
+        if fits(&self.visible, self.limit, &key) {
+            let entering = Operation::Insert {
+                new: record.clone(),
+            };
+            self.visible.insert(key, record);
+            if self.visible.len() <= self.limit {
+                return vec![entering];
+            }
+            (key, record) = self
+                .visible
+                .pop_last()
+                .expect("visible records can't be empty");
+            ops.push(Operation::Delete {
+                old: record.clone(),
+            });
+            ops.push(entering);
+        }
+        self.rest.insert(key, record);
+        ops
+    }
+
+    pub fn delete(&mut self, key: SortKey, record: &Record) -> Vec<Operation> {
+        let range = (key.clone(), 0)..=(key, u64::MAX);
+        let find = |records: &BTreeMap<RankKey, Record>| {
+            records
+                .range(range.clone())
+                .find(|(_, r)| *r == record)
+                .map(|(key, _)| key.clone())
+        };
+
+        let mut ops = vec![];
+        if let Some(key) = find(&self.skipped) {
+            self.skipped.remove(&key);
+            // The first visible record is skipped instead
+            let Some((key, record)) = self.visible.pop_first() else {
+                return vec![];
+            };
+            ops.push(Operation::Delete {
+                old: record.clone(),
+            });
+            self.skipped.insert(key, record);
+        } else if let Some(key) = find(&self.visible) {
+            let record = self.visible.remove(&key).expect("key must exist");
+            ops.push(Operation::Delete { old: record });
+        } else {
+            if let Some(key) = find(&self.rest) {
+                self.rest.remove(&key);
+            }
+            return vec![];
+        }
+
+        // The first record after the visible ones takes the free rank
+        if let Some((key, record)) = self.rest.pop_first() {
+            ops.push(Operation::Insert {
+                new: record.clone(),
+            });
+            self.visible.insert(key, record);
+        }
+        ops
+    }
+
+    pub fn serialize(&self, object: &mut Object) -> Result<(), SerializationError> {
+        serialize_u64(self.next_seq, object)?;
+        serialize_u64(
+            (self.skipped.len() + self.visible.len() + self.rest.len()) as u64,
+            object,
+        )?;
+        for ((key, seq), record) in self.skipped.iter().chain(&self.visible).chain(&self.rest) {
+            serialize_bincode(key, object)?;
+            serialize_u64(*seq, object)?;
+            serialize_record(record, object)?;
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.skipped.is_empty() && self.visible.is_empty() && self.rest.is_empty()
+    }
+
+    /// Adds a record sorted after all the others.
+    fn push_back(&mut self, key: RankKey, record: Record) {
+        if self.skipped.len() < self.offset {
+            self.skipped.insert(key, record);
+        } else if self.visible.len() < self.limit {
+            self.visible.insert(key, record);
+        } else {
+            self.rest.insert(key, record);
+        }
+    }
+}
+
+/// Whether a record sorted at `key` belongs to a tier of `capacity` records. Lower tiers are always full.
+fn fits(records: &BTreeMap<RankKey, Record>, capacity: usize, key: &RankKey) -> bool {
+    records.len() < capacity || records.last_key_value().is_some_and(|(last, _)| key < last)
+}
@@ -0,0 +1,136 @@
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::checkpoint::serialize::Cursor;
+use dozer_core::dozer_log::storage::Object;
+use dozer_core::epoch::Epoch;
+use dozer_core::node::{PortHandle, Processor};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_sql_expression::execution::Expression;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::types::{Operation, OperationWithId, Record, Schema};
+
+use crate::errors::{PipelineError, TopNError};
+
+use super::operator::{PartitionKey, PartitionedTopN, SortField, SortKey};
+
+#[derive(Debug)]
+pub struct OrderByExpression {
+    pub expression: Expression,
+    pub descending: bool,
+    pub nulls_first: bool,
+}
+
+#[derive(Debug)]
+pub struct TopNProcessor {
+    _id: String,
+    input_schema: Schema,
+    partition_by: Vec<Expression>,
+    order_by: Vec<OrderByExpression>,
+    top_n: PartitionedTopN,
+}
+
+impl TopNProcessor {
+    pub fn new(
+        id: String,
+        input_schema: Schema,
+        mut partition_by: Vec<Expression>,
+        mut order_by: Vec<OrderByExpression>,
+        offset: usize,
+        limit: usize,
+        checkpoint_data: Option<Vec<u8>>,
+    ) -> Result<Self, TopNError> {
+        let mut cursor = checkpoint_data.as_deref().map(Cursor::new);
+        if let Some(cursor) = cursor.as_mut() {
+            for expression in &mut partition_by {
+                expression.deserialize_state(cursor)?;
+            }
+            for order_by in &mut order_by {
+                order_by.expression.deserialize_state(cursor)?;
+            }
+        }
+        Ok(Self {
+            _id: id,
+            input_schema,
+            partition_by,
+            order_by,
+            top_n: PartitionedTopN::new(offset, limit, cursor.as_mut())?,
+        })
+    }
+
+    fn get_partition_key(&mut self, record: &Record) -> Result<PartitionKey, PipelineError> {
+        self.partition_by
+            .iter_mut()
+            .map(|expression| Ok(expression.evaluate(record, &self.input_schema)?))
+            .collect()
+    }
+
+    fn get_sort_key(&mut self, record: &Record) -> Result<SortKey, PipelineError> {
+        self.order_by
+            .iter_mut()
+            .map(|order_by| {
+                Ok(SortField {
+                    value: order_by.expression.evaluate(record, &self.input_schema)?,
+                    descending: order_by.descending,
+                    nulls_first: order_by.nulls_first,
+                })
+            })
+            .collect()
+    }
+
+    fn insert(&mut self, record: Record) -> Result<Vec<Operation>, PipelineError> {
+        let partition = self.get_partition_key(&record)?;
+        let key = self.get_sort_key(&record)?;
+        Ok(self.top_n.insert(partition, key, record))
+    }
+
+    fn delete(&mut self, record: Record) -> Result<Vec<Operation>, PipelineError> {
+        let partition = self.get_partition_key(&record)?;
+        let key = self.get_sort_key(&record)?;
+        Ok(self.top_n.delete(partition, key, &record))
+    }
+}
+
+impl Processor for TopNProcessor {
+    fn commit(&self, _epoch: &Epoch) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _from_port: PortHandle,
+        op: OperationWithId,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), BoxedError> {
+        let ops = match op.op {
+            Operation::Delete { old } => self.delete(old)?,
+            Operation::Insert { new } => self.insert(new)?,
+            Operation::Update { old, new } => {
+                let mut ops = self.delete(old)?;
+                ops.extend(self.insert(new)?);
+                ops
+            }
+            Operation::BatchInsert { new } => {
+                let mut ops = vec![];
+                for record in new {
+                    ops.extend(self.insert(record)?);
+                }
+                ops
+            }
+        };
+
+        for op in ops {
+            fw.send(OperationWithId::without_id(op), DEFAULT_PORT_HANDLE);
+        }
+        Ok(())
+    }
+
+    fn serialize(&mut self, mut object: Object) -> Result<(), BoxedError> {
+        for expression in &self.partition_by {
+            expression.serialize_state(&mut object)?;
+        }
+        for order_by in &self.order_by {
+            order_by.expression.serialize_state(&mut object)?;
+        }
+        self.top_n.serialize(&mut object)?;
+        Ok(())
+    }
+}
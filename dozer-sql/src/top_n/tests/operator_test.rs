@@ -0,0 +1,192 @@
+use dozer_types::types::{Field, Operation, Record};
+
+use crate::top_n::operator::{PartitionedTopN, SortField, SortKey, TopN};
+
+fn key(value: i64) -> SortKey {
+    vec![SortField {
+        value: Field::Int(value),
+        descending: true,
+        nulls_first: true,
+    }]
+}
+
+fn record(name: &str, score: i64) -> Record {
+    Record::new(vec![Field::String(name.to_string()), Field::Int(score)])
+}
+
+fn insert(top_n: &mut TopN, name: &str, score: i64) -> Vec<Operation> {
+    top_n.insert(key(score), record(name, score))
+}
+
+fn delete(top_n: &mut TopN, name: &str, score: i64) -> Vec<Operation> {
+    top_n.delete(key(score), &record(name, score))
+}
+
+#[test]
+fn test_top_n() {
+    let mut top_n = TopN::new(0, 2, None).unwrap();
+
+    assert_eq!(
+        insert(&mut top_n, "a", 10),
+        vec![Operation::Insert {
+            new: record("a", 10)
+        }]
+    );
+    assert_eq!(
+        insert(&mut top_n, "b", 20),
+        vec![Operation::Insert {
+            new: record("b", 20)
+        }]
+    );
+
+    // Not in the top 2
+    assert_eq!(insert(&mut top_n, "c", 5), vec![]);
+
+    // Pushes "a" out of the top 2
+    assert_eq!(
+        insert(&mut top_n, "d", 30),
+        vec![
+            Operation::Delete {
+                old: record("a", 10)
+            },
+            Operation::Insert {
+                new: record("d", 30)
+            }
+        ]
+    );
+
+    // "a" is promoted back
+    assert_eq!(
+        delete(&mut top_n, "b", 20),
+        vec![
+            Operation::Delete {
+                old: record("b", 20)
+            },
+            Operation::Insert {
+                new: record("a", 10)
+            }
+        ]
+    );
+
+    // Not in the top 2
+    assert_eq!(delete(&mut top_n, "c", 5), vec![]);
+
+    // Unknown record
+    assert_eq!(delete(&mut top_n, "c", 5), vec![]);
+
+    assert_eq!(
+        delete(&mut top_n, "d", 30),
+        vec![Operation::Delete {
+            old: record("d", 30)
+        }]
+    );
+}
+
+#[test]
+fn test_top_n_offset() {
+    let mut top_n = TopN::new(1, 1, None).unwrap();
+
+    assert_eq!(insert(&mut top_n, "a", 10), vec![]);
+    assert_eq!(
+        insert(&mut top_n, "b", 5),
+        vec![Operation::Insert {
+            new: record("b", 5)
+        }]
+    );
+
+    // Shifts "a" into the visible range
+    assert_eq!(
+        insert(&mut top_n, "c", 20),
+        vec![
+            Operation::Delete {
+                old: record("b", 5)
+            },
+            Operation::Insert {
+                new: record("a", 10)
+            }
+        ]
+    );
+
+    // Shifts "a" out of the visible range again
+    assert_eq!(
+        delete(&mut top_n, "c", 20),
+        vec![
+            Operation::Delete {
+                old: record("a", 10)
+            },
+            Operation::Insert {
+                new: record("b", 5)
+            }
+        ]
+    );
+}
+
+#[test]
+fn test_partitioned_top_n() {
+    let mut top_n = PartitionedTopN::new(0, 1, None).unwrap();
+    let region = |name: &str| vec![Field::String(name.to_string())];
+    let mut insert = |partition: &str, name: &str, score: i64| {
+        top_n.insert(region(partition), key(score), record(name, score))
+    };
+
+    // Every region has its own top 1
+    assert_eq!(
+        insert("eu", "a", 10),
+        vec![Operation::Insert {
+            new: record("a", 10)
+        }]
+    );
+    assert_eq!(
+        insert("us", "b", 5),
+        vec![Operation::Insert {
+            new: record("b", 5)
+        }]
+    );
+    assert_eq!(insert("eu", "c", 1), vec![]);
+    assert_eq!(
+        insert("us", "d", 20),
+        vec![
+            Operation::Delete {
+                old: record("b", 5)
+            },
+            Operation::Insert {
+                new: record("d", 20)
+            }
+        ]
+    );
+
+    assert_eq!(
+        top_n.delete(region("eu"), key(10), &record("a", 10)),
+        vec![
+            Operation::Delete {
+                old: record("a", 10)
+            },
+            Operation::Insert {
+                new: record("c", 1)
+            }
+        ]
+    );
+    assert_eq!(
+        top_n.delete(region("ap"), key(10), &record("a", 10)),
+        vec![]
+    );
+}
+
+#[test]
+fn test_sort_field_nulls() {
+    let ascending = |value| SortField {
+        value,
+        descending: false,
+        nulls_first: false,
+    };
+    assert!(ascending(Field::Int(1)) < ascending(Field::Int(2)));
+    assert!(ascending(Field::Int(1)) < ascending(Field::Null));
+
+    let descending = |value| SortField {
+        value,
+        descending: true,
+        nulls_first: true,
+    };
+    assert!(descending(Field::Int(2)) < descending(Field::Int(1)));
+    assert!(descending(Field::Null) < descending(Field::Int(1)));
+}
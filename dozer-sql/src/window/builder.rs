@@ -22,6 +22,10 @@ const ARG_TUMBLE_INTERVAL: usize = 2;
 const ARG_HOP_SIZE: usize = 2;
 const ARG_HOP_INTERVAL: usize = 3;
 
+const ARG_SESSION_GAP: usize = 2;
+/// The optional lateness, if it's a string, else the first key.
+const ARG_SESSION_LATENESS: usize = 3;
+
 pub(crate) fn window_from_table_operator(
     operator: &TableOperatorDescriptor,
     schema: &Schema,
@@ -71,6 +75,45 @@ pub(crate) fn window_from_table_operator(
             hop_size,
             interval,
         }));
+    } else if operator.name.to_uppercase() == "SESSION" {
+        let column_index = get_window_column_index(&operator.args, schema)?;
+        let gap_arg = operator
+            .args
+            .get(ARG_SESSION_GAP)
+            .ok_or(WindowError::WindowMissingGapArgument)?;
+        let argument = if let TableOperatorArg::Argument(arg) = gap_arg {
+            arg
+        } else {
+            return Err(WindowError::WindowInvalidGap("".to_string()));
+        };
+        let gap = get_window_gap(argument)?;
+        let (lateness, first_key) = match operator.args.get(ARG_SESSION_LATENESS) {
+            Some(TableOperatorArg::Argument(FunctionArg::Unnamed(FunctionArgExpr::Expr(
+                Expr::Value(Value::SingleQuotedString(s) | Value::DoubleQuotedString(s)),
+            )))) => {
+                let lateness = parse_duration_string(s)
+                    .map_err(|_| WindowError::WindowInvalidLateness(s.to_owned()))?;
+                if lateness < Duration::zero() {
+                    return Err(WindowError::WindowInvalidLateness(s.to_owned()));
+                }
+                (lateness, ARG_SESSION_LATENESS + 1)
+            }
+            // Records may be as late as the gap by default.
+            _ => (gap, ARG_SESSION_LATENESS),
+        };
+        let key_indexes = operator
+            .args
+            .iter()
+            .skip(first_key)
+            .map(|arg| get_column_index(arg, schema))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        return Ok(Some(WindowType::Session {
+            column_index,
+            gap,
+            lateness,
+            key_indexes,
+        }));
     } else {
         return Err(WindowError::UnsupportedRelationFunction(
             operator.name.clone(),
@@ -124,6 +167,32 @@ fn get_window_hop(hop_arg: &FunctionArg) -> Result<Duration, WindowError> {
     }
 }
 
+fn get_window_gap(gap_arg: &FunctionArg) -> Result<Duration, WindowError> {
+    match gap_arg {
+        FunctionArg::Named { name, arg: _ } => {
+            let column_name = ExpressionBuilder::normalize_ident(name);
+            Err(WindowError::WindowInvalidGap(column_name))
+        }
+        FunctionArg::Unnamed(arg_expr) => match arg_expr {
+            FunctionArgExpr::Expr(expr) => match expr {
+                Expr::Value(Value::SingleQuotedString(s) | Value::DoubleQuotedString(s)) => {
+                    let gap: Duration = parse_duration_string(s)
+                        .map_err(|_| WindowError::WindowInvalidGap(s.to_owned()))?;
+                    if gap <= Duration::zero() {
+                        return Err(WindowError::WindowInvalidGap(s.to_owned()));
+                    }
+                    Ok(gap)
+                }
+                _ => Err(WindowError::WindowInvalidGap(expr.to_string())),
+            },
+            FunctionArgExpr::QualifiedWildcard(_) => {
+                Err(WindowError::WindowInvalidGap("*".to_string()))
+            }
+            FunctionArgExpr::Wildcard => Err(WindowError::WindowInvalidGap("*".to_string())),
+        },
+    }
+}
+
 fn get_window_column_index(
     args: &[TableOperatorArg],
     schema: &Schema,
@@ -131,6 +200,10 @@ fn get_window_column_index(
     let column_arg = args
         .get(ARG_COLUMN)
         .ok_or(WindowError::WindowMissingColumnArgument)?;
+    get_column_index(column_arg, schema)
+}
+
+fn get_column_index(column_arg: &TableOperatorArg, schema: &Schema) -> Result<usize, WindowError> {
     let argument = if let TableOperatorArg::Argument(arg) = column_arg {
         arg
     } else {
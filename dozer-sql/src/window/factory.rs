@@ -83,11 +83,10 @@ impl ProcessorFactory for WindowProcessorFactory {
         match window_from_table_operator(&self.table, &input_schema)
             .map_err(PipelineError::WindowError)?
         {
-            Some(window) => Ok(Box::new(WindowProcessor::new(
-                self.id.clone(),
-                window,
-                checkpoint_data,
-            ))),
+            Some(window) => Ok(Box::new(
+                WindowProcessor::new(self.id.clone(), window, checkpoint_data)
+                    .map_err(PipelineError::WindowError)?,
+            )),
             None => Err(PipelineError::WindowError(WindowError::InvalidWindow()).into()),
         }
     }
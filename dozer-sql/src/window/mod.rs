@@ -2,4 +2,5 @@ pub(crate) mod builder;
 pub(crate) mod factory;
 mod operator;
 mod processor;
+mod session;
 pub mod tests;
@@ -16,6 +16,12 @@ pub enum WindowType {
         hop_size: Duration,
         interval: Duration,
     },
+    Session {
+        column_index: usize,
+        gap: Duration,
+        lateness: Duration,
+        key_indexes: Vec<usize>,
+    },
 }
 
 impl WindowType {
@@ -30,6 +36,8 @@ impl WindowType {
                 hop_size,
                 interval,
             } => execute_hop_window(record, *column_index, *hop_size, *interval),
+            // Session windows depend on the previous records, see `SessionWindow`
+            WindowType::Session { .. } => Err(WindowError::InvalidWindow()),
         }
     }
 
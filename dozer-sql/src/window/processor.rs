@@ -1,5 +1,6 @@
-use crate::errors::PipelineError;
+use crate::errors::{PipelineError, WindowError};
 use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::checkpoint::serialize::Cursor;
 use dozer_core::dozer_log::storage::Object;
 use dozer_core::epoch::Epoch;
 use dozer_core::node::{PortHandle, Processor};
@@ -8,16 +9,72 @@ use dozer_types::errors::internal::BoxedError;
 use dozer_types::types::{Operation, OperationWithId};
 
 use super::operator::WindowType;
+use super::session::SessionWindow;
 
 #[derive(Debug)]
 pub struct WindowProcessor {
     _id: String,
     window: WindowType,
+    /// Open sessions, only present for session windows
+    session: Option<SessionWindow>,
 }
 
 impl WindowProcessor {
-    pub fn new(id: String, window: WindowType, _checkpoint_data: Option<Vec<u8>>) -> Self {
-        Self { _id: id, window }
+    pub fn new(
+        id: String,
+        window: WindowType,
+        checkpoint_data: Option<Vec<u8>>,
+    ) -> Result<Self, WindowError> {
+        let session = if let WindowType::Session {
+            column_index,
+            gap,
+            lateness,
+            key_indexes,
+        } = &window
+        {
+            let mut cursor = checkpoint_data.as_deref().map(Cursor::new);
+            Some(SessionWindow::new(
+                *column_index,
+                *gap,
+                *lateness,
+                key_indexes.clone(),
+                cursor.as_mut(),
+            )?)
+        } else {
+            None
+        };
+        Ok(Self {
+            _id: id,
+            window,
+            session,
+        })
+    }
+
+    fn process_session(
+        session: &mut SessionWindow,
+        op: Operation,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), WindowError> {
+        let ops = match op {
+            Operation::Delete { old } => session.delete(old)?,
+            Operation::Insert { new } => session.insert(new)?,
+            Operation::Update { old, new } => {
+                let mut ops = session.delete(old)?;
+                ops.extend(session.insert(new)?);
+                ops
+            }
+            Operation::BatchInsert { new } => {
+                let mut ops = vec![];
+                for record in new {
+                    ops.extend(session.insert(record)?);
+                }
+                ops
+            }
+        };
+        for op in ops {
+            fw.send(OperationWithId::without_id(op), DEFAULT_PORT_HANDLE);
+        }
+        Ok(())
     }
 }
 
@@ -32,6 +89,11 @@ impl Processor for WindowProcessor {
         op: OperationWithId,
         fw: &mut dyn ProcessorChannelForwarder,
     ) -> Result<(), BoxedError> {
+        if let Some(session) = &mut self.session {
+            return Self::process_session(session, op.op, fw)
+                .map_err(|e| PipelineError::WindowError(e).into());
+        }
+
         match op.op {
             Operation::Delete { old } => {
                 let records = self
@@ -88,7 +150,10 @@ impl Processor for WindowProcessor {
         Ok(())
     }
 
-    fn serialize(&mut self, _object: Object) -> Result<(), BoxedError> {
+    fn serialize(&mut self, mut object: Object) -> Result<(), BoxedError> {
+        if let Some(session) = &self.session {
+            session.serialize(&mut object)?;
+        }
         Ok(())
     }
 }
@@ -0,0 +1,306 @@
+use std::collections::{BTreeMap, HashMap};
+
+use dozer_core::{
+    checkpoint::serialize::{
+        deserialize_bincode, deserialize_record, deserialize_u64, serialize_bincode,
+        serialize_record, serialize_u64, Cursor, SerializationError,
+    },
+    dozer_log::storage::Object,
+};
+use dozer_types::{
+    chrono::{DateTime, Duration, FixedOffset},
+    types::{Field, Operation, Record},
+};
+
+use crate::errors::WindowError;
+
+type SessionKey = Vec<Field>;
+
+#[derive(Debug, Clone)]
+struct Session {
+    /// Timestamp of the earliest record in the session
+    start: DateTime<FixedOffset>,
+    /// Timestamp of the latest record in the session
+    last: DateTime<FixedOffset>,
+    records: Vec<Record>,
+}
+
+#[derive(Debug)]
+struct KeySessions {
+    /// Latest timestamp inserted with the key
+    watermark: DateTime<FixedOffset>,
+    /// Open sessions, indexed by their start time
+    sessions: BTreeMap<DateTime<FixedOffset>, Session>,
+}
+
+/// State of a `SESSION` window.
+///
+/// Records are grouped by the key columns, and every record of a key opens a window of `gap`
+/// length starting at its timestamp. Overlapping windows are merged into a single session, which
+/// spans from its earliest record to `gap` after its latest one.
+///
+/// Every key has its own watermark, the latest timestamp inserted with it, and records may be up to
+/// `lateness` older than the watermark of their key. A session is closed, and its records dropped,
+/// once no such record can join it. Records later than that, and retractions of records of closed
+/// sessions, are errors. The watermark of a key is kept after its sessions are closed.
+#[derive(Debug)]
+pub struct SessionWindow {
+    column_index: usize,
+    gap: Duration,
+    lateness: Duration,
+    key_indexes: Vec<usize>,
+    sessions: HashMap<SessionKey, KeySessions>,
+}
+
+impl SessionWindow {
+    pub fn new(
+        column_index: usize,
+        gap: Duration,
+        lateness: Duration,
+        key_indexes: Vec<usize>,
+        cursor: Option<&mut Cursor>,
+    ) -> Result<Self, WindowError> {
+        let mut window = Self {
+            column_index,
+            gap,
+            lateness,
+            key_indexes,
+            sessions: HashMap::new(),
+        };
+        if let Some(cursor) = cursor {
+            window.deserialize(cursor)?;
+        }
+        Ok(window)
+    }
+
+    pub fn insert(&mut self, record: Record) -> Result<Vec<Operation>, WindowError> {
+        let ts = get_timestamp(&record, self.column_index)?;
+        let (gap, lateness) = (self.gap, self.lateness);
+        let key = self.get_key(&record);
+        let key_sessions = self.sessions.entry(key).or_insert_with(|| KeySessions {
+            watermark: ts,
+            sessions: BTreeMap::new(),
+        });
+        if ts + lateness < key_sessions.watermark {
+            return Err(WindowError::SessionRecordTooLate(ts.to_rfc3339()));
+        }
+        let sessions = &mut key_sessions.sessions;
+
+        // Sessions don't overlap, so both their starts and ends are sorted
+        let overlapping = sessions
+            .range(..ts + gap)
+            .rev()
+            .take_while(|(_, session)| session.last + gap > ts)
+            .map(|(start, _)| *start)
+            .collect::<Vec<_>>();
+
+        let mut merged = Session {
+            start: ts,
+            last: ts,
+            records: vec![],
+        };
+        let mut previous = Vec::with_capacity(overlapping.len());
+        for start in overlapping {
+            let session = sessions.remove(&start).expect("session must exist");
+            merged.start = merged.start.min(session.start);
+            merged.last = merged.last.max(session.last);
+            previous.push(session);
+        }
+
+        let mut ops = vec![];
+        for session in previous {
+            if session.start == merged.start && session.last == merged.last {
+                merged.records.extend(session.records);
+                continue;
+            }
+            for record in session.records {
+                ops.push(Operation::Update {
+                    old: window_record(&record, &session, gap),
+                    new: window_record(&record, &merged, gap),
+                });
+                merged.records.push(record);
+            }
+        }
+
+        ops.push(Operation::Insert {
+            new: window_record(&record, &merged, gap),
+        });
+        merged.records.push(record);
+        sessions.insert(merged.start, merged);
+
+        key_sessions.watermark = key_sessions.watermark.max(ts);
+        key_sessions.close(gap, lateness);
+        Ok(ops)
+    }
+
+    pub fn delete(&mut self, record: Record) -> Result<Vec<Operation>, WindowError> {
+        let ts = get_timestamp(&record, self.column_index)?;
+        let gap = self.gap;
+        let key = self.get_key(&record);
+        let Some(key_sessions) = self.sessions.get_mut(&key) else {
+            return Ok(vec![]);
+        };
+        let sessions = &mut key_sessions.sessions;
+
+        let found = sessions
+            .range(..=ts)
+            .next_back()
+            .filter(|(_, session)| session.last >= ts)
+            .and_then(|(start, session)| {
+                let position = session.records.iter().position(|r| r == &record)?;
+                Some((*start, position))
+            });
+        let Some((start, position)) = found else {
+            // The record may have been in a closed session, which can't be updated anymore.
+            if ts + self.lateness < key_sessions.watermark {
+                return Err(WindowError::SessionRetractionTooLate(ts.to_rfc3339()));
+            }
+            return Ok(vec![]);
+        };
+        let mut session = sessions.remove(&start).expect("session must exist");
+        session.records.swap_remove(position);
+
+        let mut ops = vec![Operation::Delete {
+            old: window_record(&record, &session, gap),
+        }];
+
+        // Removing a record may shrink the session or split it in several sessions
+        let mut records = session
+            .records
+            .iter()
+            .map(|record| Ok((get_timestamp(record, self.column_index)?, record)))
+            .collect::<Result<Vec<_>, WindowError>>()?;
+        records.sort_by_key(|(ts, _)| *ts);
+
+        let mut split: Vec<Session> = vec![];
+        for (ts, record) in records {
+            match split.last_mut() {
+                Some(current) if ts < current.last + gap => {
+                    current.last = ts;
+                    current.records.push(record.clone());
+                }
+                _ => split.push(Session {
+                    start: ts,
+                    last: ts,
+                    records: vec![record.clone()],
+                }),
+            }
+        }
+
+        for new_session in split {
+            if new_session.start != session.start || new_session.last != session.last {
+                for record in &new_session.records {
+                    ops.push(Operation::Update {
+                        old: window_record(record, &session, gap),
+                        new: window_record(record, &new_session, gap),
+                    });
+                }
+            }
+            sessions.insert(new_session.start, new_session);
+        }
+
+        Ok(ops)
+    }
+
+    pub fn serialize(&self, object: &mut Object) -> Result<(), SerializationError> {
+        serialize_u64(self.sessions.len() as u64, object)?;
+        for (key, key_sessions) in &self.sessions {
+            serialize_bincode(key, object)?;
+            serialize_bincode(Field::Timestamp(key_sessions.watermark), object)?;
+            serialize_u64(key_sessions.sessions.len() as u64, object)?;
+            for session in key_sessions.sessions.values() {
+                serialize_u64(session.records.len() as u64, object)?;
+                for record in &session.records {
+                    serialize_record(record, object)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn deserialize(&mut self, cursor: &mut Cursor) -> Result<(), WindowError> {
+        let num_keys = deserialize_u64(cursor)?;
+        for _ in 0..num_keys {
+            let key: SessionKey = deserialize_bincode(cursor)?;
+            let Field::Timestamp(watermark) = deserialize_bincode(cursor)? else {
+                return Err(WindowError::SessionInvalidColumnType());
+            };
+            let num_sessions = deserialize_u64(cursor)?;
+            let mut sessions = BTreeMap::new();
+            for _ in 0..num_sessions {
+                let num_records = deserialize_u64(cursor)? as usize;
+                let mut records = Vec::with_capacity(num_records);
+                for _ in 0..num_records {
+                    records.push(deserialize_record(cursor)?);
+                }
+
+                let mut bounds = None;
+                for record in &records {
+                    let ts = get_timestamp(record, self.column_index)?;
+                    bounds = Some(match bounds {
+                        Some((start, last)) => (ts.min(start), ts.max(last)),
+                        None => (ts, ts),
+                    });
+                }
+                if let Some((start, last)) = bounds {
+                    sessions.insert(
+                        start,
+                        Session {
+                            start,
+                            last,
+                            records,
+                        },
+                    );
+                }
+            }
+            self.sessions.insert(
+                key,
+                KeySessions {
+                    watermark,
+                    sessions,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    fn get_key(&self, record: &Record) -> SessionKey {
+        self.key_indexes
+            .iter()
+            .map(|index| record.values[*index].clone())
+            .collect()
+    }
+}
+
+impl KeySessions {
+    /// Closes the sessions that no record at most `lateness` older than the watermark can join.
+    fn close(&mut self, gap: Duration, lateness: Duration) {
+        while let Some(entry) = self.sessions.first_entry() {
+            if entry.get().last + gap + lateness > self.watermark {
+                break;
+            }
+            entry.remove();
+        }
+    }
+}
+
+fn get_timestamp(
+    record: &Record,
+    column_index: usize,
+) -> Result<DateTime<FixedOffset>, WindowError> {
+    match record.values.get(column_index) {
+        Some(Field::Timestamp(ts)) => Ok(*ts),
+        Some(_) => Err(WindowError::SessionInvalidColumnType()),
+        None => Err(WindowError::SessionInvalidColumnIndex()),
+    }
+}
+
+fn window_record(record: &Record, session: &Session, gap: Duration) -> Record {
+    Record::appended(
+        record,
+        &[
+            Field::Timestamp(session.start),
+            Field::Timestamp(session.last + gap),
+        ],
+    )
+}
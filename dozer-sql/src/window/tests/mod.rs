@@ -1,2 +1,4 @@
 #[cfg(test)]
 mod operator_test;
+#[cfg(test)]
+mod session_test;
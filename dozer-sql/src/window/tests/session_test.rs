@@ -0,0 +1,187 @@
+use dozer_types::chrono::{DateTime, Duration};
+use dozer_types::types::{Field, Operation, Record};
+
+use crate::errors::WindowError;
+use crate::window::session::SessionWindow;
+
+fn ts(time: &str) -> Field {
+    Field::Timestamp(DateTime::parse_from_rfc3339(&format!("2020-01-01T{time}Z")).unwrap())
+}
+
+fn record(key: &str, time: &str) -> Record {
+    Record::new(vec![Field::String(key.to_string()), ts(time)])
+}
+
+fn window_record(key: &str, time: &str, start: &str, end: &str) -> Record {
+    Record::appended(&record(key, time), &[ts(start), ts(end)])
+}
+
+fn new_window() -> SessionWindow {
+    SessionWindow::new(1, Duration::minutes(5), Duration::minutes(5), vec![0], None).unwrap()
+}
+
+#[test]
+fn test_session_insert() {
+    let mut window = new_window();
+
+    let ops = window.insert(record("a", "00:00:00")).unwrap();
+    assert_eq!(
+        ops,
+        vec![Operation::Insert {
+            new: window_record("a", "00:00:00", "00:00:00", "00:05:00")
+        }]
+    );
+
+    // Extends the session
+    let ops = window.insert(record("a", "00:03:00")).unwrap();
+    assert_eq!(
+        ops,
+        vec![
+            Operation::Update {
+                old: window_record("a", "00:00:00", "00:00:00", "00:05:00"),
+                new: window_record("a", "00:00:00", "00:00:00", "00:08:00"),
+            },
+            Operation::Insert {
+                new: window_record("a", "00:03:00", "00:00:00", "00:08:00")
+            }
+        ]
+    );
+
+    // Another key opens its own session
+    let ops = window.insert(record("b", "00:04:00")).unwrap();
+    assert_eq!(
+        ops,
+        vec![Operation::Insert {
+            new: window_record("b", "00:04:00", "00:04:00", "00:09:00")
+        }]
+    );
+
+    // After the gap, a new session is opened
+    let ops = window.insert(record("a", "00:08:00")).unwrap();
+    assert_eq!(
+        ops,
+        vec![Operation::Insert {
+            new: window_record("a", "00:08:00", "00:08:00", "00:13:00")
+        }]
+    );
+
+    // A late record merges the two sessions
+    let ops = window.insert(record("a", "00:07:00")).unwrap();
+    assert_eq!(ops.len(), 4);
+    assert!(ops.contains(&Operation::Update {
+        old: window_record("a", "00:08:00", "00:08:00", "00:13:00"),
+        new: window_record("a", "00:08:00", "00:00:00", "00:13:00"),
+    }));
+    assert!(ops.contains(&Operation::Update {
+        old: window_record("a", "00:03:00", "00:00:00", "00:08:00"),
+        new: window_record("a", "00:03:00", "00:00:00", "00:13:00"),
+    }));
+    assert_eq!(
+        ops.last().unwrap(),
+        &Operation::Insert {
+            new: window_record("a", "00:07:00", "00:00:00", "00:13:00")
+        }
+    );
+}
+
+#[test]
+fn test_session_delete() {
+    let mut window = new_window();
+    window.insert(record("a", "00:00:00")).unwrap();
+    window.insert(record("a", "00:04:00")).unwrap();
+    window.insert(record("a", "00:08:00")).unwrap();
+
+    // Removing the middle record splits the session
+    let ops = window.delete(record("a", "00:04:00")).unwrap();
+    assert_eq!(
+        ops,
+        vec![
+            Operation::Delete {
+                old: window_record("a", "00:04:00", "00:00:00", "00:13:00")
+            },
+            Operation::Update {
+                old: window_record("a", "00:00:00", "00:00:00", "00:13:00"),
+                new: window_record("a", "00:00:00", "00:00:00", "00:05:00"),
+            },
+            Operation::Update {
+                old: window_record("a", "00:08:00", "00:00:00", "00:13:00"),
+                new: window_record("a", "00:08:00", "00:08:00", "00:13:00"),
+            },
+        ]
+    );
+
+    let ops = window.delete(record("a", "00:00:00")).unwrap();
+    assert_eq!(
+        ops,
+        vec![Operation::Delete {
+            old: window_record("a", "00:00:00", "00:00:00", "00:05:00")
+        }]
+    );
+
+    // Unknown records are ignored
+    let ops = window.delete(record("a", "00:00:00")).unwrap();
+    assert!(ops.is_empty());
+}
+
+#[test]
+fn test_session_eviction() {
+    let mut window = new_window();
+    window.insert(record("a", "00:00:00")).unwrap();
+
+    // Watermarks are per key, so other keys don't close the session of "a"
+    window.insert(record("b", "00:30:00")).unwrap();
+    window.insert(record("a", "00:04:00")).unwrap();
+
+    // The session of "a" ends at 00:09:00, and is closed once the watermark of "a" passes 00:14:00
+    window.insert(record("a", "00:14:00")).unwrap();
+
+    // Its records can't be retracted anymore
+    assert!(matches!(
+        window.delete(record("a", "00:04:00")),
+        Err(WindowError::SessionRetractionTooLate(_))
+    ));
+    // Records later than the allowed lateness are rejected
+    assert!(matches!(
+        window.insert(record("a", "00:08:59")),
+        Err(WindowError::SessionRecordTooLate(_))
+    ));
+
+    // Records within the allowed lateness open a new session
+    let ops = window.insert(record("a", "00:09:00")).unwrap();
+    assert_eq!(
+        ops,
+        vec![Operation::Insert {
+            new: window_record("a", "00:09:00", "00:09:00", "00:14:00")
+        }]
+    );
+    // Unknown records within the allowed lateness are ignored
+    let ops = window.delete(record("a", "00:10:00")).unwrap();
+    assert!(ops.is_empty());
+}
+
+#[test]
+fn test_session_lateness() {
+    let mut window =
+        SessionWindow::new(1, Duration::minutes(5), Duration::zero(), vec![0], None).unwrap();
+    window.insert(record("a", "00:00:00")).unwrap();
+    window.insert(record("a", "00:06:00")).unwrap();
+
+    // Without lateness, records older than the watermark are rejected, and sessions are closed
+    // as soon as the watermark passes their end
+    assert!(matches!(
+        window.insert(record("a", "00:05:59")),
+        Err(WindowError::SessionRecordTooLate(_))
+    ));
+    assert!(matches!(
+        window.delete(record("a", "00:00:00")),
+        Err(WindowError::SessionRetractionTooLate(_))
+    ));
+
+    let ops = window.delete(record("a", "00:06:00")).unwrap();
+    assert_eq!(
+        ops,
+        vec![Operation::Delete {
+            old: window_record("a", "00:06:00", "00:06:00", "00:11:00")
+        }]
+    );
+}
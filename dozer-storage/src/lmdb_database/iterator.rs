@@ -121,6 +121,26 @@ impl<'txn, C: Cursor<'txn>, K: BorrowEncode, V> Iterator<'txn, C, K, V> {
             _value: std::marker::PhantomData,
         })
     }
+
+    /// See `RawIterator::new_after`.
+    pub fn new_after(
+        cursor: C,
+        key: K::Encode<'_>,
+        value: V::Encode<'_>,
+        ascending: bool,
+    ) -> Result<Self, StorageError>
+    where
+        V: BorrowEncode,
+    {
+        let key = key.encode()?;
+        let value = value.encode()?;
+        let inner = RawIterator::new_after(cursor, key.as_ref(), value.as_ref(), ascending)?;
+        Ok(Self {
+            inner,
+            _key: std::marker::PhantomData,
+            _value: std::marker::PhantomData,
+        })
+    }
 }
 
 fn decode_key_value<'a, K: Decode + 'a, V: Decode + 'a>(
@@ -2,7 +2,8 @@ use std::ops::Bound;
 
 use lmdb::Cursor;
 use lmdb_sys::{
-    MDB_FIRST, MDB_LAST, MDB_NEXT, MDB_NEXT_NODUP, MDB_PREV, MDB_PREV_NODUP, MDB_SET_RANGE,
+    MDB_FIRST, MDB_GET_BOTH_RANGE, MDB_GET_CURRENT, MDB_LAST, MDB_NEXT, MDB_NEXT_NODUP, MDB_PREV,
+    MDB_PREV_NODUP, MDB_SET_RANGE,
 };
 
 use crate::errors::StorageError;
@@ -69,6 +70,49 @@ impl<'txn, C: Cursor<'txn>> RawIterator<'txn, C> {
             state: IteratorState::First { item, ascending },
         })
     }
+
+    /// Creates an iterator over a `DUP_SORT` database, starting right after the `(key, value)` pair in iteration order.
+    ///
+    /// The pair doesn't have to exist in the database.
+    pub fn new_after(
+        cursor: C,
+        key: &[u8],
+        value: &[u8],
+        ascending: bool,
+    ) -> Result<Self, StorageError> {
+        let item = match cursor_get_both_greater_than_or_equal_to(&cursor, key, value)? {
+            Some((hit_key, hit_value)) => {
+                if ascending && hit_value != value {
+                    // Hit greater pair, return it.
+                    Some((hit_key, hit_value))
+                } else {
+                    // Hit equal pair, or greater pair when descending, get next or previous.
+                    cursor_get(&cursor, if ascending { MDB_NEXT } else { MDB_PREV })?
+                }
+            }
+            None => match cursor_get_greater_than(&cursor, key)? {
+                Some(item) => {
+                    if ascending {
+                        Some(item)
+                    } else {
+                        cursor_get(&cursor, MDB_PREV)?
+                    }
+                }
+                None => {
+                    if ascending {
+                        None
+                    } else {
+                        // All pairs less than given pair, get last.
+                        cursor_get(&cursor, MDB_LAST)?
+                    }
+                }
+            },
+        };
+        Ok(RawIterator {
+            cursor,
+            state: IteratorState::First { item, ascending },
+        })
+    }
 }
 
 fn cursor_get<'txn, C: Cursor<'txn>>(
@@ -99,6 +143,20 @@ fn cursor_get_greater_than_or_equal_to<'txn, 'key, C: Cursor<'txn>>(
     }
 }
 
+/// Gets the first pair with given key and a value greater than or equal to given value.
+fn cursor_get_both_greater_than_or_equal_to<'txn, C: Cursor<'txn>>(
+    cursor: &C,
+    key: &[u8],
+    value: &[u8],
+) -> Result<Option<KeyValuePair<'txn>>, lmdb::Error> {
+    match cursor.get(Some(key), Some(value), MDB_GET_BOTH_RANGE) {
+        // `MDB_GET_BOTH_RANGE` doesn't return the key, so read it from the cursor.
+        Ok(_) => cursor_get(cursor, MDB_GET_CURRENT),
+        Err(lmdb::Error::NotFound) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
 fn cursor_get_greater_than<'txn, 'key, C: Cursor<'txn>>(
     cursor: &C,
     key: &'key [u8],
@@ -423,4 +481,50 @@ mod tests {
             assert_eq!(items, vec![b"5", b"3", b"1"]);
         }
     }
+
+    #[test]
+    fn test_raw_iterator_after() {
+        let (_temp_dir, mut env, db) = test_database();
+        for key in [b"1", b"3", b"5"] {
+            for value in [b"a", b"c"] {
+                env.txn_mut()
+                    .unwrap()
+                    .put(db, key, value, WriteFlags::empty())
+                    .unwrap();
+            }
+        }
+        env.commit().unwrap();
+
+        let mut items_after = |key: &[u8], value: &[u8], ascending: bool| {
+            let cursor = env.txn_mut().unwrap().open_ro_cursor(db).unwrap();
+            RawIterator::new_after(cursor, key, value, ascending)
+                .unwrap()
+                .map(|result| result.map(|(key, value)| [key, value].concat()))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        };
+
+        // Existing pair.
+        assert_eq!(items_after(b"3", b"a", true), vec![b"3c", b"5a", b"5c"]);
+        assert_eq!(items_after(b"3", b"c", false), vec![b"3a", b"1c", b"1a"]);
+        // Missing value.
+        assert_eq!(items_after(b"3", b"b", true), vec![b"3c", b"5a", b"5c"]);
+        assert_eq!(items_after(b"3", b"b", false), vec![b"3a", b"1c", b"1a"]);
+        assert_eq!(items_after(b"3", b"d", true), vec![b"5a", b"5c"]);
+        assert_eq!(
+            items_after(b"3", b"d", false),
+            vec![b"3c", b"3a", b"1c", b"1a"]
+        );
+        // Missing key.
+        assert_eq!(items_after(b"4", b"a", true), vec![b"5a", b"5c"]);
+        assert_eq!(
+            items_after(b"4", b"a", false),
+            vec![b"3c", b"3a", b"1c", b"1a"]
+        );
+        // Past db ends.
+        assert_eq!(items_after(b"5", b"c", true), Vec::<Vec<u8>>::new());
+        assert_eq!(items_after(b"6", b"a", false).len(), 6);
+        assert_eq!(items_after(b"0", b"a", true).len(), 6);
+        assert_eq!(items_after(b"1", b"a", false), Vec::<Vec<u8>>::new());
+    }
 }
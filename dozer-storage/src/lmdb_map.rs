@@ -146,6 +146,16 @@ impl<K: LmdbKey, V: LmdbVal> LmdbMap<K, V> {
         KeyIterator::new(cursor, Bound::Unbounded, true)
     }
 
+    pub fn key_range<'txn, T: Transaction>(
+        &self,
+        txn: &'txn T,
+        starting_key: Bound<K::Encode<'_>>,
+        ascending: bool,
+    ) -> Result<KeyIterator<'txn, RoCursor<'txn>, K>, StorageError> {
+        let cursor = txn.open_ro_cursor(self.db)?;
+        KeyIterator::new(cursor, starting_key, ascending)
+    }
+
     pub fn values<'txn, T: Transaction>(
         &self,
         txn: &'txn T,
@@ -147,6 +147,18 @@ impl<K: LmdbKey, V: LmdbKey> LmdbMultimap<K, V> {
         let cursor = txn.open_ro_cursor(self.db)?;
         Iterator::new(cursor, starting_key, ascending)
     }
+
+    /// Iterates from the key-value pair right after (`ascending`) or before (`!ascending`) the given one, which doesn't have to exist.
+    pub fn range_after<'txn, T: Transaction>(
+        &self,
+        txn: &'txn T,
+        key: K::Encode<'_>,
+        value: V::Encode<'_>,
+        ascending: bool,
+    ) -> Result<Iterator<'txn, RoCursor<'txn>, K, V>, StorageError> {
+        let cursor = txn.open_ro_cursor(self.db)?;
+        Iterator::new_after(cursor, key, value, ascending)
+    }
 }
 
 fn database_flag<K: LmdbKey, V: LmdbKey>() -> DatabaseFlags {
@@ -1,3 +1,5 @@
+use std::ops::Bound;
+
 use lmdb::{Database, RoCursor, RwTransaction, Transaction};
 
 use crate::{
@@ -67,6 +69,15 @@ impl<K: LmdbKey> LmdbSet<K> {
         self.0.keys(txn)
     }
 
+    pub fn range<'txn, T: Transaction>(
+        &self,
+        txn: &'txn T,
+        starting_key: Bound<K::Encode<'_>>,
+        ascending: bool,
+    ) -> Result<KeyIterator<'txn, RoCursor<'txn>, K>, StorageError> {
+        self.0.key_range(txn, starting_key, ascending)
+    }
+
     pub fn database(&self) -> Database {
         self.0.database()
     }
@@ -19,7 +19,7 @@ use dozer_sql::builder::statement_to_pipeline;
 
 use dozer_types::errors::internal::BoxedError;
 use dozer_types::models::ingestion_types::IngestionMessage;
-use dozer_types::node::OpIdentifier;
+use dozer_types::node::{OpIdentifier, SourceCheckpoint};
 use dozer_types::types::{Operation, OperationWithId, Record, Schema, SourceDefinition};
 use std::collections::HashMap;
 use std::future::pending;
@@ -129,7 +129,7 @@ impl Source for TestSource {
     async fn start(
         &mut self,
         sender: tokio::sync::mpsc::Sender<(PortHandle, IngestionMessage)>,
-        _last_checkpoint: Option<OpIdentifier>,
+        _last_checkpoint: Option<SourceCheckpoint>,
     ) -> Result<(), BoxedError> {
         while let Ok(Some((schema_name, op))) = self.receiver.recv() {
             let port = self.name_to_port.get(&schema_name).expect("port not found");
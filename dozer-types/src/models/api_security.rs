@@ -7,4 +7,38 @@ use serde::{Deserialize, Serialize};
 pub enum ApiSecurity {
     /// Initialize with a JWT_SECRET
     Jwt(String),
+    /// Verify tokens issued by an identity provider with its PEM encoded public key
+    JwtPublicKey(JwtPublicKey),
+    /// Verify tokens with the key matching their `kid` in a JWKS document
+    Jwks(Jwks),
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JwtPublicKey {
+    pub algorithm: JwtAlgorithm,
+
+    /// The PEM encoded public key
+    pub public_key: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// The expected `aud` claim; Default: not validated
+    pub audience: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Jwks {
+    /// Path to the JWKS document. It's reloaded when a token has an unknown `kid`, so keys can be rotated.
+    pub path: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// The expected `aud` claim; Default: not validated
+    pub audience: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Hash, JsonSchema)]
+pub enum JwtAlgorithm {
+    RS256,
+    ES256,
 }
@@ -28,6 +28,10 @@ pub struct Flags {
 
     /// app checkpoints can be used to resume execution of a query.; Default: false
     pub enable_app_checkpoints: Option<bool>,
+
+    /// maximum number of times the recursive part of a `WITH RECURSIVE` query is expanded, at most 64. Every expansion adds the processors of the recursive part to the pipeline, and the pipeline fails if the data is deeper.; Default: 16
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_recursive_cte_depth: Option<u32>,
 }
 
 pub fn default_dynamic() -> bool {
@@ -45,7 +49,7 @@ pub struct EnableProbabilisticOptimizations {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub in_joins: Option<bool>,
 
-    /// enable probabilistic optimizations in aggregations (SUM, COUNT, MIN, etc.); Default: false
+    /// enable probabilistic optimizations in aggregations (SUM, COUNT, MIN, etc.), including estimating COUNT(DISTINCT) with HyperLogLog; Default: false
     #[serde(skip_serializing_if = "Option::is_none")]
     pub in_aggregations: Option<bool>,
 }
@@ -57,3 +61,7 @@ pub fn default_push_events() -> bool {
 pub fn default_enable_app_checkpoints() -> bool {
     false
 }
+
+pub fn default_max_recursive_cte_depth() -> u32 {
+    16
+}
@@ -35,6 +35,9 @@ pub enum TransactionInfo {
     Commit {
         /// If this connector supports restarting from after this commit, it should provide a `OpIdentifier`.
         id: Option<OpIdentifier>,
+        /// State that the connector needs to restart from after this commit, beyond `id`.
+        /// It's given back to the connector in `SourceCheckpoint::state`.
+        state: Option<Vec<u8>>,
     },
     /// A connector uses this message kind to notify Dozer that a initial snapshot of the source tables is started
     SnapshottingStarted,
@@ -172,6 +175,37 @@ pub struct KafkaConfig {
     pub broker: String,
 
     pub schema_registry_url: Option<String>,
+
+    /// How the keys of the messages of each topic map to the primary key. Topics that aren't listed use `Auto`.
+    /// Topics with a primary key are upserted: the primary key is read from the message key, messages update
+    /// the record of their key, and tombstones delete it. As the first message of a key is an update too,
+    /// endpoints of these topics need the `Upsert` update conflict resolution.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub topics: Vec<KafkaTopicConfig>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
+pub struct KafkaTopicConfig {
+    pub name: String,
+
+    #[serde(default)]
+    pub key: KafkaKeyMapping,
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema, Default)]
+pub enum KafkaKeyMapping {
+    /// If the key schema registered for the topic is a record whose fields are all in the value,
+    /// those fields are the primary key. Otherwise the key is stored in a column, which is the primary key.
+    #[default]
+    Auto,
+    /// The key is stored in a column with this name, which is the primary key.
+    /// Keys without a registered schema must be UTF-8 strings. Registered key schemas must have a single field.
+    Column(String),
+    /// These fields of the value are the primary key. Keys must be records with the same fields,
+    /// so this needs a schema registry.
+    Fields(Vec<String>),
+    /// There's no primary key, and messages are appended. The key is ignored.
+    None,
 }
 
 impl KafkaConfig {
@@ -278,6 +312,9 @@ pub struct Table {
 pub enum TableConfig {
     CSV(CsvConfig),
     Parquet(ParquetConfig),
+    JSON(JsonConfig),
+    Avro(AvroConfig),
+    ORC(OrcConfig),
 }
 
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
@@ -288,6 +325,9 @@ pub struct CsvConfig {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub marker_extension: Option<String>,
+
+    #[serde(default, flatten)]
+    pub selection: FileSelection,
 }
 
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
@@ -298,6 +338,90 @@ pub struct ParquetConfig {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub marker_extension: Option<String>,
+
+    #[serde(default, flatten)]
+    pub selection: FileSelection,
+}
+
+/// Selects the files of an object store table and reads Hive-style partition columns from their paths.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema, Default)]
+pub struct FileSelection {
+    /// Partition columns, read from `<name>=<value>` directories under `path`. They follow the file columns.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub partition_columns: Vec<PartitionColumn>,
+
+    /// Glob patterns relative to `path`. If not empty, only the files matching one of them are read.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+
+    /// Glob patterns relative to `path` of files that are not read.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+
+    /// Files in partitions before this one, for example `dt=2024-01-01`, are not read.
+    /// Its keys must be the leading `partition_columns`, in order.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_partition: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
+pub struct PartitionColumn {
+    pub name: String,
+
+    #[serde(default)]
+    pub typ: PartitionColumnType,
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Hash, JsonSchema, Default)]
+pub enum PartitionColumnType {
+    #[default]
+    String,
+    Int,
+    UInt,
+    Float,
+    Boolean,
+    Date,
+    Timestamp,
+}
+
+/// Newline-delimited JSON, one object per line.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
+pub struct JsonConfig {
+    pub path: String,
+
+    pub extension: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker_extension: Option<String>,
+
+    #[serde(default, flatten)]
+    pub selection: FileSelection,
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
+pub struct AvroConfig {
+    pub path: String,
+
+    pub extension: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker_extension: Option<String>,
+
+    #[serde(default, flatten)]
+    pub selection: FileSelection,
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
+pub struct OrcConfig {
+    pub path: String,
+
+    pub extension: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker_extension: Option<String>,
+
+    #[serde(default, flatten)]
+    pub selection: FileSelection,
 }
 
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
@@ -344,6 +468,7 @@ impl SchemaExample for S3Storage {
                     path: "path/to/file".to_owned(),
                     extension: ".csv".to_owned(),
                     marker_extension: None,
+                    selection: Default::default(),
                 }),
                 name: "table_name".to_owned(),
             }],
@@ -475,6 +600,7 @@ impl SchemaExample for KafkaConfig {
         Self {
             broker: "".to_owned(),
             schema_registry_url: Some("".to_owned()),
+            topics: vec![],
         }
     }
 }
@@ -501,6 +627,7 @@ impl SchemaExample for LocalStorage {
                     path: "path/to/table".to_owned(),
                     extension: ".csv".to_owned(),
                     marker_extension: None,
+                    selection: Default::default(),
                 }),
                 name: "table_name".to_owned(),
             }],
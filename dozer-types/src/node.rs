@@ -104,6 +104,29 @@ impl OpIdentifier {
     }
 }
 
+#[derive(
+    Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, bincode::Encode, bincode::Decode,
+)]
+/// Where a source restarts from.
+pub struct SourceCheckpoint {
+    /// Id of the commit to restart after.
+    pub id: OpIdentifier,
+    /// State that the connector checkpointed with the commit, if any. It's opaque to Dozer.
+    pub state: Option<Vec<u8>>,
+}
+
+impl SourceCheckpoint {
+    pub fn new(id: OpIdentifier, state: Option<Vec<u8>>) -> Self {
+        Self { id, state }
+    }
+}
+
+impl From<OpIdentifier> for SourceCheckpoint {
+    fn from(id: OpIdentifier) -> Self {
+        Self::new(id, None)
+    }
+}
+
 #[derive(
     Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, bincode::Encode, bincode::Decode,
 )]
@@ -113,8 +136,8 @@ pub enum SourceState {
     NotStarted,
     /// This source has some data ingested, and it can't be restarted.
     NonRestartable,
-    /// This source has some data ingested, and it can be restarted if it's given the op id.
-    Restartable(OpIdentifier),
+    /// This source has some data ingested, and it can be restarted if it's given the checkpoint.
+    Restartable(SourceCheckpoint),
 }
 
 /// Map from a `Source` node's handle to it state.
@@ -1,6 +1,6 @@
 use crate::models::{
     api_config::{GrpcApiOptions, RestApiOptions},
-    api_security::ApiSecurity,
+    api_security::{ApiSecurity, Jwks},
     config::Config,
 };
 
@@ -133,6 +133,26 @@ fn override_jwt() {
     assert_eq!(api_security, expected_api_security);
 }
 
+#[test]
+fn override_jwks() {
+    let input_config = r#"
+  app_name: working_app
+  version: 1
+  api:
+    api_security: !Jwks
+      path: ./jwks.json
+      audience: dozer
+  home_dir: './.dozer'
+"#;
+    let deserialize_result = serde_yaml::from_str::<Config>(input_config);
+    let api_security = deserialize_result.unwrap().api.api_security;
+    let expected_api_security = ApiSecurity::Jwks(Jwks {
+        path: "./jwks.json".to_owned(),
+        audience: Some("dozer".to_owned()),
+    });
+    assert_eq!(api_security, Some(expected_api_security));
+}
+
 #[test]
 fn override_pipeline_port() {
     let input_config = r#"